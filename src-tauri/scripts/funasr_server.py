@@ -1,29 +1,189 @@
 #!/usr/bin/env python3
 """
 FunASR 常驻服务
-保持 Python 进程运行，模型只加载一次，通过 stdin/stdout 进行 JSON-RPC 通信
+保持 Python 进程运行，模型只加载一次，通过 stdin/stdout 进行 JSON-RPC 2.0 通信
+
+协议（每行一条 JSON 消息）：
+- 请求 {"jsonrpc": "2.0", "id": 1, "method": "transcribe", "params": {...}}
+- 响应 {"jsonrpc": "2.0", "id": 1, "result": {...}} 或 {"jsonrpc": "2.0", "id": 1, "error": {"code": ..., "message": ...}}
+- 通知（无 id）：客户端发送 cancel 和 shutdown，服务器发送 progress 和 log
+- transcribe 传入 timestamps: true 时结果附带 segments（句子级时间戳，毫秒，含可对齐的字级时间戳）
+- 流式识别：stream_start 创建会话，录音中多次调用 stream_audio 返回实时结果，
+  最后 stream_finish（或 stream_cancel）结束会话
+- 模型常驻：load_model 预加载、unload_model 释放、list_models 列出已加载的模型及内存占用，
+  未预加载的模型在首次请求时加载
+客户端启动后先调用 initialize 确认协议版本
 """
 
 import sys
+import gc
+import hashlib
 import json
 import os
+import queue
+import re
+import subprocess
+import tempfile
+import threading
+import time
+import unicodedata
 from pathlib import Path
 from typing import Optional, Dict, Any
-from contextlib import contextmanager
 
-@contextmanager
-def suppress_stdout():
-    """临时重定向 stdout 到 stderr，防止 FunASR 输出污染 JSON 响应"""
-    original_stdout = sys.stdout
-    try:
-        sys.stdout = sys.stderr
-        yield
-    finally:
-        sys.stdout = original_stdout
+# 协议版本，与 src/funasr/rpc.rs 的 PROTOCOL_VERSION 保持一致
+PROTOCOL_VERSION = 1
+
+# 错误码，与 src/funasr/rpc.rs 的 ErrorCode 保持一致
+PARSE_ERROR = -32700
+INVALID_REQUEST = -32600
+METHOD_NOT_FOUND = -32601
+INVALID_PARAMS = -32602
+INTERNAL_ERROR = -32603
+AUDIO_TOO_SHORT = -32001
+AUDIO_INVALID = -32002
+MODEL_ERROR = -32003
+TRANSCRIPTION_FAILED = -32004
+REQUEST_CANCELLED = -32800
+
+
+class RpcError(Exception):
+    """带错误码的请求失败，作为 error 响应返回给客户端"""
+
+    def __init__(self, code: int, message: str):
+        super().__init__(message)
+        self.code = code
+        self.message = message
+
+
+# 协议输出流：启动时复制原 stdout，之后 fd 1 指向 stderr，
+# FunASR 及其依赖（包括 C 扩展）的打印都不会混入协议消息
+_protocol_out = None
+_output_lock = threading.Lock()
+
+
+def redirect_stdout() -> None:
+    global _protocol_out
+    sys.stdout.flush()
+    _protocol_out = os.fdopen(os.dup(sys.stdout.fileno()), "w", encoding="utf-8", buffering=1)
+    os.dup2(sys.stderr.fileno(), sys.stdout.fileno())
+    sys.stdout = sys.stderr
+
+
+def send(message: Dict[str, Any]) -> None:
+    with _output_lock:
+        _protocol_out.write(json.dumps({"jsonrpc": "2.0", **message}, ensure_ascii=False) + "\n")
+        _protocol_out.flush()
+
+
+def send_result(request_id, result: Any) -> None:
+    send({"id": request_id, "result": result})
+
+
+def send_error(request_id, code: int, message: str) -> None:
+    send({"id": request_id, "error": {"code": code, "message": message}})
+
+
+def notify(method: str, params: Dict[str, Any]) -> None:
+    send({"method": method, "params": params})
+
+
+def log(level: str, message: str) -> None:
+    """日志通知，由客户端写入应用日志"""
+    notify("log", {"level": level, "message": message})
+
 
 # 全局模型缓存
 _model_cache = {}
 
+# 已加载模型的统计：名称 -> {"memory_mb": 模型占用的内存, "load_ms": 加载耗时}
+_model_stats: Dict[str, Dict[str, Any]] = {}
+
+
+def process_rss_mb() -> Optional[float]:
+    """当前进程的常驻内存（MB），无法获取时返回 None"""
+    try:
+        import psutil
+        return psutil.Process().memory_info().rss / (1024 * 1024)
+    except ImportError:
+        pass
+
+    # Linux
+    try:
+        with open("/proc/self/statm") as f:
+            pages = int(f.read().split()[1])
+        return pages * os.sysconf("SC_PAGE_SIZE") / (1024 * 1024)
+    except (OSError, ValueError, IndexError):
+        pass
+
+    # macOS：ps 输出的单位为 KB
+    try:
+        output = subprocess.run(
+            ["ps", "-o", "rss=", "-p", str(os.getpid())],
+            capture_output=True, text=True, timeout=5,
+        ).stdout
+        return int(output.strip()) / 1024
+    except (OSError, ValueError, subprocess.SubprocessError):
+        return None
+
+
+def model_size_mb(model: Any) -> Optional[float]:
+    """模型参数和缓冲区占用的内存（MB）
+
+    AutoModel 的识别、VAD、标点和说话人子模型都计入，共享的张量只算一次；
+    找不到 PyTorch 模块时返回 None
+    """
+    try:
+        import torch
+    except ImportError:
+        return None
+
+    modules = [model] + [getattr(model, attr, None) for attr in ("model", "vad_model", "punc_model", "spk_model")]
+    seen = set()
+    total = 0
+    for module in modules:
+        if not isinstance(module, torch.nn.Module):
+            continue
+        for tensor in list(module.parameters()) + list(module.buffers()):
+            if tensor.data_ptr() in seen:
+                continue
+            seen.add(tensor.data_ptr())
+            total += tensor.numel() * tensor.element_size()
+
+    return round(total / (1024 * 1024), 1) if seen else None
+
+
+def cache_model(name: str, model: Any, started: float, rss_before: Optional[float]) -> None:
+    """缓存加载完成的模型并记录耗时和内存占用
+
+    内存占用按模型参数和缓冲区计算；无法计算时退回加载前后常驻内存之差，
+    这只是近似值：包含加载期间的临时分配和库的初始化，也受其他线程的分配影响
+    """
+    memory_mb = model_size_mb(model)
+    if memory_mb is None:
+        rss_after = process_rss_mb()
+        if rss_before is not None and rss_after is not None:
+            memory_mb = round(max(rss_after - rss_before, 0.0), 1)
+
+    _model_cache[name] = model
+    _model_stats[name] = {
+        "memory_mb": memory_mb,
+        "load_ms": int((time.monotonic() - started) * 1000),
+    }
+
+
+def model_entry(name: str) -> Dict[str, Any]:
+    stats = _model_stats.get(name, {})
+    return {
+        "name": name,
+        "memory_mb": stats.get("memory_mb"),
+        "load_ms": stats.get("load_ms", 0),
+    }
+
+# 排队中或执行中的请求 id，以及其中已取消的（请求处理完后都移除）
+_cancel_lock = threading.Lock()
+_active_ids = set()
+_cancelled_ids = set()
+
 
 def load_model(model_name: str) -> Any:
     """加载或获取缓存的模型"""
@@ -31,12 +191,14 @@ def load_model(model_name: str) -> Any:
         print(f"♻️  Using cached model: {model_name}", file=sys.stderr)
         return _model_cache[model_name]
 
-    print(f"📦 Loading model: {model_name}", file=sys.stderr)
+    log("info", f"📦 Loading model: {model_name}")
+    started = time.monotonic()
+    rss_before = process_rss_mb()
 
     try:
         from funasr import AutoModel
     except ImportError as e:
-        raise ImportError(f"FunASR not installed: {e}")
+        raise RpcError(MODEL_ERROR, f"FunASR not installed: {e}")
 
     # 模型配置
     model_configs = {
@@ -59,7 +221,7 @@ def load_model(model_name: str) -> Any:
 
     config = model_configs.get(model_name)
     if not config:
-        raise ValueError(f"Unknown model: {model_name}")
+        raise RpcError(INVALID_PARAMS, f"Unknown model: {model_name}")
 
     # 初始化模型
     model_kwargs = {
@@ -76,180 +238,874 @@ def load_model(model_name: str) -> Any:
     if config["punc_model"]:
         model_kwargs["punc_model"] = config["punc_model"]
 
-    # 使用 suppress_stdout 防止 FunASR 输出污染 JSON 响应
-    with suppress_stdout():
+    try:
         model = AutoModel(**model_kwargs)
+    except Exception as e:
+        raise RpcError(MODEL_ERROR, f"模型加载失败: {e}")
+
+    cache_model(model_name, model, started, rss_before)
+
+    log("info", f"✅ Model loaded and cached: {model_name} ({_model_stats[model_name]['load_ms']} ms)")
+    return model
+
+
+def preload_model(model_name: str) -> Dict[str, Any]:
+    """预加载模型，之后的请求不再等待加载"""
+    already_loaded = model_name in _model_cache
+    load_model(model_name)
+    return {"model": model_entry(model_name), "already_loaded": already_loaded}
+
+
+def release_memory() -> None:
+    """回收释放的模型占用的内存（包括 GPU / MPS 缓存）"""
+    gc.collect()
+    try:
+        import torch
+
+        if torch.cuda.is_available():
+            torch.cuda.empty_cache()
+        if hasattr(torch, "mps") and torch.backends.mps.is_available():
+            torch.mps.empty_cache()
+    except Exception:
+        pass
+
+
+def unload_model(model_name: str) -> Dict[str, Any]:
+    """释放已加载的模型；流式模型成对加载，按 stream-asr 一起释放"""
+    names = ["stream-asr", "stream-vad"] if model_name in ("stream-asr", "stream-vad") else [model_name]
+    unloaded = False
+    for name in names:
+        if _model_cache.pop(name, None) is not None:
+            unloaded = True
+        _model_stats.pop(name, None)
+
+    if unloaded:
+        release_memory()
+        log("info", f"🗑️  Model unloaded: {model_name}")
+    return {"unloaded": unloaded, "rss_mb": process_rss_mb()}
+
+
+def list_models() -> Dict[str, Any]:
+    """已加载的模型及服务器进程的常驻内存"""
+    return {
+        "models": [model_entry(name) for name in list(_model_cache)],
+        "rss_mb": process_rss_mb(),
+    }
+
+
+# 长音频分块：超过 CHUNK_SECONDS 的音频逐块识别并上报进度
+SAMPLE_RATE = 16000
+CHUNK_SECONDS = 60
+# 在每块末尾这段范围内找能量最低的位置切分，避免切断词语
+CUT_SEARCH_SECONDS = 10
+CUT_FRAME_SECONDS = 0.1
+
+
+def read_wav_16k(audio_path: str):
+    """读取 16kHz 单声道 16bit WAV 为 float32 数组，格式不符时返回 None"""
+    import wave
+    import numpy as np
+
+    with wave.open(audio_path, "rb") as wav:
+        if (
+            wav.getframerate() != SAMPLE_RATE
+            or wav.getnchannels() != 1
+            or wav.getsampwidth() != 2
+        ):
+            return None
+        frames = wav.readframes(wav.getnframes())
+
+    return np.frombuffer(frames, dtype=np.int16).astype(np.float32) / 32768.0
+
+
+def decode_pcm16(data: str):
+    """解码请求中的 base64 PCM16（小端、16kHz 单声道）为 float32 数组"""
+    import base64
+    import numpy as np
+
+    return np.frombuffer(base64.b64decode(data), dtype="<i2").astype(np.float32) / 32768.0
+
+
+def split_chunks(audio) -> list:
+    """按 CHUNK_SECONDS 切分，切点取每块末尾能量最低的帧"""
+    import numpy as np
+
+    chunk_len = CHUNK_SECONDS * SAMPLE_RATE
+    search_len = CUT_SEARCH_SECONDS * SAMPLE_RATE
+    frame_len = int(CUT_FRAME_SECONDS * SAMPLE_RATE)
+
+    chunks = []
+    start = 0
+    while len(audio) - start > chunk_len:
+        window_start = start + chunk_len - search_len
+        window = audio[window_start:start + chunk_len]
+        n_frames = len(window) // frame_len
+        energies = (window[:n_frames * frame_len].reshape(n_frames, frame_len) ** 2).mean(axis=1)
+        cut = window_start + int(np.argmin(energies)) * frame_len + frame_len // 2
+        chunks.append(audio[start:cut])
+        start = cut
+    chunks.append(audio[start:])
+    return chunks
+
+
+def join_texts(texts: list) -> str:
+    """拼接各块文本，两侧都是拉丁字母/数字时补空格"""
+    joined = ""
+    for text in texts:
+        if not text:
+            continue
+        if joined and joined[-1].isascii() and joined[-1].isalnum() and text[0].isascii() and text[0].isalnum():
+            joined += " "
+        joined += text
+    return joined
+
+
+# SenseVoice 的 <|zh|><|NEUTRAL|> 等标签（不参与字级时间戳对齐）
+TAG_PATTERN = re.compile(r"<\|[^|]*\|>")
+
+
+def split_tokens(text: str) -> list:
+    """按 FunASR 字级时间戳的粒度切分文本：拉丁字母/数字按词，其余文字逐字，忽略标点和空白"""
+    tokens = []
+    word = ""
+    for ch in TAG_PATTERN.sub(" ", text):
+        if ch.isascii() and (ch.isalnum() or ch == "'"):
+            word += ch
+            continue
+        if word:
+            tokens.append(word)
+            word = ""
+        if unicodedata.category(ch)[0] not in "PZSC":
+            tokens.append(ch)
+    if word:
+        tokens.append(word)
+    return tokens
+
+
+def token_words(text: str, timestamps, offset_ms: int) -> list:
+    """字级时间戳与文本对齐为词，数量对不上时不返回
+
+    Paraformer 的时间戳为 [start, end]，按 split_tokens 与文本对齐；
+    SenseVoice（output_timestamp）为 [token, start, end]，直接使用其中的 token
+    """
+    if not timestamps:
+        return []
+    if all(len(entry) == 3 for entry in timestamps):
+        words = []
+        for token, start, end in timestamps:
+            token = "".join(split_tokens(str(token).replace("\u2581", " ")))
+            if token:
+                words.append({"text": token, "start_ms": int(start) + offset_ms, "end_ms": int(end) + offset_ms})
+        return words
+
+    tokens = split_tokens(text)
+    if len(tokens) != len(timestamps):
+        return []
+    return [
+        {"text": token, "start_ms": int(start) + offset_ms, "end_ms": int(end) + offset_ms}
+        for token, (start, end) in zip(tokens, timestamps)
+    ]
+
+
+def result_segments(item: Dict[str, Any], offset_ms: int, duration_ms: int) -> list:
+    """模型输出转为带时间戳的段落（毫秒，相对整段音频）
+
+    优先用 VAD + 标点模型给出的句子时间戳（sentence_info）；没有标点模型时（SenseVoice）
+    整个输入作为一段（调用方按 VAD 语音段分别识别），起止取字级时间戳的范围
+    """
+    segments = []
+    for sentence in item.get("sentence_info") or []:
+        text = sentence.get("text", "").strip()
+        if not text:
+            continue
+        segments.append({
+            "text": text,
+            "start_ms": int(sentence.get("start", 0)) + offset_ms,
+            "end_ms": int(sentence.get("end", 0)) + offset_ms,
+            "words": token_words(text, sentence.get("timestamp"), offset_ms),
+        })
+    if segments:
+        return segments
+
+    text = item.get("text", "").strip()
+    if not text:
+        return []
+    timestamps = item.get("timestamp") or []
+    try:
+        start, end = int(timestamps[0][-2]), int(timestamps[-1][-1])
+    except (IndexError, TypeError, ValueError):
+        start, end = 0, duration_ms
+    return [{
+        "text": text,
+        "start_ms": start + offset_ms,
+        "end_ms": end + offset_ms,
+        "words": token_words(text, timestamps, offset_ms),
+    }]
 
-    _model_cache[model_name] = model
 
-    print(f"✅ Model loaded and cached: {model_name}", file=sys.stderr)
+def has_punctuation(model: Any) -> bool:
+    """模型是否带标点模型（只有带标点模型时 FunASR 才能给出句子时间戳）"""
+    return getattr(model, "punc_model", None) is not None
+
+
+VAD_MODEL = "damo/speech_fsmn_vad_zh-cn-16k-common-pytorch"
+
+
+def load_vad_model() -> Any:
+    """加载或获取缓存的离线 VAD 模型（SenseVoice 按语音段切分时间戳）"""
+    if "vad" in _model_cache:
+        return _model_cache["vad"]
+
+    log("info", f"📦 Loading VAD model: {VAD_MODEL}")
+    started = time.monotonic()
+    rss_before = process_rss_mb()
+    try:
+        from funasr import AutoModel
+
+        model = AutoModel(model=VAD_MODEL, disable_log=True, disable_pbar=True, disable_update=True, hub="ms")
+    except Exception as e:
+        raise RpcError(MODEL_ERROR, f"VAD 模型加载失败: {e}")
+
+    cache_model("vad", model, started, rss_before)
     return model
 
 
+def vad_segments(audio) -> list:
+    """音频中的语音段 [(start_ms, end_ms), ...]"""
+    result = load_vad_model().generate(input=audio)
+    if not result:
+        return []
+    return [(int(start), int(end)) for start, end in result[0].get("value") or []]
+
+
+def transcribe_by_vad(model: Any, audio, generate_kwargs: Dict[str, Any], offset_ms: int) -> tuple:
+    """没有标点模型时（SenseVoice）按 VAD 语音段逐段识别，每段作为一个带时间戳的段落
+
+    返回 (文本, 段落)；`offset_ms` 为 `audio` 在整段音频中的起点
+    """
+    texts = []
+    segments = []
+    for start_ms, end_ms in vad_segments(audio):
+        piece = audio[start_ms * SAMPLE_RATE // 1000:end_ms * SAMPLE_RATE // 1000]
+        if len(piece) == 0:
+            continue
+        result = model.generate(**{**generate_kwargs, "input": piece})
+        if not result or not result[0].get("text", "").strip():
+            continue
+        texts.append(result[0]["text"].strip())
+        segments.extend(result_segments(result[0], offset_ms + start_ms, end_ms - start_ms))
+    return join_texts(texts), segments
+
+
+HOTWORD_DIR = Path(tempfile.gettempdir()) / "lingcode-hotwords"
+
+
+def hotword_arg(hotword: Optional[str]) -> Optional[str]:
+    """把换行分隔的热词写入文件，返回文件路径
+
+    FunASR 对字符串形式的热词按空白切分，多词术语（如 "Visual Studio Code"）会被拆散；
+    以文件传入时每行作为一个完整热词
+    """
+    terms = [line.strip() for line in (hotword or "").splitlines() if line.strip()]
+    if not terms:
+        return None
+
+    content = "\n".join(terms) + "\n"
+    digest = hashlib.sha256(content.encode("utf-8")).hexdigest()[:16]
+    path = HOTWORD_DIR / f"hotwords-{digest}.txt"
+    if not path.exists():
+        HOTWORD_DIR.mkdir(parents=True, exist_ok=True)
+        tmp = path.with_suffix(".tmp")
+        tmp.write_text(content, encoding="utf-8")
+        os.replace(tmp, path)
+    return str(path)
+
+
+def emit_progress(request_id, done: int, total: int, text: str) -> None:
+    """进度通知（客户端据此更新进度，并刷新该请求的超时计时）"""
+    notify("progress", {"id": request_id, "done": done, "total": total, "text": text})
+
+
 def transcribe_audio(
-    audio_path: str,
+    audio_path: Optional[str] = None,
     model_name: str = "paraformer-zh",
     language: Optional[str] = None,
     hotword: Optional[str] = None,
+    request_id=None,
+    audio_pcm16: Optional[str] = None,
+    sample_rate: int = SAMPLE_RATE,
+    timestamps: bool = False,
 ) -> Dict[str, Any]:
-    """转录音频"""
-    try:
+    """转录音频（请求中的 PCM 数据优先，其次为 audio_path 指向的 WAV 文件）
+
+    `timestamps` 为 True 时结果中附带句子级段落 segments（含字级时间戳）
+    """
+    if audio_pcm16 is not None:
+        if sample_rate != SAMPLE_RATE:
+            raise RpcError(INVALID_PARAMS, f"Unsupported sample rate: {sample_rate} (expected {SAMPLE_RATE})")
+
+        audio = decode_pcm16(audio_pcm16)
+        print(f"📊 Audio samples (in memory): {len(audio)}", file=sys.stderr)
+
+        # 至少 500 个采样点（与文件方式的 1KB 下限相当）
+        if len(audio) < 500:
+            raise RpcError(AUDIO_TOO_SHORT, f"Audio too short: {len(audio)} samples (minimum 500 samples)")
+        audio_input = audio
+    else:
         # 验证音频文件
-        if not os.path.exists(audio_path):
-            return {
-                "success": False,
-                "error": f"Audio file not found: {audio_path}",
-            }
+        if not audio_path or not os.path.exists(audio_path):
+            raise RpcError(AUDIO_INVALID, f"Audio file not found: {audio_path}")
 
         # 检查音频文件大小（至少 1KB）
         file_size = os.path.getsize(audio_path)
         print(f"📊 Audio file size: {file_size} bytes", file=sys.stderr)
 
         if file_size < 1000:
-            return {
-                "success": False,
-                "error": f"Audio file too small: {file_size} bytes (minimum 1000 bytes)",
-            }
+            raise RpcError(AUDIO_TOO_SHORT, f"Audio file too small: {file_size} bytes (minimum 1000 bytes)")
+        audio = read_wav_16k(audio_path)
+        audio_input = audio_path
+
+    model = load_model(model_name)
 
-        model = load_model(model_name)
+    # 准备输入参数
+    generate_kwargs = {"input": audio_input}
 
-        # 准备输入参数
-        generate_kwargs = {"input": audio_path}
+    hotword_path = hotword_arg(hotword)
+    if hotword_path:
+        generate_kwargs["hotword"] = hotword_path
 
-        if hotword:
-            generate_kwargs["hotword"] = hotword
+    if language:
+        generate_kwargs["language"] = language
 
-        if language:
-            generate_kwargs["language"] = language
+    # 带标点模型时由 FunASR 按句给出时间戳；否则（SenseVoice）输出字级时间戳并按 VAD 语音段切分
+    by_vad = timestamps and not has_punctuation(model) and audio is not None
+    if timestamps and has_punctuation(model):
+        generate_kwargs["sentence_timestamp"] = True
+    elif timestamps:
+        generate_kwargs["output_timestamp"] = True
 
-        # 执行转录（使用 suppress_stdout 防止输出污染）
+    try:
+        # 长音频逐块识别，每块完成后上报进度
+        if audio is not None and len(audio) > CHUNK_SECONDS * SAMPLE_RATE:
+            chunks = split_chunks(audio)
+            print(f"🎤 Starting chunked transcription ({len(chunks)} chunks)...", file=sys.stderr)
+            texts = []
+            segments = []
+            offset_ms = 0
+            for index, chunk in enumerate(chunks):
+                if is_cancelled(request_id):
+                    print(f"🛑 Request #{request_id} cancelled after {index} chunks", file=sys.stderr)
+                    raise RpcError(REQUEST_CANCELLED, "Request cancelled")
+                chunk_ms = len(chunk) * 1000 // SAMPLE_RATE
+                if by_vad:
+                    chunk_text, chunk_segments = transcribe_by_vad(model, chunk, generate_kwargs, offset_ms)
+                    segments.extend(chunk_segments)
+                else:
+                    generate_kwargs["input"] = chunk
+                    result = model.generate(**generate_kwargs)
+                    chunk_text = result[0].get("text", "").strip() if result else ""
+                    if timestamps and result:
+                        segments.extend(result_segments(result[0], offset_ms, chunk_ms))
+                texts.append(chunk_text)
+                offset_ms += chunk_ms
+                emit_progress(request_id, index + 1, len(chunks), chunk_text)
+
+            print(f"✅ Transcription completed", file=sys.stderr)
+            response = {"text": join_texts(texts)}
+            if timestamps:
+                response["segments"] = segments
+            return response
+
+        if by_vad:
+            print(f"🎤 Starting transcription by VAD segments...", file=sys.stderr)
+            text, segments = transcribe_by_vad(model, audio, generate_kwargs, 0)
+            print(f"✅ Transcription completed", file=sys.stderr)
+            return {"text": text, "segments": segments}
+
+        # 执行转录
         print(f"🎤 Starting transcription...", file=sys.stderr)
-        with suppress_stdout():
-            result = model.generate(**generate_kwargs)
+        result = model.generate(**generate_kwargs)
         print(f"✅ Transcription completed", file=sys.stderr)
         print(f"📊 Raw result type: {type(result)}", file=sys.stderr)
         print(f"📊 Raw result length: {len(result) if result else 0}", file=sys.stderr)
         if result and len(result) > 0:
             print(f"📊 First result: {result[0]}", file=sys.stderr)
 
-        if not result or len(result) == 0:
-            return {
-                "success": False,
-                "error": "No transcription result (empty result from model)",
-            }
+    except RuntimeError as e:
+        error_msg = str(e)
+        if "stack expects a non-empty TensorList" in error_msg:
+            raise RpcError(AUDIO_TOO_SHORT, "音频太短或为静音，无法识别。请录制更长的音频（至少1秒）。")
+        import traceback
+        print(f"❌ Runtime error:\n{traceback.format_exc()}", file=sys.stderr)
+        raise RpcError(TRANSCRIPTION_FAILED, f"转录失败: {error_msg}")
+    except RpcError:
+        raise
+    except Exception as e:
+        import traceback
+        print(f"❌ Transcription error:\n{traceback.format_exc()}", file=sys.stderr)
+        raise RpcError(TRANSCRIPTION_FAILED, f"转录失败: {str(e)}")
 
-        # 提取文本
-        text = result[0].get("text", "")
+    if not result or len(result) == 0:
+        raise RpcError(TRANSCRIPTION_FAILED, "No transcription result (empty result from model)")
 
-        # 如果文本为空，返回特殊消息
-        if not text or text.strip() == "":
-            return {
-                "success": True,
-                "text": "",  # 空文本也算成功，可能是静音
-            }
+    # 空文本也算成功，可能是静音
+    text = result[0].get("text", "")
+    response = {"text": text if text.strip() else ""}
+    if timestamps:
+        duration_ms = len(audio) * 1000 // SAMPLE_RATE if audio is not None else 0
+        response["segments"] = result_segments(result[0], 0, duration_ms)
+    return response
 
-        return {
-            "success": True,
-            "text": text,
-        }
 
-    except RuntimeError as e:
-        error_msg = str(e)
-        if "stack expects a non-empty TensorList" in error_msg:
-            return {
-                "success": False,
-                "error": "音频太短或为静音，无法识别。请录制更长的音频（至少1秒）。",
-            }
-        else:
-            import traceback
-            error_details = traceback.format_exc()
-            print(f"❌ Runtime error:\\n{error_details}", file=sys.stderr)
-            return {
-                "success": False,
-                "error": f"转录失败: {error_msg}",
-            }
+SPEAKER_MODEL = "iic/speech_campplus_sv_zh-cn_16k-common"
+
+
+def load_speaker_model() -> Any:
+    """加载或获取缓存的 CAM++ 说话人模型"""
+    if "cam++" in _model_cache:
+        return _model_cache["cam++"]
+
+    log("info", f"📦 Loading speaker model: {SPEAKER_MODEL}")
+    started = time.monotonic()
+    rss_before = process_rss_mb()
+    try:
+        from funasr import AutoModel
+
+        model = AutoModel(
+            model=SPEAKER_MODEL,
+            disable_log=True,
+            disable_pbar=True,
+            disable_update=True,
+            hub="ms",
+        )
+    except Exception as e:
+        raise RpcError(MODEL_ERROR, f"说话人模型加载失败: {e}")
+
+    cache_model("cam++", model, started, rss_before)
+    log("info", "✅ Speaker model loaded and cached")
+    return model
+
+
+def embed_speakers(
+    audio_path: Optional[str],
+    segments: list,
+    request_id=None,
+    audio_pcm16: Optional[str] = None,
+) -> Dict[str, Any]:
+    """为每个 [start_ms, end_ms] 区间提取说话人向量，起止相同的区间返回 null"""
+    if audio_pcm16 is not None:
+        audio = decode_pcm16(audio_pcm16)
+    elif audio_path:
+        audio = read_wav_16k(audio_path)
+    else:
+        audio = None
+    if audio is None:
+        raise RpcError(AUDIO_INVALID, "Speaker embedding requires PCM audio or a 16kHz mono 16-bit WAV")
+
+    model = load_speaker_model()
+    embeddings = []
+    try:
+        for index, (start_ms, end_ms) in enumerate(segments):
+            if is_cancelled(request_id):
+                raise RpcError(REQUEST_CANCELLED, "Request cancelled")
+
+            start = int(start_ms * SAMPLE_RATE / 1000)
+            end = min(int(end_ms * SAMPLE_RATE / 1000), len(audio))
+            if end <= start:
+                embeddings.append(None)
+                continue
+
+            result = model.generate(input=audio[start:end])
+            embedding = result[0].get("spk_embedding") if result else None
+            if embedding is None:
+                embeddings.append(None)
+            else:
+                if hasattr(embedding, "cpu"):
+                    embedding = embedding.cpu().numpy()
+                embeddings.append([float(x) for x in embedding.reshape(-1)])
+
+            # 定期上报进度，避免长录音被客户端判定为超时
+            if (index + 1) % 20 == 0:
+                emit_progress(request_id, index + 1, len(segments), "")
+    except RpcError:
+        raise
     except Exception as e:
         import traceback
-        error_details = traceback.format_exc()
-        print(f"❌ Transcription error:\\n{error_details}", file=sys.stderr)
+        print(f"❌ Speaker embedding error:\n{traceback.format_exc()}", file=sys.stderr)
+        raise RpcError(TRANSCRIPTION_FAILED, f"说话人识别失败: {str(e)}")
+
+    return {"embeddings": embeddings}
+
+
+# 流式识别：录音过程中推送音频，online Paraformer 输出实时结果，
+# VAD 判断一句话结束后用离线模型重新识别该句（2-pass），修正实时结果
+STREAMING_MODEL = "iic/speech_paraformer-large_asr_nat-zh-cn-16k-common-vocab8404-online"
+STREAMING_VAD_MODEL = "damo/speech_fsmn_vad_zh-cn-16k-common-pytorch"
+# online 模型的 [0, 10, 5] 表示每次 600ms，向后看 300ms
+STREAM_CHUNK_SIZE = [0, 10, 5]
+STREAM_CHUNK_SAMPLES = STREAM_CHUNK_SIZE[1] * 960
+STREAM_CHUNK_MS = STREAM_CHUNK_SAMPLES * 1000 // SAMPLE_RATE
+# 句首之前保留的静音（VAD 起点有延迟，避免切掉第一个字）
+STREAM_PREROLL_SAMPLES = SAMPLE_RATE // 2
+
+_stream_sessions: Dict[str, "StreamSession"] = {}
+_next_session_id = 0
+
+
+def load_streaming_models() -> tuple:
+    """加载或获取缓存的 online Paraformer 和流式 VAD 模型"""
+    if "stream-asr" in _model_cache:
+        return _model_cache["stream-asr"], _model_cache["stream-vad"]
+
+    log("info", f"📦 Loading streaming model: {STREAMING_MODEL}")
+    try:
+        from funasr import AutoModel
+
+        common = {"disable_log": True, "disable_pbar": True, "disable_update": True, "hub": "ms"}
+        started, rss_before = time.monotonic(), process_rss_mb()
+        asr = AutoModel(model=STREAMING_MODEL, **common)
+        cache_model("stream-asr", asr, started, rss_before)
+        started, rss_before = time.monotonic(), process_rss_mb()
+        vad = AutoModel(model=STREAMING_VAD_MODEL, **common)
+        cache_model("stream-vad", vad, started, rss_before)
+    except Exception as e:
+        _model_cache.pop("stream-asr", None)
+        _model_stats.pop("stream-asr", None)
+        raise RpcError(MODEL_ERROR, f"流式模型加载失败: {e}")
+
+    log("info", "✅ Streaming models loaded and cached")
+    return asr, vad
+
+
+class StreamSession:
+    """一次录音的流式识别状态"""
+
+    def __init__(self, model_name: str, hotword: Optional[str]):
+        import numpy as np
+
+        self.model_name = model_name
+        self.hotword = hotword_arg(hotword)
+        self.asr, self.vad = load_streaming_models()
+        # 离线模型用于 2-pass 修正，提前加载避免第一句话结束时卡顿
+        load_model(model_name)
+
+        self.pending = np.zeros(0, dtype=np.float32)
+        self.segment = np.zeros(0, dtype=np.float32)
+        self.asr_cache: Dict[str, Any] = {}
+        self.vad_cache: Dict[str, Any] = {}
+        self.in_speech = False
+        self.confirmed: list = []
+        self.partial = ""
+
+    def feed(self, audio, is_final: bool = False) -> None:
+        """追加音频，按 600ms 步长送入 online 模型和 VAD"""
+        import numpy as np
+
+        self.pending = np.concatenate([self.pending, audio])
+        while len(self.pending) >= STREAM_CHUNK_SAMPLES:
+            chunk = self.pending[:STREAM_CHUNK_SAMPLES]
+            self.pending = self.pending[STREAM_CHUNK_SAMPLES:]
+            self._step(chunk, False)
+
+        if is_final:
+            self._step(self.pending, True)
+            self.pending = np.zeros(0, dtype=np.float32)
+
+    def _step(self, chunk, is_final: bool) -> None:
+        import numpy as np
+
+        self.segment = np.concatenate([self.segment, chunk])
+
+        if len(chunk) > 0:
+            result = self.asr.generate(
+                input=chunk,
+                cache=self.asr_cache,
+                is_final=is_final,
+                chunk_size=STREAM_CHUNK_SIZE,
+                encoder_chunk_look_back=4,
+                decoder_chunk_look_back=1,
+            )
+            if result:
+                self.partial += result[0].get("text", "")
+
+        segment_ended = False
+        if len(chunk) > 0:
+            vad_result = self.vad.generate(
+                input=chunk,
+                cache=self.vad_cache,
+                is_final=is_final,
+                chunk_size=STREAM_CHUNK_MS,
+            )
+            # value 为 [[起点, 终点], ...]，未知的一端为 -1
+            for start, end in (vad_result[0].get("value", []) if vad_result else []):
+                if start != -1:
+                    self.in_speech = True
+                if end != -1:
+                    self.in_speech = False
+                    segment_ended = True
+
+        if segment_ended or is_final:
+            self._finish_segment()
+        elif not self.in_speech and not self.partial:
+            # 句子之间的静音只保留一小段作为下一句的开头
+            self.segment = self.segment[-STREAM_PREROLL_SAMPLES:]
+
+    def _finish_segment(self) -> None:
+        """2-pass：离线模型重新识别整句，替换 online 结果"""
+        import numpy as np
+
+        text = self.partial
+        if len(self.segment) >= SAMPLE_RATE // 10 and (self.partial or self.in_speech):
+            generate_kwargs = {"input": self.segment}
+            if self.hotword:
+                generate_kwargs["hotword"] = self.hotword
+            try:
+                result = load_model(self.model_name).generate(**generate_kwargs)
+                text = result[0].get("text", "").strip() if result else text
+            except Exception as e:
+                print(f"⚠️  2-pass correction failed, keeping online result: {e}", file=sys.stderr)
+
+        if text:
+            self.confirmed.append(text)
+
+        self.segment = np.zeros(0, dtype=np.float32)
+        self.partial = ""
+        self.asr_cache = {}
+        self.in_speech = False
+
+    def snapshot(self) -> Dict[str, Any]:
+        confirmed = join_texts(self.confirmed)
         return {
-            "success": False,
-            "error": f"转录失败: {str(e)}",
+            "text": join_texts([confirmed, self.partial]),
+            "confirmed": confirmed,
+            "partial": self.partial,
         }
 
 
-def handle_request(request: Dict[str, Any]) -> Dict[str, Any]:
-    """处理单个请求"""
-    method = request.get("method")
-    params = request.get("params", {})
+def get_stream(session_id: str) -> "StreamSession":
+    session = _stream_sessions.get(session_id)
+    if session is None:
+        raise RpcError(INVALID_PARAMS, f"Unknown stream session: {session_id}")
+    return session
+
+
+def stream_start(model_name: str, hotword: Optional[str]) -> Dict[str, Any]:
+    global _next_session_id
+
+    _next_session_id += 1
+    session_id = f"stream-{_next_session_id}"
+    _stream_sessions[session_id] = StreamSession(model_name, hotword)
+    print(f"🎙️  Stream session started: {session_id} ({model_name})", file=sys.stderr)
+    return {"session_id": session_id}
+
+
+def stream_audio(session_id: str, audio_pcm16: str) -> Dict[str, Any]:
+    session = get_stream(session_id)
+    try:
+        session.feed(decode_pcm16(audio_pcm16))
+    except RpcError:
+        raise
+    except Exception as e:
+        import traceback
+        print(f"❌ Streaming error:\n{traceback.format_exc()}", file=sys.stderr)
+        raise RpcError(TRANSCRIPTION_FAILED, f"流式识别失败: {str(e)}")
+    return session.snapshot()
+
+
+def stream_finish(session_id: str) -> Dict[str, Any]:
+    session = get_stream(session_id)
+    try:
+        session.feed(decode_pcm16(""), is_final=True)
+    except Exception as e:
+        print(f"⚠️  Failed to flush stream {session_id}: {e}", file=sys.stderr)
+    finally:
+        _stream_sessions.pop(session_id, None)
+    print(f"🏁 Stream session finished: {session_id}", file=sys.stderr)
+    return {"text": join_texts(session.confirmed)}
+
+
+def stream_cancel(session_id: str) -> Dict[str, Any]:
+    _stream_sessions.pop(session_id, None)
+    print(f"🛑 Stream session cancelled: {session_id}", file=sys.stderr)
+    return {}
+
+
+# 在工作线程中按顺序执行的方法（模型推理不能并发）
+WORKER_METHODS = (
+    "transcribe",
+    "embed_speakers",
+    "stream_start",
+    "stream_audio",
+    "stream_finish",
+    "stream_cancel",
+    "load_model",
+    "unload_model",
+    "shutdown",
+)
 
+
+def handle_request(request_id, method: str, params: Dict[str, Any]) -> Any:
+    """处理工作线程中的请求，失败时抛出 RpcError"""
     if method == "transcribe":
         return transcribe_audio(
             audio_path=params.get("audio_path"),
             model_name=params.get("model_name", "paraformer-zh"),
             language=params.get("language"),
             hotword=params.get("hotword"),
+            request_id=request_id,
+            audio_pcm16=params.get("audio_pcm16"),
+            sample_rate=params.get("sample_rate", SAMPLE_RATE),
+            timestamps=bool(params.get("timestamps", False)),
         )
-    elif method == "ping":
-        return {"success": True, "message": "pong"}
+    elif method == "embed_speakers":
+        return embed_speakers(
+            audio_path=params.get("audio_path"),
+            segments=params.get("segments", []),
+            request_id=request_id,
+            audio_pcm16=params.get("audio_pcm16"),
+        )
+    elif method == "stream_start":
+        return stream_start(
+            model_name=params.get("model_name", "paraformer-zh"),
+            hotword=params.get("hotword"),
+        )
+    elif method == "stream_audio":
+        return stream_audio(params.get("session_id"), params.get("audio_pcm16", ""))
+    elif method == "stream_finish":
+        return stream_finish(params.get("session_id"))
+    elif method == "stream_cancel":
+        return stream_cancel(params.get("session_id"))
+    elif method == "load_model":
+        return preload_model(params.get("model_name", "paraformer-zh"))
+    elif method == "unload_model":
+        return unload_model(params.get("model_name", ""))
     elif method == "shutdown":
-        return {"success": True, "message": "shutting down"}
+        return {"message": "shutting down"}
+    raise RpcError(METHOD_NOT_FOUND, f"Unknown method: {method}")
+
+
+def handle_inline(request_id, method: str, params: Dict[str, Any]) -> None:
+    """在读取线程中直接应答的轻量方法，模型推理期间也能及时响应"""
+    if method == "initialize":
+        client_version = params.get("protocol_version")
+        if client_version != PROTOCOL_VERSION:
+            log("warning", f"Client protocol v{client_version} differs from server v{PROTOCOL_VERSION}")
+        send_result(request_id, {
+            "protocol_version": PROTOCOL_VERSION,
+            "methods": ["initialize", "ping", "list_models", *WORKER_METHODS],
+        })
+    elif method == "ping":
+        send_result(request_id, {"message": "pong"})
+    elif method == "list_models":
+        send_result(request_id, list_models())
     else:
-        return {
-            "success": False,
-            "error": f"Unknown method: {method}",
-        }
+        send_error(request_id, METHOD_NOT_FOUND, f"Unknown method: {method}")
+
+
+def read_requests(requests: "queue.Queue") -> None:
+    """读取 stdin 的线程：推理请求入队，cancel 通知和轻量请求立即处理
+
+    FunASR 的 generate 无法中途打断，cancel 到达后当前请求仍会执行完
+    （分块转录会在块之间停止），但结果会被替换为 cancelled 错误。
+    入队的请求发送 queued 通知，客户端在其开始执行（started 通知）前不计超时。
+    """
+    for line in sys.stdin:
+        line = line.strip()
+        if not line:
+            continue
+
+        try:
+            message = json.loads(line)
+        except json.JSONDecodeError as e:
+            send_error(None, PARSE_ERROR, f"Invalid JSON: {e}")
+            continue
+
+        if not isinstance(message, dict) or message.get("jsonrpc") != "2.0" or not isinstance(message.get("method"), str):
+            request_id = message.get("id") if isinstance(message, dict) else None
+            send_error(request_id, INVALID_REQUEST, "Invalid JSON-RPC 2.0 request")
+            continue
+
+        method = message["method"]
+        params = message.get("params") or {}
+
+        # 通知：不产生响应
+        if "id" not in message:
+            if method == "cancel":
+                # 已处理完的请求不再记录，避免 id 一直留在集合中
+                with _cancel_lock:
+                    active = params.get("id") in _active_ids
+                    if active:
+                        _cancelled_ids.add(params.get("id"))
+                print(f"🛑 Cancel received for request #{params.get('id')} (active: {active})", file=sys.stderr)
+            elif method == "shutdown":
+                # 处理完已排队的请求后退出（客户端超时后会强制结束进程）
+                print("🛑 Shutdown requested", file=sys.stderr)
+                break
+            continue
+
+        request_id = message["id"]
+        if method in WORKER_METHODS:
+            with _cancel_lock:
+                _active_ids.add(request_id)
+            # 先于入队发送，started 通知不会早于 queued 到达
+            notify("queued", {"id": request_id})
+            requests.put((request_id, method, params))
+        else:
+            handle_inline(request_id, method, params)
+
+    # stdin 关闭或收到 shutdown
+    requests.put(None)
+
+
+def is_cancelled(request_id) -> bool:
+    with _cancel_lock:
+        return request_id in _cancelled_ids
 
 
 def main():
-    """主循环：读取 stdin 的 JSON 请求，输出 JSON 响应"""
+    """主循环：在工作线程中按顺序执行推理请求"""
+    redirect_stdout()
+
     print("🚀 FunASR Server started", file=sys.stderr)
     print(f"🐍 Python: {sys.executable}", file=sys.stderr)
     print(f"🐍 Version: {sys.version}", file=sys.stderr)
 
-    # 确保 stdout 立即刷新
-    sys.stdout.reconfigure(line_buffering=True)
+    requests: "queue.Queue" = queue.Queue()
+    threading.Thread(target=read_requests, args=(requests,), daemon=True).start()
 
     while True:
-        try:
-            # 读取一行 JSON 请求
-            line = sys.stdin.readline()
-            if not line:
-                break
+        item = requests.get()
+        if item is None:
+            break
 
-            line = line.strip()
-            if not line:
-                continue
-
-            # 解析请求
-            request = json.loads(line)
-            print(f"📨 Received request: {request.get('method')}", file=sys.stderr)
+        request_id, method, params = item
+        print(f"📨 Received request #{request_id}: {method}", file=sys.stderr)
+        notify("started", {"id": request_id})
 
-            # 处理请求
-            response = handle_request(request)
+        try:
+            if is_cancelled(request_id):
+                raise RpcError(REQUEST_CANCELLED, "Request cancelled")
 
-            # 输出响应（单行 JSON）
-            print(json.dumps(response, ensure_ascii=False), flush=True)
+            result = handle_request(request_id, method, params)
+            if is_cancelled(request_id):
+                print(f"🛑 Dropping result of cancelled request #{request_id}", file=sys.stderr)
+                raise RpcError(REQUEST_CANCELLED, "Request cancelled")
 
-            # 如果是 shutdown 命令，退出
-            if request.get("method") == "shutdown":
-                break
-
-        except json.JSONDecodeError as e:
-            error_response = {
-                "success": False,
-                "error": f"Invalid JSON: {str(e)}",
-            }
-            print(json.dumps(error_response, ensure_ascii=False), flush=True)
+            send_result(request_id, result)
+        except RpcError as e:
+            send_error(request_id, e.code, e.message)
         except Exception as e:
             import traceback
-            error_details = traceback.format_exc()
-            print(f"❌ Error processing request:\\n{error_details}", file=sys.stderr)
-            error_response = {
-                "success": False,
-                "error": f"Server error: {str(e)}",
-            }
-            print(json.dumps(error_response, ensure_ascii=False), flush=True)
+            print(f"❌ Error processing request:\n{traceback.format_exc()}", file=sys.stderr)
+            send_error(request_id, INTERNAL_ERROR, f"Server error: {str(e)}")
+        finally:
+            with _cancel_lock:
+                _active_ids.discard(request_id)
+                _cancelled_ids.discard(request_id)
+
+        # 如果是 shutdown 命令，退出
+        if method == "shutdown":
+            break
 
     print("👋 FunASR Server stopped", file=sys.stderr)
 
@@ -64,6 +64,11 @@ pub fn save_current_app() -> Result<(), String> {
     Ok(())
 }
 
+/// 获取录音开始前保存的应用 Bundle ID
+pub fn get_saved_app() -> Option<String> {
+    PREVIOUS_APP.lock().unwrap().clone()
+}
+
 /// 激活之前保存的应用（在插入文本前调用）
 pub fn activate_previous_app() -> Result<(), String> {
     let previous = PREVIOUS_APP.lock().unwrap();
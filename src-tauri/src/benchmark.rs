@@ -0,0 +1,209 @@
+/// 模型基准测试工具
+/// 采集硬件信息，并在加载/解码期间采样进程内存峰值
+
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Arc;
+use std::thread::JoinHandle;
+use std::time::Duration;
+use sysinfo::{Pid, System};
+
+/// 内存采样间隔
+const SAMPLE_INTERVAL: Duration = Duration::from_millis(50);
+
+/// 硬件信息
+#[derive(Debug, Clone, serde::Serialize, serde::Deserialize)]
+pub struct HardwareInfo {
+    pub cpu_brand: String,
+    pub physical_cores: usize,
+    pub logical_cores: usize,
+    pub total_memory_mb: u64,
+    pub os: String,
+    pub arch: String,
+}
+
+impl HardwareInfo {
+    /// 采集当前机器的硬件信息
+    pub fn collect() -> Self {
+        let mut sys = System::new();
+        sys.refresh_memory();
+        sys.refresh_cpu();
+
+        Self {
+            cpu_brand: sys
+                .cpus()
+                .first()
+                .map(|cpu| cpu.brand().trim().to_string())
+                .unwrap_or_default(),
+            physical_cores: num_cpus::get_physical(),
+            logical_cores: num_cpus::get(),
+            total_memory_mb: sys.total_memory() / 1024 / 1024,
+            os: System::long_os_version().unwrap_or_else(|| std::env::consts::OS.to_string()),
+            arch: std::env::consts::ARCH.to_string(),
+        }
+    }
+}
+
+/// 读取进程当前的常驻内存（字节）
+fn process_memory(sys: &mut System, pid: Pid) -> Option<u64> {
+    sys.refresh_process(pid);
+    sys.process(pid).map(|process| process.memory())
+}
+
+/// 后台线程定期采样指定进程的内存，记录峰值
+pub struct MemorySampler {
+    stop: Arc<AtomicBool>,
+    handle: JoinHandle<u64>,
+    baseline: u64,
+}
+
+impl MemorySampler {
+    /// 开始采样（以当前内存作为基线）
+    pub fn start(pid: u32) -> Self {
+        let pid = Pid::from_u32(pid);
+        let mut sys = System::new();
+        let baseline = process_memory(&mut sys, pid).unwrap_or(0);
+
+        let stop = Arc::new(AtomicBool::new(false));
+        let stop_flag = stop.clone();
+        let handle = std::thread::spawn(move || {
+            let mut peak = baseline;
+            loop {
+                if let Some(memory) = process_memory(&mut sys, pid) {
+                    peak = peak.max(memory);
+                }
+                if stop_flag.load(Ordering::SeqCst) {
+                    break;
+                }
+                std::thread::sleep(SAMPLE_INTERVAL);
+            }
+            peak
+        });
+
+        Self {
+            stop,
+            handle,
+            baseline,
+        }
+    }
+
+    /// 停止采样，返回相对基线的峰值增量（MB）
+    pub fn finish(self) -> Option<f64> {
+        self.stop.store(true, Ordering::SeqCst);
+        let peak = self.handle.join().ok()?;
+        Some(peak.saturating_sub(self.baseline) as f64 / 1024.0 / 1024.0)
+    }
+}
+
+/// 内置参考音频时长（一个 Whisper 解码窗口）
+pub const REFERENCE_CLIP_SECS: usize = 30;
+
+/// 内置参考音频（16kHz 单声道）
+///
+/// 确定性合成的类语音信号：音节时长 120–300ms、基频 100–220Hz 起伏，
+/// 谐波按元音共振峰加权，音节和句子之间有停顿。内容与设备无关，
+/// 不同机器、不同线程数的结果可以直接比较。
+pub fn reference_clip() -> Vec<f32> {
+    use std::f32::consts::PI;
+
+    const SAMPLE_RATE: f32 = 16000.0;
+    // 元音共振峰（F1, F2, F3）：a、i、u、e、o
+    const FORMANTS: [[f32; 3]; 5] = [
+        [800.0, 1200.0, 2500.0],
+        [300.0, 2300.0, 3000.0],
+        [350.0, 800.0, 2300.0],
+        [500.0, 1900.0, 2600.0],
+        [500.0, 900.0, 2400.0],
+    ];
+
+    let total = REFERENCE_CLIP_SECS * SAMPLE_RATE as usize;
+    let mut audio = Vec::with_capacity(total);
+    // 线性同余发生器，保证每次生成的内容相同
+    let mut seed: u32 = 0x2545_f491;
+    let mut next = move || {
+        seed = seed.wrapping_mul(1_664_525).wrapping_add(1_013_904_223);
+        (seed >> 8) as f32 / (1u32 << 24) as f32
+    };
+
+    let mut syllable = 0usize;
+    while audio.len() < total {
+        let len = ((0.12 + 0.18 * next()) * SAMPLE_RATE) as usize;
+        let pitch = 100.0 + 120.0 * next();
+        let formants = FORMANTS[(next() * FORMANTS.len() as f32) as usize % FORMANTS.len()];
+
+        let mut phase = 0.0f32;
+        for n in 0..len {
+            let t = n as f32 / len as f32;
+            // 音节内基频小幅下滑，幅度为升余弦包络
+            let f0 = pitch * (1.0 - 0.15 * t);
+            phase += 2.0 * PI * f0 / SAMPLE_RATE;
+            let envelope = 0.5 - 0.5 * (2.0 * PI * t).cos();
+
+            let mut sample = 0.0;
+            let mut harmonic = 1.0;
+            while harmonic * f0 < 4000.0 {
+                let freq = harmonic * f0;
+                let gain: f32 = formants
+                    .iter()
+                    .map(|&formant| 1.0 / (1.0 + ((freq - formant) / 120.0).powi(2)))
+                    .sum();
+                sample += gain / harmonic * (phase * harmonic).sin();
+                harmonic += 1.0;
+            }
+            audio.push(0.25 * envelope * sample);
+        }
+
+        // 音节间短停顿，每 8 个音节一次句间停顿
+        syllable += 1;
+        let pause = if syllable.is_multiple_of(8) { 0.5 } else { 0.04 + 0.04 * next() };
+        audio.extend(std::iter::repeat_n(0.0, (pause * SAMPLE_RATE) as usize));
+    }
+
+    audio.truncate(total);
+    let peak = audio.iter().fold(0.0f32, |peak, s| peak.max(s.abs()));
+    if peak > 0.0 {
+        audio.iter_mut().for_each(|s| *s *= 0.8 / peak);
+    }
+    audio
+}
+
+/// 实时率：解码耗时 / 音频时长（小于 1 表示快于实时）
+pub fn real_time_factor(decode_ms: u128, audio_secs: f64) -> f64 {
+    if audio_secs <= 0.0 {
+        return 0.0;
+    }
+    decode_ms as f64 / 1000.0 / audio_secs
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_real_time_factor() {
+        assert_eq!(real_time_factor(5_000, 10.0), 0.5);
+        assert_eq!(real_time_factor(1_000, 0.0), 0.0);
+    }
+
+    #[test]
+    fn test_reference_clip_is_deterministic() {
+        let clip = reference_clip();
+        assert_eq!(clip.len(), REFERENCE_CLIP_SECS * 16000);
+        assert_eq!(clip, reference_clip());
+        assert!(clip.iter().all(|s| s.abs() <= 0.8 + 1e-6));
+
+        // 有声段和停顿交替出现
+        let silent = clip.chunks(160).filter(|frame| frame.iter().all(|s| *s == 0.0)).count();
+        assert!(silent > 100 && silent < clip.len() / 160 / 2, "silent frames: {}", silent);
+    }
+
+    #[test]
+    fn test_memory_sampler_sees_allocation() {
+        let sampler = MemorySampler::start(std::process::id());
+        let buffer = vec![1u8; 64 * 1024 * 1024];
+        std::thread::sleep(SAMPLE_INTERVAL * 3);
+        let peak = sampler.finish();
+        drop(buffer);
+
+        assert!(peak.unwrap_or(0.0) > 32.0);
+    }
+}
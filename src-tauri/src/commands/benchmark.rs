@@ -0,0 +1,323 @@
+/// 基准测试命令模块
+/// 在本机上对已下载的模型测量加载耗时、实时率和内存峰值
+
+use std::sync::Arc;
+use std::time::Instant;
+use tauri::{AppHandle, Emitter, Manager, State};
+
+use crate::benchmark::{real_time_factor, reference_clip, HardwareInfo, MemorySampler};
+use crate::db::{BenchmarkRepository, BenchmarkResult, Database};
+use crate::whisper::catalog::WHISPER_MODELS;
+use crate::whisper::{WhisperEngine, WhisperTask, BEAM_SIZE};
+
+use super::funasr::FunASRState;
+
+/// 基准测试报告
+#[derive(serde::Serialize, Clone)]
+pub struct BenchmarkReport {
+    pub hardware: HardwareInfo,
+    pub audio_secs: f64,
+    pub results: Vec<BenchmarkResult>,
+    /// 未能完成测试的模型及原因
+    pub failures: Vec<BenchmarkFailure>,
+}
+
+#[derive(serde::Serialize, Clone)]
+pub struct BenchmarkFailure {
+    pub engine: String,
+    pub model_name: String,
+    pub error: String,
+}
+
+/// 基准测试进度事件
+#[derive(serde::Serialize, Clone)]
+struct BenchmarkProgress {
+    engine: String,
+    model_name: String,
+    index: usize,
+    total: usize,
+}
+
+/// 运行基准测试
+///
+/// 依次加载每个已下载的 Whisper 模型，以及可用时的 FunASR 服务器，对参考音频计时。
+/// 两种引擎都先转录一次预热，只对第二次计时，RTF 可以跨引擎比较。
+/// 参考音频和解码参数随每条结果保存。
+///
+/// # 参数
+/// * `clip_path` - 参考音频（WAV），为空时使用内置参考音频
+/// * `use_last_recording` - 使用最后一次录音作为参考音频
+/// * `language` - 解码语言，默认 "zh"
+#[tauri::command]
+pub async fn run_benchmark(
+    app: AppHandle,
+    clip_path: Option<String>,
+    use_last_recording: Option<bool>,
+    language: Option<String>,
+    include_funasr: Option<bool>,
+    funasr_state: State<'_, FunASRState>,
+) -> Result<BenchmarkReport, String> {
+    use tracing::info;
+
+    let (audio, clip) = load_reference_clip(clip_path.as_deref(), use_last_recording.unwrap_or(false))?;
+    let audio_secs = audio.len() as f64 / 16000.0;
+    let language = language.unwrap_or_else(|| "zh".to_string());
+
+    let hardware = HardwareInfo::collect();
+    let hardware_json = serde_json::to_string(&hardware).map_err(|e| e.to_string())?;
+    info!("📊 [Benchmark] Reference clip: {} ({:.1}s), hardware: {:?}", clip, audio_secs, hardware);
+
+    let models_dir = super::model::get_models_dir(&app)?;
+    let whisper_models: Vec<(String, std::path::PathBuf)> = WHISPER_MODELS
+        .iter()
+        .map(|spec| (spec.name.to_string(), models_dir.join(spec.file_name())))
+        .filter(|(_, path)| path.exists())
+        .collect();
+
+    let run_funasr = include_funasr.unwrap_or(true) && crate::python::detect_python(&app).is_ok();
+    let total = whisper_models.len() + usize::from(run_funasr);
+
+    let audio = Arc::new(audio);
+    let mut results = Vec::new();
+    let mut failures = Vec::new();
+
+    for (index, (model_name, model_path)) in whisper_models.into_iter().enumerate() {
+        let _ = app.emit(
+            "benchmark-progress",
+            BenchmarkProgress {
+                engine: "whisper".to_string(),
+                model_name: model_name.clone(),
+                index,
+                total,
+            },
+        );
+
+        let audio = audio.clone();
+        let decode_language = language.clone();
+        let outcome = tokio::task::spawn_blocking(move || {
+            let sampler = MemorySampler::start(std::process::id());
+
+            let load_start = Instant::now();
+            let engine = WhisperEngine::new(&model_path);
+            let load_ms = load_start.elapsed().as_millis();
+
+            let decoded = engine.and_then(|engine| {
+                // 与 FunASR 相同：第一次转录包含首次推理的初始化开销，不计入解码耗时
+                engine.transcribe(&audio, Some(&decode_language), WhisperTask::Transcribe)?;
+
+                let decode_start = Instant::now();
+                engine.transcribe(&audio, Some(&decode_language), WhisperTask::Transcribe)?;
+                Ok((decode_start.elapsed().as_millis(), engine.n_threads()))
+            });
+
+            let peak_memory_mb = sampler.finish();
+            decoded.map(|(decode_ms, n_threads)| (load_ms, decode_ms, n_threads, peak_memory_mb))
+        })
+        .await
+        .map_err(|e| format!("Benchmark task failed: {}", e))?;
+
+        match outcome {
+            Ok((load_ms, decode_ms, n_threads, peak_memory_mb)) => {
+                let decode_params = serde_json::json!({
+                    "clip": clip,
+                    "language": language,
+                    "task": "transcribe",
+                    "sampling": "beam_search",
+                    "beam_size": BEAM_SIZE,
+                    "n_threads": n_threads,
+                });
+                info!(
+                    "📊 [Benchmark] whisper/{}: load {}ms, decode {}ms, RTF {:.3}",
+                    model_name,
+                    load_ms,
+                    decode_ms,
+                    real_time_factor(decode_ms, audio_secs)
+                );
+                results.push(BenchmarkResult {
+                    id: None,
+                    engine: "whisper".to_string(),
+                    model_name,
+                    audio_secs,
+                    load_ms: load_ms as i64,
+                    decode_ms: decode_ms as i64,
+                    rtf: real_time_factor(decode_ms, audio_secs),
+                    peak_memory_mb,
+                    n_threads: Some(n_threads as i64),
+                    decode_params: Some(decode_params.to_string()),
+                    hardware: hardware_json.clone(),
+                    created_at: chrono::Utc::now(),
+                });
+            }
+            Err(e) => failures.push(BenchmarkFailure {
+                engine: "whisper".to_string(),
+                model_name,
+                error: e.to_string(),
+            }),
+        }
+    }
+
+    if run_funasr {
+        let model_name = funasr_state
+            .current_model()
+            .await
+            .unwrap_or_else(|| "paraformer-zh".to_string());
+        let _ = app.emit(
+            "benchmark-progress",
+            BenchmarkProgress {
+                engine: "funasr".to_string(),
+                model_name: model_name.clone(),
+                index: total - 1,
+                total,
+            },
+        );
+
+        match benchmark_funasr(&app, &funasr_state, &model_name, &audio, &language).await {
+            Ok((load_ms, decode_ms, peak_memory_mb)) => results.push(BenchmarkResult {
+                id: None,
+                engine: "funasr".to_string(),
+                model_name,
+                audio_secs,
+                load_ms: load_ms as i64,
+                decode_ms: decode_ms as i64,
+                rtf: real_time_factor(decode_ms, audio_secs),
+                peak_memory_mb,
+                n_threads: None,
+                decode_params: Some(
+                    serde_json::json!({
+                        "clip": clip,
+                        "language": language,
+                        "task": "transcribe",
+                    })
+                    .to_string(),
+                ),
+                hardware: hardware_json.clone(),
+                created_at: chrono::Utc::now(),
+            }),
+            Err(error) => failures.push(BenchmarkFailure {
+                engine: "funasr".to_string(),
+                model_name,
+                error,
+            }),
+        }
+    }
+
+    // 保存结果，便于对比不同线程数或解码参数下的回归
+    if let Some(db) = app.try_state::<Arc<Database>>() {
+        let repo = BenchmarkRepository::new(db.connection());
+        for result in results.iter_mut() {
+            match repo.create(result) {
+                Ok(id) => result.id = Some(id),
+                Err(e) => tracing::warn!("⚠️  Failed to save benchmark result: {}", e),
+            }
+        }
+    }
+
+    Ok(BenchmarkReport {
+        hardware,
+        audio_secs,
+        results,
+        failures,
+    })
+}
+
+/// 获取历史基准测试结果
+#[tauri::command]
+pub fn get_benchmark_results(
+    db: State<Arc<Database>>,
+    limit: Option<usize>,
+) -> Result<Vec<BenchmarkResult>, String> {
+    let repo = BenchmarkRepository::new(db.connection());
+    repo.get_recent(limit.unwrap_or(50)).map_err(|e| e.to_string())
+}
+
+/// 清空基准测试结果
+#[tauri::command]
+pub fn clear_benchmark_results(db: State<Arc<Database>>) -> Result<(), String> {
+    let repo = BenchmarkRepository::new(db.connection());
+    repo.delete_all().map_err(|e| e.to_string())
+}
+
+// 辅助函数
+
+/// 加载参考音频（16kHz 单声道 f32），同时返回音频来源（随结果记录）
+fn load_reference_clip(clip_path: Option<&str>, use_last_recording: bool) -> Result<(Vec<f32>, String), String> {
+    use crate::whisper::{convert_i16_to_f32, resample_48khz_to_16khz};
+
+    let (audio, clip) = match clip_path {
+        Some(path) => (super::transcription::read_audio_file(path)?, path.to_string()),
+        None if use_last_recording => {
+            let last_recording = super::audio::LAST_RECORDING.lock();
+            let recording = last_recording
+                .as_ref()
+                .ok_or("No recording yet. Please record first or use the built-in reference clip.".to_string())?;
+            (resample_48khz_to_16khz(&convert_i16_to_f32(recording)), "last_recording".to_string())
+        }
+        None => (reference_clip(), "builtin".to_string()),
+    };
+
+    if audio.len() < 16000 {
+        return Err("Reference clip is too short (minimum 1 second)".to_string());
+    }
+
+    Ok((audio, clip))
+}
+
+/// 测试 FunASR：先释放并重新加载模型（由服务器计时），再转录计时
+///
+/// 返回 (加载耗时, 解码耗时, 服务器进程内存峰值增量)
+async fn benchmark_funasr(
+    app: &AppHandle,
+    state: &FunASRState,
+    model_name: &str,
+    audio: &[f32],
+    language: &str,
+) -> Result<(u128, u128, Option<f64>), String> {
+    state.get_or_create_server(app).await?;
+
+    let clip_path = std::env::temp_dir().join(format!("funasr_benchmark_{}.wav", chrono::Utc::now().timestamp()));
+    write_wav_16k(audio, &clip_path)?;
+    let clip = clip_path.to_str().ok_or("Invalid temp path")?.to_string();
+
+    let result: Result<(u128, u128, Option<f64>), String> = async {
+        // 先启动进程以便采样；模型已常驻时先释放，保证测到的是真实加载耗时
+        state.start_server().await?;
+        let sampler = state.server_pid().await.map(MemorySampler::start);
+
+        let model = state.reload_model(model_name).await?;
+
+        // 第一次转录包含首次推理的初始化开销，不计入解码耗时
+        state.transcribe_file(&clip, model_name, Some(language)).await?;
+
+        let decode_start = Instant::now();
+        state.transcribe_file(&clip, model_name, Some(language)).await?;
+        let decode_ms = decode_start.elapsed().as_millis();
+
+        let peak_memory_mb = sampler.and_then(|sampler| sampler.finish());
+        Ok((model.load_ms as u128, decode_ms, peak_memory_mb))
+    }
+    .await;
+
+    let _ = std::fs::remove_file(&clip_path);
+    result
+}
+
+/// 保存 16kHz 单声道 WAV
+fn write_wav_16k(audio: &[f32], path: &std::path::Path) -> Result<(), String> {
+    let spec = hound::WavSpec {
+        channels: 1,
+        sample_rate: 16000,
+        bits_per_sample: 16,
+        sample_format: hound::SampleFormat::Int,
+    };
+
+    let mut writer = hound::WavWriter::create(path, spec)
+        .map_err(|e| format!("Failed to create WAV file: {}", e))?;
+    for &sample in audio {
+        writer
+            .write_sample((sample.clamp(-1.0, 1.0) * 32767.0) as i16)
+            .map_err(|e| format!("Failed to write WAV sample: {}", e))?;
+    }
+    writer
+        .finalize()
+        .map_err(|e| format!("Failed to finalize WAV file: {}", e))
+}
@@ -1,4 +1,7 @@
-use crate::db::{Database, SettingsRepository, Transcription, TranscriptionRepository};
+use crate::db::{
+    Database, SettingsRepository, Transcription, TranscriptionRepository, VocabularyEntry,
+    VocabularyRepository,
+};
 use std::sync::Arc;
 use tauri::State;
 
@@ -59,6 +62,19 @@ pub fn search_transcriptions(
     repo.search(&query).map_err(|e| e.to_string())
 }
 
+/// 按 SenseVoice 情感 / 音频事件标签筛选历史记录
+#[tauri::command]
+pub fn filter_transcriptions(
+    db: State<Arc<Database>>,
+    emotion: Option<String>,
+    event: Option<String>,
+    limit: usize,
+) -> Result<Vec<Transcription>, String> {
+    let repo = TranscriptionRepository::new(db.connection());
+    repo.filter_by_tags(emotion.as_deref(), event.as_deref(), limit)
+        .map_err(|e| e.to_string())
+}
+
 #[tauri::command]
 pub fn delete_transcription(db: State<Arc<Database>>, id: i64) -> Result<(), String> {
     let repo = TranscriptionRepository::new(db.connection());
@@ -70,3 +86,43 @@ pub fn delete_all_transcriptions(db: State<Arc<Database>>) -> Result<(), String>
     let repo = TranscriptionRepository::new(db.connection());
     repo.delete_all().map_err(|e| e.to_string())
 }
+
+#[tauri::command]
+pub fn get_vocabulary(db: State<Arc<Database>>) -> Result<Vec<VocabularyEntry>, String> {
+    let repo = VocabularyRepository::new(db.connection());
+    repo.get_all().map_err(|e| e.to_string())
+}
+
+#[tauri::command]
+pub fn add_vocabulary_term(db: State<Arc<Database>>, term: String) -> Result<bool, String> {
+    let term = term.trim();
+    if term.is_empty() {
+        return Err("词条不能为空".to_string());
+    }
+
+    let repo = VocabularyRepository::new(db.connection());
+    repo.add(term).map_err(|e| e.to_string())
+}
+
+#[tauri::command]
+pub fn delete_vocabulary_term(db: State<Arc<Database>>, id: i64) -> Result<(), String> {
+    let repo = VocabularyRepository::new(db.connection());
+    repo.delete(id).map_err(|e| e.to_string())
+}
+
+#[tauri::command]
+pub fn clear_vocabulary(db: State<Arc<Database>>) -> Result<(), String> {
+    let repo = VocabularyRepository::new(db.connection());
+    repo.delete_all().map_err(|e| e.to_string())
+}
+
+/// 从文本文件导入词汇表（每行一个词条，# 开头为注释），返回新增数量
+#[tauri::command]
+pub fn import_vocabulary(db: State<Arc<Database>>, path: String) -> Result<usize, String> {
+    let content = std::fs::read_to_string(&path)
+        .map_err(|e| format!("Failed to read vocabulary file: {}", e))?;
+    let terms = crate::vocabulary::parse_vocabulary_text(&content);
+
+    let repo = VocabularyRepository::new(db.connection());
+    repo.add_many(&terms).map_err(|e| e.to_string())
+}
@@ -0,0 +1,254 @@
+/// 统一引擎命令模块
+/// 按 `AppConfig::model_type` 把初始化、转录、能力查询和卸载分派给 Whisper、FunASR、远程或 ONNX 引擎
+
+use std::sync::Arc;
+use tauri::{AppHandle, Manager, State};
+
+use super::funasr::FunASRState;
+use super::onnx::OnnxState;
+use super::remote::RemoteState;
+use super::transcription::{
+    is_invalid_transcription, resolve_output_mode, TranscriptionResultDTO, TranscriptionSegmentDTO, WhisperState,
+};
+use crate::config::{AppConfig, ConfigManager, ModelType};
+use crate::db::Database;
+use crate::jobs::TranscriptionJob;
+use crate::speech::{fallback_chain, fallback_error, EngineCapabilities, SpeechEngine, SpeechOutput, SpeechRequest};
+use crate::whisper::{convert_i16_to_f32, resample_to_16khz};
+
+/// 按模型类型取出对应的引擎状态并求值 `$body`
+macro_rules! with_engine {
+    ($app:expr, $model_type:expr, |$engine:ident| $body:expr) => {
+        match $model_type {
+            ModelType::Whisper => {
+                let $engine = $app.state::<WhisperState>().inner();
+                $body
+            }
+            ModelType::FunASR => {
+                let $engine = $app.state::<FunASRState>().inner();
+                $body
+            }
+            ModelType::Remote => {
+                let $engine = $app.state::<RemoteState>().inner();
+                $body
+            }
+            ModelType::Onnx => {
+                let $engine = $app.state::<OnnxState>().inner();
+                $body
+            }
+        }
+    };
+}
+
+/// 初始化模型对应的引擎，并把模型和引擎类型写入配置
+#[tauri::command]
+pub async fn initialize_engine(
+    app: AppHandle,
+    model_name: String,
+    db: State<'_, Arc<Database>>,
+) -> Result<(), String> {
+    use tracing::info;
+
+    let model_type = ModelType::for_model(&model_name);
+    info!("🎯 [Engine] Initializing {:?} engine with model: {}", model_type, model_name);
+
+    with_engine!(app, model_type.clone(), |engine| engine.init(&app, &model_name).await)?;
+
+    ConfigManager::new(db.connection()).set_model(model_type, &model_name)
+}
+
+/// 用当前引擎转录，失败时按配置的备用模型依次重试
+///
+/// `audio_data` 为前端采集的 16kHz PCM16；为空时转录最后一次录音。
+/// `output_mode` 为 "translate" 时输出英文译文，引擎不支持翻译时改用支持的备用模型，都不支持时返回错误；
+/// 未指定时按快捷键 / 应用配置 / 默认设置决定。
+/// 结果中的 `model_type` / `model_name` 为实际完成转录的引擎和模型
+#[tauri::command]
+pub async fn transcribe(
+    app: AppHandle,
+    audio_data: Option<Vec<i16>>,
+    language: Option<String>,
+    output_mode: Option<String>,
+) -> Result<TranscriptionResultDTO, String> {
+    use tracing::{info, warn};
+
+    // 注册任务，供 cancel_transcription 中止解码（回退重试沿用同一任务）；
+    // 先于任何可能失败的步骤，出错提前返回时任务析构让快捷键状态机复位
+    let job = TranscriptionJob::start(&app);
+
+    let config = load_config(&app);
+    let chain = fallback_chain(&config.model_name, &config.fallback_models);
+    info!(
+        "🎯 [Engine] transcribe, chain: {:?}, language: {:?}, output_mode: {:?}",
+        chain, language, output_mode
+    );
+
+    let audio = match audio_data {
+        Some(samples) => convert_i16_to_f32(&samples),
+        None => last_recording_16k()?,
+    };
+    let translate = resolve_output_mode(&app, output_mode.as_deref()).is_translate();
+    let request = SpeechRequest {
+        audio: &audio,
+        language: language.as_deref(),
+        translate,
+        timestamps: false,
+        source_file: None,
+    };
+
+    let mut errors = Vec::new();
+    let mut result = None;
+    // 备用模型与当前模型同属一个引擎时会替换引擎中已加载的当前模型
+    let mut displaced_primary = false;
+    for (index, model_name) in chain.iter().enumerate() {
+        // 当前模型沿用配置中的引擎类型，备用模型按名称判断
+        let model_type = if index == 0 {
+            config.model_type.clone()
+        } else {
+            ModelType::for_model(model_name)
+        };
+
+        // 未下载的备用模型（如默认的 small）直接跳过，不计入错误
+        if index > 0 && !super::model::is_model_downloaded(&app, model_name) {
+            info!("⏭️  [Engine] Fallback model '{}' is not downloaded, skipping", model_name);
+            continue;
+        }
+        displaced_primary |= index > 0 && model_type == config.model_type;
+
+        let attempt_result = with_engine!(app, model_type.clone(), |engine| {
+            attempt(engine, &app, model_name, request, &job).await
+        });
+
+        match attempt_result {
+            Ok(output) => {
+                if index > 0 {
+                    info!("↪️  [Engine] Fallback model '{}' produced the transcription", model_name);
+                }
+                result = Some(validate(output, &audio, model_type, model_name));
+                break;
+            }
+            Err(e) if crate::jobs::is_cancelled_error(&e) => {
+                result = Some(Err(e));
+                break;
+            }
+            Err(e) => {
+                warn!("⚠️  [Engine] {:?} model '{}' failed: {}", model_type, model_name, e);
+                errors.push((model_name.clone(), e));
+            }
+        }
+    }
+
+    if displaced_primary {
+        restore_primary(&app, config.model_type.clone(), config.model_name.clone());
+    }
+
+    job.finish(result.unwrap_or_else(|| Err(fallback_error(&errors))))
+}
+
+/// 回退后在后台重新加载当前模型，让下一次转录仍使用配置的模型
+fn restore_primary(app: &AppHandle, model_type: ModelType, model_name: String) {
+    use tracing::{info, warn};
+
+    let app = app.clone();
+    tauri::async_runtime::spawn(async move {
+        match with_engine!(app, model_type, |engine| engine.init(&app, &model_name).await) {
+            Ok(()) => info!("♻️  [Engine] Restored primary model '{}' after fallback", model_name),
+            Err(e) => warn!("⚠️  [Engine] Failed to restore primary model '{}': {}", model_name, e),
+        }
+    });
+}
+
+/// 当前引擎的能力
+#[tauri::command]
+pub fn get_engine_capabilities(app: AppHandle) -> Result<EngineCapabilities, String> {
+    Ok(with_engine!(app, current_model_type(&app), |engine| engine.capabilities()))
+}
+
+/// 卸载当前引擎的模型，返回是否实际释放了内存
+#[tauri::command]
+pub async fn unload_engine(app: AppHandle) -> Result<bool, String> {
+    with_engine!(app, current_model_type(&app), |engine| engine.unload().await)
+}
+
+/// 用当前引擎转录一次，不走回退链（带时间戳的转录、导入的音频文件）
+pub(super) async fn transcribe_with_current(
+    app: &AppHandle,
+    request: SpeechRequest<'_>,
+    job: &TranscriptionJob,
+) -> Result<SpeechOutput, String> {
+    let config = load_config(app);
+    with_engine!(app, config.model_type, |engine| {
+        attempt(engine, app, &config.model_name, request, job).await
+    })
+}
+
+/// 用指定模型转录一次（模型未加载时先加载）
+async fn attempt<E: SpeechEngine>(
+    engine: &E,
+    app: &AppHandle,
+    model_name: &str,
+    request: SpeechRequest<'_>,
+    job: &TranscriptionJob,
+) -> Result<SpeechOutput, String> {
+    // 不支持翻译的引擎直接报错，由回退链交给支持翻译的备用模型（不静默输出原文）
+    if request.translate && !engine.capabilities().translation {
+        return Err(format!("{:?} 引擎不支持翻译为英文，请改用 Whisper 或远程引擎", engine.model_type()));
+    }
+
+    if engine.current_model().await.as_deref() != Some(model_name) {
+        engine.init(app, model_name).await?;
+    }
+
+    engine.transcribe(app, request, job).await
+}
+
+/// 检查结果是否有效并记录实际使用的引擎
+pub(super) fn validate(
+    output: SpeechOutput,
+    audio: &[f32],
+    model_type: ModelType,
+    model_name: &str,
+) -> Result<TranscriptionResultDTO, String> {
+    use tracing::info;
+
+    // 🔑 验证转录结果是否有效（Whisper 的幻觉段落已在引擎中过滤）
+    if is_invalid_transcription(&output.text, audio) {
+        info!("🎯 [Engine] Invalid transcription detected (silence or noise): '{}'", output.text);
+        return Err("转录结果无效：可能是静音或噪音".to_string());
+    }
+
+    Ok(TranscriptionResultDTO {
+        text: output.text,
+        source_language: output.source_language,
+        translated: output.translated,
+        model_type: Some(model_type),
+        model_name: Some(model_name.to_string()),
+        segments: output.segments.into_iter().map(TranscriptionSegmentDTO::from).collect(),
+        emotion: output.emotion,
+        events: output.events,
+    })
+}
+
+/// 最后一次录音，按录音设备的实际采样率重采样到 16kHz
+fn last_recording_16k() -> Result<Vec<f32>, String> {
+    use super::audio::{AUDIO_RECORDER, LAST_RECORDING};
+
+    let samples = LAST_RECORDING
+        .lock()
+        .as_ref()
+        .map(|samples| convert_i16_to_f32(samples))
+        .ok_or("No recording available. Please record audio first.".to_string())?;
+    let sample_rate = AUDIO_RECORDER.lock().actual_sample_rate();
+
+    Ok(resample_to_16khz(&samples, sample_rate))
+}
+
+fn current_model_type(app: &AppHandle) -> ModelType {
+    load_config(app).model_type
+}
+
+pub(super) fn load_config(app: &AppHandle) -> AppConfig {
+    app.try_state::<Arc<Database>>()
+        .and_then(|db| ConfigManager::new(db.connection()).load().ok())
+        .unwrap_or_default()
+}
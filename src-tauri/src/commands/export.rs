@@ -0,0 +1,122 @@
+/// 导出命令模块
+/// 把历史记录或文件转录结果导出为字幕和文档
+
+use std::path::PathBuf;
+use std::sync::Arc;
+use tauri::{AppHandle, Manager, State};
+
+use crate::commands::transcription::TranscriptionSegmentDTO;
+use crate::db::{Database, Transcription, TranscriptionRepository};
+use crate::export::{export, CaptionOptions, ExportDocument, ExportFormat};
+use crate::whisper::TranscriptionSegment;
+
+/// 导出单条历史记录，返回写入的文件路径
+///
+/// 没有保存段落的记录（普通听写）按一个覆盖整段录音的段落导出。
+/// `output_path` 为空时写入下载目录。
+#[tauri::command]
+pub fn export_transcription(
+    app: AppHandle,
+    db: State<'_, Arc<Database>>,
+    id: i64,
+    format: ExportFormat,
+    options: Option<CaptionOptions>,
+    output_path: Option<String>,
+) -> Result<String, String> {
+    let repo = TranscriptionRepository::new(db.connection());
+    let transcription = repo
+        .get_by_id(id)
+        .map_err(|e| e.to_string())?
+        .ok_or_else(|| format!("Transcription not found: {}", id))?;
+
+    let document = history_document(transcription);
+    let file_name = format!("lingcode-{}.{}", id, format.extension());
+    write_export(&app, &document, format, options, output_path, &file_name)
+}
+
+/// 导出一次文件转录的段落，返回写入的文件路径
+#[tauri::command]
+pub fn export_segments(
+    app: AppHandle,
+    segments: Vec<TranscriptionSegmentDTO>,
+    format: ExportFormat,
+    title: Option<String>,
+    language: Option<String>,
+    options: Option<CaptionOptions>,
+    output_path: Option<String>,
+) -> Result<String, String> {
+    let file_name = format!(
+        "{}.{}",
+        sanitize_file_name(title.as_deref().unwrap_or("lingcode-export")),
+        format.extension()
+    );
+    let document = ExportDocument {
+        title,
+        language,
+        created_at: Some(chrono::Utc::now()),
+        segments: segments.into_iter().map(TranscriptionSegment::from).collect(),
+    };
+
+    write_export(&app, &document, format, options, output_path, &file_name)
+}
+
+fn history_document(transcription: Transcription) -> ExportDocument {
+    let segments = transcription.segments.unwrap_or_else(|| {
+        let end_ms = transcription.audio_duration.map_or(0, |secs| (secs.max(0.0) * 1000.0) as u64);
+        vec![TranscriptionSegment {
+            text: transcription.text.clone(),
+            start_ms: 0,
+            end_ms,
+            words: Vec::new(),
+            speaker: None,
+        }]
+    });
+
+    ExportDocument {
+        title: transcription.id.map(|id| format!("转录 #{}", id)),
+        language: transcription.source_language.or(Some(transcription.language)),
+        created_at: Some(transcription.created_at),
+        segments,
+    }
+}
+
+fn write_export(
+    app: &AppHandle,
+    document: &ExportDocument,
+    format: ExportFormat,
+    options: Option<CaptionOptions>,
+    output_path: Option<String>,
+    default_file_name: &str,
+) -> Result<String, String> {
+    use tracing::info;
+
+    let content = export(document, format, &options.unwrap_or_default());
+
+    let path = match output_path {
+        Some(path) => PathBuf::from(path),
+        None => app
+            .path()
+            .download_dir()
+            .map_err(|e| format!("Failed to resolve download directory: {}", e))?
+            .join(default_file_name),
+    };
+
+    std::fs::write(&path, content).map_err(|e| format!("Failed to write export file: {}", e))?;
+    info!("📤 [Export] Exported {} segments as {:?} to {:?}", document.segments.len(), format, path);
+
+    Ok(path.to_string_lossy().to_string())
+}
+
+/// 去掉文件名中不允许的字符
+fn sanitize_file_name(name: &str) -> String {
+    let sanitized: String = name
+        .chars()
+        .map(|c| if matches!(c, '/' | '\\' | ':' | '*' | '?' | '"' | '<' | '>' | '|') { '_' } else { c })
+        .collect();
+    let trimmed = sanitized.trim();
+    if trimmed.is_empty() {
+        "lingcode-export".to_string()
+    } else {
+        trimmed.to_string()
+    }
+}
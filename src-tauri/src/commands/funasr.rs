@@ -1,11 +1,22 @@
 /// FunASR 命令模块
 /// 提供 FunASR 相关的 Tauri commands
 
+use std::sync::atomic::{AtomicBool, Ordering};
 use std::sync::Arc;
+use std::time::{Duration, Instant};
 use tauri::{AppHandle, State};
-use tokio::sync::Mutex;
-
-use crate::funasr::FunASRServer;
+use tokio::sync::{mpsc, oneshot, Mutex};
+
+use super::transcription::TranscriptionResultDTO;
+use crate::config::ModelType;
+use crate::funasr::scripts::{resolve_script, Script, ScriptSource};
+use crate::funasr::{
+    supports_streaming, AudioInput, ChunkProgress, FunASRServer, RequestControl, ResidentModel, ServerWav, Transcript,
+    FILE_INPUT_MIN_MS, STREAMING_MODEL,
+};
+use crate::jobs::TranscriptionJob;
+use crate::speech::{EngineCapabilities, SpeechEngine, SpeechOutput, SpeechRequest};
+use crate::whisper::TranscriptionSegment;
 
 // Re-export prewarm_funasr_cmd from funasr module
 pub use crate::funasr::prewarm_funasr_cmd;
@@ -15,6 +26,17 @@ pub struct FunASRState {
     current_model: Arc<Mutex<Option<String>>>,
     server: Arc<Mutex<Option<FunASRServer>>>,
     python_env_checked: Arc<Mutex<bool>>,  // 标记是否已检查过Python环境
+    last_used: Arc<parking_lot::Mutex<Instant>>,  // 最近一次使用服务器的时间
+    streaming: Arc<parking_lot::Mutex<Option<StreamingSession>>>,  // 录音中的流式识别会话
+}
+
+/// 流式识别会话：前端推送的音频块经通道交给后台任务
+struct StreamingSession {
+    chunks: mpsc::UnboundedSender<Vec<i16>>,
+    /// 录音取消后置位，后台任务丢弃尚未发送的音频并丢弃会话
+    stopped: Arc<AtomicBool>,
+    /// 音频通道关闭后，后台任务识别剩余音频并通过它返回修正后的全文和会话的全部音频
+    text: oneshot::Receiver<Result<(String, Vec<f32>), String>>,
 }
 
 impl FunASRState {
@@ -23,11 +45,72 @@ impl FunASRState {
             current_model: Arc::new(Mutex::new(None)),
             server: Arc::new(Mutex::new(None)),
             python_env_checked: Arc::new(Mutex::new(false)),
+            last_used: Arc::new(parking_lot::Mutex::new(Instant::now())),
+            streaming: Arc::new(parking_lot::Mutex::new(None)),
         }
     }
 
+    /// 刷新最近使用时间
+    fn touch(&self) {
+        *self.last_used.lock() = Instant::now();
+    }
+
+    /// 空闲超过 `idle` 时关闭服务器进程，返回是否实际关闭
+    ///
+    /// 服务器实例保留，下次转录时 `FunASRServer::transcribe` 会自动重启进程；
+    /// 有请求未完成时直接跳过
+    pub async fn shutdown_if_idle(&self, idle: Duration) -> bool {
+        let Some(server) = self.server.lock().await.clone() else {
+            return false;
+        };
+
+        if self.last_used.lock().elapsed() < idle || server.in_flight() > 0 || !server.is_alive().await {
+            return false;
+        }
+
+        if let Err(e) = server.stop().await {
+            tracing::warn!("⚠️  [FunASR] Failed to stop idle server: {}", e);
+            return false;
+        }
+        true
+    }
+
+    /// 服务器实例已创建时启动进程并加载当前模型（用于快捷键预热）
+    pub async fn prewarm(&self) -> Result<(), String> {
+        self.touch();
+        let Some(server) = self.server.lock().await.clone() else {
+            return Ok(());
+        };
+        server.start().await?;
+
+        if let Some(model_name) = self.current_model().await {
+            server.preload_model(&model_name).await?;
+        }
+        Ok(())
+    }
+
+    /// 服务器已在运行时后台预加载模型
+    ///
+    /// 切换模型后的第一次听写不再等待加载，之前的模型按常驻上限保留
+    async fn preload_if_running(&self, model_name: &str) {
+        let Some(server) = self.server.lock().await.clone() else {
+            return;
+        };
+        if !server.is_alive().await {
+            return;
+        }
+
+        let model_name = model_name.to_string();
+        tauri::async_runtime::spawn(async move {
+            if let Err(e) = server.preload_model(&model_name).await {
+                tracing::warn!("⚠️  [FunASR] Failed to preload model {}: {}", model_name, e);
+            }
+        });
+    }
+
     /// 获取或创建服务器实例
     pub async fn get_or_create_server(&self, app: &AppHandle) -> Result<(), String> {
+        self.touch();
         let mut server_guard = self.server.lock().await;
 
         if server_guard.is_none() {
@@ -57,153 +140,222 @@ impl FunASRState {
 
         Ok(())
     }
-}
-
-/// 初始化 FunASR 引擎
-#[tauri::command]
-pub async fn initialize_funasr(
-    app: AppHandle,
-    model_name: String,
-    state: State<'_, FunASRState>,
-) -> Result<(), String> {
-    use tracing::info;
-
-    info!("🎯 [FunASR] Initializing FunASR engine with model: {}", model_name);
-
-    // 保存当前模型
-    *state.current_model.lock().await = Some(model_name.clone());
-
-    // 预先确保服务器创建（内部会在首次创建时检查Python环境）
-    state.get_or_create_server(&app).await?;
-
-    info!("✅ [FunASR] Engine initialized with model: {}", model_name);
-    Ok(())
-}
 
-/// 使用 FunASR 转录最后一次录音
-#[tauri::command]
-pub async fn transcribe_last_recording_funasr(
-    app: AppHandle,
-    language: Option<String>,
-    state: State<'_, FunASRState>,
-) -> Result<String, String> {
-    use super::audio::LAST_RECORDING;
-    use tracing::info;
+    /// 当前选择的模型
+    pub async fn current_model(&self) -> Option<String> {
+        self.current_model.lock().await.clone()
+    }
 
-    info!("🎯 [FunASR] transcribe_last_recording_funasr called, language: {:?}", language);
+    /// 启动服务器进程（已运行时直接返回）
+    pub async fn start_server(&self) -> Result<(), String> {
+        let server = self.server.lock().await.clone().ok_or("FunASR server not initialized")?;
+        server.start().await
+    }
 
-    // 获取最后一次录音
-    let audio_data = {
-        let last_recording = LAST_RECORDING.lock();
-        last_recording
-            .as_ref()
-            .ok_or("No recording available. Please record audio first.".to_string())?
-            .clone()
-    };
+    /// 应用退出时关闭服务器进程
+    pub async fn shutdown(&self) {
+        use tracing::info;
 
-    // 获取实际采样率
-    let actual_sample_rate = {
-        let recorder = super::audio::AUDIO_RECORDER.lock();
-        recorder.actual_sample_rate()
-    };
+        if let Some(session) = self.streaming.lock().take() {
+            session.stopped.store(true, Ordering::SeqCst);
+        }
 
-    info!("🎯 [FunASR] Audio data available: {} samples at {}Hz", audio_data.len(), actual_sample_rate);
+        let server = self.server.lock().await.clone();
+        if let Some(server) = server {
+            server.shutdown().await;
+            info!("👋 [FunASR] Server shut down on app exit");
+        }
+    }
 
-    // 检查音频长度（至少 0.5 秒）
-    let min_samples = (actual_sample_rate as f32 * 0.5) as usize;
-    if audio_data.len() < min_samples {
-        return Err(format!("录音太短：{:.2}秒。请录制更长的音频（至少0.5秒）。",
-            audio_data.len() as f32 / actual_sample_rate as f32));
+    /// 服务器进程 ID
+    pub async fn server_pid(&self) -> Option<u32> {
+        match self.server.lock().await.as_ref() {
+            Some(server) => server.pid().await,
+            None => None,
+        }
     }
 
-    // 获取当前模型
-    let model_name = {
-        let current_model = state.current_model.lock().await;
-        current_model
-            .as_ref()
-            .ok_or("FunASR engine not initialized. Please download a model first.".to_string())?
-            .clone()
-    };
+    /// 释放后重新加载模型，返回服务器测得的加载耗时和内存（服务器需已创建）
+    ///
+    /// 基准测试用：模型已常驻时直接转录测不到加载耗时
+    pub async fn reload_model(&self, model_name: &str) -> Result<ResidentModel, String> {
+        self.touch();
+        let server = self.server.lock().await.clone().ok_or("FunASR server not initialized")?;
+        server.unload_model(model_name).await?;
+        server.preload_model(model_name).await
+    }
 
-    // 确保服务器已启动（内部会在首次创建时检查Python环境，之后不再重复检查）
-    state.get_or_create_server(&app).await?;
+    /// 提取说话人向量（按需创建并启动服务器）
+    pub async fn embed_speakers(
+        &self,
+        app: &AppHandle,
+        audio: AudioInput<'_>,
+        ranges: &[(u64, u64)],
+        control: &RequestControl,
+    ) -> Result<Vec<Option<Vec<f32>>>, String> {
+        self.get_or_create_server(app).await?;
+        let server = self.server.lock().await.clone().ok_or("FunASR server not initialized")?;
+        server.embed_speakers(audio, ranges, control).await
+    }
 
-    // 保存音频到临时文件
-    let temp_dir = std::env::temp_dir();
-    let temp_audio_path = temp_dir.join(format!("funasr_temp_{}.wav", chrono::Utc::now().timestamp()));
+    /// 转录 16kHz WAV 文件（服务器需已创建）
+    pub async fn transcribe_file(
+        &self,
+        audio_path: &str,
+        model_name: &str,
+        language: Option<&str>,
+    ) -> Result<String, String> {
+        self.touch();
+        let server = self.server.lock().await.clone().ok_or("FunASR server not initialized")?;
+        server
+            .transcribe(AudioInput::File(audio_path), model_name, language, None, false, &RequestControl::default())
+            .await
+            .map(|transcript| transcript.text)
+    }
 
-    // 转换采样率到 16kHz（FunASR 需要）
-    let resampled_audio = if actual_sample_rate != 16000 {
-        let resampled = resample_audio(&audio_data, actual_sample_rate, 16000);
-        info!("🎯 [FunASR] Resampled audio: {} samples ({}Hz) -> {} samples (16kHz)",
-            audio_data.len(), actual_sample_rate, resampled.len());
-        resampled
-    } else {
-        info!("🎯 [FunASR] Audio already at 16kHz, no resampling needed");
-        audio_data.clone()
-    };
+    /// 用当前模型转录 16kHz 音频，`samples` 为音频的采样点数
+    async fn transcribe_audio(
+        &self,
+        app: &AppHandle,
+        input: AudioInput<'_>,
+        samples: usize,
+        language: Option<&str>,
+        timestamps: bool,
+        job: &TranscriptionJob,
+    ) -> Result<Transcript, String> {
+        use tracing::info;
+
+        // 检查音频长度（至少 0.5 秒）
+        let duration_secs = samples as f32 / 16000.0;
+        if duration_secs < 0.5 {
+            return Err(format!("录音太短：{:.2}秒。请录制更长的音频（至少0.5秒）。", duration_secs));
+        }
 
-    // 将 i16 PCM 数据保存为 WAV 文件（16kHz）
-    save_audio_to_wav_16k(&resampled_audio, &temp_audio_path)?;
+        // 获取当前模型
+        let model_name = self
+            .current_model()
+            .await
+            .ok_or("FunASR engine not initialized. Please download a model first.".to_string())?;
 
-    info!("🎯 [FunASR] Saved audio to temporary file: {:?}", temp_audio_path);
+        // 确保服务器已启动（内部会在首次创建时检查Python环境，之后不再重复检查）
+        self.get_or_create_server(app).await?;
 
-    // 使用服务器进行转录
-    let audio_path_str = temp_audio_path
-        .to_str()
-        .ok_or("Invalid temp path")?
-        .to_string();
+        // 用户词汇表作为 FunASR 热词
+        let hotwords = crate::vocabulary::funasr_hotwords(&crate::vocabulary::load_terms(app));
 
-    let text = {
-        let server_guard = state.server.lock().await;
-        let server = server_guard
-            .as_ref()
-            .ok_or("FunASR server not initialized")?;
+        // 不在持有实例锁时等待请求，其他调用方可以同时发出请求
+        let server = self.server.lock().await.clone().ok_or("FunASR server not initialized")?;
 
         info!("🎯 [FunASR] Calling server.transcribe...");
-        let result = server.transcribe(
-            &audio_path_str,
+        server.transcribe(
+            input,
             &model_name,
-            language.as_deref(),
-        ).await;
+            language,
+            hotwords.as_deref(),
+            timestamps,
+            &request_control(job),
+        ).await
+    }
+}
 
-        info!("🎯 [FunASR] Server.transcribe returned: {:?}", result);
-        result?
-    };
+impl SpeechEngine for FunASRState {
+    fn model_type(&self) -> ModelType {
+        ModelType::FunASR
+    }
 
-    info!("🎯 [FunASR] Text received, length: {}, content: '{}'", text.len(), text);
+    async fn current_model(&self) -> Option<String> {
+        FunASRState::current_model(self).await
+    }
 
-    // 删除临时文件
-    let _ = std::fs::remove_file(&temp_audio_path);
+    async fn init(&self, app: &AppHandle, model_name: &str) -> Result<(), String> {
+        use tracing::info;
 
-    info!("✅ [FunASR] Transcription complete: '{}'", text);
+        info!("🎯 [FunASR] Initializing FunASR engine with model: {}", model_name);
 
-    // 🚀 首次成功转录后，标记不再是首次启动，并触发后台预热（如果尚未预热）
-    // 这样下次应用启动时就能享受到预热的好处
-    {
-        use crate::config::ConfigManager;
-        use crate::db::Database;
-        use std::sync::Arc;
-        use tauri::Manager;
+        // 保存当前模型
+        *self.current_model.lock().await = Some(model_name.to_string());
 
-        if let Some(db) = app.try_state::<Arc<Database>>() {
-            let config_manager = ConfigManager::new(db.connection());
+        // 预先确保服务器创建（内部会在首次创建时检查Python环境）
+        self.get_or_create_server(app).await?;
 
-            // 如果仍是首次启动，标记为已使用
-            if let Ok(true) = config_manager.is_first_launch() {
-                info!("🎉 First successful transcription, marking as no longer first launch");
-                let _ = config_manager.mark_first_launch_complete();
+        // 重新初始化时允许崩溃循环后的服务器再次启动
+        if let Some(server) = self.server.lock().await.clone() {
+            server.reset_failure();
+        }
+        self.preload_if_running(model_name).await;
 
-                // 如果启用了预热，不做额外处理（服务器已经在第一次转录时启动了）
-                // 下次应用启动时会自动预热
-                if let Ok(true) = config_manager.is_prewarming_enabled() {
-                    info!("💡 Prewarming enabled, server will be prewarmed on next app launch");
-                }
-            }
+        info!("✅ [FunASR] Engine initialized with model: {}", model_name);
+        Ok(())
+    }
+
+    async fn transcribe(
+        &self,
+        app: &AppHandle,
+        request: SpeechRequest<'_>,
+        job: &TranscriptionJob,
+    ) -> Result<SpeechOutput, String> {
+        use tracing::info;
+
+        // 听写的录音较短，以 PCM 随请求发送；导入的文件和较长的录音以 WAV 文件交给服务器，
+        // 不把整段音频编码进请求
+        let audio = request.audio;
+        let wav = if request.source_file.is_some() || audio.len() as u64 / 16 > FILE_INPUT_MIN_MS {
+            Some(ServerWav::prepare(audio, request.source_file)?)
+        } else {
+            None
+        };
+        let input = wav.as_ref().map_or(AudioInput::Pcm(audio), ServerWav::input);
+
+        let transcript = self
+            .transcribe_audio(app, input, audio.len(), request.language, request.timestamps, job)
+            .await?;
+
+        // SenseVoice 的语言、情感和事件标签拆成结构化字段
+        let parsed = crate::sensevoice::parse(&transcript.text);
+
+        // 段落文本同样去掉标签，与 Whisper 的段落格式一致
+        let segments: Vec<TranscriptionSegment> = transcript
+            .segments
+            .into_iter()
+            .map(|segment| TranscriptionSegment {
+                text: crate::sensevoice::parse(&segment.text).text,
+                ..segment
+            })
+            .filter(|segment| !segment.text.is_empty())
+            .collect();
+
+        info!("✅ [FunASR] Transcription complete: '{}' ({} segments)", parsed.text, segments.len());
+        if parsed.emotion.is_some() || !parsed.events.is_empty() {
+            info!("🎭 [FunASR] Emotion: {:?}, events: {:?}", parsed.emotion, parsed.events);
         }
+        mark_first_transcription(app);
+
+        Ok(SpeechOutput {
+            text: parsed.text,
+            source_language: parsed.language,
+            segments,
+            emotion: parsed.emotion,
+            events: parsed.events,
+            ..SpeechOutput::default()
+        })
     }
 
-    Ok(text)
+    fn capabilities(&self) -> EngineCapabilities {
+        EngineCapabilities {
+            timestamps: true,
+            vocabulary: true,
+            ..EngineCapabilities::default()
+        }
+    }
+
+    async fn unload(&self) -> Result<bool, String> {
+        // 服务器实例保留，下次转录时自动重启进程
+        let server = self.server.lock().await.clone();
+        match server {
+            Some(server) if server.is_alive().await => server.stop().await.map(|_| true),
+            _ => Ok(false),
+        }
+    }
 }
 
 /// 下载 FunASR 模型
@@ -239,151 +391,293 @@ pub async fn get_current_funasr_model(state: State<'_, FunASRState>) -> Result<O
     Ok(current_model.clone())
 }
 
-/// 使用 FunASR 转录音频数据（接收前端传来的音频）
+/// FunASR 服务器中已加载的模型（最近使用的在末尾）
 #[tauri::command]
-pub async fn transcribe_audio_funasr(
+pub async fn get_funasr_resident_models(state: State<'_, FunASRState>) -> Result<Vec<ResidentModel>, String> {
+    Ok(state
+        .server
+        .lock()
+        .await
+        .as_ref()
+        .map(FunASRServer::resident_models)
+        .unwrap_or_default())
+}
+
+/// 预加载 FunASR 模型（服务器未运行时先启动）
+#[tauri::command]
+pub async fn preload_funasr_model(
     app: AppHandle,
-    audio_data: Vec<i16>,
-    language: Option<String>,
+    model_name: String,
     state: State<'_, FunASRState>,
-) -> Result<String, String> {
-    use tracing::info;
-
-    info!("🎯 [FunASR] transcribe_audio_funasr called with {} samples, language: {:?}",
-        audio_data.len(), language);
-
-    // 前端传来的音频已经是 16kHz 单声道 PCM16 格式
-    let actual_sample_rate = 16000;
+) -> Result<ResidentModel, String> {
+    if crate::funasr::find_funasr_model(&model_name).is_none() {
+        return Err(format!("Unknown FunASR model: {}", model_name));
+    }
 
-    info!("🎯 [FunASR] Audio data: {} samples at {}Hz", audio_data.len(), actual_sample_rate);
+    state.get_or_create_server(&app).await?;
+    let server = state.server.lock().await.clone().ok_or("FunASR server not initialized")?;
+    server.preload_model(&model_name).await
+}
 
-    // 检查音频长度（至少 0.5 秒）
-    let min_samples = (actual_sample_rate as f32 * 0.5) as usize;
-    if audio_data.len() < min_samples {
-        return Err(format!("录音太短：{:.2}秒。请录制更长的音频（至少0.5秒）。",
-            audio_data.len() as f32 / actual_sample_rate as f32));
+/// 释放 FunASR 模型，返回是否实际释放
+#[tauri::command]
+pub async fn unload_funasr_model(model_name: String, state: State<'_, FunASRState>) -> Result<bool, String> {
+    let server = state.server.lock().await.clone();
+    match server {
+        Some(server) => server.unload_model(&model_name).await,
+        None => Ok(false),
     }
+}
 
-    // 获取当前模型
-    let model_name = {
-        let current_model = state.current_model.lock().await;
-        current_model
-            .as_ref()
-            .ok_or("FunASR engine not initialized. Please download a model first.".to_string())?
-            .clone()
-    };
+/// Python 脚本的定位和校验结果
+#[derive(Debug, Clone, serde::Serialize)]
+pub struct FunASRScriptStatus {
+    pub name: &'static str,
+    pub path: Option<String>,
+    /// `override` / `resource` / `source`
+    pub source: Option<ScriptSource>,
+    /// 内容与应用内嵌的版本一致
+    pub verified: bool,
+    pub error: Option<String>,
+}
 
-    // 确保服务器已启动
-    state.get_or_create_server(&app).await?;
+/// 检查 FunASR 脚本能否找到、是否完整（设置界面显示）
+#[tauri::command]
+pub fn check_funasr_scripts(app: AppHandle) -> Vec<FunASRScriptStatus> {
+    Script::ALL
+        .iter()
+        .map(|&script| match resolve_script(&app, script) {
+            Ok(resolved) => FunASRScriptStatus {
+                name: script.file_name(),
+                path: Some(resolved.path.to_string_lossy().to_string()),
+                source: Some(resolved.source),
+                verified: resolved.verified,
+                error: None,
+            },
+            Err(e) => FunASRScriptStatus {
+                name: script.file_name(),
+                path: None,
+                source: None,
+                verified: false,
+                error: Some(e),
+            },
+        })
+        .collect()
+}
 
-    // 保存音频到临时文件
-    let temp_dir = std::env::temp_dir();
-    let temp_audio_path = temp_dir.join(format!("funasr_temp_{}.wav", chrono::Utc::now().timestamp()));
+/// 开始录音时启动流式识别，返回是否已启动
+///
+/// 仅在当前引擎为 FunASR、模型支持流式且识别语言为中文时启动；
+/// 实时结果通过 `streaming-transcript` 事件发送，录音结束后由 `finish_streaming_transcription` 给出最终结果
+#[tauri::command]
+pub async fn start_streaming_transcription(app: AppHandle, state: State<'_, FunASRState>) -> Result<bool, String> {
+    use tracing::info;
 
-    // 音频已经是 16kHz，直接保存
-    save_audio_to_wav_16k(&audio_data, &temp_audio_path)?;
+    let config = super::engine::load_config(&app);
+    if config.model_type != ModelType::FunASR || !supports_streaming(&config.model_name) || config.language != "zh" {
+        return Ok(false);
+    }
 
-    info!("🎯 [FunASR] Saved audio to temporary file: {:?}", temp_audio_path);
+    let (sender, receiver) = mpsc::unbounded_channel();
+    let (text_sender, text_receiver) = oneshot::channel();
+    let stopped = Arc::new(AtomicBool::new(false));
+    let previous = state.streaming.lock().replace(StreamingSession {
+        chunks: sender,
+        stopped: stopped.clone(),
+        text: text_receiver,
+    });
+    if let Some(previous) = previous {
+        previous.stopped.store(true, Ordering::SeqCst);
+    }
 
-    // 使用服务器进行转录
-    let audio_path_str = temp_audio_path
-        .to_str()
-        .ok_or("Invalid temp path")?
-        .to_string();
+    info!("🎙️  [FunASR] Streaming transcription started (model: {})", config.model_name);
+    tauri::async_runtime::spawn(async move {
+        let text = run_streaming(&app, &config.model_name, receiver, &stopped).await;
+        let _ = text_sender.send(text);
+    });
+    Ok(true)
+}
 
-    let text = {
-        let server_guard = state.server.lock().await;
-        let server = server_guard
-            .as_ref()
-            .ok_or("FunASR server not initialized")?;
+/// 推送录音中的一段 16kHz PCM16 音频（没有进行中的会话时忽略）
+///
+/// 同步命令按调用顺序执行，保证音频块不乱序
+#[tauri::command]
+pub fn push_streaming_audio(audio_data: Vec<i16>, state: State<'_, FunASRState>) {
+    if let Some(session) = state.streaming.lock().as_ref() {
+        let _ = session.chunks.send(audio_data);
+    }
+}
 
-        info!("🎯 [FunASR] Calling server.transcribe...");
-        let result = server.transcribe(
-            &audio_path_str,
-            &model_name,
-            language.as_deref(),
-        ).await;
+/// 录音结束时结束流式识别，以句末 2-pass 修正后的全文作为转录结果
+///
+/// 结果与 `transcribe` 一样检查是否有效；没有进行中的会话、需要翻译为英文、识别失败或结果无效时返回 `None`，
+/// 由前端改用 `transcribe` 整段转录（按备用模型依次重试）
+#[tauri::command]
+pub async fn finish_streaming_transcription(
+    app: AppHandle,
+    state: State<'_, FunASRState>,
+) -> Result<Option<TranscriptionResultDTO>, String> {
+    use super::transcription::resolve_output_mode;
+    use tracing::{info, warn};
 
-        info!("🎯 [FunASR] Server.transcribe returned: {:?}", result);
-        result?
+    let Some(session) = state.streaming.lock().take() else {
+        return Ok(None);
     };
 
-    info!("🎯 [FunASR] Text received, length: {}, content: '{}'", text.len(), text);
-
-    // 删除临时文件
-    let _ = std::fs::remove_file(&temp_audio_path);
-
-    info!("✅ [FunASR] Transcription complete: '{}'", text);
+    // 流式识别只输出中文原文
+    if resolve_output_mode(&app, None).is_translate() {
+        session.stopped.store(true, Ordering::SeqCst);
+        return Ok(None);
+    }
 
-    // 🚀 首次成功转录后，标记不再是首次启动
-    {
-        use crate::config::ConfigManager;
-        use crate::db::Database;
-        use std::sync::Arc;
-        use tauri::Manager;
+    // 关闭音频通道：后台任务发送完已推送的音频后结束会话
+    drop(session.chunks);
+    let (text, audio) = match session.text.await {
+        Ok(Ok(result)) => result,
+        Ok(Err(e)) => {
+            warn!("⚠️  [FunASR] Streaming result unavailable, falling back to offline transcription: {}", e);
+            return Ok(None);
+        }
+        Err(_) => return Ok(None),
+    };
 
-        if let Some(db) = app.try_state::<Arc<Database>>() {
-            let config_manager = ConfigManager::new(db.connection());
+    // 与整段转录的 FunASR 结果一样拆出标签，再按相同规则检查是否为静音或噪音
+    let parsed = crate::sensevoice::parse(&text);
+    let output = SpeechOutput {
+        text: parsed.text,
+        source_language: parsed.language,
+        emotion: parsed.emotion,
+        events: parsed.events,
+        ..SpeechOutput::default()
+    };
+    let result = match super::engine::validate(output, &audio, ModelType::FunASR, STREAMING_MODEL) {
+        Ok(result) => result,
+        Err(e) => {
+            info!("🎯 [FunASR] {}, falling back to offline transcription", e);
+            return Ok(None);
+        }
+    };
 
-            if let Ok(true) = config_manager.is_first_launch() {
-                info!("🎉 First successful transcription, marking as no longer first launch");
-                let _ = config_manager.mark_first_launch_complete();
+    // 以任务的形式完成，接管快捷键预留的任务并让状态机复位
+    let job = TranscriptionJob::start(&app);
+    info!("✅ [FunASR] Streaming transcription finished: {}", result.text);
+    mark_first_transcription(&app);
+    job.finish(Ok(Some(result)))
+}
 
-                if let Ok(true) = config_manager.is_prewarming_enabled() {
-                    info!("💡 Prewarming enabled, server will be prewarmed on next app launch");
-                }
-            }
-        }
+/// 录音取消时停止流式识别并丢弃会话
+#[tauri::command]
+pub fn stop_streaming_transcription(state: State<'_, FunASRState>) {
+    if let Some(session) = state.streaming.lock().take() {
+        session.stopped.store(true, Ordering::SeqCst);
     }
-
-    Ok(text)
 }
 
 // 辅助函数
 
-/// 简单的线性插值重采样（48kHz -> 16kHz）
-fn resample_audio(audio_data: &[i16], from_rate: u32, to_rate: u32) -> Vec<i16> {
-    if from_rate == to_rate {
-        return audio_data.to_vec();
+/// 流式识别后台任务：依次把音频块发给服务器，并把实时结果发给前端
+///
+/// 音频通道关闭后识别剩余音频，返回修正后的全文和收到的全部音频；录音取消或出错时丢弃会话并返回错误
+async fn run_streaming(
+    app: &AppHandle,
+    model_name: &str,
+    mut chunks: mpsc::UnboundedReceiver<Vec<i16>>,
+    stopped: &AtomicBool,
+) -> Result<(String, Vec<f32>), String> {
+    use crate::whisper::convert_i16_to_f32;
+    use tauri::{Emitter, Manager};
+    use tracing::{info, warn};
+
+    let state = app.state::<FunASRState>();
+    if let Err(e) = state.get_or_create_server(app).await {
+        warn!("⚠️  [FunASR] Streaming unavailable: {}", e);
+        return Err(e);
     }
 
-    let ratio = from_rate as f32 / to_rate as f32;
-    let new_len = (audio_data.len() as f32 / ratio) as usize;
-    let mut resampled = Vec::with_capacity(new_len);
+    let hotwords = crate::vocabulary::funasr_hotwords(&crate::vocabulary::load_terms(app));
+    let server = state.server.lock().await.clone().ok_or("FunASR server not initialized")?;
+    let session_id = server.stream_start(model_name, hotwords.as_deref()).await.map_err(|e| {
+        warn!("⚠️  [FunASR] Failed to start streaming session: {}", e);
+        e
+    })?;
+
+    let mut audio = Vec::new();
+    let mut failure = None;
+    while let Some(mut samples) = chunks.recv().await {
+        if stopped.load(Ordering::SeqCst) {
+            break;
+        }
+        // 服务器跟不上时把积压的音频合并为一次请求
+        while let Ok(more) = chunks.try_recv() {
+            samples.extend(more);
+        }
 
-    for i in 0..new_len {
-        let src_idx = (i as f32 * ratio) as usize;
-        if src_idx < audio_data.len() {
-            resampled.push(audio_data[src_idx]);
+        state.touch();
+        let chunk = convert_i16_to_f32(&samples);
+        let result = server.stream_audio(&session_id, &chunk).await;
+        audio.extend(chunk);
+
+        match result {
+            Ok(text) if !stopped.load(Ordering::SeqCst) => {
+                let _ = app.emit("streaming-transcript", &text);
+            }
+            Ok(_) => break,
+            Err(e) => {
+                warn!("⚠️  [FunASR] Streaming transcription failed: {}", e);
+                failure = Some(e);
+                break;
+            }
         }
     }
 
-    resampled
+    let result = if stopped.load(Ordering::SeqCst) || failure.is_some() {
+        let _ = server.stream_cancel(&session_id).await;
+        Err(failure.unwrap_or_else(|| "Streaming session cancelled".to_string()))
+    } else {
+        state.touch();
+        server.stream_finish(&session_id).await.map(|text| (text, audio))
+    };
+    info!("🏁 [FunASR] Streaming session {} closed", session_id);
+    result
+}
+
+/// 把任务的取消标志和进度上报接到 FunASR 请求上
+fn request_control(job: &TranscriptionJob) -> RequestControl {
+    let reporter = job.reporter();
+    RequestControl {
+        cancel: Some(job.cancel_flag()),
+        on_progress: Some(Arc::new(move |progress: ChunkProgress| {
+            if progress.total > 0 {
+                reporter.percent((progress.done.min(progress.total) * 100 / progress.total) as u8);
+            }
+            let text = crate::sensevoice::parse(&progress.text).text;
+            if !text.is_empty() {
+                reporter.segment(&text, None, None);
+            }
+        })),
+    }
 }
 
-/// 将 i16 PCM 数据保存为 WAV 文件（16kHz）
-fn save_audio_to_wav_16k(audio_data: &[i16], path: &std::path::Path) -> Result<(), String> {
-    use hound::{WavSpec, WavWriter};
+/// 首次成功转录后标记不再是首次启动
+///
+/// 服务器已经在这次转录时启动，启用预热时下次应用启动会自动预热
+fn mark_first_transcription(app: &AppHandle) {
+    use crate::config::ConfigManager;
+    use crate::db::Database;
+    use tauri::Manager;
+    use tracing::info;
 
-    let spec = WavSpec {
-        channels: 1,
-        sample_rate: 16000,  // FunASR 需要 16kHz
-        bits_per_sample: 16,
-        sample_format: hound::SampleFormat::Int,
+    let Some(db) = app.try_state::<Arc<Database>>() else {
+        return;
     };
+    let config_manager = ConfigManager::new(db.connection());
 
-    let mut writer = WavWriter::create(path, spec)
-        .map_err(|e| format!("Failed to create WAV file: {}", e))?;
+    if let Ok(true) = config_manager.is_first_launch() {
+        info!("🎉 First successful transcription, marking as no longer first launch");
+        let _ = config_manager.mark_first_launch_complete();
 
-    for &sample in audio_data {
-        writer
-            .write_sample(sample)
-            .map_err(|e| format!("Failed to write sample: {}", e))?;
+        if let Ok(true) = config_manager.is_prewarming_enabled() {
+            info!("💡 Prewarming enabled, server will be prewarmed on next app launch");
+        }
     }
-
-    writer
-        .finalize()
-        .map_err(|e| format!("Failed to finalize WAV file: {}", e))?;
-
-    Ok(())
 }
@@ -1,9 +1,14 @@
 pub mod accessibility;
 pub mod audio;
+pub mod benchmark;
 pub mod db;
 pub mod debug;
+pub mod engine;
+pub mod export;
 pub mod funasr;
 pub mod model;
+pub mod onnx;
+pub mod remote;
 pub mod system;
 pub mod transcription;
 pub mod window;
@@ -15,10 +20,15 @@ pub use accessibility::{
     insert_text_at_cursor_cmd,
 };
 pub use audio::*;
+pub use benchmark::*;
 pub use db::*;
 pub use debug::*;
+pub use engine::*;
+pub use export::*;
 pub use funasr::*;
 pub use model::*;
+pub use onnx::*;
+pub use remote::*;
 pub use system::*;
 pub use transcription::*;
 pub use window::*;
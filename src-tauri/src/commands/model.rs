@@ -1,12 +1,27 @@
+use once_cell::sync::Lazy;
+use parking_lot::Mutex;
+use std::collections::HashMap;
 use std::path::PathBuf;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Arc;
 use tauri::{AppHandle, Emitter, Manager, State};
 
+use crate::funasr::FUNASR_MODEL_SPECS;
+use crate::onnx_asr::{find_onnx_model, is_model_present, OnnxModelKind, OnnxModelSpec, ONNX_MODELS};
+use crate::whisper::catalog::{find_whisper_model, WhisperModelSpec, WHISPER_MODELS};
+
+/// 进行中的 Whisper 模型下载（模型名 -> 取消标志）
+static ACTIVE_DOWNLOADS: Lazy<Mutex<HashMap<String, Arc<AtomicBool>>>> =
+    Lazy::new(|| Mutex::new(HashMap::new()));
+
 /// 模型引擎类型
 #[derive(serde::Serialize, serde::Deserialize, Clone, Debug, PartialEq)]
 #[serde(rename_all = "lowercase")]
 pub enum ModelEngine {
     Whisper,
     FunASR,
+    Remote,
+    Onnx,
 }
 
 /// 模型信息
@@ -23,9 +38,21 @@ pub struct ModelInfo {
     pub download_url: String,
     #[serde(skip_serializing_if = "Option::is_none")]
     pub description: Option<String>,
+    /// 推理时大致需要的内存（MB）
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub ram_mb: Option<u64>,
+    /// 文件 SHA-256
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub sha256: Option<String>,
+    /// 是否为英文专用模型
+    #[serde(default)]
+    pub english_only: bool,
+    /// 已下载文件实际占用的磁盘空间（字节）
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub disk_bytes: Option<u64>,
 }
 
-/// 获取所有可用的模型（Whisper + FunASR）
+/// 获取所有可用的模型（Whisper + FunASR + ONNX + 远程引擎）
 #[tauri::command]
 pub fn get_available_models(app: AppHandle) -> Result<Vec<ModelInfo>, String> {
     let models_dir = get_models_dir(&app)?;
@@ -33,96 +60,90 @@ pub fn get_available_models(app: AppHandle) -> Result<Vec<ModelInfo>, String> {
     let mut models = vec![];
 
     // Whisper 模型
-    models.extend(vec![
-        ModelInfo {
-            name: "base".to_string(),
-            engine: ModelEngine::Whisper,
-            size: "74MB".to_string(),
-            size_bytes: 74 * 1024 * 1024,
-            speed: "快速".to_string(),
-            accuracy: "一般精度".to_string(),
-            is_recommended: false,
-            is_downloaded: check_model_downloaded(&models_dir, "base"),
-            download_url: "https://hf-mirror.com/ggerganov/whisper.cpp/resolve/main/ggml-base.bin".to_string(),
-            description: Some("Whisper 基础模型，支持多语言".to_string()),
-        },
-        ModelInfo {
-            name: "small".to_string(),
-            engine: ModelEngine::Whisper,
-            size: "244MB".to_string(),
-            size_bytes: 244 * 1024 * 1024,
-            speed: "较快".to_string(),
-            accuracy: "较高精度".to_string(),
-            is_recommended: false,
-            is_downloaded: check_model_downloaded(&models_dir, "small"),
-            download_url: "https://hf-mirror.com/ggerganov/whisper.cpp/resolve/main/ggml-small.bin".to_string(),
-            description: Some("Whisper 小型模型，平衡速度和精度".to_string()),
-        },
-        ModelInfo {
-            name: "medium".to_string(),
-            engine: ModelEngine::Whisper,
-            size: "769MB".to_string(),
-            size_bytes: 769 * 1024 * 1024,
-            speed: "较慢".to_string(),
-            accuracy: "高精度".to_string(),
-            is_recommended: false,
-            is_downloaded: check_model_downloaded(&models_dir, "medium"),
-            download_url: "https://hf-mirror.com/ggerganov/whisper.cpp/resolve/main/ggml-medium.bin".to_string(),
-            description: Some("Whisper 中型模型，高精度".to_string()),
-        },
-        ModelInfo {
-            name: "large".to_string(),
-            engine: ModelEngine::Whisper,
-            size: "1.5GB".to_string(),
-            size_bytes: 1536 * 1024 * 1024,
-            speed: "慢".to_string(),
-            accuracy: "最高精度".to_string(),
-            is_recommended: false,
-            is_downloaded: check_model_downloaded(&models_dir, "large"),
-            download_url: "https://hf-mirror.com/ggerganov/whisper.cpp/resolve/main/ggml-large.bin".to_string(),
-            description: Some("Whisper 大型模型，最高精度".to_string()),
-        },
-    ]);
-
-    // FunASR 模型
-    models.extend(vec![
+    models.extend(WHISPER_MODELS.iter().map(|spec| ModelInfo {
+        name: spec.name.to_string(),
+        engine: ModelEngine::Whisper,
+        size: spec.size_label(),
+        size_bytes: spec.size_bytes,
+        speed: spec.speed.to_string(),
+        accuracy: spec.accuracy.to_string(),
+        is_recommended: spec.is_recommended,
+        is_downloaded: check_model_downloaded(&models_dir, spec.name),
+        download_url: spec.download_url(),
+        description: Some(spec.description.to_string()),
+        ram_mb: Some(spec.ram_mb),
+        sha256: spec.sha256.map(|s| s.to_string()),
+        english_only: spec.english_only,
+        disk_bytes: std::fs::metadata(models_dir.join(spec.file_name()))
+            .ok()
+            .map(|meta| meta.len()),
+    }));
+
+    // FunASR 模型（直接检查 ModelScope 缓存，不启动 Python）
+    let modelscope_root = modelscope_cache_root(&app);
+    models.extend(FUNASR_MODEL_SPECS.iter().map(|spec| {
+        let presence = modelscope_root
+            .as_deref()
+            .map(|root| crate::funasr::catalog::check_model(root, spec))
+            .unwrap_or_default();
         ModelInfo {
-            name: "paraformer-zh".to_string(),
+            name: spec.name.to_string(),
             engine: ModelEngine::FunASR,
-            size: "~220MB".to_string(),
-            size_bytes: 220 * 1024 * 1024,
-            speed: "快速".to_string(),
-            accuracy: "高精度（中文）".to_string(),
-            is_recommended: true,
-            is_downloaded: check_funasr_model_downloaded(&app, "paraformer-zh"),
-            download_url: "modelscope://damo/speech_paraformer-large-vad-punc_asr_nat-zh-cn-16k-common-vocab8404-pytorch".to_string(),
-            description: Some("阿里 FunASR 中文识别模型，专为中文优化".to_string()),
-        },
-        ModelInfo {
-            name: "paraformer-large".to_string(),
-            engine: ModelEngine::FunASR,
-            size: "~380MB".to_string(),
-            size_bytes: 380 * 1024 * 1024,
-            speed: "较快".to_string(),
-            accuracy: "极高精度（中文）".to_string(),
-            is_recommended: false,
-            is_downloaded: check_funasr_model_downloaded(&app, "paraformer-large"),
-            download_url: "modelscope://iic/speech_paraformer-large_asr_nat-zh-cn-16k-common-vocab8404-pytorch".to_string(),
-            description: Some("FunASR 大型中文模型，更高精度".to_string()),
-        },
-        ModelInfo {
-            name: "sensevoice-small".to_string(),
-            engine: ModelEngine::FunASR,
-            size: "~160MB".to_string(),
-            size_bytes: 160 * 1024 * 1024,
-            speed: "快速".to_string(),
-            accuracy: "高精度（多语言+情感）".to_string(),
-            is_recommended: false,
-            is_downloaded: check_funasr_model_downloaded(&app, "sensevoice-small"),
-            download_url: "modelscope://iic/SenseVoiceSmall".to_string(),
-            description: Some("支持多语言和情感识别".to_string()),
-        },
-    ]);
+            size: format!("~{}MB", spec.size_mb),
+            size_bytes: spec.size_mb * 1024 * 1024,
+            speed: spec.speed.to_string(),
+            accuracy: spec.accuracy.to_string(),
+            is_recommended: spec.is_recommended,
+            is_downloaded: presence.downloaded,
+            download_url: spec.download_url(),
+            description: Some(spec.description.to_string()),
+            ram_mb: None,
+            sha256: None,
+            english_only: false,
+            disk_bytes: (presence.disk_bytes > 0).then_some(presence.disk_bytes),
+        }
+    }));
+
+    // ONNX 模型（无需 Python，放置导出的模型文件后即可使用）
+    let onnx_dir = super::onnx::onnx_models_dir(&app)?;
+    models.extend(ONNX_MODELS.iter().map(|spec| ModelInfo {
+        name: spec.name.to_string(),
+        engine: ModelEngine::Onnx,
+        size: format!("~{}MB", spec.size_mb),
+        size_bytes: spec.size_mb * 1024 * 1024,
+        speed: "快速".to_string(),
+        accuracy: spec.accuracy.to_string(),
+        is_recommended: false,
+        is_downloaded: is_model_present(&onnx_dir, spec),
+        download_url: format!("modelscope://{}", spec.repo.id),
+        description: Some(spec.description.to_string()),
+        ram_mb: None,
+        sha256: None,
+        english_only: false,
+        disk_bytes: None,
+    }));
+
+    // 远程引擎（配置了服务器地址即视为可用）
+    let remote_config = app
+        .try_state::<Arc<crate::db::Database>>()
+        .and_then(|db| crate::config::ConfigManager::new(db.connection()).get_remote_config().ok())
+        .unwrap_or_default();
+    models.push(ModelInfo {
+        name: crate::config::REMOTE_MODEL.to_string(),
+        engine: ModelEngine::Remote,
+        size: "-".to_string(),
+        size_bytes: 0,
+        speed: "取决于服务器".to_string(),
+        accuracy: format!("服务器模型：{}", remote_config.model),
+        is_recommended: false,
+        is_downloaded: !remote_config.endpoint.trim().is_empty(),
+        download_url: remote_config.endpoint.clone(),
+        description: Some("OpenAI 兼容的转录服务（whisper.cpp server、faster-whisper 等）".to_string()),
+        ram_mb: None,
+        sha256: None,
+        english_only: false,
+        disk_bytes: None,
+    });
 
     Ok(models)
 }
@@ -164,95 +185,193 @@ pub async fn download_model(app: AppHandle, model_name: String) -> Result<(), St
             std::fs::create_dir_all(&models_dir)
                 .map_err(|e| format!("Failed to create models directory: {}", e))?;
 
-            let model_path = models_dir.join(format!("ggml-{}.bin", model_name));
-
-            // 如果模型已存在,先删除
-            if model_path.exists() {
-                std::fs::remove_file(&model_path)
-                    .map_err(|e| format!("Failed to remove existing model: {}", e))?;
-            }
-
-            // 获取下载 URL（使用中国镜像站）
-            let download_url = match model_name.as_str() {
-                "base" => "https://hf-mirror.com/ggerganov/whisper.cpp/resolve/main/ggml-base.bin",
-                "small" => "https://hf-mirror.com/ggerganov/whisper.cpp/resolve/main/ggml-small.bin",
-                "medium" => "https://hf-mirror.com/ggerganov/whisper.cpp/resolve/main/ggml-medium.bin",
-                "large" => "https://hf-mirror.com/ggerganov/whisper.cpp/resolve/main/ggml-large.bin",
-                _ => return Err("Invalid Whisper model name".to_string()),
-            };
+            let spec = find_whisper_model(&model_name)
+                .ok_or_else(|| "Invalid Whisper model name".to_string())?;
+            let model_path = models_dir.join(spec.file_name());
 
-            download_whisper_model(&app, &model_name, download_url, &model_path).await
+            // 下载 URL 和校验值来自模型目录（使用中国镜像站）
+            download_whisper_model(&app, spec, &model_path).await
+        }
+        ModelEngine::Onnx => {
+            let spec = find_onnx_model(&model_name)
+                .ok_or_else(|| format!("Unknown ONNX model: {}", model_name))?;
+            download_onnx_model(&app, spec).await
+        }
+        ModelEngine::Remote => {
+            Err("Remote engine has no model to download. Configure the server address instead.".to_string())
         }
     }
 }
 
-/// Whisper 模型下载逻辑（从原 download_model 函数中提取）
+/// Whisper 模型下载逻辑
+///
+/// 先下载到 `.part` 临时文件（支持断点续传），SHA-256 校验通过后才替换为正式模型，
+/// 因此中断或取消不会留下损坏的 `ggml-*.bin`
 async fn download_whisper_model(
     app: &AppHandle,
-    model_name: &str,
-    download_url: &str,
+    spec: &WhisperModelSpec,
     model_path: &PathBuf,
 ) -> Result<(), String> {
-    // 使用 reqwest 下载文件
-    let client = reqwest::Client::new();
-    let response = client
-        .get(download_url)
-        .send()
-        .await
-        .map_err(|e| format!("Failed to start download: {}", e))?;
-
-    if !response.status().is_success() {
-        return Err(format!("Download failed with status: {}", response.status()));
-    }
+    use tracing::info;
 
-    let total_size = response.content_length().unwrap_or(0);
-    let mut downloaded: u64 = 0;
+    let client = crate::download::download_client().map_err(|e| e.to_string())?;
+    let cancel = register_download(spec.name)?;
 
-    // 使用 tokio 的文件写入
-    let mut file = tokio::fs::File::create(&model_path)
-        .await
-        .map_err(|e| format!("Failed to create file: {}", e))?;
+    let download_url = spec.download_url();
+    info!("📥 [Model] Downloading {} from {}", spec.name, download_url);
 
-    let mut stream = response.bytes_stream();
-    use futures_util::StreamExt;
-    use tokio::io::AsyncWriteExt;
+    // 目录中没有静态哈希的模型，使用 Hugging Face LFS 元数据中的 SHA-256 校验
+    let expected_sha256 = match spec.sha256 {
+        Some(sha256) => Some(sha256.to_string()),
+        None => crate::download::fetch_lfs_sha256(&download_url).await,
+    };
 
-    while let Some(chunk) = stream.next().await {
-        let chunk = chunk.map_err(|e| format!("Failed to read chunk: {}", e))?;
-        file.write_all(&chunk)
-            .await
-            .map_err(|e| format!("Failed to write chunk: {}", e))?;
+    let result = crate::download::download_file(
+        &client,
+        &download_url,
+        model_path,
+        expected_sha256.as_deref(),
+        &cancel,
+        |downloaded, total| {
+            // 发送进度事件
+            let progress = if total > 0 {
+                (downloaded as f64 / total as f64 * 100.0) as u32
+            } else {
+                0
+            };
 
-        downloaded += chunk.len() as u64;
+            let _ = app.emit(
+                "model-download-progress",
+                DownloadProgress {
+                    model_name: spec.name.to_string(),
+                    progress,
+                    downloaded,
+                    total,
+                },
+            );
+        },
+    )
+    .await;
 
-        // 发送进度事件
-        let progress = if total_size > 0 {
-            (downloaded as f64 / total_size as f64 * 100.0) as u32
-        } else {
-            0
-        };
+    ACTIVE_DOWNLOADS.lock().remove(spec.name);
 
-        let _ = app.emit(
-            "model-download-progress",
-            DownloadProgress {
-                model_name: model_name.to_string(),
-                progress,
-                downloaded,
-                total: total_size,
-            },
-        );
+    match result {
+        Ok(()) => {
+            info!("✅ [Model] Model {} downloaded and verified", spec.name);
+            Ok(())
+        }
+        Err(crate::download::DownloadError::Cancelled) => Err("下载已取消".to_string()),
+        Err(e) => Err(e.to_string()),
     }
+}
 
-    file.flush()
-        .await
-        .map_err(|e| format!("Failed to flush file: {}", e))?;
+/// ONNX 模型下载逻辑
+///
+/// 从 ModelScope 逐个下载导出好的模型文件到 `models/onnx/<模型目录>`，Paraformer 同时下载共用的标点模型；
+/// 已存在的文件跳过，每个文件同样先写入 `.part` 并支持断点续传
+async fn download_onnx_model(app: &AppHandle, spec: &'static OnnxModelSpec) -> Result<(), String> {
+    use crate::onnx_asr::engine::{PUNCTUATION_DIR, PUNCTUATION_REPO};
+    use tracing::info;
 
+    let models_root = super::onnx::onnx_models_dir(app)?;
+    let mut targets = vec![(spec.repo, models_root.join(spec.dir))];
+    if spec.kind == OnnxModelKind::Paraformer {
+        targets.push((PUNCTUATION_REPO, models_root.join(PUNCTUATION_DIR)));
+    }
+    let files: Vec<(String, PathBuf)> = targets
+        .iter()
+        .flat_map(|(repo, dir)| {
+            repo.required_files
+                .iter()
+                .map(move |file| (repo.file_url(file), dir.join(file)))
+        })
+        .filter(|(_, path)| !path.exists())
+        .collect();
+
+    let client = crate::download::download_client().map_err(|e| e.to_string())?;
+    let cancel = register_download(spec.name)?;
+
+    let result = async {
+        for (index, (url, path)) in files.iter().enumerate() {
+            if let Some(dir) = path.parent() {
+                std::fs::create_dir_all(dir).map_err(|e| format!("Failed to create model directory: {}", e))?;
+            }
+            info!("📥 [Model] Downloading {} ({}/{}) from {}", spec.name, index + 1, files.len(), url);
+
+            crate::download::download_file(&client, url, path, None, &cancel, |downloaded, total| {
+                // 进度按文件数平均，单个文件内按字节数推进
+                let file_progress = if total > 0 { downloaded as f64 / total as f64 } else { 0.0 };
+                let progress = ((index as f64 + file_progress) / files.len() as f64 * 100.0) as u32;
+
+                let _ = app.emit(
+                    "model-download-progress",
+                    DownloadProgress {
+                        model_name: spec.name.to_string(),
+                        progress,
+                        downloaded,
+                        total,
+                    },
+                );
+            })
+            .await
+            .map_err(|e| match e {
+                crate::download::DownloadError::Cancelled => "下载已取消".to_string(),
+                e => e.to_string(),
+            })?;
+        }
+        Ok::<_, String>(())
+    }
+    .await;
+
+    ACTIVE_DOWNLOADS.lock().remove(spec.name);
+    result?;
+
+    if !is_model_present(&models_root, spec) {
+        return Err(format!("ONNX model '{}' is incomplete after download", spec.name));
+    }
+    info!("✅ [Model] ONNX model {} downloaded", spec.name);
     Ok(())
 }
 
+/// 登记进行中的下载，返回其取消标志（同一模型不能同时下载两次）
+fn register_download(model_name: &str) -> Result<Arc<AtomicBool>, String> {
+    let mut downloads = ACTIVE_DOWNLOADS.lock();
+    if downloads.contains_key(model_name) {
+        return Err(format!("Model {} is already downloading", model_name));
+    }
+    let cancel = Arc::new(AtomicBool::new(false));
+    downloads.insert(model_name.to_string(), cancel.clone());
+    Ok(cancel)
+}
+
+/// 取消正在进行的 Whisper / ONNX 模型下载
+///
+/// 已下载的部分会保留，再次下载时从断点继续。返回是否有下载被取消。
+#[tauri::command]
+pub fn cancel_model_download(model_name: String) -> Result<bool, String> {
+    use tracing::info;
+
+    match ACTIVE_DOWNLOADS.lock().get(&model_name) {
+        Some(cancel) => {
+            info!("⏹️  [Model] Cancelling download: {}", model_name);
+            cancel.store(true, Ordering::SeqCst);
+            Ok(true)
+        }
+        None => Ok(false),
+    }
+}
+
 /// 删除模型
 #[tauri::command]
 pub fn delete_model(app: AppHandle, model_name: String) -> Result<(), String> {
+    if let Some(spec) = find_onnx_model(&model_name) {
+        let dir = super::onnx::onnx_models_dir(&app)?.join(spec.dir);
+        if !dir.exists() {
+            return Err("Model not found".to_string());
+        }
+        return std::fs::remove_dir_all(&dir).map_err(|e| format!("Failed to delete model: {}", e));
+    }
+
     let models_dir = get_models_dir(&app)?;
     let model_path = models_dir.join(format!("ggml-{}.bin", model_name));
 
@@ -262,6 +381,9 @@ pub fn delete_model(app: AppHandle, model_name: String) -> Result<(), String> {
 
     std::fs::remove_file(&model_path).map_err(|e| format!("Failed to delete model: {}", e))?;
 
+    // 同时清理未完成的下载
+    let _ = std::fs::remove_file(crate::download::partial_path(&model_path));
+
     Ok(())
 }
 
@@ -272,6 +394,13 @@ pub fn get_downloaded_models(app: AppHandle) -> Result<Vec<ModelInfo>, String> {
     Ok(models.into_iter().filter(|m| m.is_downloaded).collect())
 }
 
+/// 模型是否已下载（或远程引擎已配置），回退链据此跳过不可用的备用模型
+pub(crate) fn is_model_downloaded(app: &AppHandle, model_name: &str) -> bool {
+    get_available_models(app.clone())
+        .map(|models| models.iter().any(|m| m.name == model_name && m.is_downloaded))
+        .unwrap_or(false)
+}
+
 /// 获取模型目录路径（调试用）
 #[tauri::command]
 pub fn get_models_directory(app: AppHandle) -> Result<String, String> {
@@ -281,7 +410,7 @@ pub fn get_models_directory(app: AppHandle) -> Result<String, String> {
 
 // 辅助函数
 
-fn get_models_dir(app: &AppHandle) -> Result<PathBuf, String> {
+pub(crate) fn get_models_dir(app: &AppHandle) -> Result<PathBuf, String> {
     let app_data_dir = app
         .path()
         .app_data_dir()
@@ -295,104 +424,55 @@ fn check_model_downloaded(models_dir: &PathBuf, model_name: &str) -> bool {
     model_path.exists()
 }
 
-/// 检查 FunASR 模型是否已下载
-fn check_funasr_model_downloaded(app: &AppHandle, model_name: &str) -> bool {
-    use std::process::Command;
-    use tracing::info;
-
-    // 尝试获取 Python 环境（使用同步检测）
-    let python_env = match crate::python::detect_python(app) {
-        Ok(env) => env,
-        Err(e) => {
-            info!("Failed to detect Python env for checking FunASR model: {}", e);
-            return false;
-        }
-    };
+/// 迁移已从目录移除的旧 Whisper 模型名（如 `large`）
+///
+/// 本地旧文件重命名为对应的目录模型（按文件大小判断版本），保存的模型设置同步改为新名称，
+/// 避免旧版本下载的模型和设置无法在模型列表中管理
+pub(crate) fn migrate_legacy_whisper_models(app: &AppHandle, db: &crate::db::Database) -> Result<(), String> {
+    use crate::db::SettingsRepository;
+    use crate::whisper::catalog::{legacy_model_names, legacy_replacement};
+    use tracing::{info, warn};
+
+    let models_dir = get_models_dir(app)?;
+    let settings = SettingsRepository::new(db.connection());
+
+    for legacy in legacy_model_names() {
+        let legacy_path = models_dir.join(format!("ggml-{}.bin", legacy));
+        let file_size = std::fs::metadata(&legacy_path).ok().map(|m| m.len());
+        let Some(spec) = legacy_replacement(legacy, file_size) else {
+            continue;
+        };
 
-    // 调用 Python 脚本检查模型是否存在
-    let script_path = match std::env::current_dir() {
-        Ok(dir) => {
-            // 开发模式：使用 src-tauri/scripts 目录
-            #[cfg(debug_assertions)]
-            {
-                // 尝试两种路径：当前目录/scripts 或 当前目录/src-tauri/scripts
-                let script_path = if dir.ends_with("src-tauri") {
-                    dir.join("scripts").join("funasr_transcribe.py")
-                } else {
-                    dir.join("src-tauri").join("scripts").join("funasr_transcribe.py")
-                };
-
-                if !script_path.exists() {
-                    info!("Script not found: {:?}", script_path);
-                    return false;
-                }
-                script_path
-            }
-            #[cfg(not(debug_assertions))]
-            {
-                // 生产模式：暂未实现
-                info!("Production mode not yet implemented");
-                return false;
+        if file_size.is_some() {
+            let target = models_dir.join(spec.file_name());
+            if target.exists() {
+                warn!("⚠️  [Model] Both {:?} and {:?} exist, keeping the legacy file as is", legacy_path, target);
+            } else {
+                std::fs::rename(&legacy_path, &target)
+                    .map_err(|e| format!("Failed to migrate legacy model {}: {}", legacy, e))?;
+                info!("📦 [Model] Migrated legacy model {} -> {}", legacy, spec.name);
             }
         }
-        Err(e) => {
-            info!("Failed to get current dir: {}", e);
-            return false;
-        }
-    };
 
-    info!("🔍 Checking FunASR model '{}' with Python: {:?}", model_name, python_env.python_path);
-
-    let output = Command::new(&python_env.python_path)
-        .arg(&script_path)
-        .arg("check")
-        .arg("--model")
-        .arg(model_name)
-        .output();
-
-    match output {
-        Ok(output) => {
-            if output.status.success() {
-                let stdout = String::from_utf8_lossy(&output.stdout);
-                info!("🐍 Python script output: {}", stdout);
-
-                // 提取 JSON 结果（最后一行以 { 开头的内容）
-                let json_line = stdout
-                    .lines()
-                    .filter(|line| line.trim().starts_with('{'))
-                    .last();
-
-                match json_line {
-                    Some(line) => {
-                        info!("🐍 Extracted JSON line: {}", line);
-                        match serde_json::from_str::<serde_json::Value>(line) {
-                            Ok(json) => {
-                                let exists = json.get("exists").and_then(|v| v.as_bool()).unwrap_or(false);
-                                info!("✅ FunASR model '{}' check result: exists = {}", model_name, exists);
-                                exists
-                            }
-                            Err(e) => {
-                                info!("❌ Failed to parse check result for '{}': {}", model_name, e);
-                                false
-                            }
-                        }
-                    }
-                    None => {
-                        info!("❌ No JSON result found in Python output for '{}'", model_name);
-                        false
-                    }
-                }
-            } else {
-                let stderr = String::from_utf8_lossy(&output.stderr);
-                info!("❌ Failed to check FunASR model '{}': {}", model_name, stderr);
-                false
+        // model_name 为原始字符串，model 由前端以 JSON 字符串保存
+        for (key, old, new) in [
+            ("model_name", legacy.to_string(), spec.name.to_string()),
+            ("model", legacy.to_string(), spec.name.to_string()),
+            ("model", format!("\"{}\"", legacy), format!("\"{}\"", spec.name)),
+        ] {
+            if settings.get(key).map_err(|e| e.to_string())?.as_deref() == Some(old.as_str()) {
+                settings.set(key, &new).map_err(|e| e.to_string())?;
+                info!("📦 [Model] Setting {} migrated: {} -> {}", key, old, new);
             }
         }
-        Err(e) => {
-            info!("❌ Command failed when checking FunASR model '{}': {}", model_name, e);
-            false
-        }
     }
+
+    Ok(())
+}
+
+/// ModelScope 缓存根目录（FunASR 模型下载位置）
+fn modelscope_cache_root(app: &AppHandle) -> Option<PathBuf> {
+    crate::funasr::catalog::modelscope_cache_root(app.path().home_dir().ok().as_deref())
 }
 
 #[derive(serde::Serialize, Clone)]
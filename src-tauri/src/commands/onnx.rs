@@ -0,0 +1,157 @@
+/// ONNX 引擎命令模块
+/// 原生 Paraformer / SenseVoice 引擎的状态，模型位于 `models/onnx/<模型目录>`
+
+use parking_lot::{MappedMutexGuard, Mutex, MutexGuard};
+use std::path::PathBuf;
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+use tauri::AppHandle;
+
+use crate::config::ModelType;
+use crate::jobs::{TranscriptionJob, CANCELLED_MESSAGE};
+use crate::onnx_asr::{find_onnx_model, OnnxAsrEngine, OnnxModelKind, OnnxModelSpec};
+use crate::speech::{EngineCapabilities, SpeechEngine, SpeechOutput, SpeechRequest};
+
+/// ONNX 引擎状态
+#[derive(Clone)]
+pub struct OnnxState {
+    engine: Arc<Mutex<Option<OnnxAsrEngine>>>,
+    /// 当前模型及其根目录（空闲卸载后用于重新加载）
+    model: Arc<Mutex<Option<(&'static OnnxModelSpec, PathBuf)>>>,
+    /// 最近一次使用引擎的时间
+    last_used: Arc<Mutex<Instant>>,
+}
+
+impl OnnxState {
+    pub fn new() -> Self {
+        Self {
+            engine: Arc::new(Mutex::new(None)),
+            model: Arc::new(Mutex::new(None)),
+            last_used: Arc::new(Mutex::new(Instant::now())),
+        }
+    }
+
+    /// 获取引擎并刷新最近使用时间，空闲卸载后透明地重新加载
+    fn lock_engine(&self) -> Result<MappedMutexGuard<'_, OnnxAsrEngine>, String> {
+        use tracing::info;
+
+        let mut guard = self.engine.lock();
+        *self.last_used.lock() = Instant::now();
+
+        if guard.is_none() {
+            let (spec, models_root) = self.model.lock().clone().ok_or_else(|| {
+                "ONNX engine not initialized. Please call initialize_engine first.".to_string()
+            })?;
+
+            info!("♻️  [ONNX] Reloading unloaded model: {}", spec.name);
+            *guard = Some(OnnxAsrEngine::load(&models_root, spec)?);
+        }
+
+        Ok(MutexGuard::map(guard, |engine| engine.as_mut().expect("engine loaded above")))
+    }
+
+    /// 确保模型已加载（快捷键预热时使用）
+    pub fn ensure_loaded(&self) -> Result<(), String> {
+        self.lock_engine().map(|_| ())
+    }
+
+    /// 空闲超过 `idle` 时卸载模型，返回是否实际卸载
+    pub fn unload_if_idle(&self, idle: Duration) -> bool {
+        let Some(mut guard) = self.engine.try_lock() else {
+            return false;
+        };
+
+        if guard.is_none() || self.last_used.lock().elapsed() < idle {
+            return false;
+        }
+
+        *guard = None;
+        true
+    }
+
+    fn current_kind(&self) -> Option<OnnxModelKind> {
+        self.model.lock().as_ref().map(|(spec, _)| spec.kind)
+    }
+}
+
+impl SpeechEngine for OnnxState {
+    fn model_type(&self) -> ModelType {
+        ModelType::Onnx
+    }
+
+    async fn current_model(&self) -> Option<String> {
+        self.model.lock().as_ref().map(|(spec, _)| spec.name.to_string())
+    }
+
+    async fn init(&self, app: &AppHandle, model_name: &str) -> Result<(), String> {
+        use tracing::info;
+
+        let spec = find_onnx_model(model_name).ok_or_else(|| format!("Unknown ONNX model: {}", model_name))?;
+        let models_root = onnx_models_dir(app)?;
+
+        info!("🎯 [ONNX] Initializing ONNX engine with model: {}", model_name);
+        // 创建推理会话需要数秒，不占用异步运行时的线程
+        let root = models_root.clone();
+        let engine = tokio::task::spawn_blocking(move || OnnxAsrEngine::load(&root, spec))
+            .await
+            .map_err(|e| format!("ONNX model loading task failed: {}", e))??;
+
+        *self.engine.lock() = Some(engine);
+        *self.model.lock() = Some((spec, models_root));
+        *self.last_used.lock() = Instant::now();
+        Ok(())
+    }
+
+    async fn transcribe(
+        &self,
+        _app: &AppHandle,
+        request: SpeechRequest<'_>,
+        job: &TranscriptionJob,
+    ) -> Result<SpeechOutput, String> {
+        // 单块推理无法中途打断，在每块之前和推理结束后检查取消
+        if job.is_cancelled() {
+            return Err(CANCELLED_MESSAGE.to_string());
+        }
+        let language = request.language.map(super::transcription::normalize_language);
+
+        // 推理是 CPU 密集的同步调用，放到阻塞线程池执行
+        let state = self.clone();
+        let audio = request.audio.to_vec();
+        let cancel = job.cancel_flag();
+        let reporter = job.reporter();
+        let output = tokio::task::spawn_blocking(move || {
+            let engine = state.lock_engine()?;
+            engine.transcribe(&audio, language.as_deref(), Some(&cancel), |percent| reporter.percent(percent))
+        })
+        .await
+        .map_err(|e| format!("ONNX transcription task failed: {}", e))??;
+        if job.is_cancelled() {
+            return Err(CANCELLED_MESSAGE.to_string());
+        }
+
+        Ok(SpeechOutput {
+            text: output.text,
+            source_language: output.language,
+            emotion: output.emotion,
+            events: output.events,
+            ..SpeechOutput::default()
+        })
+    }
+
+    fn capabilities(&self) -> EngineCapabilities {
+        let sensevoice = self.current_kind() == Some(OnnxModelKind::SenseVoice);
+        EngineCapabilities {
+            language_detection: sensevoice,
+            ..EngineCapabilities::default()
+        }
+    }
+
+    async fn unload(&self) -> Result<bool, String> {
+        Ok(self.engine.lock().take().is_some())
+    }
+}
+
+/// ONNX 模型根目录
+pub(crate) fn onnx_models_dir(app: &AppHandle) -> Result<PathBuf, String> {
+    Ok(super::model::get_models_dir(app)?.join("onnx"))
+}
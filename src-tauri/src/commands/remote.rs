@@ -0,0 +1,200 @@
+/// 远程引擎命令模块
+/// OpenAI 兼容 HTTP 转录服务的引擎状态和设置命令
+
+use parking_lot::Mutex;
+use std::sync::Arc;
+use tauri::{AppHandle, Manager, State};
+
+use crate::config::{ConfigManager, ModelType, REMOTE_MODEL};
+use crate::db::Database;
+use crate::jobs::TranscriptionJob;
+use crate::remote::{RemoteClient, RemoteConfig, RemoteRequest};
+use crate::speech::{EngineCapabilities, SpeechEngine, SpeechOutput, SpeechRequest};
+
+/// 远程引擎状态
+pub struct RemoteState {
+    client: Arc<Mutex<Option<Arc<RemoteClient>>>>,
+}
+
+impl RemoteState {
+    pub fn new() -> Self {
+        Self {
+            client: Arc::new(Mutex::new(None)),
+        }
+    }
+
+    /// 按当前设置创建客户端
+    fn connect(&self, app: &AppHandle) -> Result<Arc<RemoteClient>, String> {
+        let config = load_remote_config(app)?;
+        if config.endpoint.trim().is_empty() {
+            return Err("Remote engine not configured. Please set the server address first.".to_string());
+        }
+
+        let client = Arc::new(RemoteClient::new(config)?);
+        *self.client.lock() = Some(client.clone());
+        Ok(client)
+    }
+
+    /// 设置修改后丢弃旧客户端，下次转录时按新设置重建
+    fn reset(&self) {
+        *self.client.lock() = None;
+    }
+}
+
+impl SpeechEngine for RemoteState {
+    fn model_type(&self) -> ModelType {
+        ModelType::Remote
+    }
+
+    async fn current_model(&self) -> Option<String> {
+        self.client.lock().as_ref().map(|_| REMOTE_MODEL.to_string())
+    }
+
+    async fn init(&self, app: &AppHandle, _model_name: &str) -> Result<(), String> {
+        use tracing::info;
+
+        let client = self.connect(app)?;
+        info!(
+            "🌐 [Remote] Engine initialized: {} (model: {})",
+            client.config().endpoint,
+            client.config().model
+        );
+        Ok(())
+    }
+
+    async fn transcribe(
+        &self,
+        app: &AppHandle,
+        request: SpeechRequest<'_>,
+        job: &TranscriptionJob,
+    ) -> Result<SpeechOutput, String> {
+        let existing = self.client.lock().clone();
+        let client = match existing {
+            Some(client) => client,
+            None => self.connect(app)?,
+        };
+
+        // 用户词汇表作为提示词
+        let terms = crate::vocabulary::load_terms(app);
+        let prompt = crate::vocabulary::whisper_prompt(None, &terms, request.language);
+
+        let remote_request = RemoteRequest {
+            language: request.language,
+            translate: request.translate,
+            prompt: prompt.as_deref(),
+        };
+        let cancel = job.cancel_flag();
+        let result = client
+            .transcribe(request.audio, &remote_request, Some(&*cancel))
+            .await?;
+
+        Ok(SpeechOutput {
+            text: result.text,
+            source_language: result.language,
+            translated: result.translated,
+            segments: result.segments,
+            ..SpeechOutput::default()
+        })
+    }
+
+    fn capabilities(&self) -> EngineCapabilities {
+        EngineCapabilities {
+            timestamps: true,
+            word_timestamps: true,
+            translation: true,
+            language_detection: true,
+            vocabulary: true,
+        }
+    }
+
+    async fn unload(&self) -> Result<bool, String> {
+        // 模型在服务器端，这里只释放连接池
+        Ok(self.client.lock().take().is_some())
+    }
+}
+
+/// 返回给前端的远程引擎设置：不包含 API Key 本身，只标明是否已设置
+#[derive(Debug, Clone, serde::Serialize)]
+pub struct RemoteConfigView {
+    #[serde(flatten)]
+    pub config: RemoteConfig,
+    pub api_key_set: bool,
+}
+
+/// 获取远程引擎设置（API Key 不回传给 webview）
+#[tauri::command]
+pub fn get_remote_engine_config(db: State<'_, Arc<Database>>) -> Result<RemoteConfigView, String> {
+    let config = ConfigManager::new(db.connection()).get_remote_config()?;
+    let api_key_set = config.api_key.as_deref().is_some_and(|key| !key.is_empty());
+
+    Ok(RemoteConfigView {
+        config: RemoteConfig { api_key: None, ..config },
+        api_key_set,
+    })
+}
+
+/// 保存远程引擎设置
+///
+/// `config.api_key` 为空且服务器地址未变时保留已保存的 API Key，`clear_api_key` 为 true 时清除
+#[tauri::command]
+pub fn set_remote_engine_config(
+    config: RemoteConfig,
+    clear_api_key: Option<bool>,
+    db: State<'_, Arc<Database>>,
+    state: State<'_, RemoteState>,
+) -> Result<(), String> {
+    if !config.endpoint.trim().is_empty() {
+        config.request_url(false)?;
+    }
+
+    let manager = ConfigManager::new(db.connection());
+    let config = if clear_api_key.unwrap_or(false) {
+        RemoteConfig { api_key: None, ..config }
+    } else {
+        with_stored_api_key(config, &manager.get_remote_config()?)
+    };
+    manager.set_remote_config(&config)?;
+    state.reset();
+    Ok(())
+}
+
+/// 用一秒静音测试远程服务器的连通性和认证，返回服务器的响应文本
+///
+/// `config.api_key` 为空且服务器地址与已保存的相同时使用已保存的 API Key
+#[tauri::command]
+pub async fn test_remote_engine(config: RemoteConfig, db: State<'_, Arc<Database>>) -> Result<String, String> {
+    use tracing::info;
+
+    let stored = ConfigManager::new(db.connection()).get_remote_config()?;
+    let client = RemoteClient::new(with_stored_api_key(config, &stored))?;
+    let silence = vec![0.0f32; 16000];
+    let result = client
+        .transcribe(&silence, &RemoteRequest::default(), None)
+        .await?;
+
+    info!("✅ [Remote] Connection test succeeded: '{}'", result.text);
+    Ok(result.text)
+}
+
+/// 前端未填写 API Key 时沿用已保存的值
+///
+/// 只对已保存的服务器地址沿用，避免把 API Key 发给新填写的其他服务器
+fn with_stored_api_key(config: RemoteConfig, stored: &RemoteConfig) -> RemoteConfig {
+    if config.api_key.as_deref().is_some_and(|key| !key.is_empty()) {
+        config
+    } else if config.endpoint.trim() != stored.endpoint.trim() {
+        RemoteConfig { api_key: None, ..config }
+    } else {
+        RemoteConfig {
+            api_key: stored.api_key.clone(),
+            ..config
+        }
+    }
+}
+
+fn load_remote_config(app: &AppHandle) -> Result<RemoteConfig, String> {
+    let db = app
+        .try_state::<Arc<Database>>()
+        .ok_or("Database not initialized")?;
+    ConfigManager::new(db.connection()).get_remote_config()
+}
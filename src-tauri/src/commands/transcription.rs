@@ -1,17 +1,29 @@
 /// 转录命令模块
 /// 提供音频转录相关的 Tauri commands
 
-use parking_lot::Mutex;
+use parking_lot::{MappedMutexGuard, Mutex, MutexGuard};
 use std::path::PathBuf;
 use std::sync::Arc;
+use std::time::{Duration, Instant};
 use tauri::{AppHandle, Emitter, Manager, State};
 
-use crate::whisper::{convert_i16_to_f32, WhisperEngine};
+use crate::config::{ConfigManager, ModelType, OutputMode};
+use crate::db::Database;
+use crate::jobs::TranscriptionJob;
+use crate::speech::{EngineCapabilities, SpeechEngine, SpeechOutput, SpeechRequest};
+use crate::whisper::engine::WhisperError;
+use crate::whisper::{
+    convert_i16_to_f32, TranscriptionSegment, WhisperEngine, WhisperTask, WordTiming,
+};
 
 /// Whisper 引擎状态
 pub struct WhisperState {
     engine: Arc<Mutex<Option<WhisperEngine>>>,
     current_model: Arc<Mutex<Option<String>>>,
+    /// 当前模型文件路径（空闲卸载后用于重新加载）
+    model_path: Arc<Mutex<Option<PathBuf>>>,
+    /// 最近一次使用引擎的时间
+    last_used: Arc<Mutex<Instant>>,
 }
 
 impl WhisperState {
@@ -19,205 +31,345 @@ impl WhisperState {
         Self {
             engine: Arc::new(Mutex::new(None)),
             current_model: Arc::new(Mutex::new(None)),
+            model_path: Arc::new(Mutex::new(None)),
+            last_used: Arc::new(Mutex::new(Instant::now())),
         }
     }
+
+    /// 获取引擎并刷新最近使用时间
+    ///
+    /// 引擎因空闲被卸载时，从上次的模型文件透明地重新加载
+    pub fn lock_engine(&self) -> Result<MappedMutexGuard<'_, WhisperEngine>, String> {
+        use tracing::info;
+
+        let mut guard = self.engine.lock();
+        *self.last_used.lock() = Instant::now();
+
+        if guard.is_none() {
+            let model_path = self.model_path.lock().clone().ok_or_else(|| {
+                "Whisper engine not initialized. Please call initialize_engine first.".to_string()
+            })?;
+
+            info!("♻️  [Whisper] Reloading unloaded model: {:?}", model_path);
+            let engine = WhisperEngine::new(&model_path)
+                .map_err(|e| format!("Failed to reload Whisper engine: {}", e))?;
+            *guard = Some(engine);
+        }
+
+        Ok(MutexGuard::map(guard, |engine| engine.as_mut().expect("engine loaded above")))
+    }
+
+    /// 获取引擎并绑定转录任务的取消标志和进度回调，返回的锁释放时解除绑定
+    pub fn lock_engine_for_job(&self, job: &TranscriptionJob) -> Result<JobEngine<'_>, String> {
+        let mut engine = self.lock_engine()?;
+        engine.set_cancel_flag(Some(job.cancel_flag()));
+        engine.set_progress_callback(Some(job.whisper_progress()));
+        Ok(JobEngine { engine })
+    }
+
+    /// 确保模型已加载（快捷键预热时使用）
+    pub fn ensure_loaded(&self) -> Result<(), String> {
+        self.lock_engine().map(|_| ())
+    }
+
+    /// 空闲超过 `idle` 时卸载 Whisper 上下文，返回是否实际卸载
+    ///
+    /// 正在转录时引擎处于加锁状态，此时直接跳过
+    pub fn unload_if_idle(&self, idle: Duration) -> bool {
+        let Some(mut guard) = self.engine.try_lock() else {
+            return false;
+        };
+
+        if guard.is_none() || self.last_used.lock().elapsed() < idle {
+            return false;
+        }
+
+        *guard = None;
+        true
+    }
 }
 
-/// 初始化 Whisper 引擎
-#[tauri::command]
-pub async fn initialize_whisper(
-    app: AppHandle,
-    model_name: String,
-    state: State<'_, WhisperState>,
-) -> Result<(), String> {
-    use tracing::info;
+impl WhisperState {
+    /// 加载 `models/ggml-{model_name}.bin`
+    fn load_model(&self, app: &AppHandle, model_name: &str) -> Result<(), String> {
+        use tracing::info;
 
-    info!("🎯 [Whisper] Initializing Whisper engine with model: {}", model_name);
+        info!("🎯 [Whisper] Initializing Whisper engine with model: {}", model_name);
 
-    // 获取模型路径
-    let models_dir = get_models_dir(&app)?;
-    let model_path = models_dir.join(format!("ggml-{}.bin", model_name));
+        // 获取模型路径
+        let models_dir = get_models_dir(app)?;
+        let model_path = models_dir.join(format!("ggml-{}.bin", model_name));
 
-    info!("🎯 [Whisper] Looking for model at: {:?}", model_path);
+        info!("🎯 [Whisper] Looking for model at: {:?}", model_path);
 
-    if !model_path.exists() {
-        info!("🎯 [Whisper] Model not found, checking models directory: {:?}", models_dir);
+        if !model_path.exists() {
+            info!("🎯 [Whisper] Model not found, checking models directory: {:?}", models_dir);
 
-        // 列出 models 目录中的所有文件
-        if models_dir.exists() {
-            if let Ok(entries) = std::fs::read_dir(&models_dir) {
-                info!("🎯 [Whisper] Files in models directory:");
-                for entry in entries {
-                    if let Ok(entry) = entry {
+            // 列出 models 目录中的所有文件
+            if models_dir.exists() {
+                if let Ok(entries) = std::fs::read_dir(&models_dir) {
+                    info!("🎯 [Whisper] Files in models directory:");
+                    for entry in entries.flatten() {
                         info!("  - {:?}", entry.file_name());
                     }
                 }
+            } else {
+                info!("🎯 [Whisper] Models directory does not exist!");
             }
-        } else {
-            info!("🎯 [Whisper] Models directory does not exist!");
-        }
 
-        return Err(format!("Model '{}' not found at {}. Please download it first.", model_name, model_path.display()));
-    }
+            return Err(format!("Model '{}' not found at {}. Please download it first.", model_name, model_path.display()));
+        }
 
-    // 创建 Whisper 引擎
-    let engine = WhisperEngine::new(&model_path)
-        .map_err(|e| format!("Failed to initialize Whisper engine: {}", e))?;
+        // 创建 Whisper 引擎
+        let engine = WhisperEngine::new(&model_path)
+            .map_err(|e| format!("Failed to initialize Whisper engine: {}", e))?;
 
-    // 保存到状态
-    *state.engine.lock() = Some(engine);
-    *state.current_model.lock() = Some(model_name);
+        // 保存到状态
+        *self.engine.lock() = Some(engine);
+        *self.current_model.lock() = Some(model_name.to_string());
+        *self.model_path.lock() = Some(model_path);
+        *self.last_used.lock() = Instant::now();
 
-    Ok(())
+        Ok(())
+    }
 }
 
-/// 转录音频
-#[tauri::command]
-pub async fn transcribe_audio(
-    audio_data: Vec<i16>,
-    language: Option<String>,
-    state: State<'_, WhisperState>,
-) -> Result<String, String> {
-    use tracing::info;
-
-    info!("🎯 [Transcription] transcribe_audio called, language: {:?}", language);
-
-    // 如果是中文相关的语言代码，统一使用 "zh"
-    let normalized_language = language.map(|lang| {
-        if lang.starts_with("zh") || lang == "chinese" || lang == "Chinese" {
-            info!("🎯 [Transcription] Normalizing language '{}' to 'zh'", lang);
-            "zh".to_string()
-        } else {
-            lang
-        }
-    });
+/// 绑定了转录任务的 Whisper 引擎
+///
+/// 析构时清除任务的取消标志和进度回调，避免后续不经任务的调用（如性能测试）沿用已结束的任务
+pub struct JobEngine<'a> {
+    engine: MappedMutexGuard<'a, WhisperEngine>,
+}
 
-    // 检查引擎是否已初始化
-    let engine = state.engine.lock();
-    let engine = engine.as_ref().ok_or_else(|| {
-        "Whisper engine not initialized. Please call initialize_whisper first.".to_string()
-    })?;
+impl std::ops::Deref for JobEngine<'_> {
+    type Target = WhisperEngine;
 
-    // 转换音频格式 (i16 -> f32)
-    let audio_f32 = convert_i16_to_f32(&audio_data);
+    fn deref(&self) -> &WhisperEngine {
+        &self.engine
+    }
+}
 
-    // 执行转录
-    let text = engine
-        .transcribe(&audio_f32, normalized_language.as_deref())
-        .map_err(|e| format!("Transcription failed: {}", e))?;
+impl std::ops::DerefMut for JobEngine<'_> {
+    fn deref_mut(&mut self) -> &mut WhisperEngine {
+        &mut self.engine
+    }
+}
 
-    Ok(text)
+impl Drop for JobEngine<'_> {
+    fn drop(&mut self) {
+        self.engine.set_cancel_flag(None);
+        self.engine.set_progress_callback(None);
+    }
 }
 
-/// 转录最后一次录音
-/// 从全局 LAST_RECORDING 中获取录音数据并转录
-#[tauri::command]
-pub async fn transcribe_last_recording(
-    language: Option<String>,
-    state: State<'_, WhisperState>,
-) -> Result<String, String> {
-    use tracing::info;
+impl SpeechEngine for WhisperState {
+    fn model_type(&self) -> ModelType {
+        ModelType::Whisper
+    }
 
-    info!("🎯 [Transcription] transcribe_last_recording called, language: {:?}", language);
+    async fn current_model(&self) -> Option<String> {
+        self.current_model.lock().clone()
+    }
+
+    async fn init(&self, app: &AppHandle, model_name: &str) -> Result<(), String> {
+        self.load_model(app, model_name)
+    }
 
-    // 如果是中文相关的语言代码，统一使用 "zh"
-    let normalized_language = language.map(|lang| {
-        if lang.starts_with("zh") || lang == "chinese" || lang == "Chinese" {
-            info!("🎯 [Transcription] Normalizing language '{}' to 'zh'", lang);
-            "zh".to_string()
+    async fn transcribe(
+        &self,
+        app: &AppHandle,
+        request: SpeechRequest<'_>,
+        job: &TranscriptionJob,
+    ) -> Result<SpeechOutput, String> {
+        let language = request.language.map(normalize_language);
+        let task = if request.translate {
+            WhisperTask::Translate
         } else {
-            lang
+            WhisperTask::Transcribe
+        };
+
+        // 获取引擎（空闲卸载后会自动重新加载）
+        let mut engine = self.lock_engine_for_job(job)?;
+
+        // 用户词汇表作为 Whisper 提示词
+        engine.set_vocabulary(crate::vocabulary::load_terms(app));
+
+        // 带时间戳时使用分块并行的长音频路径（短音频单次解码），段落带词级时间戳
+        if request.timestamps {
+            let output = engine
+                .transcribe_long(request.audio, language.as_deref(), task)
+                .map_err(whisper_error_message)?;
+            return Ok(SpeechOutput {
+                text: output.text(),
+                source_language: output.language,
+                translated: request.translate,
+                segments: output.segments,
+                ..SpeechOutput::default()
+            });
         }
-    });
-
-    info!("🎯 [Transcription] Using normalized language: {:?}", normalized_language);
 
-    // 获取最后一次录音 - 使用全局静态变量
-    use super::audio::{LAST_RECORDING};
-    let last_recording = LAST_RECORDING.lock();
-    let audio_data = last_recording
-        .as_ref()
-        .ok_or("No recording available. Please record audio first.".to_string())?;
+        let output = engine
+            .transcribe(request.audio, language.as_deref(), task)
+            .map_err(whisper_error_message)?;
 
-    info!("🎯 [Transcription] Audio data available: {} samples at 48kHz", audio_data.len());
-
-    // 检查引擎是否已初始化
-    let engine = state.engine.lock();
-    let engine = engine.as_ref().ok_or_else(|| {
-        info!("🎯 [Transcription] Whisper engine not initialized!");
-        "Whisper engine not initialized. Please download a model first.".to_string()
-    })?;
+        Ok(SpeechOutput {
+            text: output.text,
+            source_language: output.language,
+            translated: output.translated,
+            ..SpeechOutput::default()
+        })
+    }
 
-    // 转换音频格式 (i16 -> f32)
-    let mut audio_f32 = convert_i16_to_f32(audio_data);
-
-    // 重采样从 48kHz 到 16kHz（Whisper 需要 16kHz）
-    use crate::whisper::resample_48khz_to_16khz;
-    audio_f32 = resample_48khz_to_16khz(&audio_f32);
-    info!("🎯 [Transcription] Resampled to 16kHz: {} samples", audio_f32.len());
-
-    // 执行转录
-    let text = engine
-        .transcribe(&audio_f32, normalized_language.as_deref())
-        .map_err(|e| format!("Transcription failed: {}", e))?;
-
-    // 🔑 验证转录结果是否有效
-    // 检测 Whisper 的"幻觉"输出（静音时经常输出的无意义内容）
-    if is_invalid_transcription(&text, &audio_f32) {
-        info!("🎯 [Transcription] Invalid transcription detected (hallucination or silence): '{}'", text);
-        return Err("转录结果无效：可能是静音或噪音".to_string());
+    fn capabilities(&self) -> EngineCapabilities {
+        EngineCapabilities {
+            timestamps: true,
+            word_timestamps: true,
+            translation: true,
+            language_detection: true,
+            vocabulary: true,
+        }
     }
 
-    info!("🎯 [Transcription] Valid transcription: '{}'", text);
-    Ok(text)
+    async fn unload(&self) -> Result<bool, String> {
+        // 保留模型路径，下次转录时 `lock_engine` 自动重新加载
+        Ok(self.engine.lock().take().is_some())
+    }
 }
 
 /// 转录音频（带时间戳）
+///
+/// 按当前引擎分派（模型未加载时先加载），引擎不给出时间戳时整段作为一个段落；
+/// `diarize` 为 true 时为段落标注说话人（需要 FunASR 环境），`max_speakers` 为已知的说话人数上限
 #[tauri::command]
 pub async fn transcribe_audio_with_timestamps(
+    app: AppHandle,
     audio_data: Vec<i16>,
     language: Option<String>,
-    state: State<'_, WhisperState>,
+    output_mode: Option<String>,
+    diarize: Option<bool>,
+    max_speakers: Option<usize>,
 ) -> Result<Vec<TranscriptionSegmentDTO>, String> {
     use tracing::info;
 
     info!("🎯 [Transcription] transcribe_audio_with_timestamps called, language: {:?}", language);
 
-    // 如果是中文相关的语言代码，统一使用 "zh"
-    let normalized_language = language.map(|lang| {
-        if lang.starts_with("zh") || lang == "chinese" || lang == "Chinese" {
-            info!("🎯 [Transcription] Normalizing language '{}' to 'zh'", lang);
-            "zh".to_string()
-        } else {
-            lang
-        }
-    });
-
-    // 检查引擎是否已初始化
-    let engine = state.engine.lock();
-    let engine = engine.as_ref().ok_or_else(|| {
-        "Whisper engine not initialized. Please call initialize_whisper first.".to_string()
-    })?;
+    // 注册任务，供 cancel_transcription 中止解码
+    let job = TranscriptionJob::start(&app);
 
     // 转换音频格式 (i16 -> f32)
     let audio_f32 = convert_i16_to_f32(&audio_data);
 
-    // 执行转录
-    let segments = engine
-        .transcribe_with_timestamps(&audio_f32, normalized_language.as_deref())
-        .map_err(|e| format!("Transcription failed: {}", e))?;
+    // 执行转录（引擎锁在说话人分离之前释放）
+    let request = SpeechRequest {
+        audio: &audio_f32,
+        language: language.as_deref(),
+        translate: resolve_output_mode(&app, output_mode.as_deref()).is_translate(),
+        timestamps: true,
+        source_file: None,
+    };
+    let segments = transcribe_segments(&app, request, &job).await;
+    let segments = job.finish(with_speakers(&app, &job, &audio_f32, None, segments, diarize, max_speakers).await)?;
 
     // 转换为 DTO
-    let segments_dto: Vec<TranscriptionSegmentDTO> = segments
-        .into_iter()
-        .map(|s| TranscriptionSegmentDTO {
-            text: s.text,
-            start_ms: s.start_ms,
-            end_ms: s.end_ms,
-        })
-        .collect();
+    Ok(segments.into_iter().map(TranscriptionSegmentDTO::from).collect())
+}
+
+/// 转录音频文件（归档录音、导入的长音频）
+///
+/// 支持任意时长的 WAV 文件，Whisper 使用分块并行的长音频路径，FunASR 由服务器分块转录；
+/// `diarize` 为 true 时为段落标注说话人（需要 FunASR 环境），`max_speakers` 为已知的说话人数上限
+#[tauri::command]
+pub async fn transcribe_audio_file(
+    app: AppHandle,
+    file_path: String,
+    language: Option<String>,
+    output_mode: Option<String>,
+    diarize: Option<bool>,
+    max_speakers: Option<usize>,
+) -> Result<Vec<TranscriptionSegmentDTO>, String> {
+    use tracing::info;
+
+    info!("🎯 [Transcription] transcribe_audio_file called: {}, language: {:?}", file_path, language);
+
+    // 注册任务，供 cancel_transcription 中止解码；导入与快捷键无关，不接管听写的预留
+    let job = TranscriptionJob::start_detached(&app);
 
-    Ok(segments_dto)
+    let audio_f32 = read_audio_file(&file_path)?;
+    info!("🎯 [Transcription] Loaded {:.1}s of audio", audio_f32.len() as f32 / 16000.0);
+
+    let request = SpeechRequest {
+        audio: &audio_f32,
+        language: language.as_deref(),
+        translate: resolve_output_mode(&app, output_mode.as_deref()).is_translate(),
+        timestamps: true,
+        source_file: Some(&file_path),
+    };
+    let segments = transcribe_segments(&app, request, &job).await;
+    let segments =
+        job.finish(with_speakers(&app, &job, &audio_f32, Some(&file_path), segments, diarize, max_speakers).await)?;
+
+    Ok(segments.into_iter().map(TranscriptionSegmentDTO::from).collect())
+}
+
+/// 用当前引擎转录并返回带时间戳的段落
+///
+/// 不提供时间戳的引擎（如 ONNX）把整段文本作为一个覆盖全部音频的段落
+async fn transcribe_segments(
+    app: &AppHandle,
+    request: SpeechRequest<'_>,
+    job: &TranscriptionJob,
+) -> Result<Vec<TranscriptionSegment>, String> {
+    let output = super::engine::transcribe_with_current(app, request, job).await?;
+    if !output.segments.is_empty() || output.text.trim().is_empty() {
+        return Ok(output.segments);
+    }
+
+    Ok(vec![TranscriptionSegment {
+        text: output.text,
+        start_ms: 0,
+        end_ms: request.audio.len() as u64 / 16,
+        words: Vec::new(),
+        speaker: None,
+    }])
+}
+
+/// 按需为转录结果标注说话人
+///
+/// 说话人分离失败不影响转录结果（段落不带说话人编号），取消除外
+async fn with_speakers(
+    app: &AppHandle,
+    job: &TranscriptionJob,
+    audio: &[f32],
+    source_file: Option<&str>,
+    segments: Result<Vec<TranscriptionSegment>, String>,
+    diarize: Option<bool>,
+    max_speakers: Option<usize>,
+) -> Result<Vec<TranscriptionSegment>, String> {
+    use crate::diarization::{diarize as label_speakers, ClusterOptions};
+    use crate::funasr::RequestControl;
+    use tracing::warn;
+
+    let mut segments = segments?;
+    if !diarize.unwrap_or(false) {
+        return Ok(segments);
+    }
+
+    let control = RequestControl {
+        cancel: Some(job.cancel_flag()),
+        on_progress: None,
+    };
+    let options = ClusterOptions {
+        max_speakers,
+        ..ClusterOptions::default()
+    };
+    match label_speakers(app, audio, source_file, &mut segments, &options, &control).await {
+        Ok(()) => Ok(segments),
+        Err(e) if crate::jobs::is_cancelled_error(&e) => Err(e),
+        Err(e) => {
+            warn!("⚠️  [Transcription] Speaker diarization failed, returning unlabelled segments: {}", e);
+            Ok(segments)
+        }
+    }
 }
 
 /// 获取当前使用的模型名称
@@ -227,16 +379,195 @@ pub fn get_current_model(state: State<'_, WhisperState>) -> Result<Option<String
     Ok(model.clone())
 }
 
+/// 取消进行中的转录（Whisper 和 FunASR）
+///
+/// `job_id` 为空时取消所有任务。被取消的转录命令返回 "Transcription cancelled"，
+/// 并发送 outcome 为 "cancelled" 的 `transcription-outcome` 事件。返回被取消的任务数。
+#[tauri::command]
+pub fn cancel_transcription(job_id: Option<String>) -> Result<usize, String> {
+    use tracing::info;
+
+    let cancelled = crate::jobs::cancel(job_id.as_deref());
+    info!("🛑 [Transcription] Cancel requested (job: {:?}), {} job(s) cancelled", job_id, cancelled);
+    Ok(cancelled)
+}
+
+/// 输出模式设置：默认模式和按应用配置的模式
+#[derive(serde::Serialize, Clone)]
+pub struct OutputModeSettings {
+    pub default_mode: OutputMode,
+    /// 应用 bundle ID -> 输出模式
+    pub app_modes: std::collections::HashMap<String, OutputMode>,
+}
+
+/// 获取输出模式设置
+#[tauri::command]
+pub fn get_output_mode_settings(db: State<'_, Arc<Database>>) -> Result<OutputModeSettings, String> {
+    let config_manager = ConfigManager::new(db.connection());
+    Ok(OutputModeSettings {
+        default_mode: config_manager.get_output_mode()?,
+        app_modes: config_manager.get_app_output_profiles()?,
+    })
+}
+
+/// 设置默认输出模式（"transcribe" / "translate"）
+#[tauri::command]
+pub fn set_default_output_mode(mode: OutputMode, db: State<'_, Arc<Database>>) -> Result<(), String> {
+    ConfigManager::new(db.connection()).set_output_mode(mode)
+}
+
+/// 设置或清除某个应用的输出模式（`mode` 为空时清除）
+#[tauri::command]
+pub fn set_app_output_mode(
+    bundle_id: String,
+    mode: Option<OutputMode>,
+    db: State<'_, Arc<Database>>,
+) -> Result<(), String> {
+    let bundle_id = bundle_id.trim();
+    if bundle_id.is_empty() {
+        return Err("应用标识不能为空".to_string());
+    }
+    ConfigManager::new(db.connection()).set_app_output_mode(bundle_id, mode)
+}
+
+/// 转录结果 DTO
+#[derive(serde::Serialize, serde::Deserialize, Clone)]
+pub struct TranscriptionResultDTO {
+    pub text: String,
+    /// 源语言（说话使用的语言）
+    pub source_language: Option<String>,
+    /// 文本是否为英文译文
+    pub translated: bool,
+    /// 实际完成转录的引擎（可能是备用引擎）
+    #[serde(default)]
+    pub model_type: Option<ModelType>,
+    /// 实际完成转录的模型
+    #[serde(default)]
+    pub model_name: Option<String>,
+    /// 带时间戳的段落（引擎给出时）
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub segments: Vec<TranscriptionSegmentDTO>,
+    /// 说话人情感（SenseVoice）
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub emotion: Option<String>,
+    /// 音频事件（SenseVoice）
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub events: Vec<String>,
+}
+
 /// 转录段落 DTO
 #[derive(serde::Serialize, serde::Deserialize, Clone)]
 pub struct TranscriptionSegmentDTO {
     pub text: String,
     pub start_ms: u64,
     pub end_ms: u64,
+    /// 词级时间戳
+    #[serde(default)]
+    pub words: Vec<WordTiming>,
+    /// 说话人编号
+    #[serde(default)]
+    pub speaker: Option<u32>,
+}
+
+impl From<TranscriptionSegment> for TranscriptionSegmentDTO {
+    fn from(segment: TranscriptionSegment) -> Self {
+        Self {
+            text: segment.text,
+            start_ms: segment.start_ms,
+            end_ms: segment.end_ms,
+            words: segment.words,
+            speaker: segment.speaker,
+        }
+    }
+}
+
+impl From<TranscriptionSegmentDTO> for TranscriptionSegment {
+    fn from(segment: TranscriptionSegmentDTO) -> Self {
+        Self {
+            text: segment.text,
+            start_ms: segment.start_ms,
+            end_ms: segment.end_ms,
+            words: segment.words,
+            speaker: segment.speaker,
+        }
+    }
 }
 
 // 辅助函数
 
+/// 读取 WAV 文件并转换为 16kHz 单声道 f32
+pub(crate) fn read_audio_file(path: &str) -> Result<Vec<f32>, String> {
+    use crate::whisper::{mix_to_mono, resample_to_16khz};
+
+    let mut reader = hound::WavReader::open(path)
+        .map_err(|e| format!("Failed to open audio file: {}", e))?;
+    let spec = reader.spec();
+
+    let samples: Vec<f32> = match spec.sample_format {
+        hound::SampleFormat::Float => reader
+            .samples::<f32>()
+            .collect::<Result<_, _>>()
+            .map_err(|e| format!("Failed to read audio file: {}", e))?,
+        hound::SampleFormat::Int => {
+            let scale = (1i64 << (spec.bits_per_sample - 1)) as f32;
+            reader
+                .samples::<i32>()
+                .map(|s| s.map(|s| s as f32 / scale))
+                .collect::<Result<_, _>>()
+                .map_err(|e| format!("Failed to read audio file: {}", e))?
+        }
+    };
+
+    let mono = mix_to_mono(&samples, spec.channels);
+    Ok(resample_to_16khz(&mono, spec.sample_rate))
+}
+
+/// 决定本次转录的输出模式
+///
+/// 优先级：调用方显式指定 > 触发录音的快捷键 > 当前应用配置 > 默认设置
+pub(crate) fn resolve_output_mode(app: &AppHandle, explicit: Option<&str>) -> OutputMode {
+    if let Some(mode) = explicit {
+        return OutputMode::from_str(mode);
+    }
+
+    if let Some(mode) = crate::shortcut::active_output_mode() {
+        return mode;
+    }
+
+    let Some(db) = app.try_state::<Arc<Database>>() else {
+        return OutputMode::Transcribe;
+    };
+    let config_manager = ConfigManager::new(db.connection());
+
+    #[cfg(target_os = "macos")]
+    {
+        if let Some(bundle_id) = crate::app_tracker::get_saved_app() {
+            if let Ok(Some(mode)) = config_manager.get_app_output_mode(&bundle_id) {
+                return mode;
+            }
+        }
+    }
+
+    config_manager.get_output_mode().unwrap_or(OutputMode::Transcribe)
+}
+
+/// 中文相关的语言代码统一为 "zh"
+pub(crate) fn normalize_language(language: &str) -> String {
+    if language.starts_with("zh") || language.eq_ignore_ascii_case("chinese") {
+        "zh".to_string()
+    } else {
+        language.to_string()
+    }
+}
+
+/// 引擎错误转为命令错误信息（取消时返回固定信息供前端识别）
+fn whisper_error_message(error: WhisperError) -> String {
+    match error {
+        WhisperError::Cancelled => crate::jobs::CANCELLED_MESSAGE.to_string(),
+        error => format!("Transcription failed: {}", error),
+    }
+}
+
 fn get_models_dir(app: &AppHandle) -> Result<PathBuf, String> {
     let app_data_dir = app
         .path()
@@ -246,13 +577,14 @@ fn get_models_dir(app: &AppHandle) -> Result<PathBuf, String> {
     Ok(app_data_dir.join("models"))
 }
 
-/// 检测转录结果是否无效（Whisper 幻觉或静音）
+/// 检测转录结果是否无效（静音或噪音）
 ///
-/// 常见的无效情况：
+/// 幻觉段落已由引擎根据解码信号逐段过滤（见 `whisper::hallucination`），
+/// 这里只处理过滤后仍然无意义的结果：
 /// 1. 音频太短（少于 0.3 秒）
-/// 2. 包含 Whisper 常见的幻觉词汇（"字幕"、"J Chong" 等）
-/// 3. 文本太短或只有标点符号
-fn is_invalid_transcription(text: &str, audio_f32: &[f32]) -> bool {
+/// 2. 文本为空或太短
+/// 3. 只有标点符号
+pub(crate) fn is_invalid_transcription(text: &str, audio_f32: &[f32]) -> bool {
     // 1. 检查音频长度（16kHz 采样率）
     let duration_seconds = audio_f32.len() as f32 / 16000.0;
     if duration_seconds < 0.3 {
@@ -276,38 +608,6 @@ fn is_invalid_transcription(text: &str, audio_f32: &[f32]) -> bool {
         return true;
     }
 
-    // 5. 检查常见的 Whisper 幻觉模式（不区分大小写）
-    let lower_text = text.to_lowercase();
-    let hallucination_patterns = [
-        "字幕",
-        "subtitle",
-        "j chong",
-        "jchong",
-        "thanks for watching",
-        "请不吝点赞",
-        "订阅",
-        "subscribe",
-        "翻译",
-        "translation",
-        "(music)",
-        "[music]",
-        "(laughs)",
-        "[laughs]",
-        "(applause)",
-        "[applause]",
-    ];
-
-    for pattern in &hallucination_patterns {
-        if lower_text.contains(pattern) {
-            return true;
-        }
-    }
-
-    // 6. 检查是否只是单个括号内容（如 "(字幕:xxx)"）
-    if trimmed.starts_with('(') && trimmed.ends_with(')') && !trimmed[1..trimmed.len()-1].contains('(') {
-        return true;
-    }
-
     // 所有检查通过，认为是有效的转录
     false
 }
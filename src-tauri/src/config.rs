@@ -1,29 +1,92 @@
 use crate::db::{DbConnection, SettingsRepository};
+use crate::remote::RemoteConfig;
 use serde::{Deserialize, Serialize};
+use std::path::{Path, PathBuf};
+
+/// FunASR 模型名称（其余模型均由 Whisper 加载）
+pub const FUNASR_MODELS: &[&str] = &["paraformer-zh", "paraformer-large", "sensevoice-small"];
+
+/// 远程引擎的模型名称（服务器端模型在 `RemoteConfig` 中配置）
+pub const REMOTE_MODEL: &str = "remote";
 
 #[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 #[serde(rename_all = "lowercase")]
 pub enum ModelType {
     Whisper,
     FunASR,
+    /// OpenAI 兼容的 HTTP 转录服务
+    Remote,
+    /// 原生 ONNX 模型（Paraformer / SenseVoice，无需 Python）
+    Onnx,
 }
 
 impl ModelType {
     pub fn from_str(s: &str) -> Self {
         match s.to_lowercase().as_str() {
             "funasr" => ModelType::FunASR,
+            "remote" => ModelType::Remote,
+            "onnx" => ModelType::Onnx,
             _ => ModelType::Whisper,
         }
     }
 
+    /// 根据模型名称判断由哪个引擎加载
+    pub fn for_model(model_name: &str) -> Self {
+        if FUNASR_MODELS.contains(&model_name) {
+            ModelType::FunASR
+        } else if model_name == REMOTE_MODEL {
+            ModelType::Remote
+        } else if crate::onnx_asr::find_onnx_model(model_name).is_some() {
+            ModelType::Onnx
+        } else {
+            ModelType::Whisper
+        }
+    }
+
     pub fn to_string(&self) -> String {
         match self {
             ModelType::Whisper => "whisper".to_string(),
             ModelType::FunASR => "funasr".to_string(),
+            ModelType::Remote => "remote".to_string(),
+            ModelType::Onnx => "onnx".to_string(),
+        }
+    }
+}
+
+/// 输出模式：原文转写或翻译为英文
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum OutputMode {
+    Transcribe,
+    Translate,
+}
+
+impl OutputMode {
+    pub fn from_str(s: &str) -> Self {
+        match s.to_lowercase().as_str() {
+            "translate" => OutputMode::Translate,
+            _ => OutputMode::Transcribe,
+        }
+    }
+
+    pub fn to_string(&self) -> String {
+        match self {
+            OutputMode::Transcribe => "transcribe".to_string(),
+            OutputMode::Translate => "translate".to_string(),
         }
     }
+
+    pub fn is_translate(&self) -> bool {
+        *self == OutputMode::Translate
+    }
 }
 
+/// Default idle timeout before releasing loaded models
+pub const DEFAULT_IDLE_UNLOAD_MINUTES: u64 = 10;
+
+/// Models tried in order when the configured model fails (skipped when not downloaded)
+pub const DEFAULT_FALLBACK_MODELS: &[&str] = &["small"];
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct AppConfig {
     pub model_type: ModelType,
@@ -32,6 +95,13 @@ pub struct AppConfig {
     pub enable_prewarming: bool,
     pub language: String,
     pub shortcut: String,
+    pub output_mode: OutputMode,
+    /// 模型空闲多少分钟后卸载（0 表示常驻）
+    pub idle_unload_minutes: u64,
+    /// 按下快捷键时预先加载已卸载的模型
+    pub prewarm_on_shortcut: bool,
+    /// 当前模型失败时依次尝试的备用模型
+    pub fallback_models: Vec<String>,
 }
 
 impl Default for AppConfig {
@@ -43,6 +113,10 @@ impl Default for AppConfig {
             enable_prewarming: true,
             language: "zh".to_string(),
             shortcut: "Cmd+Shift+S".to_string(),
+            output_mode: OutputMode::Transcribe,
+            idle_unload_minutes: DEFAULT_IDLE_UNLOAD_MINUTES,
+            prewarm_on_shortcut: true,
+            fallback_models: DEFAULT_FALLBACK_MODELS.iter().map(|m| m.to_string()).collect(),
         }
     }
 }
@@ -75,6 +149,10 @@ impl ConfigManager {
                 .get("shortcut")
                 .map_err(|e| e.to_string())?
                 .unwrap_or_else(|| "Cmd+Shift+S".to_string()),
+            output_mode: self.get_output_mode()?,
+            idle_unload_minutes: self.get_idle_unload_minutes()?,
+            prewarm_on_shortcut: self.is_prewarm_on_shortcut_enabled()?,
+            fallback_models: self.get_fallback_models()?,
         })
     }
 
@@ -149,6 +227,167 @@ impl ConfigManager {
             .map_err(|e| e.to_string())
     }
 
+    /// Get default output mode (transcribe or translate)
+    pub fn get_output_mode(&self) -> Result<OutputMode, String> {
+        let value = self
+            .repo
+            .get("output_mode")
+            .map_err(|e| e.to_string())?
+            .unwrap_or_else(|| "transcribe".to_string());
+        Ok(OutputMode::from_str(&value))
+    }
+
+    /// Set default output mode
+    pub fn set_output_mode(&self, mode: OutputMode) -> Result<(), String> {
+        self.repo
+            .set("output_mode", &mode.to_string())
+            .map_err(|e| e.to_string())
+    }
+
+    /// Get output mode configured for a specific app (by bundle ID)
+    ///
+    /// Per-app profiles are stored as a JSON object under `app_output_modes`,
+    /// e.g. `{"com.apple.Terminal": "translate"}`.
+    pub fn get_app_output_mode(&self, bundle_id: &str) -> Result<Option<OutputMode>, String> {
+        let profiles = self.get_app_output_modes()?;
+        Ok(profiles.get(bundle_id).map(|mode| OutputMode::from_str(mode)))
+    }
+
+    /// Set or clear the output mode for a specific app
+    pub fn set_app_output_mode(&self, bundle_id: &str, mode: Option<OutputMode>) -> Result<(), String> {
+        let mut profiles = self.get_app_output_modes()?;
+        match mode {
+            Some(mode) => {
+                profiles.insert(bundle_id.to_string(), mode.to_string());
+            }
+            None => {
+                profiles.remove(bundle_id);
+            }
+        }
+
+        let value = serde_json::to_string(&profiles).map_err(|e| e.to_string())?;
+        self.repo
+            .set("app_output_modes", &value)
+            .map_err(|e| e.to_string())
+    }
+
+    /// Get all per-app output mode profiles (bundle ID -> mode)
+    pub fn get_app_output_profiles(&self) -> Result<std::collections::HashMap<String, OutputMode>, String> {
+        Ok(self
+            .get_app_output_modes()?
+            .into_iter()
+            .map(|(bundle_id, mode)| (bundle_id, OutputMode::from_str(&mode)))
+            .collect())
+    }
+
+    fn get_app_output_modes(&self) -> Result<std::collections::HashMap<String, String>, String> {
+        match self.repo.get("app_output_modes").map_err(|e| e.to_string())? {
+            Some(value) => serde_json::from_str(&value).map_err(|e| e.to_string()),
+            None => Ok(std::collections::HashMap::new()),
+        }
+    }
+
+    /// Get idle timeout (minutes) after which loaded models are released, 0 = never
+    ///
+    /// Written by the settings UI, hence the camelCase key
+    pub fn get_idle_unload_minutes(&self) -> Result<u64, String> {
+        let value = self
+            .repo
+            .get("idleUnloadMinutes")
+            .map_err(|e| e.to_string())?;
+        // Frontend stores values as JSON, accept both `10` and `"10"`
+        Ok(value
+            .and_then(|v| v.trim().trim_matches('"').parse().ok())
+            .unwrap_or(DEFAULT_IDLE_UNLOAD_MINUTES))
+    }
+
+    /// Set idle unload timeout
+    pub fn set_idle_unload_minutes(&self, minutes: u64) -> Result<(), String> {
+        self.repo
+            .set("idleUnloadMinutes", &minutes.to_string())
+            .map_err(|e| e.to_string())
+    }
+
+    /// Check if models should be prewarmed when the recording shortcut is pressed
+    pub fn is_prewarm_on_shortcut_enabled(&self) -> Result<bool, String> {
+        let value = self
+            .repo
+            .get("prewarmOnShortcut")
+            .map_err(|e| e.to_string())?
+            .unwrap_or_else(|| "true".to_string());
+        Ok(value.trim_matches('"') != "false")
+    }
+
+    /// Set prewarm-on-shortcut state
+    pub fn set_prewarm_on_shortcut_enabled(&self, enabled: bool) -> Result<(), String> {
+        self.repo
+            .set("prewarmOnShortcut", if enabled { "true" } else { "false" })
+            .map_err(|e| e.to_string())
+    }
+
+    /// Get fallback models, tried in order when the configured model fails
+    ///
+    /// Written by the settings UI as a JSON array; a comma-separated list is also accepted
+    pub fn get_fallback_models(&self) -> Result<Vec<String>, String> {
+        let value = self
+            .repo
+            .get("fallbackModels")
+            .map_err(|e| e.to_string())?;
+        Ok(match value {
+            Some(value) => parse_model_list(&value),
+            None => DEFAULT_FALLBACK_MODELS.iter().map(|m| m.to_string()).collect(),
+        })
+    }
+
+    /// Set fallback models
+    pub fn set_fallback_models(&self, models: &[String]) -> Result<(), String> {
+        let value = serde_json::to_string(models).map_err(|e| e.to_string())?;
+        self.repo
+            .set("fallbackModels", &value)
+            .map_err(|e| e.to_string())
+    }
+
+    /// Get remote (OpenAI-compatible) engine settings
+    pub fn get_remote_config(&self) -> Result<RemoteConfig, String> {
+        match self.repo.get("remoteEngine").map_err(|e| e.to_string())? {
+            Some(value) => serde_json::from_str(&value).map_err(|e| format!("Invalid remote engine settings: {}", e)),
+            None => Ok(RemoteConfig::default()),
+        }
+    }
+
+    /// Set remote engine settings
+    pub fn set_remote_config(&self, config: &RemoteConfig) -> Result<(), String> {
+        let value = serde_json::to_string(config).map_err(|e| e.to_string())?;
+        self.repo
+            .set("remoteEngine", &value)
+            .map_err(|e| e.to_string())
+    }
+
+    /// Get the directory overriding the bundled FunASR Python scripts, None = use bundled scripts
+    ///
+    /// Written by the settings UI as a JSON string; empty means no override
+    pub fn get_funasr_scripts_dir(&self) -> Result<Option<PathBuf>, String> {
+        let value = self
+            .repo
+            .get("funasrScriptsDir")
+            .map_err(|e| e.to_string())?;
+        // Frontend stores values as JSON, decode so escaped Windows paths survive; accept raw paths too
+        Ok(value
+            .map(|v| serde_json::from_str::<String>(&v).unwrap_or(v))
+            .map(|v| v.trim().to_string())
+            .filter(|v| !v.is_empty())
+            .map(PathBuf::from))
+    }
+
+    /// Set the FunASR scripts override directory (None clears it)
+    pub fn set_funasr_scripts_dir(&self, dir: Option<&Path>) -> Result<(), String> {
+        let value = dir.map(|d| d.to_string_lossy().to_string()).unwrap_or_default();
+        let value = serde_json::to_string(&value).map_err(|e| e.to_string())?;
+        self.repo
+            .set("funasrScriptsDir", &value)
+            .map_err(|e| e.to_string())
+    }
+
     /// Check if FunASR is being used
     pub fn is_funasr_active(&self) -> Result<bool, String> {
         Ok(self.get_model_type()? == ModelType::FunASR)
@@ -160,12 +399,46 @@ impl ConfigManager {
     }
 }
 
+fn parse_model_list(value: &str) -> Vec<String> {
+    let models = serde_json::from_str::<Vec<String>>(value).unwrap_or_else(|_| {
+        value
+            .trim_matches('"')
+            .split(',')
+            .map(|m| m.to_string())
+            .collect()
+    });
+
+    models
+        .into_iter()
+        .map(|m| m.trim().to_string())
+        .filter(|m| !m.is_empty())
+        .collect()
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
     use crate::db::Database;
     use std::path::PathBuf;
 
+    #[test]
+    fn test_model_type_for_model() {
+        assert_eq!(ModelType::for_model("paraformer-zh"), ModelType::FunASR);
+        assert_eq!(ModelType::for_model("sensevoice-small"), ModelType::FunASR);
+        assert_eq!(ModelType::for_model("large-v3-turbo"), ModelType::Whisper);
+        assert_eq!(ModelType::for_model(REMOTE_MODEL), ModelType::Remote);
+        assert_eq!(ModelType::from_str(&ModelType::Remote.to_string()), ModelType::Remote);
+        assert_eq!(ModelType::for_model("paraformer-zh-onnx"), ModelType::Onnx);
+        assert_eq!(ModelType::from_str(&ModelType::Onnx.to_string()), ModelType::Onnx);
+    }
+
+    #[test]
+    fn test_parse_model_list() {
+        assert_eq!(parse_model_list(r#"["small", "base"]"#), vec!["small", "base"]);
+        assert_eq!(parse_model_list("small, base"), vec!["small", "base"]);
+        assert!(parse_model_list("[]").is_empty());
+    }
+
     #[test]
     fn test_config_manager() {
         let temp_dir = std::env::temp_dir();
@@ -191,7 +464,80 @@ mod tests {
         config.mark_first_launch_complete().unwrap();
         assert!(!config.is_first_launch().unwrap());
 
+        // Test fallback models
+        assert_eq!(config.get_fallback_models().unwrap(), vec!["small"]);
+        config.set_fallback_models(&["base".to_string()]).unwrap();
+        assert_eq!(config.get_fallback_models().unwrap(), vec!["base"]);
+
+        // Test scripts override directory
+        assert_eq!(config.get_funasr_scripts_dir().unwrap(), None);
+        config.set_funasr_scripts_dir(Some(Path::new("/opt/lingcode/scripts"))).unwrap();
+        assert_eq!(config.get_funasr_scripts_dir().unwrap(), Some(PathBuf::from("/opt/lingcode/scripts")));
+        config.set_funasr_scripts_dir(None).unwrap();
+        assert_eq!(config.get_funasr_scripts_dir().unwrap(), None);
+
         // Clean up
         let _ = std::fs::remove_file(&db_path);
     }
+
+    #[test]
+    fn test_output_mode_profiles() {
+        let temp_dir = std::env::temp_dir();
+        let db_path = temp_dir.join("test_config_output_mode.db");
+        let _ = std::fs::remove_file(&db_path);
+
+        let db = Database::new(db_path.clone()).unwrap();
+        let config = ConfigManager::new(db.connection());
+
+        // Default is plain transcription
+        assert_eq!(config.get_output_mode().unwrap(), OutputMode::Transcribe);
+
+        config.set_output_mode(OutputMode::Translate).unwrap();
+        assert_eq!(config.get_output_mode().unwrap(), OutputMode::Translate);
+
+        // Per-app profiles
+        assert_eq!(config.get_app_output_mode("com.apple.Terminal").unwrap(), None);
+        config
+            .set_app_output_mode("com.apple.Terminal", Some(OutputMode::Translate))
+            .unwrap();
+        assert_eq!(
+            config.get_app_output_mode("com.apple.Terminal").unwrap(),
+            Some(OutputMode::Translate)
+        );
+        assert_eq!(
+            config.get_app_output_profiles().unwrap().get("com.apple.Terminal"),
+            Some(&OutputMode::Translate)
+        );
+
+        config.set_app_output_mode("com.apple.Terminal", None).unwrap();
+        assert_eq!(config.get_app_output_mode("com.apple.Terminal").unwrap(), None);
+        assert!(config.get_app_output_profiles().unwrap().is_empty());
+
+        let _ = std::fs::remove_file(&db_path);
+    }
+
+    #[test]
+    fn test_idle_unload_settings() {
+        let temp_dir = std::env::temp_dir();
+        let db_path = temp_dir.join("test_config_idle_unload.db");
+        let _ = std::fs::remove_file(&db_path);
+
+        let db = Database::new(db_path.clone()).unwrap();
+        let config = ConfigManager::new(db.connection());
+
+        assert_eq!(config.get_idle_unload_minutes().unwrap(), DEFAULT_IDLE_UNLOAD_MINUTES);
+        assert!(config.is_prewarm_on_shortcut_enabled().unwrap());
+
+        config.set_idle_unload_minutes(0).unwrap();
+        assert_eq!(config.get_idle_unload_minutes().unwrap(), 0);
+        config.set_prewarm_on_shortcut_enabled(false).unwrap();
+        assert!(!config.is_prewarm_on_shortcut_enabled().unwrap());
+
+        // JSON-encoded values written by the frontend
+        let repo = SettingsRepository::new(db.connection());
+        repo.set("idleUnloadMinutes", "\"30\"").unwrap();
+        assert_eq!(config.get_idle_unload_minutes().unwrap(), 30);
+
+        let _ = std::fs::remove_file(&db_path);
+    }
 }
@@ -1,6 +1,8 @@
 use chrono::{DateTime, Utc};
 use serde::{Deserialize, Serialize};
 
+use crate::whisper::TranscriptionSegment;
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct Setting {
     pub key: String,
@@ -17,6 +19,21 @@ pub struct Transcription {
     pub language: String,
     pub created_at: DateTime<Utc>,
     pub app_context: Option<String>,
+    /// 源语言（识别出的说话语言）
+    #[serde(default)]
+    pub source_language: Option<String>,
+    /// 是否为翻译输出（文本为英文译文）
+    #[serde(default)]
+    pub translated: bool,
+    /// 带时间戳的段落（文件转录、长录音），用于导出字幕
+    #[serde(default)]
+    pub segments: Option<Vec<TranscriptionSegment>>,
+    /// 说话人情感（SenseVoice 识别，小写，如 "happy"）
+    #[serde(default)]
+    pub emotion: Option<String>,
+    /// 音频事件（SenseVoice 识别，小写，如 "laughter"）
+    #[serde(default)]
+    pub events: Vec<String>,
 }
 
 impl Transcription {
@@ -29,6 +46,45 @@ impl Transcription {
             language: "zh".to_string(),
             created_at: Utc::now(),
             app_context: None,
+            source_language: None,
+            translated: false,
+            segments: None,
+            emotion: None,
+            events: Vec::new(),
         }
     }
 }
+
+/// 模型基准测试结果
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct BenchmarkResult {
+    pub id: Option<i64>,
+    /// "whisper" 或 "funasr"
+    pub engine: String,
+    pub model_name: String,
+    /// 参考音频时长（秒）
+    pub audio_secs: f64,
+    /// 模型加载耗时（毫秒）
+    pub load_ms: i64,
+    /// 解码耗时（毫秒）
+    pub decode_ms: i64,
+    /// 实时率（解码耗时 / 音频时长，越小越快）
+    pub rtf: f64,
+    /// 加载和解码期间相对基线的内存峰值增量（MB）
+    pub peak_memory_mb: Option<f64>,
+    /// 推理线程数
+    pub n_threads: Option<i64>,
+    /// 参考音频和解码参数（JSON），参数不同的结果不可直接比较
+    pub decode_params: Option<String>,
+    /// 硬件信息（JSON）
+    pub hardware: String,
+    pub created_at: DateTime<Utc>,
+}
+
+/// 自定义词汇（产品名、人名、技术术语等）
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct VocabularyEntry {
+    pub id: i64,
+    pub term: String,
+    pub created_at: DateTime<Utc>,
+}
@@ -1,6 +1,6 @@
-use super::{DbConnection, Setting, Transcription};
+use super::{BenchmarkResult, DbConnection, Setting, Transcription, VocabularyEntry};
 use chrono::Utc;
-use rusqlite::{params, Result};
+use rusqlite::{params, Result, Row};
 
 pub struct SettingsRepository {
     conn: DbConnection,
@@ -64,10 +64,19 @@ impl TranscriptionRepository {
     }
 
     pub fn create(&self, transcription: &Transcription) -> Result<i64> {
+        let segments = transcription
+            .segments
+            .as_ref()
+            .and_then(|segments| serde_json::to_string(segments).ok());
+        let events = (!transcription.events.is_empty())
+            .then(|| serde_json::to_string(&transcription.events).ok())
+            .flatten();
+
         let conn = self.conn.lock().unwrap();
         conn.execute(
-            "INSERT INTO transcriptions (text, audio_duration, model_version, language, created_at, app_context)
-             VALUES (?1, ?2, ?3, ?4, ?5, ?6)",
+            "INSERT INTO transcriptions (text, audio_duration, model_version, language, created_at, app_context,
+                                         source_language, translated, segments, emotion, events)
+             VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10, ?11)",
             params![
                 transcription.text,
                 transcription.audio_duration,
@@ -75,6 +84,11 @@ impl TranscriptionRepository {
                 transcription.language,
                 transcription.created_at.to_rfc3339(),
                 transcription.app_context,
+                transcription.source_language,
+                transcription.translated,
+                segments,
+                transcription.emotion,
+                events,
             ],
         )?;
         Ok(conn.last_insert_rowid())
@@ -83,21 +97,14 @@ impl TranscriptionRepository {
     pub fn get_by_id(&self, id: i64) -> Result<Option<Transcription>> {
         let conn = self.conn.lock().unwrap();
         let mut stmt = conn.prepare(
-            "SELECT id, text, audio_duration, model_version, language, created_at, app_context
+            "SELECT id, text, audio_duration, model_version, language, created_at, app_context,
+                    source_language, translated, segments, emotion, events
              FROM transcriptions WHERE id = ?1",
         )?;
         let mut rows = stmt.query(params![id])?;
 
         if let Some(row) = rows.next()? {
-            Ok(Some(Transcription {
-                id: Some(row.get(0)?),
-                text: row.get(1)?,
-                audio_duration: row.get(2)?,
-                model_version: row.get(3)?,
-                language: row.get(4)?,
-                created_at: row.get::<_, String>(5)?.parse().unwrap_or(Utc::now()),
-                app_context: row.get(6)?,
-            }))
+            Ok(Some(map_transcription_row(row)?))
         } else {
             Ok(None)
         }
@@ -106,22 +113,13 @@ impl TranscriptionRepository {
     pub fn get_recent(&self, limit: usize) -> Result<Vec<Transcription>> {
         let conn = self.conn.lock().unwrap();
         let mut stmt = conn.prepare(
-            "SELECT id, text, audio_duration, model_version, language, created_at, app_context
+            "SELECT id, text, audio_duration, model_version, language, created_at, app_context,
+                    source_language, translated, segments, emotion, events
              FROM transcriptions
              ORDER BY created_at DESC
              LIMIT ?1",
         )?;
-        let rows = stmt.query_map(params![limit], |row| {
-            Ok(Transcription {
-                id: Some(row.get(0)?),
-                text: row.get(1)?,
-                audio_duration: row.get(2)?,
-                model_version: row.get(3)?,
-                language: row.get(4)?,
-                created_at: row.get::<_, String>(5)?.parse().unwrap_or(Utc::now()),
-                app_context: row.get(6)?,
-            })
-        })?;
+        let rows = stmt.query_map(params![limit], map_transcription_row)?;
 
         rows.collect()
     }
@@ -130,23 +128,40 @@ impl TranscriptionRepository {
         let conn = self.conn.lock().unwrap();
         let search_pattern = format!("%{}%", query);
         let mut stmt = conn.prepare(
-            "SELECT id, text, audio_duration, model_version, language, created_at, app_context
+            "SELECT id, text, audio_duration, model_version, language, created_at, app_context,
+                    source_language, translated, segments, emotion, events
              FROM transcriptions
              WHERE text LIKE ?1
              ORDER BY created_at DESC
              LIMIT 100",
         )?;
-        let rows = stmt.query_map(params![search_pattern], |row| {
-            Ok(Transcription {
-                id: Some(row.get(0)?),
-                text: row.get(1)?,
-                audio_duration: row.get(2)?,
-                model_version: row.get(3)?,
-                language: row.get(4)?,
-                created_at: row.get::<_, String>(5)?.parse().unwrap_or(Utc::now()),
-                app_context: row.get(6)?,
-            })
-        })?;
+        let rows = stmt.query_map(params![search_pattern], map_transcription_row)?;
+
+        rows.collect()
+    }
+
+    /// 按情感和音频事件筛选（条件为空时不限制）
+    pub fn filter_by_tags(
+        &self,
+        emotion: Option<&str>,
+        event: Option<&str>,
+        limit: usize,
+    ) -> Result<Vec<Transcription>> {
+        let conn = self.conn.lock().unwrap();
+        // events 存为 JSON 数组，按数组元素精确匹配
+        let mut stmt = conn.prepare(
+            "SELECT id, text, audio_duration, model_version, language, created_at, app_context,
+                    source_language, translated, segments, emotion, events
+             FROM transcriptions
+             WHERE (?1 IS NULL OR emotion = ?1)
+               AND (?2 IS NULL OR EXISTS (SELECT 1 FROM json_each(events) WHERE json_each.value = ?2))
+             ORDER BY created_at DESC
+             LIMIT ?3",
+        )?;
+        let rows = stmt.query_map(
+            params![emotion, event, limit],
+            map_transcription_row,
+        )?;
 
         rows.collect()
     }
@@ -163,3 +178,201 @@ impl TranscriptionRepository {
         Ok(())
     }
 }
+
+pub struct VocabularyRepository {
+    conn: DbConnection,
+}
+
+impl VocabularyRepository {
+    pub fn new(conn: DbConnection) -> Self {
+        Self { conn }
+    }
+
+    pub fn get_all(&self) -> Result<Vec<VocabularyEntry>> {
+        let conn = self.conn.lock().unwrap();
+        let mut stmt = conn.prepare("SELECT id, term, created_at FROM vocabulary ORDER BY id")?;
+        let rows = stmt.query_map([], |row| {
+            Ok(VocabularyEntry {
+                id: row.get(0)?,
+                term: row.get(1)?,
+                created_at: row.get::<_, String>(2)?.parse().unwrap_or(Utc::now()),
+            })
+        })?;
+
+        rows.collect()
+    }
+
+    /// 获取所有词条文本（按添加顺序）
+    pub fn get_terms(&self) -> Result<Vec<String>> {
+        let conn = self.conn.lock().unwrap();
+        let mut stmt = conn.prepare("SELECT term FROM vocabulary ORDER BY id")?;
+        let rows = stmt.query_map([], |row| row.get(0))?;
+
+        rows.collect()
+    }
+
+    /// 添加词条，已存在时忽略。返回是否实际插入
+    pub fn add(&self, term: &str) -> Result<bool> {
+        let conn = self.conn.lock().unwrap();
+        let inserted = conn.execute(
+            "INSERT OR IGNORE INTO vocabulary (term, created_at) VALUES (?1, ?2)",
+            params![term, Utc::now().to_rfc3339()],
+        )?;
+        Ok(inserted > 0)
+    }
+
+    /// 批量添加词条（单个事务），返回新增数量
+    pub fn add_many(&self, terms: &[String]) -> Result<usize> {
+        let mut conn = self.conn.lock().unwrap();
+        let tx = conn.transaction()?;
+        let mut inserted = 0;
+        {
+            let mut stmt = tx.prepare(
+                "INSERT OR IGNORE INTO vocabulary (term, created_at) VALUES (?1, ?2)",
+            )?;
+            let now = Utc::now().to_rfc3339();
+            for term in terms {
+                inserted += stmt.execute(params![term, now])?;
+            }
+        }
+        tx.commit()?;
+        Ok(inserted)
+    }
+
+    pub fn delete(&self, id: i64) -> Result<()> {
+        let conn = self.conn.lock().unwrap();
+        conn.execute("DELETE FROM vocabulary WHERE id = ?1", params![id])?;
+        Ok(())
+    }
+
+    pub fn delete_all(&self) -> Result<()> {
+        let conn = self.conn.lock().unwrap();
+        conn.execute("DELETE FROM vocabulary", [])?;
+        Ok(())
+    }
+}
+
+pub struct BenchmarkRepository {
+    conn: DbConnection,
+}
+
+impl BenchmarkRepository {
+    pub fn new(conn: DbConnection) -> Self {
+        Self { conn }
+    }
+
+    pub fn create(&self, result: &BenchmarkResult) -> Result<i64> {
+        let conn = self.conn.lock().unwrap();
+        conn.execute(
+            "INSERT INTO benchmark_results
+             (engine, model_name, audio_secs, load_ms, decode_ms, rtf, peak_memory_mb, n_threads, decode_params, hardware, created_at)
+             VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10, ?11)",
+            params![
+                result.engine,
+                result.model_name,
+                result.audio_secs,
+                result.load_ms,
+                result.decode_ms,
+                result.rtf,
+                result.peak_memory_mb,
+                result.n_threads,
+                result.decode_params,
+                result.hardware,
+                result.created_at.to_rfc3339(),
+            ],
+        )?;
+        Ok(conn.last_insert_rowid())
+    }
+
+    pub fn get_recent(&self, limit: usize) -> Result<Vec<BenchmarkResult>> {
+        let conn = self.conn.lock().unwrap();
+        let mut stmt = conn.prepare(
+            "SELECT id, engine, model_name, audio_secs, load_ms, decode_ms, rtf, peak_memory_mb, n_threads, hardware, created_at, decode_params
+             FROM benchmark_results ORDER BY created_at DESC, id DESC LIMIT ?1",
+        )?;
+        let rows = stmt.query_map(params![limit], |row| {
+            Ok(BenchmarkResult {
+                id: Some(row.get(0)?),
+                engine: row.get(1)?,
+                model_name: row.get(2)?,
+                audio_secs: row.get(3)?,
+                load_ms: row.get(4)?,
+                decode_ms: row.get(5)?,
+                rtf: row.get(6)?,
+                peak_memory_mb: row.get(7)?,
+                n_threads: row.get(8)?,
+                hardware: row.get(9)?,
+                created_at: row.get::<_, String>(10)?.parse().unwrap_or(Utc::now()),
+                decode_params: row.get(11)?,
+            })
+        })?;
+
+        rows.collect()
+    }
+
+    pub fn delete_all(&self) -> Result<()> {
+        let conn = self.conn.lock().unwrap();
+        conn.execute("DELETE FROM benchmark_results", [])?;
+        Ok(())
+    }
+}
+
+fn map_transcription_row(row: &Row) -> Result<Transcription> {
+    Ok(Transcription {
+        id: Some(row.get(0)?),
+        text: row.get(1)?,
+        audio_duration: row.get(2)?,
+        model_version: row.get(3)?,
+        language: row.get(4)?,
+        created_at: row.get::<_, String>(5)?.parse().unwrap_or(Utc::now()),
+        app_context: row.get(6)?,
+        source_language: row.get(7)?,
+        translated: row.get(8)?,
+        segments: row
+            .get::<_, Option<String>>(9)?
+            .and_then(|json| serde_json::from_str(&json).ok()),
+        emotion: row.get(10)?,
+        events: row
+            .get::<_, Option<String>>(11)?
+            .and_then(|json| serde_json::from_str(&json).ok())
+            .unwrap_or_default(),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::{Arc, Mutex};
+
+    fn repository() -> TranscriptionRepository {
+        let conn = Arc::new(Mutex::new(rusqlite::Connection::open_in_memory().unwrap()));
+        crate::db::init_database(&conn).unwrap();
+        TranscriptionRepository::new(conn)
+    }
+
+    fn transcription(text: &str, emotion: Option<&str>, events: &[&str]) -> Transcription {
+        let mut transcription = Transcription::new(text.to_string());
+        transcription.emotion = emotion.map(str::to_string);
+        transcription.events = events.iter().map(|event| event.to_string()).collect();
+        transcription
+    }
+
+    #[test]
+    fn test_filter_by_tags() {
+        let repo = repository();
+        repo.create(&transcription("哈哈", Some("happy"), &["laughter", "bgm"])).unwrap();
+        repo.create(&transcription("唉", Some("sad"), &["cough"])).unwrap();
+        repo.create(&transcription("普通", None, &[])).unwrap();
+
+        let texts = |rows: Vec<Transcription>| rows.into_iter().map(|row| row.text).collect::<Vec<_>>();
+        assert_eq!(texts(repo.filter_by_tags(Some("happy"), None, 10).unwrap()), vec!["哈哈"]);
+        assert_eq!(texts(repo.filter_by_tags(None, Some("cough"), 10).unwrap()), vec!["唉"]);
+        assert!(repo.filter_by_tags(Some("sad"), Some("bgm"), 10).unwrap().is_empty());
+        assert_eq!(repo.filter_by_tags(None, None, 10).unwrap().len(), 3);
+
+        // 事件按数组元素精确匹配，通配符和引号不会扩大匹配范围
+        assert!(repo.filter_by_tags(None, Some("%"), 10).unwrap().is_empty());
+        assert!(repo.filter_by_tags(None, Some("laugh"), 10).unwrap().is_empty());
+        assert!(repo.filter_by_tags(None, Some("laughter\",\"bgm"), 10).unwrap().is_empty());
+    }
+}
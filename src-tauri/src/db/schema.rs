@@ -1,7 +1,7 @@
 use rusqlite::{Connection, Result};
 use std::sync::{Arc, Mutex};
 
-const CURRENT_VERSION: i32 = 1;
+const CURRENT_VERSION: i32 = 6;
 
 pub fn init_database(conn: &Arc<Mutex<Connection>>) -> Result<()> {
     let conn = conn.lock().unwrap();
@@ -56,7 +56,12 @@ fn create_initial_schema(conn: &Connection) -> Result<()> {
             model_version TEXT,
             language TEXT DEFAULT 'zh',
             created_at DATETIME DEFAULT CURRENT_TIMESTAMP,
-            app_context TEXT
+            app_context TEXT,
+            source_language TEXT,
+            translated INTEGER NOT NULL DEFAULT 0,
+            segments TEXT,
+            emotion TEXT,
+            events TEXT
         )",
         [],
     )?;
@@ -68,6 +73,9 @@ fn create_initial_schema(conn: &Connection) -> Result<()> {
         [],
     )?;
 
+    create_vocabulary_table(conn)?;
+    create_benchmark_table(conn)?;
+
     // Insert default settings
     conn.execute(
         "INSERT OR IGNORE INTO settings (key, value) VALUES
@@ -103,9 +111,12 @@ fn migrate_database(conn: &Connection, from_version: i32, to_version: i32) -> Re
     // Apply migrations incrementally
     for version in from_version..to_version {
         match version {
+            1 => migrate_v1_to_v2(conn)?,
+            2 => migrate_v2_to_v3(conn)?,
+            3 => migrate_v3_to_v4(conn)?,
+            4 => migrate_v4_to_v5(conn)?,
+            5 => migrate_v5_to_v6(conn)?,
             // Future migrations will go here
-            // 1 => migrate_v1_to_v2(conn)?,
-            // 2 => migrate_v2_to_v3(conn)?,
             _ => {}
         }
     }
@@ -113,3 +124,69 @@ fn migrate_database(conn: &Connection, from_version: i32, to_version: i32) -> Re
     set_db_version(conn, to_version)?;
     Ok(())
 }
+
+/// v2: 记录源语言和翻译标记
+fn migrate_v1_to_v2(conn: &Connection) -> Result<()> {
+    conn.execute("ALTER TABLE transcriptions ADD COLUMN source_language TEXT", [])?;
+    conn.execute(
+        "ALTER TABLE transcriptions ADD COLUMN translated INTEGER NOT NULL DEFAULT 0",
+        [],
+    )?;
+    Ok(())
+}
+
+/// v3: 用户自定义词汇表（热词）
+fn migrate_v2_to_v3(conn: &Connection) -> Result<()> {
+    create_vocabulary_table(conn)
+}
+
+/// v4: 模型基准测试结果
+fn migrate_v3_to_v4(conn: &Connection) -> Result<()> {
+    create_benchmark_table(conn)
+}
+
+/// v5: 带时间戳的段落（JSON），用于字幕导出
+fn migrate_v4_to_v5(conn: &Connection) -> Result<()> {
+    conn.execute("ALTER TABLE transcriptions ADD COLUMN segments TEXT", [])?;
+    Ok(())
+}
+
+/// v6: SenseVoice 情感和音频事件标签（事件为 JSON 数组），用于历史筛选
+fn migrate_v5_to_v6(conn: &Connection) -> Result<()> {
+    conn.execute("ALTER TABLE transcriptions ADD COLUMN emotion TEXT", [])?;
+    conn.execute("ALTER TABLE transcriptions ADD COLUMN events TEXT", [])?;
+    Ok(())
+}
+
+fn create_vocabulary_table(conn: &Connection) -> Result<()> {
+    conn.execute(
+        "CREATE TABLE IF NOT EXISTS vocabulary (
+            id INTEGER PRIMARY KEY AUTOINCREMENT,
+            term TEXT NOT NULL UNIQUE,
+            created_at DATETIME DEFAULT CURRENT_TIMESTAMP
+        )",
+        [],
+    )?;
+    Ok(())
+}
+
+fn create_benchmark_table(conn: &Connection) -> Result<()> {
+    conn.execute(
+        "CREATE TABLE IF NOT EXISTS benchmark_results (
+            id INTEGER PRIMARY KEY AUTOINCREMENT,
+            engine TEXT NOT NULL,
+            model_name TEXT NOT NULL,
+            audio_secs REAL NOT NULL,
+            load_ms INTEGER NOT NULL,
+            decode_ms INTEGER NOT NULL,
+            rtf REAL NOT NULL,
+            peak_memory_mb REAL,
+            n_threads INTEGER,
+            decode_params TEXT,
+            hardware TEXT NOT NULL,
+            created_at DATETIME DEFAULT CURRENT_TIMESTAMP
+        )",
+        [],
+    )?;
+    Ok(())
+}
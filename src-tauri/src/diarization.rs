@@ -0,0 +1,239 @@
+/// 说话人分离
+/// FunASR 服务器用 CAM++ 模型为每个段落提取说话人向量，Rust 端按余弦相似度聚类，
+/// 为段落标注说话人编号（按首次出现顺序从 0 开始）
+
+use tauri::{AppHandle, Manager};
+
+use crate::commands::FunASRState;
+use crate::funasr::{AudioInput, RequestControl, ServerWav, FILE_INPUT_MIN_MS};
+use crate::whisper::TranscriptionSegment;
+
+/// 同一说话人的最低余弦相似度
+pub const DEFAULT_SIMILARITY_THRESHOLD: f32 = 0.5;
+
+/// 短于该时长的段落不单独提取向量（太短的语音向量不可靠），沿用相邻段落的说话人
+const MIN_EMBED_MS: u64 = 800;
+
+/// 聚类参数
+#[derive(Debug, Clone)]
+pub struct ClusterOptions {
+    pub threshold: f32,
+    /// 已知说话人数上限（会议人数）
+    pub max_speakers: Option<usize>,
+}
+
+impl Default for ClusterOptions {
+    fn default() -> Self {
+        Self {
+            threshold: DEFAULT_SIMILARITY_THRESHOLD,
+            max_speakers: None,
+        }
+    }
+}
+
+/// 为段落标注说话人
+///
+/// `audio` 为 16kHz 单声道音频（段落时间戳相对于它），`source_file` 为其来源文件（导入的音频）；
+/// 长音频以 WAV 文件交给服务器，短录音直接随请求发送。失败时段落保持原样
+pub async fn diarize(
+    app: &AppHandle,
+    audio: &[f32],
+    source_file: Option<&str>,
+    segments: &mut [TranscriptionSegment],
+    options: &ClusterOptions,
+    control: &RequestControl,
+) -> Result<(), String> {
+    use tracing::info;
+
+    if segments.is_empty() {
+        return Ok(());
+    }
+
+    let ranges: Vec<(u64, u64)> = segments
+        .iter()
+        .map(|segment| {
+            if segment.end_ms.saturating_sub(segment.start_ms) < MIN_EMBED_MS {
+                (segment.start_ms, segment.start_ms)
+            } else {
+                (segment.start_ms, segment.end_ms)
+            }
+        })
+        .collect();
+
+    let wav = if audio.len() as u64 / 16 > FILE_INPUT_MIN_MS {
+        Some(ServerWav::prepare(audio, source_file)?)
+    } else {
+        None
+    };
+    let input = wav.as_ref().map_or(AudioInput::Pcm(audio), ServerWav::input);
+
+    let state = app.state::<FunASRState>();
+    let embeddings = state.embed_speakers(app, input, &ranges, control).await?;
+
+    let labels = cluster_speakers(&embeddings, options);
+    apply_speakers(segments, &labels);
+
+    let n_speakers = labels.iter().flatten().max().map_or(0, |max| max + 1);
+    info!("🗣️  [Diarization] {} segments labelled with {} speakers", segments.len(), n_speakers);
+    Ok(())
+}
+
+/// 按余弦相似度聚类说话人向量，返回每个向量的说话人编号（没有向量的为 None）
+///
+/// 先按时间顺序在线聚类，再合并相似的簇（并满足说话人数上限），
+/// 最后把每个向量重新分配到最近的簇中心
+pub fn cluster_speakers(embeddings: &[Option<Vec<f32>>], options: &ClusterOptions) -> Vec<Option<u32>> {
+    let normalized: Vec<Option<Vec<f32>>> = embeddings
+        .iter()
+        .map(|embedding| embedding.as_deref().and_then(normalize))
+        .collect();
+
+    // 1. 在线聚类：相似度达到阈值加入最近的簇，否则新建簇
+    let mut sums: Vec<Vec<f32>> = Vec::new();
+    for embedding in normalized.iter().flatten() {
+        match nearest(&sums, embedding) {
+            Some((index, similarity)) if similarity >= options.threshold => add_assign(&mut sums[index], embedding),
+            _ => sums.push(embedding.clone()),
+        }
+    }
+
+    // 2. 合并相似的簇，直到没有可合并的簇且满足人数上限
+    loop {
+        let mut best: Option<(usize, usize, f32)> = None;
+        for i in 0..sums.len() {
+            for j in i + 1..sums.len() {
+                let similarity = cosine(&sums[i], &sums[j]);
+                if !matches!(best, Some((_, _, s)) if s >= similarity) {
+                    best = Some((i, j, similarity));
+                }
+            }
+        }
+
+        let Some((i, j, similarity)) = best else {
+            break;
+        };
+        let over_limit = options.max_speakers.is_some_and(|max| sums.len() > max.max(1));
+        if similarity < options.threshold && !over_limit {
+            break;
+        }
+
+        let merged = sums.remove(j);
+        add_assign(&mut sums[i], &merged);
+    }
+
+    // 3. 重新分配到最近的簇中心，并按首次出现顺序编号
+    let mut order: Vec<usize> = Vec::new();
+    normalized
+        .iter()
+        .map(|embedding| {
+            let (cluster, _) = nearest(&sums, embedding.as_ref()?)?;
+            let label = match order.iter().position(|&c| c == cluster) {
+                Some(label) => label,
+                None => {
+                    order.push(cluster);
+                    order.len() - 1
+                }
+            };
+            Some(label as u32)
+        })
+        .collect()
+}
+
+/// 写入说话人编号；没有编号的段落沿用时间上最近的有编号段落
+pub fn apply_speakers(segments: &mut [TranscriptionSegment], labels: &[Option<u32>]) {
+    for (index, segment) in segments.iter_mut().enumerate() {
+        segment.speaker = labels.get(index).copied().flatten().or_else(|| {
+            let before = labels[..index.min(labels.len())].iter().rposition(Option::is_some);
+            let after = labels.iter().skip(index + 1).position(Option::is_some).map(|offset| index + 1 + offset);
+            let nearest = match (before, after) {
+                (Some(before), Some(after)) if after - index < index - before => after,
+                (Some(before), _) => before,
+                (None, Some(after)) => after,
+                (None, None) => return None,
+            };
+            labels[nearest]
+        });
+    }
+}
+
+fn nearest(centroids: &[Vec<f32>], embedding: &[f32]) -> Option<(usize, f32)> {
+    centroids
+        .iter()
+        .map(|centroid| cosine(centroid, embedding))
+        .enumerate()
+        .max_by(|a, b| a.1.total_cmp(&b.1))
+}
+
+fn normalize(vector: &[f32]) -> Option<Vec<f32>> {
+    let norm = vector.iter().map(|x| x * x).sum::<f32>().sqrt();
+    if norm <= f32::EPSILON || !norm.is_finite() {
+        return None;
+    }
+    Some(vector.iter().map(|x| x / norm).collect())
+}
+
+fn cosine(a: &[f32], b: &[f32]) -> f32 {
+    let dot: f32 = a.iter().zip(b).map(|(x, y)| x * y).sum();
+    let norm_a = a.iter().map(|x| x * x).sum::<f32>().sqrt();
+    let norm_b = b.iter().map(|x| x * x).sum::<f32>().sqrt();
+    if norm_a <= f32::EPSILON || norm_b <= f32::EPSILON {
+        0.0
+    } else {
+        dot / (norm_a * norm_b)
+    }
+}
+
+fn add_assign(sum: &mut [f32], embedding: &[f32]) {
+    for (total, value) in sum.iter_mut().zip(embedding) {
+        *total += value;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// 以 `base` 方向为主、带少量扰动的向量
+    fn voice(base: usize, jitter: f32) -> Option<Vec<f32>> {
+        let mut v = vec![jitter; 8];
+        v[base] = 1.0;
+        Some(v)
+    }
+
+    fn segment(start_ms: u64) -> TranscriptionSegment {
+        TranscriptionSegment {
+            text: String::new(),
+            start_ms,
+            end_ms: start_ms + 1_000,
+            words: Vec::new(),
+            speaker: None,
+        }
+    }
+
+    #[test]
+    fn test_clusters_by_similarity() {
+        let embeddings = vec![voice(0, 0.1), voice(3, 0.1), voice(0, 0.2), None, voice(5, 0.05), voice(3, 0.15)];
+        let labels = cluster_speakers(&embeddings, &ClusterOptions::default());
+        assert_eq!(labels, vec![Some(0), Some(1), Some(0), None, Some(2), Some(1)]);
+    }
+
+    #[test]
+    fn test_max_speakers_merges_closest_clusters() {
+        let embeddings = vec![voice(0, 0.0), voice(1, 0.0), voice(2, 0.0)];
+        let options = ClusterOptions {
+            max_speakers: Some(2),
+            ..ClusterOptions::default()
+        };
+        let labels = cluster_speakers(&embeddings, &options);
+        let distinct: std::collections::HashSet<_> = labels.iter().flatten().collect();
+        assert_eq!(distinct.len(), 2);
+    }
+
+    #[test]
+    fn test_unlabelled_segments_take_nearest_label() {
+        let mut segments: Vec<_> = (0..5).map(|i| segment(i * 1_000)).collect();
+        apply_speakers(&mut segments, &[None, Some(0), None, None, Some(1)]);
+        let speakers: Vec<_> = segments.iter().map(|s| s.speaker).collect();
+        assert_eq!(speakers, vec![Some(0), Some(0), Some(0), Some(1), Some(1)]);
+    }
+}
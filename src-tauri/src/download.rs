@@ -0,0 +1,583 @@
+/// 模型文件下载
+/// 先写入 `.part` 临时文件，支持 HTTP Range 断点续传和取消，
+/// SHA-256 校验通过后再原子重命名为最终文件
+
+use sha2::{Digest, Sha256};
+use std::fmt;
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::time::Duration;
+
+/// 建立连接的超时时间
+const CONNECT_TIMEOUT: Duration = Duration::from_secs(15);
+
+/// 两次读取之间的最长间隔（连接卡住时不会无限等待）
+const READ_TIMEOUT: Duration = Duration::from_secs(60);
+
+/// 下载过程中检查取消标志的间隔
+const CANCEL_POLL_INTERVAL: Duration = Duration::from_millis(200);
+
+#[derive(Debug)]
+pub enum DownloadError {
+    /// 用户取消（临时文件保留，下次可续传）
+    Cancelled,
+    /// 校验失败（临时文件已删除）
+    ChecksumMismatch { expected: String, actual: String },
+    Http(String),
+    Io(String),
+}
+
+impl fmt::Display for DownloadError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            DownloadError::Cancelled => write!(f, "Download cancelled"),
+            DownloadError::ChecksumMismatch { expected, actual } => {
+                write!(f, "Checksum mismatch (expected {}, got {})", expected, actual)
+            }
+            DownloadError::Http(err) => write!(f, "Download failed: {}", err),
+            DownloadError::Io(err) => write!(f, "File error: {}", err),
+        }
+    }
+}
+
+impl std::error::Error for DownloadError {}
+
+impl From<std::io::Error> for DownloadError {
+    fn from(err: std::io::Error) -> Self {
+        DownloadError::Io(err.to_string())
+    }
+}
+
+impl From<reqwest::Error> for DownloadError {
+    fn from(err: reqwest::Error) -> Self {
+        DownloadError::Http(err.to_string())
+    }
+}
+
+/// 用于模型下载的 HTTP 客户端（带连接和读取超时）
+pub fn download_client() -> Result<reqwest::Client, DownloadError> {
+    Ok(reqwest::Client::builder()
+        .connect_timeout(CONNECT_TIMEOUT)
+        .read_timeout(READ_TIMEOUT)
+        .build()?)
+}
+
+/// 下载中的临时文件路径（`ggml-base.bin` -> `ggml-base.bin.part`）
+pub fn partial_path(dest: &Path) -> PathBuf {
+    let mut name = dest.file_name().map(|n| n.to_os_string()).unwrap_or_default();
+    name.push(".part");
+    dest.with_file_name(name)
+}
+
+/// 下载文件到 `dest`
+///
+/// # 参数
+/// * `expected_sha256` - 期望的 SHA-256（十六进制），None 时只记录实际哈希
+/// * `cancel` - 置为 true 后在下一个数据块处停止
+/// * `on_progress` - 进度回调 `(已下载字节, 总字节)`，总字节未知时为 0
+///
+/// 已存在的 `dest` 只有在新文件校验通过后才会被替换。
+pub async fn download_file<F>(
+    client: &reqwest::Client,
+    url: &str,
+    dest: &Path,
+    expected_sha256: Option<&str>,
+    cancel: &AtomicBool,
+    mut on_progress: F,
+) -> Result<(), DownloadError>
+where
+    F: FnMut(u64, u64),
+{
+    use futures_util::StreamExt;
+    use reqwest::header::{CONTENT_RANGE, RANGE};
+    use reqwest::StatusCode;
+    use tokio::io::{AsyncReadExt, AsyncWriteExt};
+    use tracing::{info, warn};
+
+    let part_path = partial_path(dest);
+    let mut hasher = Sha256::new();
+    let mut downloaded: u64 = 0;
+
+    // 续传：先把已下载部分计入哈希
+    if part_path.exists() {
+        let mut existing = tokio::fs::File::open(&part_path).await?;
+        let mut buffer = vec![0u8; 1024 * 1024];
+        loop {
+            let n = existing.read(&mut buffer).await?;
+            if n == 0 {
+                break;
+            }
+            hasher.update(&buffer[..n]);
+            downloaded += n as u64;
+        }
+        info!("📥 [Download] Resuming {} from {} bytes", url, downloaded);
+    }
+
+    let mut request = client.get(url);
+    if downloaded > 0 {
+        request = request.header(RANGE, format!("bytes={}-", downloaded));
+    }
+    let mut response = request.send().await?;
+
+    // 206 的起点必须等于已下载的字节数，否则拼接出的文件是错的，从头重新下载
+    if response.status() == StatusCode::PARTIAL_CONTENT {
+        let start = response
+            .headers()
+            .get(CONTENT_RANGE)
+            .and_then(|value| value.to_str().ok())
+            .and_then(content_range_start);
+        if start != Some(downloaded) {
+            warn!(
+                "⚠️  [Download] Content-Range starts at {:?}, expected {}, restarting {}",
+                start, downloaded, url
+            );
+            hasher = Sha256::new();
+            downloaded = 0;
+            response = client.get(url).send().await?;
+        }
+    }
+    let status = response.status();
+
+    let mut file = if status == StatusCode::PARTIAL_CONTENT {
+        tokio::fs::OpenOptions::new().append(true).open(&part_path).await?
+    } else if status == StatusCode::RANGE_NOT_SATISFIABLE && downloaded > 0 {
+        // 临时文件已经完整，直接校验
+        return finish_download(&part_path, dest, hasher, expected_sha256).await;
+    } else if status.is_success() {
+        // 服务器不支持 Range（或首次下载），从头开始
+        if downloaded > 0 {
+            warn!("⚠️  [Download] Server ignored range request, restarting {}", url);
+        }
+        hasher = Sha256::new();
+        downloaded = 0;
+        tokio::fs::File::create(&part_path).await?
+    } else {
+        return Err(DownloadError::Http(format!("status {}", status)));
+    };
+
+    let total = response.content_length().map(|len| len + downloaded).unwrap_or(0);
+    on_progress(downloaded, total);
+
+    let mut stream = response.bytes_stream();
+    loop {
+        // 等待数据时也响应取消，不必等到下一个数据块到达
+        let chunk = tokio::select! {
+            chunk = stream.next() => chunk,
+            _ = wait_cancelled(cancel) => None,
+        };
+        if cancel.load(Ordering::SeqCst) {
+            file.flush().await?;
+            info!("⏹️  [Download] Cancelled at {} bytes: {}", downloaded, url);
+            return Err(DownloadError::Cancelled);
+        }
+        let Some(chunk) = chunk else {
+            break;
+        };
+
+        let chunk = chunk?;
+        file.write_all(&chunk).await?;
+        hasher.update(&chunk);
+        downloaded += chunk.len() as u64;
+        on_progress(downloaded, total);
+    }
+
+    file.flush().await?;
+    file.sync_all().await?;
+    drop(file);
+
+    finish_download(&part_path, dest, hasher, expected_sha256).await
+}
+
+/// 取消标志置位后返回
+async fn wait_cancelled(cancel: &AtomicBool) {
+    while !cancel.load(Ordering::SeqCst) {
+        tokio::time::sleep(CANCEL_POLL_INTERVAL).await;
+    }
+}
+
+/// 解析 `Content-Range: bytes <start>-<end>/<total>` 的起点
+fn content_range_start(value: &str) -> Option<u64> {
+    value
+        .trim()
+        .strip_prefix("bytes ")?
+        .split('-')
+        .next()?
+        .trim()
+        .parse()
+        .ok()
+}
+
+/// 校验临时文件并重命名为最终文件
+async fn finish_download(
+    part_path: &Path,
+    dest: &Path,
+    hasher: Sha256,
+    expected_sha256: Option<&str>,
+) -> Result<(), DownloadError> {
+    use tracing::{info, warn};
+
+    let actual = to_hex(&hasher.finalize());
+
+    match expected_sha256 {
+        Some(expected) if !expected.eq_ignore_ascii_case(&actual) => {
+            let _ = tokio::fs::remove_file(part_path).await;
+            return Err(DownloadError::ChecksumMismatch {
+                expected: expected.to_string(),
+                actual,
+            });
+        }
+        Some(_) => info!("✅ [Download] SHA-256 verified: {}", actual),
+        None => warn!("⚠️  [Download] No checksum to verify against, SHA-256: {}", actual),
+    }
+
+    // rename 在同一目录内是原子的，且会替换已存在的旧文件
+    tokio::fs::rename(part_path, dest).await?;
+    Ok(())
+}
+
+/// 从 Hugging Face 的 LFS 元数据获取文件 SHA-256
+///
+/// `resolve` 地址的 HEAD 响应（不跟随重定向）在 `X-Linked-Etag` 中携带 LFS 对象的 SHA-256，
+/// 目录中没有静态哈希的文件据此校验。获取失败时返回 None。
+pub async fn fetch_lfs_sha256(url: &str) -> Option<String> {
+    use tracing::warn;
+
+    let client = reqwest::Client::builder()
+        .redirect(reqwest::redirect::Policy::none())
+        .connect_timeout(std::time::Duration::from_secs(10))
+        .timeout(std::time::Duration::from_secs(30))
+        .build()
+        .ok()?;
+
+    let response = match client.head(url).send().await {
+        Ok(response) => response,
+        Err(e) => {
+            warn!("⚠️  [Download] Failed to fetch LFS metadata for {}: {}", url, e);
+            return None;
+        }
+    };
+
+    response
+        .headers()
+        .get("x-linked-etag")
+        .and_then(|value| value.to_str().ok())
+        .and_then(parse_lfs_etag)
+}
+
+/// 解析 `X-Linked-Etag`（`"<sha256>"`，可能带弱校验前缀 `W/`）
+fn parse_lfs_etag(value: &str) -> Option<String> {
+    let value = value.trim().trim_start_matches("W/").trim_matches('"');
+    if value.len() == 64 && value.chars().all(|c| c.is_ascii_hexdigit()) {
+        Some(value.to_ascii_lowercase())
+    } else {
+        None
+    }
+}
+
+/// 计算字节的 SHA-256（十六进制）
+pub fn sha256_hex(data: &[u8]) -> String {
+    to_hex(&Sha256::digest(data))
+}
+
+fn to_hex(bytes: &[u8]) -> String {
+    bytes.iter().map(|b| format!("{:02x}", b)).collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use parking_lot::Mutex;
+    use std::sync::Arc;
+    use tokio::io::{AsyncReadExt, AsyncWriteExt};
+    use tokio::net::TcpListener;
+
+    /// 测试服务器对 Range 请求的处理方式
+    #[derive(Clone, Copy, PartialEq)]
+    enum RangeMode {
+        /// 正常返回 206
+        Honor,
+        /// 忽略 Range，返回 200 和完整内容
+        Ignore,
+        /// 返回 206，但内容从头开始（Content-Range 起点为 0）
+        Misreport,
+    }
+
+    /// 本地 HTTP 替身：返回固定内容，记录每次请求的 Range 起点
+    async fn serve(body: Vec<u8>, mode: RangeMode) -> (String, Arc<Mutex<Vec<Option<u64>>>>) {
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let url = format!("http://{}/ggml-test.bin", listener.local_addr().unwrap());
+        let ranges = Arc::new(Mutex::new(Vec::new()));
+        let recorded = ranges.clone();
+
+        tokio::spawn(async move {
+            loop {
+                let Ok((mut socket, _)) = listener.accept().await else {
+                    break;
+                };
+
+                let mut request = Vec::new();
+                let mut buffer = [0u8; 1024];
+                while !request.windows(4).any(|w| w == b"\r\n\r\n") {
+                    let n = socket.read(&mut buffer).await.unwrap();
+                    if n == 0 {
+                        break;
+                    }
+                    request.extend_from_slice(&buffer[..n]);
+                }
+
+                let request = String::from_utf8_lossy(&request).to_lowercase();
+                let range_start = request
+                    .lines()
+                    .find_map(|line| line.strip_prefix("range: bytes="))
+                    .and_then(|range| range.trim().trim_end_matches('-').parse::<u64>().ok());
+                recorded.lock().push(range_start);
+
+                let response = match range_start {
+                    Some(start) if mode == RangeMode::Honor && start >= body.len() as u64 => {
+                        b"HTTP/1.1 416 Range Not Satisfiable\r\ncontent-length: 0\r\nconnection: close\r\n\r\n".to_vec()
+                    }
+                    Some(start) if mode != RangeMode::Ignore => {
+                        let start = if mode == RangeMode::Misreport { 0 } else { start as usize };
+                        let slice = &body[start..];
+                        let mut response = format!(
+                            "HTTP/1.1 206 Partial Content\r\ncontent-length: {}\r\ncontent-range: bytes {}-{}/{}\r\nconnection: close\r\n\r\n",
+                            slice.len(),
+                            start,
+                            body.len() - 1,
+                            body.len()
+                        )
+                        .into_bytes();
+                        response.extend_from_slice(slice);
+                        response
+                    }
+                    _ => {
+                        let mut response = format!(
+                            "HTTP/1.1 200 OK\r\ncontent-length: {}\r\nconnection: close\r\n\r\n",
+                            body.len()
+                        )
+                        .into_bytes();
+                        response.extend_from_slice(&body);
+                        response
+                    }
+                };
+
+                let _ = socket.write_all(&response).await;
+                let _ = socket.shutdown().await;
+            }
+        });
+
+        (url, ranges)
+    }
+
+    #[test]
+    fn test_parse_lfs_etag() {
+        let sha = "be07e048e1e599ad46341c8d2a135645097a538221678b7acdd1b1919c6e1b21";
+        assert_eq!(parse_lfs_etag(&format!("\"{}\"", sha)).as_deref(), Some(sha));
+        assert_eq!(parse_lfs_etag(&format!("W/\"{}\"", sha.to_uppercase())).as_deref(), Some(sha));
+        // 普通 git 对象的 ETag 是 40 位 SHA-1，不能当作文件哈希
+        assert_eq!(parse_lfs_etag("\"7d99f41a10525d0206bddadd86760181fa920438\""), None);
+    }
+
+    fn test_body() -> Vec<u8> {
+        (0..200_000u32).map(|i| (i % 251) as u8).collect()
+    }
+
+    fn temp_dest(name: &str) -> PathBuf {
+        let dir = std::env::temp_dir().join(format!("lingcode-download-{}-{}", name, std::process::id()));
+        let _ = std::fs::remove_dir_all(&dir);
+        std::fs::create_dir_all(&dir).unwrap();
+        dir.join("ggml-test.bin")
+    }
+
+    #[tokio::test]
+    async fn test_download_verifies_and_renames() {
+        let body = test_body();
+        let (url, _) = serve(body.clone(), RangeMode::Honor).await;
+        let dest = temp_dest("full");
+        std::fs::write(&dest, b"old model").unwrap();
+
+        let mut last_progress = (0, 0);
+        download_file(
+            &reqwest::Client::new(),
+            &url,
+            &dest,
+            Some(&sha256_hex(&body)),
+            &AtomicBool::new(false),
+            |downloaded, total| last_progress = (downloaded, total),
+        )
+        .await
+        .unwrap();
+
+        assert_eq!(std::fs::read(&dest).unwrap(), body);
+        assert!(!partial_path(&dest).exists());
+        assert_eq!(last_progress, (body.len() as u64, body.len() as u64));
+    }
+
+    #[tokio::test]
+    async fn test_download_resumes_partial_file() {
+        let body = test_body();
+        let (url, ranges) = serve(body.clone(), RangeMode::Honor).await;
+        let dest = temp_dest("resume");
+        std::fs::write(partial_path(&dest), &body[..75_000]).unwrap();
+
+        download_file(
+            &reqwest::Client::new(),
+            &url,
+            &dest,
+            Some(&sha256_hex(&body)),
+            &AtomicBool::new(false),
+            |_, _| {},
+        )
+        .await
+        .unwrap();
+
+        assert_eq!(ranges.lock().as_slice(), &[Some(75_000)]);
+        assert_eq!(std::fs::read(&dest).unwrap(), body);
+    }
+
+    #[tokio::test]
+    async fn test_download_restarts_when_range_ignored() {
+        let body = test_body();
+        let (url, _) = serve(body.clone(), RangeMode::Ignore).await;
+        let dest = temp_dest("norange");
+        std::fs::write(partial_path(&dest), b"stale partial data").unwrap();
+
+        download_file(
+            &reqwest::Client::new(),
+            &url,
+            &dest,
+            Some(&sha256_hex(&body)),
+            &AtomicBool::new(false),
+            |_, _| {},
+        )
+        .await
+        .unwrap();
+
+        assert_eq!(std::fs::read(&dest).unwrap(), body);
+    }
+
+    #[tokio::test]
+    async fn test_download_restarts_when_content_range_mismatches() {
+        let body = test_body();
+        let (url, ranges) = serve(body.clone(), RangeMode::Misreport).await;
+        let dest = temp_dest("badrange");
+        std::fs::write(partial_path(&dest), &body[..75_000]).unwrap();
+
+        download_file(
+            &reqwest::Client::new(),
+            &url,
+            &dest,
+            Some(&sha256_hex(&body)),
+            &AtomicBool::new(false),
+            |_, _| {},
+        )
+        .await
+        .unwrap();
+
+        // 第二次请求不带 Range，从头下载
+        assert_eq!(ranges.lock().as_slice(), &[Some(75_000), None]);
+        assert_eq!(std::fs::read(&dest).unwrap(), body);
+    }
+
+    #[test]
+    fn test_content_range_start() {
+        assert_eq!(content_range_start("bytes 75000-199999/200000"), Some(75_000));
+        assert_eq!(content_range_start("bytes 0-9/*"), Some(0));
+        assert_eq!(content_range_start("bytes */200000"), None);
+    }
+
+    #[tokio::test]
+    async fn test_complete_partial_file_is_verified() {
+        let body = test_body();
+        let (url, _) = serve(body.clone(), RangeMode::Honor).await;
+        let dest = temp_dest("complete");
+        std::fs::write(partial_path(&dest), &body).unwrap();
+
+        download_file(
+            &reqwest::Client::new(),
+            &url,
+            &dest,
+            Some(&sha256_hex(&body)),
+            &AtomicBool::new(false),
+            |_, _| {},
+        )
+        .await
+        .unwrap();
+
+        assert_eq!(std::fs::read(&dest).unwrap(), body);
+    }
+
+    #[tokio::test]
+    async fn test_checksum_mismatch_keeps_existing_model() {
+        let body = test_body();
+        let (url, _) = serve(body, RangeMode::Honor).await;
+        let dest = temp_dest("mismatch");
+        std::fs::write(&dest, b"old model").unwrap();
+
+        let result = download_file(
+            &reqwest::Client::new(),
+            &url,
+            &dest,
+            Some(&sha256_hex(b"something else")),
+            &AtomicBool::new(false),
+            |_, _| {},
+        )
+        .await;
+
+        assert!(matches!(result, Err(DownloadError::ChecksumMismatch { .. })));
+        assert_eq!(std::fs::read(&dest).unwrap(), b"old model");
+        assert!(!partial_path(&dest).exists());
+    }
+
+    #[tokio::test]
+    async fn test_cancelled_download_keeps_partial_file() {
+        let body = test_body();
+        let (url, _) = serve(body, RangeMode::Honor).await;
+        let dest = temp_dest("cancel");
+
+        let result = download_file(
+            &reqwest::Client::new(),
+            &url,
+            &dest,
+            None,
+            &AtomicBool::new(true),
+            |_, _| {},
+        )
+        .await;
+
+        assert!(matches!(result, Err(DownloadError::Cancelled)));
+        assert!(!dest.exists());
+        assert!(partial_path(&dest).exists());
+    }
+
+    #[tokio::test]
+    async fn test_cancel_while_server_stalls() {
+        // 发送部分内容后不再响应，取消必须在等待数据时生效
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let url = format!("http://{}/ggml-test.bin", listener.local_addr().unwrap());
+        tokio::spawn(async move {
+            let (mut socket, _) = listener.accept().await.unwrap();
+            let mut buffer = [0u8; 1024];
+            let _ = socket.read(&mut buffer).await;
+            let _ = socket
+                .write_all(b"HTTP/1.1 200 OK\r\ncontent-length: 1000\r\n\r\n0123456789")
+                .await;
+            tokio::time::sleep(std::time::Duration::from_secs(30)).await;
+        });
+
+        let dest = temp_dest("stall");
+        let cancel = Arc::new(AtomicBool::new(false));
+        let flag = cancel.clone();
+        tokio::spawn(async move {
+            tokio::time::sleep(std::time::Duration::from_millis(300)).await;
+            flag.store(true, Ordering::SeqCst);
+        });
+
+        let started = std::time::Instant::now();
+        let result = download_file(&reqwest::Client::new(), &url, &dest, None, &cancel, |_, _| {}).await;
+
+        assert!(matches!(result, Err(DownloadError::Cancelled)));
+        assert!(started.elapsed() < std::time::Duration::from_secs(5));
+        assert_eq!(std::fs::read(partial_path(&dest)).unwrap(), b"0123456789");
+    }
+}
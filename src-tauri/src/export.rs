@@ -0,0 +1,621 @@
+/// 转录导出
+/// 把带时间戳的段落导出为 SRT / WebVTT 字幕、JSON、带时间戳的纯文本和 Markdown；
+/// 字幕按行宽、行数和单条时长限制重新切分
+
+use chrono::{DateTime, Local, Utc};
+use serde::{Deserialize, Serialize};
+
+use crate::whisper::TranscriptionSegment;
+
+/// 导出格式
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum ExportFormat {
+    Srt,
+    Vtt,
+    Json,
+    /// 带时间戳的纯文本
+    Txt,
+    #[serde(alias = "md")]
+    Markdown,
+}
+
+impl ExportFormat {
+    /// 文件扩展名
+    pub fn extension(self) -> &'static str {
+        match self {
+            Self::Srt => "srt",
+            Self::Vtt => "vtt",
+            Self::Json => "json",
+            Self::Txt => "txt",
+            Self::Markdown => "md",
+        }
+    }
+}
+
+/// 字幕切分限制
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct CaptionOptions {
+    /// 每行最大宽度（中日韩文字按 2 计）
+    pub max_line_chars: usize,
+    /// 每条字幕最多行数
+    pub max_lines: usize,
+    /// 单条字幕最短显示时长（毫秒），不足时延长到下一条开始前
+    pub min_duration_ms: u64,
+    /// 单条字幕最长显示时长（毫秒），超过时拆分
+    pub max_duration_ms: u64,
+}
+
+impl Default for CaptionOptions {
+    fn default() -> Self {
+        Self {
+            max_line_chars: 42,
+            max_lines: 2,
+            min_duration_ms: 1_000,
+            max_duration_ms: 7_000,
+        }
+    }
+}
+
+/// 待导出的转录（单条历史记录或一次文件转录）
+#[derive(Debug, Clone, Default)]
+pub struct ExportDocument {
+    pub title: Option<String>,
+    pub language: Option<String>,
+    pub created_at: Option<DateTime<Utc>>,
+    pub segments: Vec<TranscriptionSegment>,
+}
+
+impl ExportDocument {
+    fn duration_ms(&self) -> u64 {
+        self.segments.iter().map(|s| s.end_ms).max().unwrap_or(0)
+    }
+}
+
+/// 一条字幕
+#[derive(Debug, Clone, PartialEq)]
+pub struct Caption {
+    pub start_ms: u64,
+    pub end_ms: u64,
+    pub lines: Vec<String>,
+    pub speaker: Option<u32>,
+}
+
+/// 说话人显示名称
+pub fn speaker_label(speaker: u32) -> String {
+    format!("发言人 {}", speaker + 1)
+}
+
+/// 段落文本，带说话人时加上前缀
+fn labelled_text(segment: &TranscriptionSegment) -> String {
+    match segment.speaker {
+        Some(speaker) => format!("{}：{}", speaker_label(speaker), segment.text.trim()),
+        None => segment.text.trim().to_string(),
+    }
+}
+
+/// 按格式导出
+pub fn export(document: &ExportDocument, format: ExportFormat, options: &CaptionOptions) -> String {
+    match format {
+        // SRT 没有说话人标记，说话人变化时在字幕文本前加名称
+        ExportFormat::Srt => to_srt(&build_captions(&document.segments, options, true)),
+        ExportFormat::Vtt => to_vtt(&build_captions(&document.segments, options, false)),
+        ExportFormat::Json => to_json(document),
+        ExportFormat::Txt => to_text(document),
+        ExportFormat::Markdown => to_markdown(document),
+    }
+}
+
+fn to_srt(captions: &[Caption]) -> String {
+    let mut output = String::new();
+    for (index, caption) in captions.iter().enumerate() {
+        output.push_str(&format!(
+            "{}\n{} --> {}\n{}\n\n",
+            index + 1,
+            format_timestamp(caption.start_ms, ','),
+            format_timestamp(caption.end_ms, ','),
+            caption.lines.join("\n")
+        ));
+    }
+    output
+}
+
+fn to_vtt(captions: &[Caption]) -> String {
+    let mut output = String::from("WEBVTT\n\n");
+    for caption in captions {
+        // WebVTT 用 voice 标签标注说话人
+        let voice = caption
+            .speaker
+            .map(|speaker| format!("<v {}>", speaker_label(speaker)))
+            .unwrap_or_default();
+        output.push_str(&format!(
+            "{} --> {}\n{}{}\n\n",
+            format_timestamp(caption.start_ms, '.'),
+            format_timestamp(caption.end_ms, '.'),
+            voice,
+            caption.lines.join("\n")
+        ));
+    }
+    output
+}
+
+fn to_json(document: &ExportDocument) -> String {
+    let segments: Vec<_> = document
+        .segments
+        .iter()
+        .enumerate()
+        .map(|(id, segment)| {
+            serde_json::json!({
+                "id": id,
+                "start_ms": segment.start_ms,
+                "end_ms": segment.end_ms,
+                "text": segment.text,
+                "speaker": segment.speaker,
+                "words": segment.words,
+            })
+        })
+        .collect();
+
+    let value = serde_json::json!({
+        "title": document.title,
+        "language": document.language,
+        "created_at": document.created_at.map(|t| t.to_rfc3339()),
+        "duration_ms": document.duration_ms(),
+        "segments": segments,
+    });
+
+    serde_json::to_string_pretty(&value).unwrap_or_default()
+}
+
+fn to_text(document: &ExportDocument) -> String {
+    document
+        .segments
+        .iter()
+        .map(|segment| format!("[{}] {}\n", format_clock(segment.start_ms), labelled_text(segment)))
+        .collect()
+}
+
+fn to_markdown(document: &ExportDocument) -> String {
+    let mut output = format!("# {}\n\n", document.title.as_deref().unwrap_or("转录"));
+
+    if let Some(created_at) = document.created_at {
+        output.push_str(&format!(
+            "- 时间：{}\n",
+            created_at.with_timezone(&Local).format("%Y-%m-%d %H:%M")
+        ));
+    }
+    if let Some(language) = &document.language {
+        output.push_str(&format!("- 语言：{}\n", language));
+    }
+    output.push_str(&format!("- 时长：{}\n\n", format_clock(document.duration_ms())));
+
+    for segment in &document.segments {
+        output.push_str(&format!("**[{}]** {}\n\n", format_clock(segment.start_ms), labelled_text(segment)));
+    }
+
+    output
+}
+
+/// `HH:MM:SS,mmm`（SRT）或 `HH:MM:SS.mmm`（WebVTT）
+fn format_timestamp(ms: u64, separator: char) -> String {
+    format!("{}{}{:03}", format_clock(ms), separator, ms % 1000)
+}
+
+/// `HH:MM:SS`
+fn format_clock(ms: u64) -> String {
+    let secs = ms / 1000;
+    format!("{:02}:{:02}:{:02}", secs / 3600, secs / 60 % 60, secs % 60)
+}
+
+/// 把段落切分为满足行宽、行数和时长限制的字幕
+///
+/// `inline_speakers` 时在说话人变化处的字幕文本前加上说话人名称（计入行宽）
+pub fn build_captions(segments: &[TranscriptionSegment], options: &CaptionOptions, inline_speakers: bool) -> Vec<Caption> {
+    let max_line = options.max_line_chars.max(1);
+    let max_width = max_line * options.max_lines.max(1);
+    let max_duration = options.max_duration_ms.max(1);
+
+    let mut captions: Vec<Caption> = Vec::new();
+    let mut previous_speaker = None;
+    for segment in segments {
+        let label = segment
+            .speaker
+            .filter(|_| inline_speakers && segment.speaker != previous_speaker)
+            .map(|speaker| format!("{}：", speaker_label(speaker)));
+        previous_speaker = segment.speaker;
+
+        for (units, start_ms, end_ms) in split_segment(segment, label.as_deref(), max_width, max_duration) {
+            let lines = wrap_lines(&units, max_line);
+            if lines.is_empty() {
+                continue;
+            }
+            captions.push(Caption {
+                start_ms,
+                end_ms,
+                lines,
+                speaker: segment.speaker,
+            });
+        }
+    }
+
+    // 过短的字幕延长显示，但不与下一条重叠
+    for i in 0..captions.len() {
+        let next_start = captions.get(i + 1).map_or(u64::MAX, |next| next.start_ms);
+        let caption = &mut captions[i];
+        if caption.end_ms - caption.start_ms < options.min_duration_ms {
+            caption.end_ms = (caption.start_ms + options.min_duration_ms).min(next_start.max(caption.end_ms));
+        }
+    }
+
+    captions
+}
+
+/// 排版单元：一个词（以空格分词的语言）或一个中日韩字符
+#[derive(Debug, Clone)]
+struct Unit {
+    text: String,
+    /// 与前一单元之间是否有空格
+    space_before: bool,
+}
+
+impl Unit {
+    fn width(&self) -> usize {
+        display_width(&self.text)
+    }
+}
+
+/// 切分一个段落，返回 (单元, 开始, 结束)；`label` 作为第一条字幕的首个单元
+fn split_segment(
+    segment: &TranscriptionSegment,
+    label: Option<&str>,
+    max_width: usize,
+    max_duration: u64,
+) -> Vec<(Vec<Unit>, u64, u64)> {
+    let mut pieces = split_segment_text(segment, label.map_or(0, display_width), max_width, max_duration);
+    if let (Some(label), Some((units, _, _))) = (label, pieces.first_mut()) {
+        units.insert(
+            0,
+            Unit {
+                text: label.to_string(),
+                space_before: false,
+            },
+        );
+        if let Some(first) = units.get_mut(1) {
+            first.space_before = false;
+        }
+    }
+    pieces
+}
+
+/// 切分段落文本；第一条字幕预留 `reserved` 宽度给说话人名称
+fn split_segment_text(
+    segment: &TranscriptionSegment,
+    reserved: usize,
+    max_width: usize,
+    max_duration: u64,
+) -> Vec<(Vec<Unit>, u64, u64)> {
+    let start_ms = segment.start_ms;
+    let end_ms = segment.end_ms.max(start_ms);
+
+    // 有词级时间戳时按词的实际时间切分
+    if !segment.words.is_empty() {
+        let mut pieces = Vec::new();
+        let mut current: Vec<Unit> = Vec::new();
+        let mut current_start = start_ms;
+        let mut current_end = start_ms;
+
+        for word in &segment.words {
+            let mut unit = Unit {
+                text: word.text.clone(),
+                space_before: current.last().is_some_and(|last| needs_space(&last.text, &word.text)),
+            };
+            let limit = if pieces.is_empty() { max_width.saturating_sub(reserved) } else { max_width };
+            let width = units_width(&current) + usize::from(unit.space_before) + unit.width();
+            let too_long = word.end_ms.saturating_sub(current_start) > max_duration;
+
+            if !current.is_empty() && (width > limit || too_long) {
+                pieces.push((std::mem::take(&mut current), current_start, current_end));
+            }
+            if current.is_empty() {
+                current_start = word.start_ms;
+                unit.space_before = false;
+            }
+            current.push(unit);
+            current_end = word.end_ms.max(current_start);
+        }
+        if !current.is_empty() {
+            pieces.push((current, current_start, current_end));
+        }
+        return pieces;
+    }
+
+    // 没有词级时间时，按宽度均分并按宽度比例分配时间
+    let units = split_units(&segment.text);
+    let total_width = units_width(&units);
+    if total_width == 0 {
+        return Vec::new();
+    }
+
+    let by_width = (total_width + reserved).div_ceil(max_width);
+    let by_duration = (end_ms - start_ms).div_ceil(max_duration) as usize;
+    let n_pieces = by_width.max(by_duration).max(1);
+    let target = (total_width + reserved).div_ceil(n_pieces);
+
+    let mut groups: Vec<Vec<Unit>> = Vec::new();
+    let mut current: Vec<Unit> = Vec::new();
+    for unit in units {
+        let reserved = if groups.is_empty() { reserved } else { 0 };
+        let width = reserved + units_width(&current) + usize::from(unit.space_before) + unit.width();
+        if !current.is_empty() && width > target {
+            groups.push(std::mem::take(&mut current));
+        }
+        current.push(unit);
+    }
+    groups.push(current);
+
+    let mut pieces = Vec::with_capacity(groups.len());
+    let mut consumed = 0;
+    for mut group in groups {
+        if let Some(first) = group.first_mut() {
+            first.space_before = false;
+        }
+        let width = units_width(&group);
+        let piece_start = start_ms + (end_ms - start_ms) * consumed as u64 / total_width as u64;
+        consumed += width;
+        let piece_end = start_ms + (end_ms - start_ms) * consumed as u64 / total_width as u64;
+        pieces.push((group, piece_start, piece_end));
+    }
+    pieces
+}
+
+/// 把一条字幕的单元折行，各行宽度尽量均衡
+fn wrap_lines(units: &[Unit], max_line: usize) -> Vec<String> {
+    let total = units_width(units);
+    if total == 0 {
+        return Vec::new();
+    }
+    let target = total.div_ceil(total.div_ceil(max_line));
+
+    let mut lines = Vec::new();
+    let mut line = String::new();
+    let mut width = 0;
+    for unit in units {
+        let extra = usize::from(unit.space_before && !line.is_empty()) + unit.width();
+        if !line.is_empty() && width + extra > target {
+            lines.push(std::mem::take(&mut line));
+            width = 0;
+        }
+        if unit.space_before && !line.is_empty() {
+            line.push(' ');
+            width += 1;
+        }
+        line.push_str(&unit.text);
+        width += unit.width();
+    }
+    if !line.is_empty() {
+        lines.push(line);
+    }
+    lines
+}
+
+/// 把文本拆为排版单元：空格分隔的词、单个中日韩字符；标点附着在前一单元上
+fn split_units(text: &str) -> Vec<Unit> {
+    let mut units: Vec<Unit> = Vec::new();
+    let mut word = String::new();
+    let mut space_before = false;
+
+    let flush = |units: &mut Vec<Unit>, word: &mut String, space_before: &mut bool| {
+        if !word.is_empty() {
+            units.push(Unit {
+                text: std::mem::take(word),
+                space_before: *space_before && !units.is_empty(),
+            });
+            *space_before = false;
+        }
+    };
+
+    for c in text.trim().chars() {
+        if c.is_whitespace() {
+            flush(&mut units, &mut word, &mut space_before);
+            space_before = true;
+        } else if is_punctuation(c) && word.is_empty() && !space_before {
+            match units.last_mut() {
+                Some(last) => last.text.push(c),
+                None => word.push(c),
+            }
+        } else if is_wide(c) {
+            flush(&mut units, &mut word, &mut space_before);
+            word.push(c);
+            flush(&mut units, &mut word, &mut space_before);
+        } else {
+            word.push(c);
+        }
+    }
+    flush(&mut units, &mut word, &mut space_before);
+
+    units
+}
+
+fn units_width(units: &[Unit]) -> usize {
+    units
+        .iter()
+        .enumerate()
+        .map(|(i, unit)| unit.width() + usize::from(i > 0 && unit.space_before))
+        .sum()
+}
+
+/// 相邻两个词之间是否需要空格（中日韩文字之间不加空格）
+fn needs_space(previous: &str, next: &str) -> bool {
+    let previous_wide = previous.chars().last().is_some_and(is_wide);
+    let next_wide = next.chars().next().is_some_and(is_wide);
+    !previous_wide && !next_wide
+}
+
+/// 显示宽度（中日韩文字和全角符号按 2 计）
+pub fn display_width(text: &str) -> usize {
+    text.chars().map(|c| if is_wide(c) { 2 } else { 1 }).sum()
+}
+
+fn is_wide(c: char) -> bool {
+    matches!(
+        c as u32,
+        0x1100..=0x115F
+            | 0x2E80..=0xA4CF
+            | 0xAC00..=0xD7A3
+            | 0xF900..=0xFAFF
+            | 0xFE30..=0xFE4F
+            | 0xFF00..=0xFF60
+            | 0xFFE0..=0xFFE6
+            | 0x20000..=0x3FFFD
+    )
+}
+
+fn is_punctuation(c: char) -> bool {
+    c.is_ascii_punctuation() || matches!(c, '，' | '。' | '、' | '？' | '！' | '：' | '；' | '”' | '’' | '）' | '》' | '…')
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::whisper::WordTiming;
+
+    fn segment(text: &str, start_ms: u64, end_ms: u64) -> TranscriptionSegment {
+        TranscriptionSegment {
+            text: text.to_string(),
+            start_ms,
+            end_ms,
+            words: Vec::new(),
+            speaker: None,
+        }
+    }
+
+    fn document(segments: Vec<TranscriptionSegment>) -> ExportDocument {
+        ExportDocument {
+            title: Some("周会".to_string()),
+            language: Some("zh".to_string()),
+            created_at: None,
+            segments,
+        }
+    }
+
+    #[test]
+    fn test_srt_and_vtt() {
+        let doc = document(vec![segment("你好。", 1_000, 2_500), segment("Hello world", 3_723_004, 3_725_000)]);
+        let options = CaptionOptions::default();
+
+        assert_eq!(
+            export(&doc, ExportFormat::Srt, &options),
+            "1\n00:00:01,000 --> 00:00:02,500\n你好。\n\n2\n01:02:03,004 --> 01:02:05,000\nHello world\n\n"
+        );
+        assert!(export(&doc, ExportFormat::Vtt, &options)
+            .starts_with("WEBVTT\n\n00:00:01.000 --> 00:00:02.500\n你好。\n\n"));
+    }
+
+    #[test]
+    fn test_long_segment_is_split_by_width_and_duration() {
+        let text = "今天我们讨论一下下个季度的产品规划，包括新功能的优先级、发布节奏以及各个团队之间的协作方式。";
+        let options = CaptionOptions::default();
+        let captions = build_captions(&[segment(text, 0, 20_000)], &options, false);
+
+        assert!(captions.len() >= 3);
+        for caption in &captions {
+            assert!(caption.lines.len() <= options.max_lines);
+            assert!(caption.lines.iter().all(|line| display_width(line) <= options.max_line_chars));
+            assert!(caption.end_ms - caption.start_ms <= options.max_duration_ms);
+        }
+        assert_eq!(captions[0].start_ms, 0);
+        assert!(captions.windows(2).all(|pair| pair[0].end_ms <= pair[1].start_ms));
+        let joined: String = captions.iter().flat_map(|c| c.lines.iter().cloned()).collect();
+        assert_eq!(joined, text);
+    }
+
+    #[test]
+    fn test_latin_lines_break_at_spaces() {
+        let options = CaptionOptions {
+            max_line_chars: 20,
+            ..CaptionOptions::default()
+        };
+        let captions = build_captions(&[segment("the quick brown fox jumps over", 0, 4_000)], &options, false);
+
+        assert_eq!(captions.len(), 1);
+        assert_eq!(captions[0].lines, vec!["the quick brown", "fox jumps over"]);
+    }
+
+    #[test]
+    fn test_word_timings_drive_caption_boundaries() {
+        let words: Vec<WordTiming> = (0..6)
+            .map(|i| WordTiming {
+                text: format!("word{}", i),
+                start_ms: i * 2_000,
+                end_ms: i * 2_000 + 1_500,
+            })
+            .collect();
+        let segment = TranscriptionSegment {
+            text: String::new(),
+            start_ms: 0,
+            end_ms: 11_500,
+            words,
+            speaker: None,
+        };
+        let captions = build_captions(&[segment], &CaptionOptions::default(), false);
+
+        assert_eq!(captions.len(), 2);
+        assert_eq!((captions[0].start_ms, captions[0].end_ms), (0, 5_500));
+        assert_eq!(captions[0].lines, vec!["word0 word1 word2".to_string()]);
+        assert_eq!(captions[1].start_ms, 6_000);
+    }
+
+    #[test]
+    fn test_min_duration_does_not_overlap() {
+        let captions = build_captions(
+            &[segment("嗯", 0, 200), segment("好的", 600, 800)],
+            &CaptionOptions::default(),
+            false,
+        );
+        assert_eq!((captions[0].start_ms, captions[0].end_ms), (0, 600));
+        assert_eq!((captions[1].start_ms, captions[1].end_ms), (600, 1_600));
+    }
+
+    #[test]
+    fn test_speaker_labels() {
+        let mut first = segment("大家好", 0, 1_500);
+        first.speaker = Some(0);
+        let mut second = segment("我补充一点", 2_000, 3_500);
+        second.speaker = Some(0);
+        let mut third = segment("好的", 4_000, 5_000);
+        third.speaker = Some(1);
+        let doc = document(vec![first, second, third]);
+        let options = CaptionOptions::default();
+
+        // SRT 只在说话人变化处加名称
+        let srt = export(&doc, ExportFormat::Srt, &options);
+        assert!(srt.contains("\n发言人 1：大家好\n"));
+        assert!(srt.contains("\n我补充一点\n"));
+        assert!(srt.contains("\n发言人 2：好的\n"));
+
+        let vtt = export(&doc, ExportFormat::Vtt, &options);
+        assert!(vtt.contains("<v 发言人 1>我补充一点\n"));
+
+        assert!(export(&doc, ExportFormat::Txt, &options).contains("[00:00:04] 发言人 2：好的\n"));
+        let json: serde_json::Value = serde_json::from_str(&export(&doc, ExportFormat::Json, &options)).unwrap();
+        assert_eq!(json["segments"][2]["speaker"], 1);
+    }
+
+    #[test]
+    fn test_text_markdown_and_json() {
+        let doc = document(vec![segment("第一句", 0, 1_000), segment("第二句", 65_000, 66_000)]);
+        let options = CaptionOptions::default();
+
+        assert_eq!(export(&doc, ExportFormat::Txt, &options), "[00:00:00] 第一句\n[00:01:05] 第二句\n");
+
+        let markdown = export(&doc, ExportFormat::Markdown, &options);
+        assert!(markdown.starts_with("# 周会\n\n"));
+        assert!(markdown.contains("- 时长：00:01:06\n"));
+        assert!(markdown.contains("**[00:01:05]** 第二句\n"));
+
+        let json: serde_json::Value = serde_json::from_str(&export(&doc, ExportFormat::Json, &options)).unwrap();
+        assert_eq!(json["duration_ms"], 66_000);
+        assert_eq!(json["segments"][1]["text"], "第二句");
+    }
+}
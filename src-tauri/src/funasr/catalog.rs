@@ -0,0 +1,237 @@
+/// FunASR 模型目录
+/// 维护各模型对应的 ModelScope 仓库和必需文件清单，直接检查 ModelScope 缓存目录
+/// 判断模型是否已下载，不需要启动 Python
+
+use std::path::{Path, PathBuf};
+
+/// ModelScope 上的单个模型仓库
+#[derive(Debug, Clone, Copy)]
+pub struct ModelScopeRepo {
+    /// 仓库 ID（`组织/模型`）
+    pub id: &'static str,
+    /// 加载模型必需的文件（相对仓库目录）
+    pub required_files: &'static [&'static str],
+}
+
+impl ModelScopeRepo {
+    /// 仓库中单个文件的直接下载地址（主分支）
+    pub fn file_url(&self, file: &str) -> String {
+        format!("https://www.modelscope.cn/models/{}/resolve/master/{}", self.id, file)
+    }
+}
+
+/// FunASR pytorch 模型仓库共有的文件
+const FUNASR_REPO_FILES: &[&str] = &["configuration.json", "config.yaml", "model.pt"];
+
+const PARAFORMER_ZH_REPO: ModelScopeRepo = ModelScopeRepo {
+    id: "damo/speech_paraformer-large-vad-punc_asr_nat-zh-cn-16k-common-vocab8404-pytorch",
+    required_files: FUNASR_REPO_FILES,
+};
+
+const PARAFORMER_LARGE_REPO: ModelScopeRepo = ModelScopeRepo {
+    id: "iic/speech_paraformer-large_asr_nat-zh-cn-16k-common-vocab8404-pytorch",
+    required_files: FUNASR_REPO_FILES,
+};
+
+const SENSEVOICE_SMALL_REPO: ModelScopeRepo = ModelScopeRepo {
+    id: "iic/SenseVoiceSmall",
+    required_files: FUNASR_REPO_FILES,
+};
+
+/// VAD 模型（所有模型共用）
+const FSMN_VAD_REPO: ModelScopeRepo = ModelScopeRepo {
+    id: "damo/speech_fsmn_vad_zh-cn-16k-common-pytorch",
+    required_files: FUNASR_REPO_FILES,
+};
+
+/// 标点模型（Paraformer 使用，SenseVoice 自带标点）
+const CT_PUNC_REPO: ModelScopeRepo = ModelScopeRepo {
+    id: "damo/punc_ct-transformer_zh-cn-common-vocab272727-pytorch",
+    required_files: FUNASR_REPO_FILES,
+};
+
+/// 目录中的单个 FunASR 模型
+#[derive(Debug, Clone, Copy)]
+pub struct FunASRModelSpec {
+    /// 模型名称（设置中保存的值）
+    pub name: &'static str,
+    /// 主模型仓库
+    pub repo: ModelScopeRepo,
+    /// 依赖的 VAD / 标点模型
+    pub dependencies: &'static [ModelScopeRepo],
+    /// 下载大小（MB，含依赖）
+    pub size_mb: u64,
+    pub speed: &'static str,
+    pub accuracy: &'static str,
+    pub description: &'static str,
+    pub is_recommended: bool,
+}
+
+impl FunASRModelSpec {
+    /// 下载地址
+    pub fn download_url(&self) -> String {
+        format!("modelscope://{}", self.repo.id)
+    }
+
+    /// 主模型和依赖模型
+    pub fn repos(&self) -> impl Iterator<Item = &ModelScopeRepo> {
+        std::iter::once(&self.repo).chain(self.dependencies)
+    }
+}
+
+/// 所有可下载的 FunASR 模型
+pub const FUNASR_MODEL_SPECS: &[FunASRModelSpec] = &[
+    FunASRModelSpec {
+        name: "paraformer-zh",
+        repo: PARAFORMER_ZH_REPO,
+        dependencies: &[FSMN_VAD_REPO, CT_PUNC_REPO],
+        size_mb: 220,
+        speed: "快速",
+        accuracy: "高精度（中文）",
+        description: "阿里 FunASR 中文识别模型，专为中文优化",
+        is_recommended: true,
+    },
+    FunASRModelSpec {
+        name: "paraformer-large",
+        repo: PARAFORMER_LARGE_REPO,
+        dependencies: &[FSMN_VAD_REPO, CT_PUNC_REPO],
+        size_mb: 380,
+        speed: "较快",
+        accuracy: "极高精度（中文）",
+        description: "FunASR 大型中文模型，更高精度",
+        is_recommended: false,
+    },
+    FunASRModelSpec {
+        name: "sensevoice-small",
+        repo: SENSEVOICE_SMALL_REPO,
+        dependencies: &[FSMN_VAD_REPO],
+        size_mb: 160,
+        speed: "快速",
+        accuracy: "高精度（多语言+情感）",
+        description: "支持多语言和情感识别",
+        is_recommended: false,
+    },
+];
+
+/// 按名称查找 FunASR 模型
+pub fn find_funasr_model(name: &str) -> Option<&'static FunASRModelSpec> {
+    FUNASR_MODEL_SPECS.iter().find(|spec| spec.name == name)
+}
+
+/// 模型在磁盘上的状态
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct ModelPresence {
+    /// 主模型和依赖模型的必需文件都存在
+    pub downloaded: bool,
+    /// 已存在的仓库目录占用的磁盘空间（字节，含依赖）
+    pub disk_bytes: u64,
+    /// 缺失或不完整的仓库
+    pub missing: Vec<&'static str>,
+}
+
+/// ModelScope 缓存根目录，与 Python 脚本一致：`$MODELSCOPE_CACHE`，默认 `~/.cache/modelscope`
+pub fn modelscope_cache_root(home_dir: Option<&Path>) -> Option<PathBuf> {
+    match std::env::var("MODELSCOPE_CACHE") {
+        Ok(dir) if !dir.trim().is_empty() => match (dir.strip_prefix("~/"), home_dir) {
+            (Some(rest), Some(home)) => Some(home.join(rest)),
+            _ => Some(PathBuf::from(dir)),
+        },
+        _ => home_dir.map(|home| home.join(".cache").join("modelscope")),
+    }
+}
+
+/// 仓库在缓存中的目录
+///
+/// 指定 cache_dir 下载时是 `{root}/{org}/{name}`，默认位置是 `{root}/hub/models/{org}/{name}`，
+/// 旧版 ModelScope 使用 `{root}/hub/{org}/{name}`；取第一个包含全部必需文件的目录
+pub fn find_repo_dir(cache_root: &Path, repo: &ModelScopeRepo) -> Option<PathBuf> {
+    let candidates = [
+        cache_root.join(repo.id),
+        cache_root.join("hub").join("models").join(repo.id),
+        cache_root.join("hub").join(repo.id),
+    ];
+    candidates.into_iter().find(|dir| is_repo_complete(dir, repo))
+}
+
+/// 必需文件都存在且非空（下载中断时可能留下空文件）
+fn is_repo_complete(dir: &Path, repo: &ModelScopeRepo) -> bool {
+    repo.required_files.iter().all(|file| {
+        std::fs::metadata(dir.join(file))
+            .map(|meta| meta.is_file() && meta.len() > 0)
+            .unwrap_or(false)
+    })
+}
+
+/// 检查模型及其依赖是否已下载，并统计磁盘占用
+pub fn check_model(cache_root: &Path, spec: &FunASRModelSpec) -> ModelPresence {
+    let mut presence = ModelPresence::default();
+    for repo in spec.repos() {
+        match find_repo_dir(cache_root, repo) {
+            Some(dir) => presence.disk_bytes += dir_size(&dir),
+            None => presence.missing.push(repo.id),
+        }
+    }
+    presence.downloaded = presence.missing.is_empty();
+    presence
+}
+
+/// 目录下所有文件的总大小（不跟随符号链接）
+fn dir_size(dir: &Path) -> u64 {
+    let Ok(entries) = std::fs::read_dir(dir) else {
+        return 0;
+    };
+    entries
+        .flatten()
+        .map(|entry| match entry.file_type() {
+            Ok(file_type) if file_type.is_dir() => dir_size(&entry.path()),
+            Ok(file_type) if file_type.is_file() => entry.metadata().map(|meta| meta.len()).unwrap_or(0),
+            _ => 0,
+        })
+        .sum()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn write_repo(dir: &Path, model_bytes: usize) {
+        std::fs::create_dir_all(dir).unwrap();
+        std::fs::write(dir.join("configuration.json"), "{}").unwrap();
+        std::fs::write(dir.join("config.yaml"), "model: x").unwrap();
+        std::fs::write(dir.join("model.pt"), vec![0u8; model_bytes]).unwrap();
+    }
+
+    #[test]
+    fn test_check_model_layouts() {
+        let root = std::env::temp_dir().join(format!("lingcode-modelscope-{}", std::process::id()));
+        let _ = std::fs::remove_dir_all(&root);
+        let spec = find_funasr_model("sensevoice-small").unwrap();
+
+        let presence = check_model(&root, spec);
+        assert!(!presence.downloaded);
+        assert_eq!(presence.missing, vec![SENSEVOICE_SMALL_REPO.id, FSMN_VAD_REPO.id]);
+
+        // 主模型在 cache_dir 布局，VAD 在默认 hub/models 布局
+        write_repo(&root.join(SENSEVOICE_SMALL_REPO.id), 100);
+        write_repo(&root.join("hub/models").join(FSMN_VAD_REPO.id), 50);
+        let presence = check_model(&root, spec);
+        assert!(presence.downloaded);
+        assert_eq!(presence.disk_bytes, 2 * (2 + 8) + 150);
+
+        // 中断的下载留下空的 model.pt
+        std::fs::write(root.join(SENSEVOICE_SMALL_REPO.id).join("model.pt"), b"").unwrap();
+        let presence = check_model(&root, spec);
+        assert!(!presence.downloaded);
+        assert_eq!(presence.missing, vec![SENSEVOICE_SMALL_REPO.id]);
+
+        std::fs::remove_dir_all(&root).unwrap();
+    }
+
+    #[test]
+    fn test_catalog_matches_config() {
+        for name in crate::config::FUNASR_MODELS {
+            assert!(find_funasr_model(name).is_some(), "missing {}", name);
+        }
+        assert!(FUNASR_MODEL_SPECS.iter().all(|spec| spec.repos().any(|repo| repo.id == FSMN_VAD_REPO.id)));
+    }
+}
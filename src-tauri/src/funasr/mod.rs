@@ -5,13 +5,20 @@ use std::path::PathBuf;
 use std::process::Command;
 use tauri::AppHandle;
 
+pub mod catalog;
 pub mod engine;
 pub mod prewarmer;
+pub mod rpc;
+pub mod scripts;
 pub mod server;
+pub mod supervisor;
 
+pub use catalog::{find_funasr_model, FUNASR_MODEL_SPECS};
 pub use engine::FunASREngine;
 pub use prewarmer::{prewarm_funasr, prewarm_funasr_cmd, quick_health_check, PythonEnvStatus};
-pub use server::FunASRServer;
+pub use rpc::{ChunkProgress, RequestControl};
+pub use scripts::Script;
+pub use server::{supports_streaming, AudioInput, FunASRServer, ResidentModel, ServerWav, StreamingText, Transcript, FILE_INPUT_MIN_MS, STREAMING_MODEL};
 
 /// FunASR 转录结果
 #[derive(Debug, serde::Deserialize)]
@@ -23,36 +30,6 @@ pub struct TranscriptionResult {
     pub error: String,
 }
 
-/// 获取 Python 脚本路径
-fn get_script_path(_app: &AppHandle) -> Result<PathBuf, String> {
-    // 开发模式：使用 src-tauri/scripts 目录
-    #[cfg(debug_assertions)]
-    {
-        let current_dir = std::env::current_dir()
-            .map_err(|e| format!("Failed to get current dir: {}", e))?;
-
-        // 尝试两种路径：当前目录/scripts 或 当前目录/src-tauri/scripts
-        let script_path = if current_dir.ends_with("src-tauri") {
-            current_dir.join("scripts").join("funasr_transcribe.py")
-        } else {
-            current_dir.join("src-tauri").join("scripts").join("funasr_transcribe.py")
-        };
-
-        if !script_path.exists() {
-            return Err(format!("Script not found: {:?}", script_path));
-        }
-
-        return Ok(script_path);
-    }
-
-    // 生产模式：脚本应该打包在资源目录中（暂未实现）
-    #[cfg(not(debug_assertions))]
-    {
-        // TODO: 生产模式需要打包 Python 脚本
-        Err("Production mode not yet implemented".to_string())
-    }
-}
-
 /// 调用 Python 脚本执行转录
 pub async fn transcribe_with_python(
     app: &AppHandle,
@@ -63,7 +40,7 @@ pub async fn transcribe_with_python(
 ) -> Result<String, String> {
     use tracing::info;
 
-    let script_path = get_script_path(app)?;
+    let script_path = scripts::script_path(app, Script::Transcribe)?;
 
     info!("🐍 Calling FunASR Python script: {:?}", script_path);
     info!("   Model: {}, Audio: {}", model_name, audio_path);
@@ -123,7 +100,7 @@ pub async fn download_funasr_model(
     use tauri::Emitter;
     use tracing::{info, warn};
 
-    let script_path = get_script_path(app)?;
+    let script_path = scripts::script_path(app, Script::Transcribe)?;
 
     info!("📥 Downloading FunASR model: {}", model_name);
 
@@ -0,0 +1,772 @@
+/// FunASR 服务器通信协议
+/// JSON-RPC 2.0 风格的按行协议：每个请求带 id，响应可以乱序返回，
+/// 进度和日志以不带 id 的通知随时插入；启动时通过 `initialize` 握手确认协议版本。
+/// 需要排队执行的请求在入队和开始执行时各有一条通知，排队期间不计超时。
+/// 不是协议消息的输出行（第三方库的杂散打印）会被忽略，不会打乱后续响应
+
+use parking_lot::Mutex;
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use std::collections::HashMap;
+use std::io::{BufRead, BufReader, Read, Write};
+use std::sync::atomic::{AtomicBool, AtomicU64, Ordering};
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+use tokio::sync::oneshot;
+use tracing::{debug, error, info, warn};
+
+/// 协议版本，与 `scripts/funasr_server.py` 中的 `PROTOCOL_VERSION` 保持一致
+pub const PROTOCOL_VERSION: u32 = 1;
+
+/// 错误码
+///
+/// -32700 ~ -32600 为 JSON-RPC 2.0 标准错误，-32001 起为服务器的业务错误，
+/// -32090 ~ -32099 只由客户端产生（连接层面的故障）
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ErrorCode {
+    ParseError,
+    InvalidRequest,
+    MethodNotFound,
+    InvalidParams,
+    InternalError,
+    /// 音频过短或为静音
+    AudioTooShort,
+    /// 音频文件不存在或格式不符
+    AudioInvalid,
+    /// 模型加载失败
+    ModelError,
+    /// 推理失败
+    TranscriptionFailed,
+    /// 请求已被取消
+    RequestCancelled,
+    /// 服务器进程退出或连接断开
+    ServerExited,
+    /// 等待响应超时
+    Timeout,
+    /// 服务器协议版本不匹配
+    ProtocolMismatch,
+    Other(i64),
+}
+
+impl ErrorCode {
+    pub fn code(self) -> i64 {
+        match self {
+            ErrorCode::ParseError => -32700,
+            ErrorCode::InvalidRequest => -32600,
+            ErrorCode::MethodNotFound => -32601,
+            ErrorCode::InvalidParams => -32602,
+            ErrorCode::InternalError => -32603,
+            ErrorCode::AudioTooShort => -32001,
+            ErrorCode::AudioInvalid => -32002,
+            ErrorCode::ModelError => -32003,
+            ErrorCode::TranscriptionFailed => -32004,
+            ErrorCode::RequestCancelled => -32800,
+            ErrorCode::ServerExited => -32099,
+            ErrorCode::Timeout => -32098,
+            ErrorCode::ProtocolMismatch => -32097,
+            ErrorCode::Other(code) => code,
+        }
+    }
+
+    pub fn from_code(code: i64) -> Self {
+        match code {
+            -32700 => ErrorCode::ParseError,
+            -32600 => ErrorCode::InvalidRequest,
+            -32601 => ErrorCode::MethodNotFound,
+            -32602 => ErrorCode::InvalidParams,
+            -32603 => ErrorCode::InternalError,
+            -32001 => ErrorCode::AudioTooShort,
+            -32002 => ErrorCode::AudioInvalid,
+            -32003 => ErrorCode::ModelError,
+            -32004 => ErrorCode::TranscriptionFailed,
+            -32800 => ErrorCode::RequestCancelled,
+            -32099 => ErrorCode::ServerExited,
+            -32098 => ErrorCode::Timeout,
+            -32097 => ErrorCode::ProtocolMismatch,
+            code => ErrorCode::Other(code),
+        }
+    }
+}
+
+/// 错误对象
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct RpcError {
+    pub code: i64,
+    pub message: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub data: Option<Value>,
+}
+
+impl RpcError {
+    pub fn new(code: ErrorCode, message: impl Into<String>) -> Self {
+        Self {
+            code: code.code(),
+            message: message.into(),
+            data: None,
+        }
+    }
+
+    pub fn kind(&self) -> ErrorCode {
+        ErrorCode::from_code(self.code)
+    }
+
+    /// 转为命令层使用的错误字符串，取消统一为 `jobs::CANCELLED_MESSAGE`
+    pub fn into_message(self) -> String {
+        match self.kind() {
+            ErrorCode::RequestCancelled => crate::jobs::CANCELLED_MESSAGE.to_string(),
+            _ => self.message,
+        }
+    }
+}
+
+impl std::fmt::Display for RpcError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{} (code {})", self.message, self.code)
+    }
+}
+
+/// 分块转录进度（服务器每完成一块发送一次）
+#[derive(Debug, Clone, PartialEq, Deserialize)]
+pub struct ChunkProgress {
+    pub done: u32,
+    pub total: u32,
+    /// 该块的识别文本
+    #[serde(default)]
+    pub text: String,
+}
+
+/// 进度回调
+pub type ChunkProgressCallback = Arc<dyn Fn(ChunkProgress) + Send + Sync>;
+
+/// 请求控制：任务取消标志和分块进度回调
+#[derive(Clone, Default)]
+pub struct RequestControl {
+    pub cancel: Option<Arc<AtomicBool>>,
+    pub on_progress: Option<ChunkProgressCallback>,
+}
+
+impl RequestControl {
+    pub(crate) fn is_cancelled(&self) -> bool {
+        self.cancel.as_ref().is_some_and(|cancel| cancel.load(Ordering::SeqCst))
+    }
+}
+
+/// `initialize` 握手返回的服务器信息
+#[derive(Debug, Clone, Deserialize)]
+pub struct ServerInfo {
+    pub protocol_version: u32,
+    /// 服务器支持的方法
+    #[serde(default)]
+    pub methods: Vec<String>,
+}
+
+/// 服务器输出的一行消息
+#[derive(Debug, PartialEq)]
+enum Incoming {
+    /// 请求的响应
+    Response { id: u64, result: Result<Value, RpcError> },
+    /// 进度通知（`params.id` 为对应请求）
+    Progress { id: u64, progress: ChunkProgress },
+    /// 请求进入服务器的执行队列
+    Queued { id: u64 },
+    /// 请求出队、开始执行
+    Started { id: u64 },
+    /// 日志通知
+    Log { level: String, message: String },
+    /// 无法对应到请求的错误（如服务器无法解析的请求行）
+    OrphanError(RpcError),
+    /// 未知通知
+    Notification { method: String },
+}
+
+#[derive(Deserialize)]
+struct RawMessage {
+    jsonrpc: String,
+    #[serde(default)]
+    id: Option<u64>,
+    #[serde(default)]
+    method: Option<String>,
+    #[serde(default)]
+    params: Value,
+    #[serde(default)]
+    result: Option<Value>,
+    #[serde(default)]
+    error: Option<RpcError>,
+}
+
+/// 解析一行输出，不是协议消息时返回 None
+fn parse_line(line: &str) -> Option<Incoming> {
+    let message: RawMessage = serde_json::from_str(line.trim()).ok()?;
+    if message.jsonrpc != "2.0" {
+        return None;
+    }
+
+    if let Some(method) = message.method {
+        return Some(match method.as_str() {
+            "progress" => {
+                let id = message.params.get("id")?.as_u64()?;
+                let progress = serde_json::from_value(message.params).ok()?;
+                Incoming::Progress { id, progress }
+            }
+            "queued" => Incoming::Queued {
+                id: message.params.get("id")?.as_u64()?,
+            },
+            "started" => Incoming::Started {
+                id: message.params.get("id")?.as_u64()?,
+            },
+            "log" => Incoming::Log {
+                level: message.params["level"].as_str().unwrap_or("info").to_string(),
+                message: message.params["message"].as_str().unwrap_or_default().to_string(),
+            },
+            _ => Incoming::Notification { method },
+        });
+    }
+
+    match (message.id, message.error) {
+        (Some(id), Some(error)) => Some(Incoming::Response { id, result: Err(error) }),
+        (Some(id), None) => Some(Incoming::Response {
+            id,
+            result: Ok(message.result.unwrap_or(Value::Null)),
+        }),
+        (None, Some(error)) => Some(Incoming::OrphanError(error)),
+        (None, None) => None,
+    }
+}
+
+/// 等待响应中的请求
+struct Pending {
+    sender: oneshot::Sender<Result<Value, RpcError>>,
+    on_progress: Option<ChunkProgressCallback>,
+    /// 最近一次收到该请求消息的时间（开始执行和进度通知会刷新），在服务器队列中等待时为 None
+    last_activity: Arc<Mutex<Option<Instant>>>,
+}
+
+/// 读取线程与调用方共享的状态
+#[derive(Default)]
+struct Shared {
+    pending: Mutex<HashMap<u64, Pending>>,
+    closed: AtomicBool,
+}
+
+impl Shared {
+    /// 更新等待中请求的计时起点，返回其进度回调（请求已结束时为 None）
+    fn set_activity(&self, id: u64, activity: Option<Instant>) -> Option<Option<ChunkProgressCallback>> {
+        self.pending.lock().get(&id).map(|pending| {
+            *pending.last_activity.lock() = activity;
+            pending.on_progress.clone()
+        })
+    }
+
+    fn dispatch(&self, line: &str) {
+        match parse_line(line) {
+            Some(Incoming::Response { id, result }) => match self.pending.lock().remove(&id) {
+                Some(pending) => {
+                    let _ = pending.sender.send(result);
+                }
+                // 已取消或超时的请求，服务器稍后仍可能送来响应
+                None => debug!("Dropping response for abandoned FunASR request #{}", id),
+            },
+            Some(Incoming::Progress { id, progress }) => {
+                if let Some(Some(on_progress)) = self.set_activity(id, Some(Instant::now())) {
+                    on_progress(progress);
+                }
+            }
+            // 排队期间暂停计时：前面的请求可能要执行很久，服务器是否卡死由心跳判断
+            Some(Incoming::Queued { id }) => {
+                self.set_activity(id, None);
+            }
+            Some(Incoming::Started { id }) => {
+                debug!("▶️  FunASR request #{} started", id);
+                self.set_activity(id, Some(Instant::now()));
+            }
+            Some(Incoming::Log { level, message }) => match level.as_str() {
+                "error" => error!("🐍 [FunASR] {}", message),
+                "warning" | "warn" => warn!("🐍 [FunASR] {}", message),
+                "debug" => debug!("🐍 [FunASR] {}", message),
+                _ => info!("🐍 [FunASR] {}", message),
+            },
+            Some(Incoming::OrphanError(error)) => {
+                warn!("⚠️  FunASR server reported an error without request id: {}", error)
+            }
+            Some(Incoming::Notification { method }) => {
+                debug!("Ignoring unknown FunASR notification: {}", method)
+            }
+            None if line.trim().is_empty() => {}
+            None => warn!("⚠️  Ignoring non-protocol output from FunASR server: {}", line.trim()),
+        }
+    }
+
+    /// 连接断开：所有等待中的请求以 ServerExited 结束
+    fn close(&self) {
+        self.closed.store(true, Ordering::SeqCst);
+        for (_, pending) in self.pending.lock().drain() {
+            let _ = pending
+                .sender
+                .send(Err(RpcError::new(ErrorCode::ServerExited, "FunASR server exited")));
+        }
+    }
+}
+
+/// 协议客户端
+///
+/// 后台线程持续读取服务器输出并按 id 分发响应，多个请求可以同时等待
+pub struct RpcClient {
+    writer: Mutex<Box<dyn Write + Send>>,
+    shared: Arc<Shared>,
+    next_id: AtomicU64,
+}
+
+impl RpcClient {
+    /// 在服务器的 stdin / stdout 上建立连接
+    pub fn new(writer: impl Write + Send + 'static, reader: impl Read + Send + 'static) -> Self {
+        let shared = Arc::new(Shared::default());
+
+        let reader_shared = shared.clone();
+        let spawned = std::thread::Builder::new()
+            .name("funasr-rpc-reader".to_string())
+            .spawn(move || {
+                let mut reader = BufReader::new(reader);
+                let mut buffer = Vec::new();
+                loop {
+                    buffer.clear();
+                    match reader.read_until(b'\n', &mut buffer) {
+                        Ok(0) => break,
+                        Ok(_) => reader_shared.dispatch(&String::from_utf8_lossy(&buffer)),
+                        Err(e) => {
+                            warn!("⚠️  Failed to read from FunASR server: {}", e);
+                            break;
+                        }
+                    }
+                }
+                reader_shared.close();
+            });
+        if let Err(e) = spawned {
+            error!("❌ Failed to spawn FunASR reader thread: {}", e);
+            shared.close();
+        }
+
+        Self {
+            writer: Mutex::new(Box::new(writer)),
+            shared,
+            next_id: AtomicU64::new(1),
+        }
+    }
+
+    /// 连接是否已断开（服务器退出或关闭了 stdout）
+    pub fn is_closed(&self) -> bool {
+        self.shared.closed.load(Ordering::SeqCst)
+    }
+
+    fn write(&self, message: &Value) -> Result<(), RpcError> {
+        let line = serde_json::to_string(message)
+            .map_err(|e| RpcError::new(ErrorCode::InternalError, format!("Failed to serialize request: {}", e)))?;
+        let io_error = |e: std::io::Error| RpcError::new(ErrorCode::ServerExited, format!("Failed to write request: {}", e));
+
+        let mut writer = self.writer.lock();
+        writeln!(writer, "{}", line).map_err(io_error)?;
+        writer.flush().map_err(io_error)
+    }
+
+    /// 发送通知（不等待响应）
+    pub fn notify(&self, method: &str, params: Value) -> Result<(), RpcError> {
+        self.write(&serde_json::json!({
+            "jsonrpc": "2.0",
+            "method": method,
+            "params": params,
+        }))
+    }
+
+    /// 发送请求并等待响应
+    ///
+    /// 超时按该请求两条消息之间的间隔计算，持续上报进度的长请求不会超时；
+    /// 在服务器队列中等待的请求不计时，从开始执行时重新计算。
+    /// 请求被取消时通知服务器放弃该请求并立即返回 `RequestCancelled`
+    pub async fn call(
+        &self,
+        method: &str,
+        params: Value,
+        control: &RequestControl,
+        idle_timeout: Duration,
+    ) -> Result<Value, RpcError> {
+        let id = self.next_id.fetch_add(1, Ordering::SeqCst);
+        let (sender, mut receiver) = oneshot::channel();
+        let last_activity = Arc::new(Mutex::new(Some(Instant::now())));
+
+        self.shared.pending.lock().insert(
+            id,
+            Pending {
+                sender,
+                on_progress: control.on_progress.clone(),
+                last_activity: last_activity.clone(),
+            },
+        );
+        // 插入前连接已断开时没有人会结束这个请求
+        if self.is_closed() {
+            self.shared.pending.lock().remove(&id);
+            return Err(RpcError::new(ErrorCode::ServerExited, "FunASR server is not running"));
+        }
+
+        debug!("📤 FunASR request #{}: {}", id, method);
+        if let Err(e) = self.write(&serde_json::json!({
+            "jsonrpc": "2.0",
+            "id": id,
+            "method": method,
+            "params": params,
+        })) {
+            self.shared.pending.lock().remove(&id);
+            return Err(e);
+        }
+
+        loop {
+            tokio::select! {
+                result = &mut receiver => {
+                    return result.unwrap_or_else(|_| {
+                        Err(RpcError::new(ErrorCode::ServerExited, "FunASR server exited"))
+                    });
+                }
+                _ = tokio::time::sleep(Duration::from_millis(50)) => {
+                    let abandoned = if control.is_cancelled() {
+                        info!("🛑 Cancelling in-flight {} request #{}", method, id);
+                        Some(RpcError::new(ErrorCode::RequestCancelled, "Request cancelled"))
+                    } else if last_activity.lock().is_some_and(|since| since.elapsed() > idle_timeout) {
+                        error!("❌ Request #{} timeout after {:?} for method: {}", id, idle_timeout, method);
+                        Some(RpcError::new(
+                            ErrorCode::Timeout,
+                            format!("Request timeout after {:?}", idle_timeout),
+                        ))
+                    } else {
+                        None
+                    };
+
+                    if let Some(error) = abandoned {
+                        self.shared.pending.lock().remove(&id);
+                        if let Err(e) = self.notify("cancel", serde_json::json!({ "id": id })) {
+                            warn!("⚠️  Failed to send cancel notification: {}", e);
+                        }
+                        return Err(error);
+                    }
+                }
+            }
+        }
+    }
+
+    /// 版本握手，服务器协议版本不一致时返回 `ProtocolMismatch`
+    pub async fn initialize(&self, timeout: Duration) -> Result<ServerInfo, RpcError> {
+        let result = self
+            .call(
+                "initialize",
+                serde_json::json!({ "protocol_version": PROTOCOL_VERSION }),
+                &RequestControl::default(),
+                timeout,
+            )
+            .await?;
+
+        let info: ServerInfo = serde_json::from_value(result).map_err(|e| {
+            RpcError::new(ErrorCode::ProtocolMismatch, format!("Invalid initialize response: {}", e))
+        })?;
+        if info.protocol_version != PROTOCOL_VERSION {
+            return Err(RpcError::new(
+                ErrorCode::ProtocolMismatch,
+                format!(
+                    "FunASR server speaks protocol v{}, expected v{}. Please update funasr_server.py.",
+                    info.protocol_version, PROTOCOL_VERSION
+                ),
+            ));
+        }
+
+        Ok(info)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::process::{Child, Command, Stdio};
+
+    #[test]
+    fn test_parse_line() {
+        assert_eq!(
+            parse_line(r#"{"jsonrpc": "2.0", "id": 3, "result": {"text": "你好"}}"#),
+            Some(Incoming::Response {
+                id: 3,
+                result: Ok(serde_json::json!({ "text": "你好" })),
+            })
+        );
+        assert_eq!(
+            parse_line(r#"{"jsonrpc": "2.0", "id": 4, "error": {"code": -32001, "message": "too short"}}"#),
+            Some(Incoming::Response {
+                id: 4,
+                result: Err(RpcError::new(ErrorCode::AudioTooShort, "too short")),
+            })
+        );
+        assert_eq!(
+            parse_line(r#"{"jsonrpc": "2.0", "method": "progress", "params": {"id": 5, "done": 2, "total": 5, "text": "你好"}}"#),
+            Some(Incoming::Progress {
+                id: 5,
+                progress: ChunkProgress {
+                    done: 2,
+                    total: 5,
+                    text: "你好".to_string(),
+                },
+            })
+        );
+        assert_eq!(
+            parse_line(r#"{"jsonrpc": "2.0", "method": "queued", "params": {"id": 6}}"#),
+            Some(Incoming::Queued { id: 6 })
+        );
+        assert_eq!(
+            parse_line(r#"{"jsonrpc": "2.0", "method": "started", "params": {"id": 6}}"#),
+            Some(Incoming::Started { id: 6 })
+        );
+        assert_eq!(
+            parse_line(r#"{"jsonrpc": "2.0", "method": "log", "params": {"level": "warning", "message": "slow"}}"#),
+            Some(Incoming::Log {
+                level: "warning".to_string(),
+                message: "slow".to_string(),
+            })
+        );
+        assert_eq!(
+            parse_line(r#"{"jsonrpc": "2.0", "id": null, "error": {"code": -32700, "message": "bad"}}"#),
+            Some(Incoming::OrphanError(RpcError::new(ErrorCode::ParseError, "bad")))
+        );
+
+        // 杂散输出和旧协议的消息都不是协议消息
+        assert_eq!(parse_line("Downloading model: 100%"), None);
+        assert_eq!(parse_line(r#"{"success": true, "text": "你好"}"#), None);
+        assert_eq!(parse_line(r#"{"jsonrpc": "1.0", "id": 1, "result": 1}"#), None);
+    }
+
+    #[test]
+    fn test_error_codes() {
+        for code in [
+            ErrorCode::ParseError,
+            ErrorCode::MethodNotFound,
+            ErrorCode::AudioTooShort,
+            ErrorCode::ModelError,
+            ErrorCode::RequestCancelled,
+            ErrorCode::Timeout,
+            ErrorCode::Other(-1),
+        ] {
+            assert_eq!(ErrorCode::from_code(code.code()), code);
+        }
+
+        assert_eq!(
+            RpcError::new(ErrorCode::RequestCancelled, "cancelled").into_message(),
+            crate::jobs::CANCELLED_MESSAGE
+        );
+    }
+
+    /// 运行 `tests/fixtures/fake_funasr_server.py` 的测试服务器，测试结束时结束进程
+    struct FakeServer {
+        child: Child,
+        client: RpcClient,
+    }
+
+    impl Drop for FakeServer {
+        fn drop(&mut self) {
+            let _ = self.child.kill();
+            let _ = self.child.wait();
+        }
+    }
+
+    /// 启动测试服务器，环境中没有 Python 时返回 None（跳过测试）
+    fn fake_server(args: &[&str]) -> Option<FakeServer> {
+        let script = concat!(env!("CARGO_MANIFEST_DIR"), "/tests/fixtures/fake_funasr_server.py");
+
+        let spawned = ["python3", "python"].iter().find_map(|python| {
+            Command::new(python)
+                .arg(script)
+                .args(args)
+                .stdin(Stdio::piped())
+                .stdout(Stdio::piped())
+                .stderr(Stdio::inherit())
+                .spawn()
+                .ok()
+        });
+        let Some(mut child) = spawned else {
+            eprintln!("Python not found, skipping FunASR protocol test");
+            return None;
+        };
+
+        let client = RpcClient::new(child.stdin.take().unwrap(), child.stdout.take().unwrap());
+        Some(FakeServer { child, client })
+    }
+
+    const TIMEOUT: Duration = Duration::from_secs(10);
+
+    #[tokio::test]
+    async fn test_handshake() {
+        let Some(server) = fake_server(&[]) else { return };
+        let info = server.client.initialize(TIMEOUT).await.unwrap();
+        assert_eq!(info.protocol_version, PROTOCOL_VERSION);
+        assert!(info.methods.iter().any(|method| method == "echo"));
+
+        let Some(server) = fake_server(&["--protocol-version", "99"]) else { return };
+        let error = server.client.initialize(TIMEOUT).await.unwrap_err();
+        assert_eq!(error.kind(), ErrorCode::ProtocolMismatch);
+    }
+
+    #[tokio::test]
+    async fn test_concurrent_requests_complete_out_of_order() {
+        let Some(server) = fake_server(&[]) else { return };
+        let client = &server.client;
+        let control = RequestControl::default();
+
+        let order = Mutex::new(Vec::new());
+        let call = |value: &'static str, delay_ms: u64| {
+            let (control, order) = (&control, &order);
+            async move {
+                let params = serde_json::json!({ "value": value, "delay_ms": delay_ms });
+                let result = client.call("echo", params, control, TIMEOUT).await.unwrap();
+                order.lock().push(value);
+                result["value"].as_str().unwrap().to_string()
+            }
+        };
+
+        let (slow, fast) = tokio::join!(call("slow", 400), call("fast", 0));
+        assert_eq!((slow.as_str(), fast.as_str()), ("slow", "fast"));
+        assert_eq!(*order.lock(), vec!["fast", "slow"]);
+    }
+
+    #[tokio::test]
+    async fn test_progress_notifications_and_stray_output() {
+        let Some(server) = fake_server(&[]) else { return };
+        let received = Arc::new(Mutex::new(Vec::new()));
+        let control = RequestControl {
+            cancel: None,
+            on_progress: Some({
+                let received = received.clone();
+                Arc::new(move |progress: ChunkProgress| received.lock().push(progress))
+            }),
+        };
+
+        // 服务器在响应前后打印非协议输出和日志通知
+        let result = server
+            .client
+            .call("slow", serde_json::json!({ "chunks": 3, "interval_ms": 10, "noisy": true }), &control, TIMEOUT)
+            .await
+            .unwrap();
+
+        assert_eq!(result["text"], "chunk1 chunk2 chunk3");
+        {
+            let received = received.lock();
+            assert_eq!(received.iter().map(|p| p.done).collect::<Vec<_>>(), vec![1, 2, 3]);
+            assert!(received.iter().all(|p| p.total == 3));
+            assert_eq!(received[1].text, "chunk2");
+        }
+
+        // 杂散输出之后的请求仍能正确对应
+        let result = server
+            .client
+            .call("echo", serde_json::json!({ "value": "after" }), &RequestControl::default(), TIMEOUT)
+            .await
+            .unwrap();
+        assert_eq!(result["value"], "after");
+    }
+
+    #[tokio::test]
+    async fn test_cancel_in_flight_request() {
+        let Some(server) = fake_server(&[]) else { return };
+        let cancel = Arc::new(AtomicBool::new(false));
+        let control = RequestControl {
+            cancel: Some(cancel.clone()),
+            on_progress: Some({
+                let cancel = cancel.clone();
+                Arc::new(move |_| cancel.store(true, Ordering::SeqCst))
+            }),
+        };
+
+        let error = server
+            .client
+            .call("slow", serde_json::json!({ "chunks": 100, "interval_ms": 50 }), &control, TIMEOUT)
+            .await
+            .unwrap_err();
+        assert_eq!(error.kind(), ErrorCode::RequestCancelled);
+
+        // 服务器收到了取消通知，连接仍然可用
+        let cancelled = server
+            .client
+            .call("cancelled_ids", Value::Null, &RequestControl::default(), TIMEOUT)
+            .await
+            .unwrap();
+        assert_eq!(cancelled, serde_json::json!([1]));
+    }
+
+    #[tokio::test]
+    async fn test_typed_errors() {
+        let Some(server) = fake_server(&[]) else { return };
+        let control = RequestControl::default();
+
+        let error = server
+            .client
+            .call("fail", serde_json::json!({ "code": -32001, "message": "Audio too short" }), &control, TIMEOUT)
+            .await
+            .unwrap_err();
+        assert_eq!(error.kind(), ErrorCode::AudioTooShort);
+        assert_eq!(error.message, "Audio too short");
+
+        let error = server.client.call("no_such_method", Value::Null, &control, TIMEOUT).await.unwrap_err();
+        assert_eq!(error.kind(), ErrorCode::MethodNotFound);
+    }
+
+    #[tokio::test]
+    async fn test_idle_timeout() {
+        let Some(server) = fake_server(&[]) else { return };
+        let error = server
+            .client
+            .call(
+                "echo",
+                serde_json::json!({ "value": "late", "delay_ms": 2000 }),
+                &RequestControl::default(),
+                Duration::from_millis(200),
+            )
+            .await
+            .unwrap_err();
+        assert_eq!(error.kind(), ErrorCode::Timeout);
+    }
+
+    #[tokio::test]
+    async fn test_queued_request_waits_without_timeout() {
+        let Some(server) = fake_server(&[]) else { return };
+        let control = RequestControl::default();
+        let idle_timeout = Duration::from_millis(200);
+
+        // 排队时间远超超时，开始执行后很快完成
+        let result = server
+            .client
+            .call("queued", serde_json::json!({ "value": "done", "wait_ms": 800 }), &control, idle_timeout)
+            .await
+            .unwrap();
+        assert_eq!(result["value"], "done");
+
+        // 开始执行后重新计时
+        let error = server
+            .client
+            .call(
+                "queued",
+                serde_json::json!({ "value": "late", "wait_ms": 50, "delay_ms": 2000 }),
+                &control,
+                idle_timeout,
+            )
+            .await
+            .unwrap_err();
+        assert_eq!(error.kind(), ErrorCode::Timeout);
+    }
+
+    #[tokio::test]
+    async fn test_server_exit_fails_pending_requests() {
+        let Some(server) = fake_server(&[]) else { return };
+        let control = RequestControl::default();
+
+        let (pending, exit) = tokio::join!(
+            server.client.call("echo", serde_json::json!({ "value": "x", "delay_ms": 5000 }), &control, TIMEOUT),
+            server.client.call("exit", Value::Null, &control, TIMEOUT),
+        );
+        assert_eq!(pending.unwrap_err().kind(), ErrorCode::ServerExited);
+        assert_eq!(exit.unwrap_err().kind(), ErrorCode::ServerExited);
+        assert!(server.client.is_closed());
+
+        let error = server.client.call("echo", Value::Null, &control, TIMEOUT).await.unwrap_err();
+        assert_eq!(error.kind(), ErrorCode::ServerExited);
+    }
+}
@@ -0,0 +1,204 @@
+/// FunASR Python 脚本定位
+/// 依次查找设置中的覆盖目录、Tauri 资源目录（打包的 `scripts/*.py`）和开发时的源码目录，
+/// 打包的脚本用编译时嵌入的内容校验 SHA-256，防止使用损坏或被改动的文件
+
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+use tauri::{AppHandle, Manager};
+use tracing::{info, warn};
+
+use crate::config::ConfigManager;
+use crate::db::Database;
+use crate::download::sha256_hex;
+
+/// 随应用分发的 Python 脚本
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Script {
+    /// 常驻服务器（JSON-RPC）
+    Server,
+    /// 单次转录 / 模型下载
+    Transcribe,
+}
+
+impl Script {
+    pub const ALL: [Script; 2] = [Script::Server, Script::Transcribe];
+
+    pub fn file_name(self) -> &'static str {
+        match self {
+            Script::Server => "funasr_server.py",
+            Script::Transcribe => "funasr_transcribe.py",
+        }
+    }
+
+    /// 编译时的脚本内容
+    fn embedded(self) -> &'static [u8] {
+        match self {
+            Script::Server => include_bytes!("../../scripts/funasr_server.py"),
+            Script::Transcribe => include_bytes!("../../scripts/funasr_transcribe.py"),
+        }
+    }
+
+    /// 期望的 SHA-256（十六进制）
+    pub fn expected_sha256(self) -> String {
+        sha256_hex(self.embedded())
+    }
+}
+
+/// 脚本所在位置
+#[derive(Debug, Clone, Copy, PartialEq, Eq, serde::Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum ScriptSource {
+    /// 设置中指定的目录
+    Override,
+    /// 应用资源目录
+    Resource,
+    /// 源码目录（仅开发构建）
+    Source,
+}
+
+/// 定位到的脚本
+#[derive(Debug, Clone, PartialEq, serde::Serialize)]
+pub struct ResolvedScript {
+    pub path: PathBuf,
+    pub source: ScriptSource,
+    /// 内容与应用内嵌的版本一致（覆盖目录中的脚本允许不一致）
+    pub verified: bool,
+}
+
+/// 定位脚本
+///
+/// 设置了覆盖目录时只在该目录查找，脚本内容不一致只记录警告；
+/// 否则使用资源目录中通过校验的脚本，开发构建再回退到源码目录
+pub fn resolve_script(app: &AppHandle, script: Script) -> Result<ResolvedScript, String> {
+    let resource_dir = app.path().resource_dir().ok();
+    resolve(script, scripts_dir_override(app).as_deref(), resource_dir.as_deref())
+}
+
+/// 定位脚本，只返回路径
+pub fn script_path(app: &AppHandle, script: Script) -> Result<PathBuf, String> {
+    resolve_script(app, script).map(|resolved| resolved.path)
+}
+
+fn resolve(script: Script, override_dir: Option<&Path>, resource_dir: Option<&Path>) -> Result<ResolvedScript, String> {
+    let name = script.file_name();
+
+    if let Some(dir) = override_dir {
+        let path = dir.join(name);
+        if !path.is_file() {
+            return Err(format!("Script not found in override directory: {:?}", path));
+        }
+        let verified = match verify(script, &path) {
+            Ok(()) => true,
+            Err(e) => {
+                warn!("⚠️  [Scripts] Using modified script from override directory: {}", e);
+                false
+            }
+        };
+        info!("📜 [Scripts] {} from override directory: {:?}", name, path);
+        return Ok(ResolvedScript {
+            path,
+            source: ScriptSource::Override,
+            verified,
+        });
+    }
+
+    let mut candidates = Vec::new();
+    if let Some(dir) = resource_dir {
+        candidates.push((dir.join("scripts").join(name), ScriptSource::Resource));
+    }
+    if cfg!(debug_assertions) {
+        let source_dir = Path::new(env!("CARGO_MANIFEST_DIR")).join("scripts");
+        candidates.push((source_dir.join(name), ScriptSource::Source));
+    }
+
+    let mut errors = Vec::new();
+    for (path, source) in candidates {
+        match verify(script, &path) {
+            Ok(()) => {
+                return Ok(ResolvedScript {
+                    path,
+                    source,
+                    verified: true,
+                })
+            }
+            Err(e) => errors.push(e),
+        }
+    }
+
+    Err(format!(
+        "FunASR script {} is missing or corrupted, please reinstall the app: {}",
+        name,
+        errors.join("; ")
+    ))
+}
+
+/// 校验脚本内容与应用内嵌的版本一致
+pub fn verify(script: Script, path: &Path) -> Result<(), String> {
+    let data = std::fs::read(path).map_err(|e| format!("{:?}: {}", path, e))?;
+    let actual = sha256_hex(&data);
+    let expected = script.expected_sha256();
+    if actual != expected {
+        return Err(format!("{:?}: SHA-256 mismatch (expected {}, got {})", path, expected, actual));
+    }
+    Ok(())
+}
+
+/// 设置中的脚本覆盖目录
+fn scripts_dir_override(app: &AppHandle) -> Option<PathBuf> {
+    app.try_state::<Arc<Database>>()
+        .and_then(|db| ConfigManager::new(db.connection()).get_funasr_scripts_dir().ok())
+        .flatten()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn temp_dir(name: &str) -> PathBuf {
+        let dir = std::env::temp_dir().join(format!("lingcode-scripts-{}-{}", name, std::process::id()));
+        let _ = std::fs::remove_dir_all(&dir);
+        std::fs::create_dir_all(&dir).unwrap();
+        dir
+    }
+
+    #[test]
+    fn test_resolve_resource_dir() {
+        let resource_dir = temp_dir("resource");
+        let scripts_dir = resource_dir.join("scripts");
+        std::fs::create_dir_all(&scripts_dir).unwrap();
+        std::fs::write(scripts_dir.join("funasr_server.py"), Script::Server.embedded()).unwrap();
+
+        let resolved = resolve(Script::Server, None, Some(&resource_dir)).unwrap();
+        assert_eq!(resolved.path, scripts_dir.join("funasr_server.py"));
+        assert_eq!(resolved.source, ScriptSource::Resource);
+        assert!(resolved.verified);
+
+        // 损坏的脚本不使用：开发构建回退到源码目录，发布构建报错
+        std::fs::write(scripts_dir.join("funasr_server.py"), b"print('tampered')").unwrap();
+        let result = resolve(Script::Server, None, Some(&resource_dir));
+        if cfg!(debug_assertions) {
+            assert_eq!(result.unwrap().source, ScriptSource::Source);
+        } else {
+            assert!(result.unwrap_err().contains("SHA-256 mismatch"));
+        }
+
+        std::fs::remove_dir_all(&resource_dir).unwrap();
+    }
+
+    #[test]
+    fn test_resolve_override_dir() {
+        let override_dir = temp_dir("override");
+
+        // 覆盖目录中没有脚本时不回退
+        let error = resolve(Script::Transcribe, Some(&override_dir), None).unwrap_err();
+        assert!(error.contains("override directory"));
+
+        // 修改过的脚本允许使用，但标记为未校验
+        std::fs::write(override_dir.join("funasr_transcribe.py"), b"print('patched')").unwrap();
+        let resolved = resolve(Script::Transcribe, Some(&override_dir), None).unwrap();
+        assert_eq!(resolved.source, ScriptSource::Override);
+        assert!(!resolved.verified);
+
+        std::fs::remove_dir_all(&override_dir).unwrap();
+    }
+}
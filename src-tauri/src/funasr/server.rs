@@ -1,57 +1,250 @@
 /// FunASR 常驻服务器管理
 /// 保持 Python 进程运行，模型只加载一次，大幅提升性能
 
-use serde::{Deserialize, Serialize};
-use std::io::{BufRead, BufReader, Write};
+use std::collections::VecDeque;
 use std::path::PathBuf;
-use std::process::{Child, ChildStdin, ChildStdout, Command, Stdio};
+use std::process::{Child, Command, Stdio};
+use std::sync::atomic::{AtomicBool, AtomicUsize, Ordering};
 use std::sync::Arc;
-use std::time::Duration;
+use std::time::{Duration, Instant};
 use tauri::AppHandle;
 use tokio::sync::Mutex;
-use tokio::time::timeout;
 use tracing::{error, info, warn};
 
-/// JSON-RPC 请求
-#[derive(Debug, Serialize)]
-struct Request {
-    method: String,
-    params: serde_json::Value,
-}
+use super::rpc::{ErrorCode, RequestControl, RpcClient, RpcError};
+use super::scripts::{self, Script};
+use super::supervisor::{self, RestartDecision, RestartPolicy, STDERR_TAIL_LINES};
+use crate::whisper::TranscriptionSegment;
+
+/// 主动停止时等待进程自行退出的时间，超时后强制结束
+const SHUTDOWN_GRACE: Duration = Duration::from_secs(3);
+
+/// 请求的默认超时（两条消息之间的间隔）
+const REQUEST_TIMEOUT: Duration = Duration::from_secs(60);
 
-/// JSON-RPC 响应
-#[derive(Debug, Deserialize)]
-struct Response {
-    success: bool,
+/// 需要加载模型的请求的超时（首次使用时还要下载模型）
+const MODEL_LOAD_TIMEOUT: Duration = Duration::from_secs(300);
+
+/// 转录结果
+#[derive(Debug, Clone, Default, PartialEq, serde::Deserialize)]
+pub struct Transcript {
+    /// 原始文本（SenseVoice 的标签未去除）
+    pub text: String,
+    /// 句子级段落，仅请求时间戳时返回（时间相对整段音频）
     #[serde(default)]
-    text: String,
+    pub segments: Vec<TranscriptionSegment>,
+}
+
+/// 发送给服务器的音频
+#[derive(Debug, Clone, Copy)]
+pub enum AudioInput<'a> {
+    /// 16kHz 单声道 16bit WAV 文件（导入的大文件）
+    File(&'a str),
+    /// 16kHz 单声道采样，编码为 base64 PCM16 随请求直接发送，不落盘
+    Pcm(&'a [f32]),
+}
+
+impl AudioInput<'_> {
+    /// 写入请求参数：`audio_path`，或 `audio_pcm16` + `sample_rate`
+    fn add_to(&self, params: &mut serde_json::Value) {
+        match self {
+            AudioInput::File(path) => params["audio_path"] = serde_json::json!(path),
+            AudioInput::Pcm(samples) => {
+                params["audio_pcm16"] = serde_json::json!(encode_pcm16(samples));
+                params["sample_rate"] = serde_json::json!(16000);
+            }
+        }
+    }
+
+    fn describe(&self) -> String {
+        match self {
+            AudioInput::File(path) => path.to_string(),
+            AudioInput::Pcm(samples) => format!("{} samples in memory", samples.len()),
+        }
+    }
+}
+
+/// 超过该时长的音频以 WAV 文件交给服务器，避免把整段音频 base64 编码进一条请求
+pub const FILE_INPUT_MIN_MS: u64 = 60_000;
+
+/// 以文件形式发送给服务器的音频
+///
+/// 源文件已是 16kHz 单声道 16bit WAV 时直接使用，否则把重采样后的音频写入临时文件，析构时删除
+pub struct ServerWav {
+    path: String,
+    temporary: bool,
+}
+
+impl ServerWav {
+    /// `audio` 为 16kHz 单声道音频，`source` 为其来源文件（可能是其他采样率或多声道）
+    pub fn prepare(audio: &[f32], source: Option<&str>) -> Result<Self, String> {
+        if let Some(path) = source.filter(|path| is_server_wav(path)) {
+            return Ok(Self {
+                path: path.to_string(),
+                temporary: false,
+            });
+        }
+
+        static NEXT_ID: std::sync::atomic::AtomicU64 = std::sync::atomic::AtomicU64::new(0);
+        let path = std::env::temp_dir().join(format!(
+            "lingcode-funasr-{}-{}.wav",
+            std::process::id(),
+            NEXT_ID.fetch_add(1, Ordering::SeqCst)
+        ));
+        write_wav_16k(&path, audio)?;
+
+        Ok(Self {
+            path: path.to_string_lossy().to_string(),
+            temporary: true,
+        })
+    }
+
+    pub fn input(&self) -> AudioInput<'_> {
+        AudioInput::File(&self.path)
+    }
+}
+
+impl Drop for ServerWav {
+    fn drop(&mut self) {
+        if self.temporary {
+            let _ = std::fs::remove_file(&self.path);
+        }
+    }
+}
+
+/// 文件是否为服务器可直接读取的 16kHz 单声道 16bit WAV
+fn is_server_wav(path: &str) -> bool {
+    hound::WavReader::open(path).is_ok_and(|reader| {
+        let spec = reader.spec();
+        spec.sample_rate == 16000
+            && spec.channels == 1
+            && spec.bits_per_sample == 16
+            && spec.sample_format == hound::SampleFormat::Int
+    })
+}
+
+fn write_wav_16k(path: &std::path::Path, audio: &[f32]) -> Result<(), String> {
+    let spec = hound::WavSpec {
+        channels: 1,
+        sample_rate: 16000,
+        bits_per_sample: 16,
+        sample_format: hound::SampleFormat::Int,
+    };
+
+    let mut writer =
+        hound::WavWriter::create(path, spec).map_err(|e| format!("Failed to write temporary WAV: {}", e))?;
+    for &sample in audio {
+        writer
+            .write_sample((sample.clamp(-1.0, 1.0) * i16::MAX as f32) as i16)
+            .map_err(|e| format!("Failed to write temporary WAV: {}", e))?;
+    }
+    writer
+        .finalize()
+        .map_err(|e| format!("Failed to write temporary WAV: {}", e))
+}
+
+/// f32 采样转为小端 PCM16 并做 base64 编码
+pub fn encode_pcm16(samples: &[f32]) -> String {
+    use base64::Engine;
+
+    let bytes: Vec<u8> = samples
+        .iter()
+        .flat_map(|&sample| ((sample.clamp(-1.0, 1.0) * i16::MAX as f32) as i16).to_le_bytes())
+        .collect();
+    base64::engine::general_purpose::STANDARD.encode(bytes)
+}
+
+/// 支持流式识别的模型（中文 Paraformer，实时结果由 online 模型给出，句末用该模型修正）
+pub const STREAMING_MODELS: &[&str] = &["paraformer-zh", "paraformer-large"];
+
+/// 流式识别结果的模型名（实时结果来自 online Paraformer，不是当前选择的模型）
+pub const STREAMING_MODEL: &str = "paraformer-zh-streaming";
+
+pub fn supports_streaming(model_name: &str) -> bool {
+    STREAMING_MODELS.contains(&model_name)
+}
+
+/// 流式识别的实时结果
+#[derive(Debug, Clone, Default, PartialEq, serde::Serialize, serde::Deserialize)]
+pub struct StreamingText {
+    /// 已确认文本 + 当前句的实时文本
+    pub text: String,
+    /// 已结束并经离线模型修正的句子
+    pub confirmed: String,
+    /// 当前句的 online 结果，后续可能被修正
+    pub partial: String,
+}
+
+/// 同时常驻的识别模型上限（说话人模型和流式模型不计入），超出时释放最久未用的
+pub const MAX_RESIDENT_MODELS: usize = 2;
+
+/// 服务器中已加载的模型
+#[derive(Debug, Clone, PartialEq, serde::Serialize, serde::Deserialize)]
+pub struct ResidentModel {
+    pub name: String,
+    /// 模型参数和缓冲区占用的内存（MB）；服务器无法计算时为加载前后常驻内存之差（近似值），都无法测量时为 None
     #[serde(default)]
-    error: String,
+    pub memory_mb: Option<f64>,
+    /// 加载耗时（毫秒）
     #[serde(default)]
-    message: String,
+    pub load_ms: u64,
 }
 
 /// FunASR 服务器实例
+///
+/// 各字段共享所有权，克隆得到的是同一个服务器（监督任务持有一份）
+#[derive(Clone)]
 pub struct FunASRServer {
+    /// 测试中的服务器不关联应用
+    app: Option<AppHandle>,
     process: Arc<Mutex<Option<Child>>>,
-    stdin: Arc<Mutex<Option<ChildStdin>>>,
-    stdout: Arc<Mutex<Option<BufReader<ChildStdout>>>>,
+    /// 当前连接，请求时取出一份 Arc，多个请求可以同时进行
+    rpc: Arc<parking_lot::Mutex<Option<Arc<RpcClient>>>>,
     python_path: PathBuf,
     script_path: PathBuf,
+    /// 服务器应当运行：启动后置位，主动停止后清除，监督任务只重启应当运行的服务器
+    should_run: Arc<AtomicBool>,
+    /// 应用退出，监督任务结束
+    shut_down: Arc<AtomicBool>,
+    /// 监督任务是否已启动
+    supervised: Arc<AtomicBool>,
+    /// 崩溃循环的错误信息，重新初始化引擎前不再自动启动
+    failure: Arc<parking_lot::Mutex<Option<String>>>,
+    restart_policy: Arc<parking_lot::Mutex<RestartPolicy>>,
+    /// 串行化重启，避免监督任务和转录请求同时重启进程；请求在持有该锁时登记，重启期间到达的请求等待重启完成
+    restart_lock: Arc<Mutex<()>>,
+    /// 已登记、尚未完成的请求数
+    requests: Arc<AtomicUsize>,
+    /// 服务器最近的 stderr 输出
+    stderr_tail: Arc<parking_lot::Mutex<VecDeque<String>>>,
+    /// 服务器中已加载的模型，最近使用的在末尾
+    resident: Arc<parking_lot::Mutex<Vec<ResidentModel>>>,
 }
 
 impl FunASRServer {
     /// 创建新的服务器实例
     pub fn new(app: &AppHandle, python_path: PathBuf) -> Result<Self, String> {
-        let script_path = get_server_script_path(app)?;
+        let script_path = scripts::script_path(app, Script::Server)?;
+        Ok(Self::with_script(Some(app.clone()), python_path, script_path))
+    }
 
-        Ok(Self {
+    fn with_script(app: Option<AppHandle>, python_path: PathBuf, script_path: PathBuf) -> Self {
+        Self {
+            app,
             process: Arc::new(Mutex::new(None)),
-            stdin: Arc::new(Mutex::new(None)),
-            stdout: Arc::new(Mutex::new(None)),
+            rpc: Arc::new(parking_lot::Mutex::new(None)),
             python_path,
             script_path,
-        })
+            should_run: Arc::new(AtomicBool::new(false)),
+            shut_down: Arc::new(AtomicBool::new(false)),
+            supervised: Arc::new(AtomicBool::new(false)),
+            failure: Arc::new(parking_lot::Mutex::new(None)),
+            restart_policy: Arc::new(parking_lot::Mutex::new(RestartPolicy::default())),
+            restart_lock: Arc::new(Mutex::new(())),
+            requests: Arc::new(AtomicUsize::new(0)),
+            stderr_tail: Arc::new(parking_lot::Mutex::new(VecDeque::with_capacity(STDERR_TAIL_LINES))),
+            resident: Arc::new(parking_lot::Mutex::new(Vec::new())),
+        }
     }
 
     /// 检查服务器是否存活
@@ -60,7 +253,14 @@ impl FunASRServer {
 
         if let Some(child) = process_guard.as_mut() {
             match child.try_wait() {
-                Ok(None) => true,  // 进程还在运行
+                Ok(None) => {
+                    // 进程还在运行，但可能已经关闭了 stdout
+                    let connected = self.rpc.lock().as_ref().is_some_and(|rpc| !rpc.is_closed());
+                    if !connected {
+                        warn!("⚠️  FunASR server connection closed");
+                    }
+                    connected
+                }
                 Ok(Some(status)) => {
                     warn!("⚠️  FunASR server exited with status: {:?}", status);
                     false
@@ -75,16 +275,18 @@ impl FunASRServer {
         }
     }
 
-    /// 启动服务器（带超时和重试）
+    /// 启动服务器并完成协议握手
+    ///
+    /// 进入崩溃循环后返回该错误，需先调用 `reset_failure`
     pub async fn start(&self) -> Result<(), String> {
-        // 先释放 process_guard 的锁，避免死锁
-        drop(self.process.lock().await);
-
-        // 使用 is_alive 检查（内部会加锁）
         if self.is_alive().await {
             info!("🔄 FunASR server already running");
             return Ok(());
         }
+        if let Some(failure) = self.failure() {
+            return Err(failure);
+        }
+        self.should_run.store(true, Ordering::SeqCst);
 
         info!("🚀 Starting FunASR server...");
         info!("   Python: {:?}", self.python_path);
@@ -95,10 +297,17 @@ impl FunASRServer {
             .arg(&self.script_path)
             .stdin(Stdio::piped())
             .stdout(Stdio::piped())
-            .stderr(Stdio::inherit())
+            .stderr(Stdio::piped())
             .spawn()
             .map_err(|e| format!("Failed to start FunASR server: {}", e))?;
 
+        if let Some(stderr) = child.stderr.take() {
+            supervisor::forward_stderr(stderr, self.stderr_tail.clone());
+        }
+
+        // 新进程中还没有加载任何模型
+        self.resident.lock().clear();
+
         // 获取 stdin 和 stdout
         let stdin = child
             .stdin
@@ -110,30 +319,28 @@ impl FunASRServer {
             .take()
             .ok_or("Failed to get stdout")?;
 
-        *self.stdin.lock().await = Some(stdin);
-        *self.stdout.lock().await = Some(BufReader::new(stdout));
+        let rpc = Arc::new(RpcClient::new(stdin, stdout));
+        *self.rpc.lock() = Some(rpc.clone());
         *self.process.lock().await = Some(child);
 
         info!("✅ FunASR server process started");
 
-        // 发送 ping 测试连接（带超时 30 秒）
-        match timeout(
-            Duration::from_secs(30),
-            self.send_request("ping", serde_json::json!({}))
-        ).await {
-            Ok(Ok(_)) => {
-                info!("✅ FunASR server responding to ping");
+        // 版本握手（30 秒内无响应视为启动失败）
+        match rpc.initialize(Duration::from_secs(30)).await {
+            Ok(server_info) => {
+                info!("✅ FunASR server ready (protocol v{})", server_info.protocol_version);
+                self.ensure_supervised();
                 Ok(())
             }
-            Ok(Err(e)) => {
-                error!("❌ FunASR server ping failed: {}", e);
+            Err(e) if e.kind() == ErrorCode::Timeout => {
+                error!("❌ FunASR server handshake timeout (30s)");
                 self.force_stop().await;
-                Err(format!("Server initialization failed: {}", e))
+                Err("Server initialization timeout. Model may be too large or Python environment is slow.".to_string())
             }
-            Err(_) => {
-                error!("❌ FunASR server ping timeout (30s)");
+            Err(e) => {
+                error!("❌ FunASR server handshake failed: {}", e);
                 self.force_stop().await;
-                Err("Server initialization timeout. Model may be too large or Python environment is slow.".to_string())
+                Err(format!("Server initialization failed: {}", e.message))
             }
         }
     }
@@ -148,223 +355,823 @@ impl FunASRServer {
             let _ = child.wait();
         }
 
-        *self.stdin.lock().await = None;
-        *self.stdout.lock().await = None;
+        *self.rpc.lock() = None;
+    }
+
+    /// 服务器进程 ID（未启动时为 None）
+    pub async fn pid(&self) -> Option<u32> {
+        self.process.lock().await.as_ref().map(|child| child.id())
     }
 
-    /// 停止服务器
+    /// 停止服务器（主动停止，监督任务不会重启）
+    ///
+    /// 服务器处理完当前请求后退出，超过 SHUTDOWN_GRACE 仍未退出时强制结束
     pub async fn stop(&self) -> Result<(), String> {
         info!("🛑 Stopping FunASR server...");
+        self.should_run.store(false, Ordering::SeqCst);
 
-        // 发送 shutdown 命令
-        let _ = self.send_request("shutdown", serde_json::json!({})).await;
+        // 发送 shutdown 命令，不等待响应（服务器可能正在处理其他请求）
+        if let Some(rpc) = self.rpc.lock().clone() {
+            let _ = rpc.notify("shutdown", serde_json::json!({}));
+        }
 
-        // 等待进程退出
         let mut process_guard = self.process.lock().await;
         if let Some(mut child) = process_guard.take() {
-            let _ = child.wait();
+            let deadline = Instant::now() + SHUTDOWN_GRACE;
+            loop {
+                match child.try_wait() {
+                    Ok(Some(_)) => break,
+                    Ok(None) if Instant::now() < deadline => {
+                        tokio::time::sleep(Duration::from_millis(50)).await;
+                    }
+                    _ => {
+                        warn!("⚠️  FunASR server did not exit in {:?}, killing it", SHUTDOWN_GRACE);
+                        let _ = child.kill();
+                        let _ = child.wait();
+                        break;
+                    }
+                }
+            }
         }
 
-        *self.stdin.lock().await = None;
-        *self.stdout.lock().await = None;
+        *self.rpc.lock() = None;
+        self.resident.lock().clear();
 
         info!("👋 FunASR server stopped");
         Ok(())
     }
 
-    /// 发送请求并等待响应（带超时）
-    async fn send_request(&self, method: &str, params: serde_json::Value) -> Result<Response, String> {
-        let request = Request {
-            method: method.to_string(),
-            params,
-        };
+    /// 应用退出时关闭服务器并结束监督任务
+    pub async fn shutdown(&self) {
+        self.shut_down.store(true, Ordering::SeqCst);
+        if self.process.lock().await.is_some() {
+            let _ = self.stop().await;
+        }
+    }
 
-        let request_json = serde_json::to_string(&request)
-            .map_err(|e| format!("Failed to serialize request: {}", e))?;
+    /// 崩溃循环的错误信息
+    pub fn failure(&self) -> Option<String> {
+        self.failure.lock().clone()
+    }
 
-        // 发送请求
-        {
-            let mut stdin_guard = self.stdin.lock().await;
-            let stdin = stdin_guard
-                .as_mut()
-                .ok_or("Server not running")?;
+    /// 清除崩溃循环状态，允许再次启动（用户重新初始化引擎时）
+    pub fn reset_failure(&self) {
+        if self.failure.lock().take().is_some() {
+            info!("🔄 FunASR server crash-loop state cleared");
+        }
+        self.restart_policy.lock().reset();
+    }
+
+    pub(super) fn should_run(&self) -> bool {
+        self.should_run.load(Ordering::SeqCst)
+    }
+
+    pub(super) fn is_shut_down(&self) -> bool {
+        self.shut_down.load(Ordering::SeqCst)
+    }
+
+    /// 已登记、尚未完成的请求数（包括已取得连接但还没发出的请求）
+    pub fn in_flight(&self) -> usize {
+        self.requests.load(Ordering::SeqCst)
+    }
+
+    /// 首次启动成功后启动监督任务
+    fn ensure_supervised(&self) {
+        if !self.supervised.swap(true, Ordering::SeqCst) {
+            tauri::async_runtime::spawn(supervisor::supervise(self.clone()));
+        }
+    }
+
+    /// 心跳：进程存活且在 `timeout` 内应答 ping，失败时结束进程并返回原因
+    pub(super) async fn heartbeat(&self, timeout: Duration) -> Result<(), String> {
+        if !self.is_alive().await {
+            return Err("server process exited".to_string());
+        }
 
-            writeln!(stdin, "{}", request_json)
-                .map_err(|e| format!("Failed to write request: {}", e))?;
+        let rpc = self.rpc.lock().clone().ok_or("server not connected")?;
+        match rpc.call("ping", serde_json::json!({}), &RequestControl::default(), timeout).await {
+            Ok(_) => Ok(()),
+            Err(e) => {
+                self.force_stop().await;
+                Err(format!("ping failed: {}", e))
+            }
+        }
+    }
 
-            stdin
-                .flush()
-                .map_err(|e| format!("Failed to flush stdin: {}", e))?;
+    /// 服务器进程的常驻内存（MB）
+    pub(super) async fn memory_mb(&self, system: &mut sysinfo::System) -> Option<u64> {
+        let pid = sysinfo::Pid::from_u32(self.pid().await?);
+        system.refresh_process(pid);
+        system.process(pid).map(|process| process.memory() / 1024 / 1024)
+    }
+
+    /// 空闲时主动重启（内存回收），不计入崩溃次数；有请求未完成时不重启并返回 false
+    ///
+    /// 检查和重启都在重启锁内进行，期间到达的请求等重启完成后发给新进程。
+    /// 只重新加载最近使用的识别模型，其余模型在下次使用时加载
+    pub(super) async fn recycle(&self) -> Result<bool, String> {
+        let recent = self.resident_asr_models().pop();
+        {
+            let _restart = self.restart_lock.lock().await;
+            if self.in_flight() > 0 {
+                return Ok(false);
+            }
+            self.stop().await?;
+            self.start().await?;
         }
+        self.preload_in_background(recent.into_iter().collect());
+        Ok(true)
+    }
 
-        // 读取响应（带超时：ping 用 30s，transcribe 用 60s）
-        let timeout_duration = if method == "ping" {
-            Duration::from_secs(30)
+    /// 确保服务器在运行：意外退出的按重启策略重启，主动停止的直接启动
+    pub async fn ensure_running(&self) -> Result<(), String> {
+        if self.is_alive().await {
+            return Ok(());
+        }
+        if self.should_run() {
+            self.restart_after_crash("server exited unexpectedly").await
         } else {
-            Duration::from_secs(60)
+            self.start().await
+        }
+    }
+
+    /// 崩溃后按指数退避重启；短时间内崩溃过多时进入崩溃循环状态并通知前端
+    pub(super) async fn restart_after_crash(&self, reason: &str) -> Result<(), String> {
+        let _restart = self.restart_lock.lock().await;
+
+        // 等锁期间可能已被其他调用方重启
+        if self.is_alive().await {
+            return Ok(());
+        }
+        if let Some(failure) = self.failure() {
+            return Err(failure);
+        }
+        self.force_stop().await;
+
+        let decision = self.restart_policy.lock().record_crash(Instant::now());
+        match decision {
+            RestartDecision::Retry { attempt, delay } => {
+                warn!(
+                    "🔁 FunASR server crashed ({}), restarting in {:?} (attempt {})",
+                    reason, delay, attempt
+                );
+                tokio::time::sleep(delay).await;
+                let previous = self.resident_asr_models();
+                self.start().await?;
+                // 恢复崩溃前已加载的模型
+                self.preload_in_background(previous);
+                Ok(())
+            }
+            RestartDecision::GiveUp { crashes } => {
+                let last_output = self.stderr_tail.lock().back().cloned();
+                let message = supervisor::crash_loop_message(crashes, last_output.as_deref());
+                error!("❌ {}", message);
+
+                self.should_run.store(false, Ordering::SeqCst);
+                *self.failure.lock() = Some(message.clone());
+                self.report_failure(&message);
+                Err(message)
+            }
+        }
+    }
+
+    /// 在环境检测中显示崩溃循环错误
+    fn report_failure(&self, message: &str) {
+        use tauri::Emitter;
+
+        let Some(app) = &self.app else {
+            return;
+        };
+        let tail: Vec<String> = self.stderr_tail.lock().iter().cloned().collect();
+        let _ = app.emit(
+            "python-env-status",
+            super::PythonEnvStatus {
+                status: "error".to_string(),
+                message: message.to_string(),
+                details: (!tail.is_empty()).then(|| tail.join("\n")),
+            },
+        );
+    }
+
+    /// 发送请求并等待响应
+    ///
+    /// 超时按该请求两条消息之间的间隔计算：ping 用 30s，stream_start 和 load_model 用 300s，其余方法用 60s
+    async fn send_request(
+        &self,
+        method: &str,
+        params: serde_json::Value,
+        control: &RequestControl,
+    ) -> Result<serde_json::Value, RpcError> {
+        let idle_timeout = match method {
+            "ping" => Duration::from_secs(30),
+            // 首次使用时需要下载并加载模型
+            "stream_start" | "load_model" => MODEL_LOAD_TIMEOUT,
+            _ => REQUEST_TIMEOUT,
+        };
+        self.send_request_with_timeout(method, params, control, idle_timeout).await
+    }
+
+    async fn send_request_with_timeout(
+        &self,
+        method: &str,
+        params: serde_json::Value,
+        control: &RequestControl,
+        idle_timeout: Duration,
+    ) -> Result<serde_json::Value, RpcError> {
+        // 在重启锁内登记，内存回收不会停掉已登记请求所用的进程
+        let _request = {
+            let _restart = self.restart_lock.lock().await;
+            RequestGuard::register(&self.requests)
         };
+        let rpc = self
+            .rpc
+            .lock()
+            .clone()
+            .ok_or_else(|| RpcError::new(ErrorCode::ServerExited, "Server not running"))?;
+
+        rpc.call(method, params, control, idle_timeout).await
+    }
 
-        let response_line = {
-            let stdout_arc = self.stdout.clone();
-
-            match timeout(timeout_duration, tokio::task::spawn_blocking(move || {
-                let mut stdout_guard = stdout_arc.blocking_lock();
-                let stdout = stdout_guard
-                    .as_mut()
-                    .ok_or("Server not running")?;
-
-                let mut line = String::new();
-                stdout
-                    .read_line(&mut line)
-                    .map_err(|e| format!("Failed to read response: {}", e))?;
-
-                Ok::<String, String>(line)
-            })).await {
-                Ok(Ok(Ok(line))) => line,
-                Ok(Ok(Err(e))) => return Err(e),
-                Ok(Err(e)) => return Err(format!("Task error: {}", e)),
-                Err(_) => {
-                    error!("❌ Request timeout after {:?} for method: {}", timeout_duration, method);
-                    return Err(format!("Request timeout after {:?}", timeout_duration));
+    /// 已加载的模型，最近使用的在末尾
+    pub fn resident_models(&self) -> Vec<ResidentModel> {
+        self.resident.lock().clone()
+    }
+
+    fn is_resident(&self, model_name: &str) -> bool {
+        self.resident.lock().iter().any(|model| model.name == model_name)
+    }
+
+    /// 已加载的识别模型名称，最近使用的在末尾
+    fn resident_asr_models(&self) -> Vec<String> {
+        self.resident
+            .lock()
+            .iter()
+            .filter(|model| is_asr_model(&model.name))
+            .map(|model| model.name.clone())
+            .collect()
+    }
+
+    /// 预加载模型，之后的转录不再等待加载；超出常驻上限时释放最久未用的识别模型
+    pub async fn preload_model(&self, model_name: &str) -> Result<ResidentModel, String> {
+        self.ensure_running().await?;
+
+        info!("📦 Preloading FunASR model: {}", model_name);
+        let params = serde_json::json!({ "model_name": model_name });
+        let mut result = self
+            .send_request("load_model", params, &RequestControl::default())
+            .await
+            .map_err(RpcError::into_message)?;
+        let model: ResidentModel = serde_json::from_value(result["model"].take())
+            .map_err(|e| format!("Invalid load_model response: {}", e))?;
+
+        if !result["already_loaded"].as_bool().unwrap_or(false) {
+            info!(
+                "✅ FunASR model loaded: {} ({} ms, {:?} MB)",
+                model.name, model.load_ms, model.memory_mb
+            );
+        }
+        mark_used(&mut self.resident.lock(), model.clone());
+        self.evict_models(model_name).await;
+
+        Ok(model)
+    }
+
+    /// 释放模型，返回是否实际释放（服务器未运行时只清除记录）
+    pub async fn unload_model(&self, model_name: &str) -> Result<bool, String> {
+        self.resident.lock().retain(|model| model.name != model_name);
+        if !self.is_alive().await {
+            return Ok(false);
+        }
+
+        let params = serde_json::json!({ "model_name": model_name });
+        let result = self
+            .send_request("unload_model", params, &RequestControl::default())
+            .await
+            .map_err(RpcError::into_message)?;
+        let unloaded = result["unloaded"].as_bool().unwrap_or(false);
+        if unloaded {
+            info!("🗑️  FunASR model unloaded: {} (server RSS {:?} MB)", model_name, result["rss_mb"].as_f64());
+        }
+
+        Ok(unloaded)
+    }
+
+    /// 从服务器同步已加载的模型（按需加载的模型不经过 `preload_model`）
+    pub async fn refresh_resident_models(&self) -> Result<Vec<ResidentModel>, String> {
+        let mut result = self
+            .send_request("list_models", serde_json::json!({}), &RequestControl::default())
+            .await
+            .map_err(RpcError::into_message)?;
+        let models: Vec<ResidentModel> = serde_json::from_value(result["models"].take())
+            .map_err(|e| format!("Invalid list_models response: {}", e))?;
+
+        let mut resident = self.resident.lock();
+        sync_resident(&mut resident, models);
+        Ok(resident.clone())
+    }
+
+    /// 超出常驻上限时释放最久未用的识别模型（`keep` 除外）
+    async fn evict_models(&self, keep: &str) {
+        let evicted = eviction_candidates(&self.resident.lock(), keep, MAX_RESIDENT_MODELS);
+        for name in evicted {
+            info!("♻️  Evicting least recently used FunASR model: {}", name);
+            if let Err(e) = self.unload_model(&name).await {
+                warn!("⚠️  Failed to unload FunASR model {}: {}", name, e);
+            }
+        }
+    }
+
+    /// 记录模型被使用；服务器按需加载的新模型在后台同步并按上限释放旧模型
+    fn note_model_used(&self, model_name: &str) {
+        let known = {
+            let mut resident = self.resident.lock();
+            match resident.iter().position(|model| model.name == model_name) {
+                Some(index) => {
+                    let model = resident.remove(index);
+                    resident.push(model);
+                    true
                 }
+                None => false,
             }
         };
+        if known {
+            return;
+        }
 
-        // 解析响应
-        info!("📨 Received response line: {}", response_line.trim());
-        let response: Response = serde_json::from_str(&response_line)
-            .map_err(|e| format!("Failed to parse response: {}", e))?;
+        let server = self.clone();
+        let model_name = model_name.to_string();
+        tauri::async_runtime::spawn(async move {
+            match server.refresh_resident_models().await {
+                Ok(models) => {
+                    if let Some(model) = models.into_iter().find(|model| model.name == model_name) {
+                        mark_used(&mut server.resident.lock(), model);
+                    }
+                    server.evict_models(&model_name).await;
+                }
+                Err(e) => warn!("⚠️  Failed to list FunASR models: {}", e),
+            }
+        });
+    }
 
-        info!("📊 Parsed response - success: {}, text length: {}",
-            response.success, response.text.len());
+    /// 在后台依次预加载模型（重启后恢复常驻模型）
+    fn preload_in_background(&self, models: Vec<String>) {
+        if models.is_empty() {
+            return;
+        }
 
-        Ok(response)
+        let server = self.clone();
+        tauri::async_runtime::spawn(async move {
+            for model_name in models {
+                if let Err(e) = server.preload_model(&model_name).await {
+                    warn!("⚠️  Failed to preload FunASR model {}: {}", model_name, e);
+                }
+            }
+        });
     }
 
     /// 转录音频（带自动重试）
+    ///
+    /// `hotwords` 为换行分隔的热词列表（每行一个词条），由 FunASR 用于偏置识别结果；
+    /// `timestamps` 为 true 时同时返回句子级段落；
+    /// `control` 提供取消标志（被取消时返回 `jobs::CANCELLED_MESSAGE`）和分块进度回调。
+    /// 只有服务器进程退出会重启服务器重试；超时（已通知服务器取消该请求）和服务器返回的业务错误直接返回
     pub async fn transcribe(
         &self,
-        audio_path: &str,
+        audio: AudioInput<'_>,
         model_name: &str,
         language: Option<&str>,
-    ) -> Result<String, String> {
+        hotwords: Option<&str>,
+        timestamps: bool,
+        control: &RequestControl,
+    ) -> Result<Transcript, String> {
         const MAX_RETRIES: u32 = 2;
 
         for attempt in 1..=MAX_RETRIES {
-            // 健康检查：如果服务器挂了，尝试重启
-            if !self.is_alive().await {
-                warn!("⚠️  Server not alive, attempting to restart (attempt {}/{})", attempt, MAX_RETRIES);
-
-                // 清理旧进程
-                self.force_stop().await;
+            if control.is_cancelled() {
+                return Err(crate::jobs::CANCELLED_MESSAGE.to_string());
+            }
 
-                // 尝试重新启动
-                match self.start().await {
-                    Ok(_) => info!("✅ Server restarted successfully"),
-                    Err(e) => {
-                        if attempt == MAX_RETRIES {
-                            return Err(format!("Failed to restart server after {} attempts: {}", MAX_RETRIES, e));
-                        }
-                        warn!("⚠️  Restart failed, will retry: {}", e);
-                        continue;
-                    }
+            // 服务器意外退出时按重启策略重启（崩溃循环时直接返回错误）
+            if let Err(e) = self.ensure_running().await {
+                if attempt == MAX_RETRIES || self.failure().is_some() {
+                    return Err(format!("Failed to start server: {}", e));
                 }
-            } else {
-                // 服务器正常，确保启动
-                self.start().await?;
+                warn!("⚠️  Server start failed, will retry: {}", e);
+                continue;
             }
 
-            info!("🎤 Transcribing audio (attempt {}/{}): {}", attempt, MAX_RETRIES, audio_path);
+            info!("🎤 Transcribing audio (attempt {}/{}): {}", attempt, MAX_RETRIES, audio.describe());
             info!("   Model: {}", model_name);
             info!("   Language: {:?}", language);
 
             let mut params = serde_json::json!({
-                "audio_path": audio_path,
                 "model_name": model_name,
             });
+            audio.add_to(&mut params);
 
             if let Some(lang) = language {
                 params["language"] = serde_json::json!(lang);
             }
 
-            match self.send_request("transcribe", params).await {
-                Ok(response) => {
-                    if !response.success {
-                        return Err(response.error);
-                    }
+            if let Some(hotword) = hotwords {
+                params["hotword"] = serde_json::json!(hotword);
+            }
 
-                    info!("✅ Transcription complete");
-                    return Ok(response.text);
+            if timestamps {
+                params["timestamps"] = serde_json::json!(true);
+            }
+
+            // 模型未常驻时服务器先加载模型，按加载模型的超时等待
+            let idle_timeout = if self.is_resident(model_name) {
+                REQUEST_TIMEOUT
+            } else {
+                MODEL_LOAD_TIMEOUT
+            };
+
+            match self.send_request_with_timeout("transcribe", params, control, idle_timeout).await {
+                Ok(result) => {
+                    let transcript: Transcript = serde_json::from_value(result)
+                        .map_err(|e| format!("Invalid transcription result: {}", e))?;
+                    info!(
+                        "✅ Transcription complete, text length: {}, segments: {}",
+                        transcript.text.len(),
+                        transcript.segments.len()
+                    );
+                    self.note_model_used(model_name);
+                    return Ok(transcript);
+                }
+                // 取消、超时和服务器报告的错误都不重启服务器：超时的请求已被取消，
+                // 进程是否卡死由心跳判断，重启会丢掉已加载的模型
+                Err(e) if e.kind() != ErrorCode::ServerExited => {
+                    if e.kind() != ErrorCode::RequestCancelled {
+                        error!("❌ Transcription failed: {}", e);
+                    }
+                    return Err(e.into_message());
                 }
                 Err(e) => {
                     error!("❌ Transcription failed (attempt {}/{}): {}", attempt, MAX_RETRIES, e);
 
                     if attempt == MAX_RETRIES {
-                        return Err(format!("Transcription failed after {} attempts: {}", MAX_RETRIES, e));
+                        return Err(format!("Transcription failed after {} attempts: {}", MAX_RETRIES, e.message));
                     }
 
-                    // 强制停止服务器，下次循环会重启
+                    // 结束无响应的进程，下次循环按重启策略重启
                     self.force_stop().await;
-
-                    // 等待一小段时间再重试
-                    tokio::time::sleep(Duration::from_millis(500)).await;
                 }
             }
         }
 
         Err("Transcription failed: max retries exceeded".to_string())
     }
-}
 
-// 注意：Drop trait 不能是异步的，所以我们不在这里清理
-// 服务器进程会在程序退出时自动终止
+    /// 用 CAM++ 模型为 `ranges`（毫秒）内的语音提取说话人向量
+    ///
+    /// 起止相同的区间不提取，对应位置返回 None
+    pub async fn embed_speakers(
+        &self,
+        audio: AudioInput<'_>,
+        ranges: &[(u64, u64)],
+        control: &RequestControl,
+    ) -> Result<Vec<Option<Vec<f32>>>, String> {
+        self.ensure_running().await?;
 
-/// 获取服务器脚本路径
-fn get_server_script_path(app: &AppHandle) -> Result<PathBuf, String> {
-    #[cfg(debug_assertions)]
-    {
-        let current_dir = std::env::current_dir()
-            .map_err(|e| format!("Failed to get current dir: {}", e))?;
+        info!("🗣️  Extracting speaker embeddings for {} segments: {}", ranges.len(), audio.describe());
 
-        let script_path = if current_dir.ends_with("src-tauri") {
-            current_dir.join("scripts").join("funasr_server.py")
-        } else {
-            current_dir
-                .join("src-tauri")
-                .join("scripts")
-                .join("funasr_server.py")
-        };
+        let mut params = serde_json::json!({
+            "segments": ranges,
+        });
+        audio.add_to(&mut params);
+
+        let mut result = self
+            .send_request("embed_speakers", params, control)
+            .await
+            .map_err(RpcError::into_message)?;
+        let embeddings: Vec<Option<Vec<f32>>> = serde_json::from_value(result["embeddings"].take())
+            .map_err(|e| format!("Invalid speaker embeddings: {}", e))?;
+        if embeddings.len() != ranges.len() {
+            return Err(format!(
+                "Speaker embedding count mismatch: expected {}, got {}",
+                ranges.len(),
+                embeddings.len()
+            ));
+        }
+
+        Ok(embeddings)
+    }
+
+    /// 开始流式识别会话，返回会话 id
+    ///
+    /// `model_name` 为句末 2-pass 修正使用的离线模型
+    pub async fn stream_start(&self, model_name: &str, hotwords: Option<&str>) -> Result<String, String> {
+        self.ensure_running().await?;
 
-        if !script_path.exists() {
-            return Err(format!("Server script not found: {:?}", script_path));
+        info!("🎙️  Starting streaming session (2-pass model: {})", model_name);
+
+        let mut params = serde_json::json!({
+            "model_name": model_name,
+        });
+        if let Some(hotword) = hotwords {
+            params["hotword"] = serde_json::json!(hotword);
         }
 
-        return Ok(script_path);
+        let result = self
+            .send_request("stream_start", params, &RequestControl::default())
+            .await
+            .map_err(RpcError::into_message)?;
+        self.note_model_used(model_name);
+        result["session_id"]
+            .as_str()
+            .map(str::to_string)
+            .ok_or_else(|| "Invalid stream_start response: missing session_id".to_string())
+    }
+
+    /// 推送一段 16kHz 单声道音频，返回截至目前的识别结果
+    pub async fn stream_audio(&self, session_id: &str, samples: &[f32]) -> Result<StreamingText, String> {
+        let params = serde_json::json!({
+            "session_id": session_id,
+            "audio_pcm16": encode_pcm16(samples),
+        });
+
+        let result = self
+            .send_request("stream_audio", params, &RequestControl::default())
+            .await
+            .map_err(RpcError::into_message)?;
+        serde_json::from_value(result).map_err(|e| format!("Invalid stream_audio response: {}", e))
     }
 
-    #[cfg(not(debug_assertions))]
-    {
-        // TODO: 生产模式
-        Err("Production mode not yet implemented".to_string())
+    /// 结束会话，识别剩余音频并返回全部已确认文本
+    pub async fn stream_finish(&self, session_id: &str) -> Result<String, String> {
+        let params = serde_json::json!({ "session_id": session_id });
+
+        let result = self
+            .send_request("stream_finish", params, &RequestControl::default())
+            .await
+            .map_err(RpcError::into_message)?;
+        Ok(result["text"].as_str().unwrap_or_default().to_string())
+    }
+
+    /// 丢弃会话（录音取消或结果改由整段转录给出时）
+    pub async fn stream_cancel(&self, session_id: &str) -> Result<(), String> {
+        let params = serde_json::json!({ "session_id": session_id });
+
+        self.send_request("stream_cancel", params, &RequestControl::default())
+            .await
+            .map(|_| ())
+            .map_err(RpcError::into_message)
     }
 }
 
+// 注意：Drop trait 不能是异步的，应用退出时由 `shutdown` 关闭进程
+
+/// 请求登记，完成或中途返回时注销
+struct RequestGuard(Arc<AtomicUsize>);
+
+impl RequestGuard {
+    fn register(requests: &Arc<AtomicUsize>) -> Self {
+        requests.fetch_add(1, Ordering::SeqCst);
+        Self(requests.clone())
+    }
+}
+
+impl Drop for RequestGuard {
+    fn drop(&mut self) {
+        self.0.fetch_sub(1, Ordering::SeqCst);
+    }
+}
+
+/// 计入常驻上限的识别模型（说话人模型和流式模型除外）
+fn is_asr_model(name: &str) -> bool {
+    crate::config::FUNASR_MODELS.contains(&name)
+}
+
+/// 把模型移到末尾（最近使用），未记录时追加
+fn mark_used(resident: &mut Vec<ResidentModel>, model: ResidentModel) {
+    resident.retain(|existing| existing.name != model.name);
+    resident.push(model);
+}
+
+/// 用服务器返回的模型列表更新记录：保留已有的使用顺序，新加载的追加到末尾
+fn sync_resident(resident: &mut Vec<ResidentModel>, models: Vec<ResidentModel>) {
+    resident.retain(|existing| models.iter().any(|model| model.name == existing.name));
+    for model in models {
+        match resident.iter_mut().find(|existing| existing.name == model.name) {
+            Some(existing) => *existing = model,
+            None => resident.push(model),
+        }
+    }
+}
+
+/// 超出上限时应释放的识别模型，最久未用的在前；`keep` 不会被释放
+fn eviction_candidates(resident: &[ResidentModel], keep: &str, limit: usize) -> Vec<String> {
+    let asr: Vec<&str> = resident
+        .iter()
+        .map(|model| model.name.as_str())
+        .filter(|name| is_asr_model(name))
+        .collect();
+    asr.iter()
+        .filter(|name| **name != keep)
+        .take(asr.len().saturating_sub(limit))
+        .map(|name| name.to_string())
+        .collect()
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
 
     #[test]
-    fn test_request_serialization() {
-        let request = Request {
-            method: "transcribe".to_string(),
-            params: serde_json::json!({
-                "audio_path": "/tmp/test.wav",
-                "model_name": "paraformer-zh",
-            }),
-        };
+    fn test_audio_input_params() {
+        assert_eq!(encode_pcm16(&[0.0, 1.0, -1.0]), "AAD/fwGA");
+        // 超出范围的采样被截断
+        assert_eq!(encode_pcm16(&[2.0]), encode_pcm16(&[1.0]));
+
+        let mut params = serde_json::json!({});
+        AudioInput::Pcm(&[0.0, 1.0, -1.0]).add_to(&mut params);
+        assert_eq!(params["audio_pcm16"], "AAD/fwGA");
+        assert_eq!(params["sample_rate"], 16000);
+        assert!(params.get("audio_path").is_none());
+
+        let mut params = serde_json::json!({});
+        AudioInput::File("/tmp/import.wav").add_to(&mut params);
+        assert_eq!(params["audio_path"], "/tmp/import.wav");
+        assert!(params.get("audio_pcm16").is_none());
+    }
+
+    #[test]
+    fn test_server_wav_reuses_matching_source() {
+        let audio: Vec<f32> = (0..1600).map(|i| (i as f32 / 100.0).sin() * 0.5).collect();
+
+        // 非 16kHz 源文件：写入临时文件，析构时删除
+        let converted = ServerWav::prepare(&audio, Some("/nonexistent/import.mp3")).unwrap();
+        let temp_path = converted.path.clone();
+        assert!(converted.temporary);
+        assert!(is_server_wav(&temp_path));
+
+        // 符合格式的源文件直接使用
+        let reused = ServerWav::prepare(&audio, Some(&temp_path)).unwrap();
+        assert!(!reused.temporary);
+        assert!(matches!(reused.input(), AudioInput::File(path) if path == temp_path));
+        drop(reused);
+        assert!(std::path::Path::new(&temp_path).exists());
+
+        drop(converted);
+        assert!(!std::path::Path::new(&temp_path).exists());
+    }
+
+    #[test]
+    fn test_streaming_text() {
+        assert!(supports_streaming("paraformer-zh"));
+        assert!(!supports_streaming("sensevoice-small"));
+
+        let text: StreamingText = serde_json::from_value(serde_json::json!({
+            "text": "你好。今天",
+            "confirmed": "你好。",
+            "partial": "今天",
+        }))
+        .unwrap();
+        assert_eq!(text.confirmed, "你好。");
+        assert_eq!(text.partial, "今天");
+    }
+
+    #[test]
+    fn test_transcript_segments() {
+        let transcript: Transcript = serde_json::from_value(serde_json::json!({ "text": "你好" })).unwrap();
+        assert_eq!(transcript.text, "你好");
+        assert!(transcript.segments.is_empty());
+
+        let transcript: Transcript = serde_json::from_value(serde_json::json!({
+            "text": "你好。今天",
+            "segments": [
+                {
+                    "text": "你好。",
+                    "start_ms": 100,
+                    "end_ms": 600,
+                    "words": [
+                        { "text": "你", "start_ms": 100, "end_ms": 300 },
+                        { "text": "好", "start_ms": 300, "end_ms": 600 },
+                    ],
+                },
+                { "text": "今天", "start_ms": 700, "end_ms": 1200, "words": [] },
+            ],
+        }))
+        .unwrap();
+        assert_eq!(transcript.segments.len(), 2);
+        assert_eq!(transcript.segments[0].words[1].text, "好");
+        assert_eq!(transcript.segments[1].start_ms, 700);
+        assert_eq!(transcript.segments[1].speaker, None);
+    }
+
+    fn resident(name: &str) -> ResidentModel {
+        ResidentModel {
+            name: name.to_string(),
+            memory_mb: None,
+            load_ms: 0,
+        }
+    }
+
+    fn names(models: &[ResidentModel]) -> Vec<&str> {
+        models.iter().map(|model| model.name.as_str()).collect()
+    }
+
+    #[test]
+    fn test_resident_tracking() {
+        let mut models = vec![resident("paraformer-zh"), resident("cam++")];
+        mark_used(&mut models, resident("sensevoice-small"));
+        mark_used(&mut models, resident("paraformer-zh"));
+        assert_eq!(names(&models), vec!["cam++", "sensevoice-small", "paraformer-zh"]);
+
+        // 服务器重启后 cam++ 不在了，按需加载了 paraformer-large
+        let mut loaded = resident("paraformer-large");
+        loaded.memory_mb = Some(1200.0);
+        sync_resident(&mut models, vec![resident("paraformer-zh"), resident("sensevoice-small"), loaded]);
+        assert_eq!(names(&models), vec!["sensevoice-small", "paraformer-zh", "paraformer-large"]);
+        assert_eq!(models[2].memory_mb, Some(1200.0));
+    }
+
+    #[test]
+    fn test_eviction_candidates() {
+        let models = vec![
+            resident("sensevoice-small"),
+            resident("stream-asr"),
+            resident("paraformer-zh"),
+            resident("paraformer-large"),
+        ];
+        assert_eq!(eviction_candidates(&models, "paraformer-large", 2), vec!["sensevoice-small"]);
+        // 要保留的模型即使最久未用也不释放
+        assert_eq!(eviction_candidates(&models, "sensevoice-small", 2), vec!["paraformer-zh"]);
+        assert!(eviction_candidates(&models, "paraformer-zh", 3).is_empty());
+    }
+
+    /// 以 `tests/fixtures/fake_funasr_server.py` 为脚本启动服务器，环境中没有 Python 时返回 None（跳过测试）
+    async fn fake_server() -> Option<FunASRServer> {
+        let script = PathBuf::from(concat!(env!("CARGO_MANIFEST_DIR"), "/tests/fixtures/fake_funasr_server.py"));
+        for python in ["python3", "python"] {
+            let server = FunASRServer::with_script(None, PathBuf::from(python), script.clone());
+            if server.start().await.is_ok() {
+                return Some(server);
+            }
+        }
+        eprintln!("Python not found, skipping FunASR restart test");
+        None
+    }
+
+    async fn echo(server: &FunASRServer, value: &str, delay_ms: u64) -> Result<String, RpcError> {
+        let params = serde_json::json!({ "value": value, "delay_ms": delay_ms });
+        let result = server.send_request("echo", params, &RequestControl::default()).await?;
+        Ok(result["value"].as_str().unwrap_or_default().to_string())
+    }
+
+    #[tokio::test]
+    async fn test_restart_after_crash() {
+        let Some(server) = fake_server().await else { return };
+        let pid = server.pid().await;
+
+        // 进程意外退出后，下一次请求前按重启策略重启
+        let error = server
+            .send_request("exit", serde_json::json!({}), &RequestControl::default())
+            .await
+            .unwrap_err();
+        assert_eq!(error.kind(), ErrorCode::ServerExited);
+        for _ in 0..40 {
+            if !server.is_alive().await {
+                break;
+            }
+            tokio::time::sleep(Duration::from_millis(50)).await;
+        }
+        assert!(!server.is_alive().await);
+        assert!(server.should_run());
+
+        server.ensure_running().await.unwrap();
+        assert_ne!(server.pid().await, pid);
+        assert_eq!(echo(&server, "restarted", 0).await.unwrap(), "restarted");
+
+        server.shutdown().await;
+    }
+
+    #[tokio::test]
+    async fn test_recycle_waits_for_requests() {
+        let Some(server) = fake_server().await else { return };
+        let pid = server.pid().await;
+
+        // 有请求未完成时不回收
+        let (slow, recycled) = tokio::join!(echo(&server, "slow", 300), async {
+            tokio::time::sleep(Duration::from_millis(50)).await;
+            server.recycle().await
+        });
+        assert_eq!(slow.unwrap(), "slow");
+        assert!(!recycled.unwrap());
+        assert_eq!(server.pid().await, pid);
+
+        // 回收期间到达的请求等新进程就绪后发出
+        let (recycled, late) = tokio::join!(server.recycle(), async {
+            tokio::time::sleep(Duration::from_millis(20)).await;
+            echo(&server, "late", 0).await
+        });
+        assert!(recycled.unwrap());
+        assert_eq!(late.unwrap(), "late");
+        assert_ne!(server.pid().await, pid);
+        assert_eq!(server.in_flight(), 0);
 
-        let json = serde_json::to_string(&request).unwrap();
-        assert!(json.contains("transcribe"));
-        assert!(json.contains("/tmp/test.wav"));
+        server.shutdown().await;
     }
 }
@@ -0,0 +1,230 @@
+/// FunASR 服务器监督
+/// 定期心跳检查和内存监控，进程崩溃时按指数退避重启，短时间内反复崩溃则停止重启并报告错误；
+/// 服务器的 stderr 转发到应用日志
+
+use std::collections::VecDeque;
+use std::io::{BufRead, BufReader, Read};
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+use tracing::{debug, error, info, warn};
+
+use super::server::FunASRServer;
+
+/// 心跳间隔
+pub const HEARTBEAT_INTERVAL: Duration = Duration::from_secs(15);
+
+/// 心跳超时（ping 在服务器读取线程中直接应答，推理期间也能及时响应）
+pub const HEARTBEAT_TIMEOUT: Duration = Duration::from_secs(20);
+
+/// 常驻内存超过该值且空闲时重启服务器，释放 PyTorch 缓存等持续增长的内存
+pub const MEMORY_LIMIT_MB: u64 = 6 * 1024;
+
+/// 第一次重启前的等待时间，之后每次翻倍
+const BACKOFF_BASE: Duration = Duration::from_secs(1);
+const BACKOFF_MAX: Duration = Duration::from_secs(30);
+
+/// 在 CRASH_LOOP_WINDOW 内崩溃 CRASH_LOOP_THRESHOLD 次视为崩溃循环
+const CRASH_LOOP_WINDOW: Duration = Duration::from_secs(120);
+const CRASH_LOOP_THRESHOLD: usize = 5;
+
+/// 保留的 stderr 行数（崩溃时附在错误信息中）
+pub const STDERR_TAIL_LINES: usize = 20;
+
+/// 崩溃后的处理
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum RestartDecision {
+    /// 等待 `delay` 后进行第 `attempt` 次重启
+    Retry { attempt: usize, delay: Duration },
+    /// 崩溃过于频繁，停止自动重启
+    GiveUp { crashes: usize },
+}
+
+/// 重启策略：只统计最近 CRASH_LOOP_WINDOW 内的崩溃，稳定运行一段时间后退避自动清零
+#[derive(Debug, Default)]
+pub struct RestartPolicy {
+    crashes: VecDeque<Instant>,
+}
+
+impl RestartPolicy {
+    /// 记录一次崩溃并决定是否重启
+    pub fn record_crash(&mut self, now: Instant) -> RestartDecision {
+        while self
+            .crashes
+            .front()
+            .is_some_and(|&crash| now.duration_since(crash) > CRASH_LOOP_WINDOW)
+        {
+            self.crashes.pop_front();
+        }
+        self.crashes.push_back(now);
+
+        let crashes = self.crashes.len();
+        if crashes >= CRASH_LOOP_THRESHOLD {
+            return RestartDecision::GiveUp { crashes };
+        }
+
+        let delay = BACKOFF_BASE
+            .saturating_mul(1 << (crashes - 1).min(16))
+            .min(BACKOFF_MAX);
+        RestartDecision::Retry { attempt: crashes, delay }
+    }
+
+    pub fn reset(&mut self) {
+        self.crashes.clear();
+    }
+}
+
+/// 崩溃循环的错误信息，附上服务器最后的输出
+pub fn crash_loop_message(crashes: usize, last_output: Option<&str>) -> String {
+    let mut message = format!(
+        "FunASR 服务器在 {} 秒内崩溃了 {} 次，已停止自动重启",
+        CRASH_LOOP_WINDOW.as_secs(),
+        crashes
+    );
+    if let Some(line) = last_output {
+        message.push_str(&format!("（最后输出: {}）", line));
+    }
+    message
+}
+
+/// 后台线程逐行读取服务器 stderr，写入应用日志并保留最近几行
+pub fn forward_stderr(stderr: impl Read + Send + 'static, tail: Arc<parking_lot::Mutex<VecDeque<String>>>) {
+    let spawned = std::thread::Builder::new()
+        .name("funasr-stderr".to_string())
+        .spawn(move || {
+            let mut reader = BufReader::new(stderr);
+            let mut buffer = Vec::new();
+            loop {
+                buffer.clear();
+                match reader.read_until(b'\n', &mut buffer) {
+                    Ok(0) | Err(_) => break,
+                    Ok(_) => {
+                        let line = String::from_utf8_lossy(&buffer);
+                        let line = line.trim_end();
+                        if line.is_empty() {
+                            continue;
+                        }
+
+                        if is_error_line(line) {
+                            warn!("🐍 [FunASR] {}", line);
+                        } else {
+                            debug!("🐍 [FunASR] {}", line);
+                        }
+
+                        let mut tail = tail.lock();
+                        if tail.len() == STDERR_TAIL_LINES {
+                            tail.pop_front();
+                        }
+                        tail.push_back(line.to_string());
+                    }
+                }
+            }
+        });
+    if let Err(e) = spawned {
+        warn!("⚠️  Failed to spawn FunASR stderr reader: {}", e);
+    }
+}
+
+/// 服务器脚本用 ❌ / ⚠️ 标记错误，Python 异常以 Traceback 开头
+fn is_error_line(line: &str) -> bool {
+    line.starts_with('❌')
+        || line.starts_with("⚠️")
+        || line.starts_with("Traceback")
+        || line.contains("Error:")
+}
+
+/// 监督任务：服务器首次启动后运行，直到应用退出
+pub async fn supervise(server: FunASRServer) {
+    let mut system = sysinfo::System::new();
+
+    loop {
+        tokio::time::sleep(HEARTBEAT_INTERVAL).await;
+
+        if server.is_shut_down() {
+            break;
+        }
+        // 主动停止（空闲释放）或已进入崩溃循环时不做检查
+        if !server.should_run() || server.failure().is_some() {
+            continue;
+        }
+
+        if let Err(reason) = server.heartbeat(HEARTBEAT_TIMEOUT).await {
+            warn!("💔 [FunASR] Heartbeat failed: {}", reason);
+            if let Err(e) = server.restart_after_crash(&reason).await {
+                error!("❌ [FunASR] Supervisor restart failed: {}", e);
+            }
+            continue;
+        }
+
+        let Some(memory_mb) = server.memory_mb(&mut system).await else {
+            continue;
+        };
+        debug!("💓 [FunASR] Heartbeat ok, memory: {} MB", memory_mb);
+
+        if memory_mb > MEMORY_LIMIT_MB {
+            match server.recycle().await {
+                Ok(true) => info!("♻️  [FunASR] Recycled server, memory {} MB exceeded {} MB", memory_mb, MEMORY_LIMIT_MB),
+                Ok(false) => warn!("⚠️  [FunASR] Server memory {} MB exceeds limit, will recycle when idle", memory_mb),
+                Err(e) => error!("❌ [FunASR] Failed to recycle server: {}", e),
+            }
+        }
+    }
+
+    debug!("[FunASR] Supervisor stopped");
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_restart_backoff() {
+        let mut policy = RestartPolicy::default();
+        let start = Instant::now();
+
+        let delays: Vec<_> = (0..4)
+            .map(|i| policy.record_crash(start + Duration::from_secs(i)))
+            .collect();
+        assert_eq!(
+            delays,
+            vec![
+                RestartDecision::Retry { attempt: 1, delay: Duration::from_secs(1) },
+                RestartDecision::Retry { attempt: 2, delay: Duration::from_secs(2) },
+                RestartDecision::Retry { attempt: 3, delay: Duration::from_secs(4) },
+                RestartDecision::Retry { attempt: 4, delay: Duration::from_secs(8) },
+            ]
+        );
+        assert_eq!(
+            policy.record_crash(start + Duration::from_secs(5)),
+            RestartDecision::GiveUp { crashes: 5 }
+        );
+
+        policy.reset();
+        assert_eq!(
+            policy.record_crash(start + Duration::from_secs(6)),
+            RestartDecision::Retry { attempt: 1, delay: Duration::from_secs(1) }
+        );
+    }
+
+    #[test]
+    fn test_old_crashes_expire() {
+        let mut policy = RestartPolicy::default();
+        let start = Instant::now();
+
+        for i in 0..4 {
+            policy.record_crash(start + Duration::from_secs(i));
+        }
+        // 稳定运行超过统计窗口后，退避重新从头开始
+        assert_eq!(
+            policy.record_crash(start + CRASH_LOOP_WINDOW + Duration::from_secs(10)),
+            RestartDecision::Retry { attempt: 1, delay: Duration::from_secs(1) }
+        );
+    }
+
+    #[test]
+    fn test_error_lines() {
+        assert!(is_error_line("❌ Transcription error:"));
+        assert!(is_error_line("Traceback (most recent call last):"));
+        assert!(is_error_line("RuntimeError: CUDA out of memory"));
+        assert!(!is_error_line("🚀 FunASR Server started"));
+    }
+}
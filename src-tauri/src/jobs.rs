@@ -0,0 +1,323 @@
+/// 转录任务管理
+/// 每次转录分配一个任务 ID 和取消标志；解码过程中以任务 ID 发送进度和段落事件，
+/// 任务结束（含出错、被取消）时发送结果事件，接管了快捷键预留的任务还会让快捷键状态机回到空闲
+
+use once_cell::sync::Lazy;
+use parking_lot::Mutex;
+use std::collections::HashMap;
+use std::sync::atomic::{AtomicBool, AtomicU64, Ordering};
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+use tauri::{AppHandle, Emitter};
+
+use crate::whisper::{DecodeProgress, ProgressCallback};
+
+/// 被取消的转录命令返回的错误信息（前端据此区分取消和失败）
+pub const CANCELLED_MESSAGE: &str = "Transcription cancelled";
+
+static NEXT_JOB_ID: AtomicU64 = AtomicU64::new(1);
+
+/// 进行中的转录任务（任务 ID -> 取消标志）
+static ACTIVE_JOBS: Lazy<Mutex<HashMap<String, Arc<AtomicBool>>>> =
+    Lazy::new(|| Mutex::new(HashMap::new()));
+
+/// 快捷键松开后等待前端发起转录的时间上限（超时视为前端已放弃，避免状态机卡在处理中）
+const PENDING_JOB_TIMEOUT: Duration = Duration::from_secs(120);
+
+/// 快捷键松开、前端尚未发起转录命令的时刻
+static PENDING_SINCE: Lazy<Mutex<Option<Instant>>> = Lazy::new(|| Mutex::new(None));
+
+/// 接管了快捷键预留的任务 ID（锁顺序：先 PENDING_SINCE 后 SHORTCUT_JOB）
+static SHORTCUT_JOB: Lazy<Mutex<Option<String>>> = Lazy::new(|| Mutex::new(None));
+
+/// 进度事件
+#[derive(Debug, Clone, serde::Serialize)]
+struct ProgressEvent<'a> {
+    job_id: &'a str,
+    percent: u8,
+}
+
+/// 段落事件（FunASR 分块结果没有时间戳）
+#[derive(Debug, Clone, serde::Serialize)]
+struct SegmentEvent<'a> {
+    job_id: &'a str,
+    text: &'a str,
+    start_ms: Option<u64>,
+    end_ms: Option<u64>,
+}
+
+/// 转录任务结果
+#[derive(Debug, Clone, Copy, PartialEq, Eq, serde::Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum JobOutcome {
+    Completed,
+    Cancelled,
+    Failed,
+}
+
+/// 进行中的转录任务
+///
+/// 析构时从任务表中移除并发送结果事件；接管了快捷键预留的任务同时让快捷键状态机复位
+pub struct TranscriptionJob {
+    id: String,
+    cancel: Arc<AtomicBool>,
+    completed: bool,
+    /// 接管了快捷键预留（见 [`expect_job`]），结束时由它复位快捷键状态机和输出模式
+    owns_shortcut: bool,
+    app: Option<AppHandle>,
+}
+
+impl TranscriptionJob {
+    /// 注册听写任务，并发送 `transcription-started` 事件（前端据此关联后续进度事件）
+    ///
+    /// 有快捷键预留的任务时接管它（见 [`expect_job`]）
+    pub fn start(app: &AppHandle) -> Self {
+        let mut job = Self::register();
+        job.take_shortcut_reservation();
+        job.attach(app);
+        job
+    }
+
+    /// 注册与快捷键无关的任务（导入音频文件等），不接管预留，结束时不影响快捷键状态机
+    pub fn start_detached(app: &AppHandle) -> Self {
+        let mut job = Self::register();
+        job.attach(app);
+        job
+    }
+
+    fn attach(&mut self, app: &AppHandle) {
+        let _ = app.emit("transcription-started", serde_json::json!({ "job_id": self.id }));
+        self.app = Some(app.clone());
+    }
+
+    /// 在预留的锁内登记为快捷键任务，`is_busy` 不会看到两者之间的空档
+    fn take_shortcut_reservation(&mut self) {
+        let mut pending = PENDING_SINCE.lock();
+        if pending.take().is_some() {
+            *SHORTCUT_JOB.lock() = Some(self.id.clone());
+            self.owns_shortcut = true;
+        }
+    }
+
+    fn register() -> Self {
+        let id = format!("job-{}", NEXT_JOB_ID.fetch_add(1, Ordering::SeqCst));
+        let cancel = Arc::new(AtomicBool::new(false));
+        ACTIVE_JOBS.lock().insert(id.clone(), cancel.clone());
+
+        Self {
+            id,
+            cancel,
+            completed: false,
+            owns_shortcut: false,
+            app: None,
+        }
+    }
+
+    pub fn id(&self) -> &str {
+        &self.id
+    }
+
+    /// 取消标志（交给解码器轮询）
+    pub fn cancel_flag(&self) -> Arc<AtomicBool> {
+        self.cancel.clone()
+    }
+
+    pub fn is_cancelled(&self) -> bool {
+        self.cancel.load(Ordering::SeqCst)
+    }
+
+    /// 进度上报器（可跨线程使用）
+    pub fn reporter(&self) -> JobReporter {
+        JobReporter {
+            job_id: Arc::from(self.id.as_str()),
+            app: self.app.clone(),
+        }
+    }
+
+    /// 交给 Whisper 引擎的进度回调
+    pub fn whisper_progress(&self) -> ProgressCallback {
+        let reporter = self.reporter();
+        Arc::new(move |progress| match progress {
+            DecodeProgress::Percent(percent) => reporter.percent(percent),
+            DecodeProgress::Segment(segment) => {
+                reporter.segment(&segment.text, Some(segment.start_ms), Some(segment.end_ms))
+            }
+        })
+    }
+
+    /// 记录任务结果，原样返回以便链式使用
+    pub fn finish<T>(mut self, result: Result<T, String>) -> Result<T, String> {
+        self.completed = result.is_ok();
+        result
+    }
+
+    fn outcome(&self) -> JobOutcome {
+        if self.is_cancelled() {
+            JobOutcome::Cancelled
+        } else if self.completed {
+            JobOutcome::Completed
+        } else {
+            JobOutcome::Failed
+        }
+    }
+}
+
+impl Drop for TranscriptionJob {
+    fn drop(&mut self) {
+        ACTIVE_JOBS.lock().remove(&self.id);
+        if self.owns_shortcut {
+            SHORTCUT_JOB.lock().take();
+        }
+
+        if let Some(app) = &self.app {
+            let outcome = self.outcome();
+            if self.owns_shortcut {
+                crate::shortcut::finish_processing();
+            }
+            tracing::info!("🏁 [Jobs] Transcription job {} finished: {:?}", self.id, outcome);
+            let _ = app.emit(
+                "transcription-outcome",
+                serde_json::json!({ "job_id": self.id, "outcome": outcome }),
+            );
+        }
+    }
+}
+
+/// 以任务 ID 发送 `transcription-progress` / `transcription-segment` 事件
+#[derive(Clone)]
+pub struct JobReporter {
+    job_id: Arc<str>,
+    app: Option<AppHandle>,
+}
+
+impl JobReporter {
+    /// 整体进度（0-100）
+    pub fn percent(&self, percent: u8) {
+        if let Some(app) = &self.app {
+            let _ = app.emit(
+                "transcription-progress",
+                ProgressEvent {
+                    job_id: &self.job_id,
+                    percent,
+                },
+            );
+        }
+    }
+
+    /// 解码完成前先行送出的段落
+    pub fn segment(&self, text: &str, start_ms: Option<u64>, end_ms: Option<u64>) {
+        if let Some(app) = &self.app {
+            let _ = app.emit(
+                "transcription-segment",
+                SegmentEvent {
+                    job_id: &self.job_id,
+                    text,
+                    start_ms,
+                    end_ms,
+                },
+            );
+        }
+    }
+}
+
+/// 取消指定任务（`None` 表示取消所有进行中的任务），返回被取消的任务数
+pub fn cancel(job_id: Option<&str>) -> usize {
+    let jobs = ACTIVE_JOBS.lock();
+    let mut cancelled = 0;
+
+    for (id, flag) in jobs.iter() {
+        if job_id.is_none() || job_id == Some(id.as_str()) {
+            flag.store(true, Ordering::SeqCst);
+            cancelled += 1;
+        }
+    }
+
+    cancelled
+}
+
+/// 快捷键停止录音后预留一个任务：前端随后发起的转录命令会接管它
+pub fn expect_job() {
+    *PENDING_SINCE.lock() = Some(Instant::now());
+}
+
+/// 前端未发起转录（录音失败、录音过短等）时撤销预留，返回是否确有预留
+pub fn abandon_pending() -> bool {
+    PENDING_SINCE.lock().take().is_some()
+}
+
+/// 快捷键录音的转录是否进行中或即将开始（导入文件等其他任务不计入）
+pub fn is_busy() -> bool {
+    let pending = PENDING_SINCE.lock();
+    let waiting = pending.is_some_and(|since| since.elapsed() < PENDING_JOB_TIMEOUT);
+    waiting || SHORTCUT_JOB.lock().is_some()
+}
+
+/// 错误信息是否表示任务被取消
+pub fn is_cancelled_error(error: &str) -> bool {
+    error == CANCELLED_MESSAGE
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_cancel_by_id() {
+        let first = TranscriptionJob::register();
+        let second = TranscriptionJob::register();
+        assert_ne!(first.id(), second.id());
+
+        assert_eq!(cancel(Some(first.id())), 1);
+        assert!(first.is_cancelled());
+        assert!(!second.is_cancelled());
+        assert_eq!(first.outcome(), JobOutcome::Cancelled);
+    }
+
+    #[test]
+    fn test_finished_job_is_unregistered() {
+        let job = TranscriptionJob::register();
+        let id = job.id().to_string();
+        let flag = job.cancel_flag();
+
+        assert_eq!(job.finish(Ok::<_, String>(())), Ok(()));
+        assert_eq!(cancel(Some(&id)), 0);
+        assert!(!flag.load(Ordering::SeqCst));
+    }
+
+    #[test]
+    fn test_outcome() {
+        let mut job = TranscriptionJob::register();
+        assert_eq!(job.outcome(), JobOutcome::Failed);
+        job.completed = true;
+        assert_eq!(job.outcome(), JobOutcome::Completed);
+        assert!(is_cancelled_error(CANCELLED_MESSAGE));
+        assert!(!is_cancelled_error("Transcription failed: timeout"));
+    }
+
+    #[test]
+    fn test_pending_job() {
+        expect_job();
+        assert!(is_busy());
+
+        // 与快捷键无关的任务不接管预留
+        let detached = TranscriptionJob::register();
+        assert!(!detached.owns_shortcut);
+
+        // 听写命令开始后预留转为进行中的任务
+        let mut job = TranscriptionJob::register();
+        job.take_shortcut_reservation();
+        assert!(job.owns_shortcut);
+        assert!(!abandon_pending());
+        assert!(is_busy());
+
+        // 其他任务结束不影响快捷键任务
+        drop(detached);
+        assert!(is_busy());
+        drop(job);
+        assert!(!is_busy());
+
+        expect_job();
+        assert!(abandon_pending());
+        assert!(!abandon_pending());
+    }
+}
@@ -1,4 +1,5 @@
 mod audio;
+mod benchmark;
 #[cfg(target_os = "macos")]
 mod accessibility;
 #[cfg(target_os = "macos")]
@@ -6,14 +7,25 @@ mod app_tracker;
 mod commands;
 mod config;
 mod db;
+mod diarization;
+mod download;
+mod export;
 mod funasr;
+mod jobs;
+mod memory_governor;
+mod onnx_asr;
 mod python;
+mod remote;
+mod sensevoice;
 mod shortcut;
+mod speech;
 mod tray;
+mod vocabulary;
 mod whisper;
 
 use commands::{
-    audio::*, db::*, funasr::*, model::*, system::*, transcription::*, window::*,
+    audio::*, benchmark::*, db::*, engine::*, export::*, funasr::*, model::*, onnx::*, remote::*,
+    system::*, transcription::*, window::*,
 };
 use crate::commands::{
     check_accessibility_permission_cmd,
@@ -60,8 +72,14 @@ pub fn run() {
             get_transcription,
             get_recent_transcriptions,
             search_transcriptions,
+            filter_transcriptions,
             delete_transcription,
             delete_all_transcriptions,
+            get_vocabulary,
+            add_vocabulary_term,
+            delete_vocabulary_term,
+            clear_vocabulary,
+            import_vocabulary,
             show_recording_float,
             hide_recording_float,
             toggle_recording_float,
@@ -74,23 +92,46 @@ pub fn run() {
             // Model commands
             get_available_models,
             download_model,
+            cancel_model_download,
+            run_benchmark,
+            get_benchmark_results,
+            clear_benchmark_results,
             delete_model,
             get_downloaded_models,
             get_models_directory,
             setup_funasr_environment,
+            // Engine commands (dispatch on configured model type)
+            initialize_engine,
+            transcribe,
+            get_engine_capabilities,
+            unload_engine,
             // Transcription commands (Whisper)
-            initialize_whisper,
-            transcribe_audio,
-            transcribe_last_recording,
             transcribe_audio_with_timestamps,
+            transcribe_audio_file,
             get_current_model,
+            cancel_transcription,
+            get_output_mode_settings,
+            set_default_output_mode,
+            set_app_output_mode,
+            // Export commands
+            export_transcription,
+            export_segments,
             // FunASR commands
-            initialize_funasr,
-            transcribe_last_recording_funasr,
-            transcribe_audio_funasr,
             download_funasr_model,
             get_current_funasr_model,
             prewarm_funasr_cmd,
+            start_streaming_transcription,
+            push_streaming_audio,
+            finish_streaming_transcription,
+            stop_streaming_transcription,
+            get_funasr_resident_models,
+            preload_funasr_model,
+            unload_funasr_model,
+            check_funasr_scripts,
+            // Remote engine commands
+            get_remote_engine_config,
+            set_remote_engine_config,
+            test_remote_engine,
             // Audio commands
             initialize_audio_system,
             check_microphone_permission,
@@ -112,6 +153,7 @@ pub fn run() {
             insert_text_at_cursor_cmd,
             // Shortcut commands
             shortcut::recording_window_ready,
+            shortcut::abandon_recording,
             shortcut::unregister_shortcuts,
             shortcut::register_shortcuts_cmd,
         ])
@@ -131,6 +173,11 @@ pub fn run() {
             let db_arc = Arc::new(database);
             app.manage(db_arc.clone());
 
+            // Rename models and settings that use names removed from the Whisper catalog
+            if let Err(e) = commands::model::migrate_legacy_whisper_models(&app.handle(), &db_arc) {
+                error!("❌ Failed to migrate legacy Whisper models: {}", e);
+            }
+
             // Load application configuration
             let config_manager = ConfigManager::new(db_arc.connection());
             let app_config = config_manager.load()
@@ -151,6 +198,12 @@ pub fn run() {
             // Initialize FunASR state
             app.manage(FunASRState::new());
 
+            // Initialize remote (OpenAI-compatible) engine state
+            app.manage(RemoteState::new());
+
+            // Initialize native ONNX engine state
+            app.manage(OnnxState::new());
+
             // Smart initialization based on configuration
             if app_config.model_type == config::ModelType::FunASR {
                 info!("🔍 FunASR is configured, checking Python environment...");
@@ -184,6 +237,9 @@ pub fn run() {
                 info!("ℹ️ Whisper is configured, no Python environment needed");
             }
 
+            // Release idle models (Whisper context / FunASR server) to save memory
+            memory_governor::start(app.handle().clone());
+
             // Initialize audio system with error handling (async)
             tauri::async_runtime::spawn(async {
                 use commands::audio::initialize_audio_system;
@@ -243,6 +299,12 @@ pub fn run() {
             }
             Ok(())
         })
-        .run(tauri::generate_context!())
-        .expect("error while running tauri application");
+        .build(tauri::generate_context!())
+        .expect("error while building tauri application")
+        .run(|app, event| {
+            // 退出前关闭 FunASR 服务器进程
+            if let tauri::RunEvent::Exit = event {
+                tauri::async_runtime::block_on(app.state::<FunASRState>().shutdown());
+            }
+        });
 }
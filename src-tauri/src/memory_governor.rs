@@ -0,0 +1,91 @@
+/// 内存管理
+/// 模型空闲一段时间后卸载 Whisper 上下文和 ONNX 模型、关闭 FunASR 服务器进程，
+/// 下次听写时透明地重新加载；可选在按下快捷键时提前预热
+
+use std::sync::Arc;
+use std::time::Duration;
+use tauri::{AppHandle, Manager, Runtime};
+
+use crate::commands::{FunASRState, OnnxState, WhisperState};
+use crate::config::{AppConfig, ConfigManager, ModelType};
+use crate::db::Database;
+
+/// 空闲检查间隔
+const CHECK_INTERVAL: Duration = Duration::from_secs(30);
+
+/// 启动后台空闲检查
+pub fn start<R: Runtime>(app: AppHandle<R>) {
+    tauri::async_runtime::spawn(async move {
+        use tracing::info;
+
+        loop {
+            tokio::time::sleep(CHECK_INTERVAL).await;
+
+            // 每次重新读取配置，设置修改后无需重启
+            let Some(config) = load_config(&app) else {
+                continue;
+            };
+            if config.idle_unload_minutes == 0 {
+                continue;
+            }
+            let idle = Duration::from_secs(config.idle_unload_minutes * 60);
+
+            if app.state::<WhisperState>().unload_if_idle(idle) {
+                info!("💤 [Memory] Whisper model unloaded after {} min idle", config.idle_unload_minutes);
+            }
+
+            if app.state::<OnnxState>().unload_if_idle(idle) {
+                info!("💤 [Memory] ONNX model unloaded after {} min idle", config.idle_unload_minutes);
+            }
+
+            if app.state::<FunASRState>().shutdown_if_idle(idle).await {
+                info!("💤 [Memory] FunASR server stopped after {} min idle", config.idle_unload_minutes);
+            }
+        }
+    });
+}
+
+/// 按下录音快捷键时预热当前引擎
+///
+/// 录音通常持续数秒，足以在用户说完之前完成模型加载
+pub fn prewarm_on_shortcut<R: Runtime>(app: &AppHandle<R>) {
+    let Some(config) = load_config(app) else {
+        return;
+    };
+    if !config.prewarm_on_shortcut {
+        return;
+    }
+
+    let app = app.clone();
+    match config.model_type {
+        ModelType::Whisper => {
+            tauri::async_runtime::spawn_blocking(move || {
+                // 未初始化过模型时无需预热
+                if let Err(e) = app.state::<WhisperState>().ensure_loaded() {
+                    tracing::debug!("[Memory] Skipping Whisper prewarm: {}", e);
+                }
+            });
+        }
+        ModelType::FunASR => {
+            tauri::async_runtime::spawn(async move {
+                if let Err(e) = app.state::<FunASRState>().prewarm().await {
+                    tracing::warn!("⚠️  [Memory] FunASR prewarm failed: {}", e);
+                }
+            });
+        }
+        ModelType::Onnx => {
+            tauri::async_runtime::spawn_blocking(move || {
+                if let Err(e) = app.state::<OnnxState>().ensure_loaded() {
+                    tracing::debug!("[Memory] Skipping ONNX prewarm: {}", e);
+                }
+            });
+        }
+        // 模型在远程服务器上，无需预热
+        ModelType::Remote => {}
+    }
+}
+
+fn load_config<R: Runtime>(app: &AppHandle<R>) -> Option<AppConfig> {
+    let db = app.try_state::<Arc<Database>>()?;
+    ConfigManager::new(db.connection()).load().ok()
+}
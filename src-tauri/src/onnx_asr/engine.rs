@@ -0,0 +1,389 @@
+/// ONNX 语音识别引擎
+/// 在 CPU 上直接运行 FunASR 导出的 Paraformer / SenseVoice 模型，不依赖 Python 环境
+
+use std::path::Path;
+use std::sync::atomic::{AtomicBool, Ordering};
+
+use crate::funasr::catalog::ModelScopeRepo;
+use crate::sensevoice;
+use crate::whisper::chunker::{plan_chunks, ChunkConfig};
+use crate::whisper::vad::detect_speech;
+
+use super::features::{extract_features, Cmvn, Fbank, LFR_M, NUM_MEL_BINS};
+use super::punctuation::CtPunctuation;
+use super::session::{Input, OnnxSession};
+use super::tokens::{argmax_frames, ctc_collapse, TokenTable};
+
+/// 标点模型目录（位于 ONNX 模型根目录下，Paraformer 共用）
+pub const PUNCTUATION_DIR: &str = "ct-punc";
+
+/// Paraformer 的句尾符 id（与 blank 一起从输出中去掉）
+const PARAFORMER_EOS_ID: usize = 2;
+
+/// Paraformer 预测的 token 数包含句尾符
+const PARAFORMER_PREDICTOR_BIAS: usize = 1;
+
+/// SenseVoice 的语言编号（`auto` 为自动检测）
+const SENSEVOICE_LANGUAGES: &[(&str, i32)] =
+    &[("auto", 0), ("zh", 3), ("en", 4), ("yue", 7), ("ja", 11), ("ko", 12)];
+
+/// SenseVoice 输出带标点和逆文本正则化的结果
+const SENSEVOICE_WITH_ITN: i32 = 14;
+
+/// 长音频分块：模型整段推理的内存和耗时随时长增长，超过 30 秒的音频在 VAD 停顿处切分后逐块推理。
+/// 模型只输出文本，无法按时间戳去重，因此固定窗口切分时不重叠
+const CHUNK_CONFIG: ChunkConfig = ChunkConfig {
+    target_ms: 20_000,
+    min_ms: 10_000,
+    max_ms: 30_000,
+    overlap_ms: 0,
+};
+
+/// 导出模型共有的文件
+const ONNX_REPO_FILES: &[&str] = &["model_quant.onnx", "am.mvn", "tokens.json", "config.yaml"];
+
+/// 标点模型仓库（下载到 [`PUNCTUATION_DIR`]）
+pub const PUNCTUATION_REPO: ModelScopeRepo = ModelScopeRepo {
+    id: "iic/punc_ct-transformer_zh-cn-common-vocab272727-onnx",
+    required_files: &["model_quant.onnx", "tokens.json", "config.yaml"],
+};
+
+/// 模型结构
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum OnnxModelKind {
+    /// 非自回归 Paraformer（中文，输出不带标点）
+    Paraformer,
+    /// SenseVoice（多语种 CTC，自带标点和语言检测）
+    SenseVoice,
+}
+
+/// ONNX 模型目录项
+#[derive(Debug, Clone, Copy)]
+pub struct OnnxModelSpec {
+    /// 模型名称（设置中保存的值）
+    pub name: &'static str,
+    /// ONNX 模型根目录下的子目录
+    pub dir: &'static str,
+    pub kind: OnnxModelKind,
+    /// ModelScope 上导出好的 ONNX 模型仓库
+    pub repo: ModelScopeRepo,
+    /// 量化模型大小（MB）
+    pub size_mb: u64,
+    pub accuracy: &'static str,
+    pub description: &'static str,
+}
+
+pub const ONNX_MODELS: &[OnnxModelSpec] = &[
+    OnnxModelSpec {
+        name: "paraformer-zh-onnx",
+        dir: "paraformer-zh",
+        kind: OnnxModelKind::Paraformer,
+        repo: ModelScopeRepo {
+            id: "iic/speech_paraformer-large_asr_nat-zh-cn-16k-common-vocab8404-onnx",
+            required_files: ONNX_REPO_FILES,
+        },
+        size_mb: 230,
+        accuracy: "高精度（中文）",
+        description: "Paraformer 中文模型（ONNX，无需 Python 环境）",
+    },
+    OnnxModelSpec {
+        name: "sensevoice-small-onnx",
+        dir: "sensevoice-small",
+        kind: OnnxModelKind::SenseVoice,
+        repo: ModelScopeRepo {
+            id: "iic/SenseVoiceSmall-onnx",
+            required_files: ONNX_REPO_FILES,
+        },
+        size_mb: 240,
+        accuracy: "高精度（多语言）",
+        description: "SenseVoice 多语种模型（ONNX，无需 Python 环境）",
+    },
+];
+
+pub fn find_onnx_model(name: &str) -> Option<&'static OnnxModelSpec> {
+    ONNX_MODELS.iter().find(|spec| spec.name == name)
+}
+
+/// 模型目录是否包含推理所需的文件
+pub fn is_model_present(models_root: &Path, spec: &OnnxModelSpec) -> bool {
+    let dir = models_root.join(spec.dir);
+    super::model_file(&dir).is_ok()
+        && dir.join("am.mvn").exists()
+        && (dir.join("tokens.json").exists() || dir.join("tokens.txt").exists())
+}
+
+/// 转录结果
+#[derive(Debug, Clone, Default)]
+pub struct OnnxOutput {
+    pub text: String,
+    /// SenseVoice 检测到的语言
+    pub language: Option<String>,
+    /// SenseVoice 检测到的情感
+    pub emotion: Option<String>,
+    /// SenseVoice 检测到的音频事件
+    pub events: Vec<String>,
+}
+
+/// 已加载的 ONNX 模型
+pub struct OnnxAsrEngine {
+    spec: OnnxModelSpec,
+    session: OnnxSession,
+    tokens: TokenTable,
+    cmvn: Cmvn,
+    fbank: Fbank,
+    /// 标点模型（仅 Paraformer，目录不存在时不加标点）
+    punctuation: Option<CtPunctuation>,
+}
+
+impl OnnxAsrEngine {
+    /// 从 `models_root/<spec.dir>` 加载模型
+    pub fn load(models_root: &Path, spec: &OnnxModelSpec) -> Result<Self, String> {
+        use tracing::{info, warn};
+
+        let dir = models_root.join(spec.dir);
+        if !dir.exists() {
+            return Err(format!(
+                "ONNX model '{}' not found at {}. Please place the exported model files there first.",
+                spec.name,
+                dir.display()
+            ));
+        }
+
+        let model_path = super::model_file(&dir)?;
+        info!("📦 [ONNX] Loading {:?} model: {:?}", spec.kind, model_path);
+
+        let session = OnnxSession::load(&model_path)?;
+        let tokens = TokenTable::load_dir(&dir)?;
+        let cmvn_content = std::fs::read_to_string(dir.join("am.mvn"))
+            .map_err(|e| format!("Failed to read {}: {}", dir.join("am.mvn").display(), e))?;
+        let cmvn = Cmvn::parse(&cmvn_content)?;
+        if cmvn.dim() != LFR_M * NUM_MEL_BINS {
+            return Err(format!(
+                "Unexpected CMVN dimension {} (expected {})",
+                cmvn.dim(),
+                LFR_M * NUM_MEL_BINS
+            ));
+        }
+
+        let punctuation = match spec.kind {
+            OnnxModelKind::Paraformer => {
+                let punc_dir = models_root.join(PUNCTUATION_DIR);
+                if punc_dir.exists() {
+                    match CtPunctuation::load(&punc_dir) {
+                        Ok(punctuation) => Some(punctuation),
+                        Err(e) => {
+                            warn!("⚠️  [ONNX] Failed to load punctuation model, output will have no punctuation: {}", e);
+                            None
+                        }
+                    }
+                } else {
+                    info!("📦 [ONNX] Punctuation model not found at {:?}, skipping", punc_dir);
+                    None
+                }
+            }
+            OnnxModelKind::SenseVoice => None,
+        };
+
+        info!("✅ [ONNX] Model loaded: {} ({} tokens)", spec.name, tokens.vocab_size());
+
+        Ok(Self {
+            spec: *spec,
+            session,
+            tokens,
+            cmvn,
+            fbank: Fbank::new(),
+            punctuation,
+        })
+    }
+
+    /// 转录 16kHz 单声道音频
+    ///
+    /// 长音频在 VAD 停顿处切分后逐块推理，每块之前检查 `cancel`，每块完成后以 0-100 上报进度。
+    /// `language` 只对 SenseVoice 生效，`None` 表示自动检测
+    pub fn transcribe(
+        &self,
+        audio: &[f32],
+        language: Option<&str>,
+        cancel: Option<&AtomicBool>,
+        on_progress: impl Fn(u8),
+    ) -> Result<OnnxOutput, String> {
+        let duration_ms = audio.len() as u64 / 16;
+        let chunks = if duration_ms > CHUNK_CONFIG.max_ms {
+            plan_chunks(duration_ms, &detect_speech(audio), &CHUNK_CONFIG)
+        } else {
+            Vec::new()
+        };
+        if chunks.len() <= 1 {
+            return self.transcribe_chunk(audio, language);
+        }
+
+        let mut outputs = Vec::with_capacity(chunks.len());
+        for (index, chunk) in chunks.iter().enumerate() {
+            if cancel.is_some_and(|flag| flag.load(Ordering::SeqCst)) {
+                return Err(crate::jobs::CANCELLED_MESSAGE.to_string());
+            }
+            outputs.push(self.transcribe_chunk(&audio[chunk.sample_range(audio.len())], language)?);
+            on_progress(((index + 1) * 100 / chunks.len()) as u8);
+        }
+
+        Ok(merge_outputs(outputs))
+    }
+
+    /// 整段推理一块音频
+    fn transcribe_chunk(&self, audio: &[f32], language: Option<&str>) -> Result<OnnxOutput, String> {
+        let features = extract_features(&self.fbank, &self.cmvn, audio);
+        if features.is_empty() {
+            return Ok(OnnxOutput::default());
+        }
+
+        let frames = features.len();
+        let speech = Input::F32 {
+            shape: vec![1, frames, self.cmvn.dim()],
+            data: features.concat(),
+        };
+        let speech_lengths = Input::I32 {
+            shape: vec![1],
+            data: vec![frames as i32],
+        };
+
+        match self.spec.kind {
+            OnnxModelKind::Paraformer => self.decode_paraformer(speech, speech_lengths),
+            OnnxModelKind::SenseVoice => self.decode_sensevoice(speech, speech_lengths, language),
+        }
+    }
+
+    fn decode_paraformer(&self, speech: Input, speech_lengths: Input) -> Result<OnnxOutput, String> {
+        let outputs = self.session.run(
+            vec![("speech", speech), ("speech_lengths", speech_lengths)],
+            &["logits", "token_num"],
+        )?;
+        let (logits, token_num) = (&outputs[0], &outputs[1]);
+        let vocab_size = logits.shape.last().copied().unwrap_or(0).max(1);
+        let token_num = token_num.data.first().copied().unwrap_or(0.0) as usize;
+
+        let ids: Vec<usize> = argmax_frames(&logits.data, vocab_size)
+            .into_iter()
+            .filter(|&id| id != super::tokens::BLANK_ID && id != PARAFORMER_EOS_ID)
+            .take(token_num.saturating_sub(PARAFORMER_PREDICTOR_BIAS))
+            .collect();
+        let text = self.tokens.decode_paraformer(&ids);
+
+        let text = match &self.punctuation {
+            Some(punctuation) if !text.is_empty() => punctuation.punctuate(&text)?,
+            _ => text,
+        };
+
+        Ok(OnnxOutput {
+            text,
+            ..OnnxOutput::default()
+        })
+    }
+
+    fn decode_sensevoice(
+        &self,
+        speech: Input,
+        speech_lengths: Input,
+        language: Option<&str>,
+    ) -> Result<OnnxOutput, String> {
+        let language_id = language
+            .and_then(|code| SENSEVOICE_LANGUAGES.iter().find(|(name, _)| *name == code))
+            .map_or(0, |(_, id)| *id);
+
+        let outputs = self.session.run(
+            vec![
+                ("speech", speech),
+                ("speech_lengths", speech_lengths),
+                ("language", Input::I32 { shape: vec![1], data: vec![language_id] }),
+                ("textnorm", Input::I32 { shape: vec![1], data: vec![SENSEVOICE_WITH_ITN] }),
+            ],
+            &["ctc_logits", "encoder_out_lens"],
+        )?;
+        let (logits, lengths) = (&outputs[0], &outputs[1]);
+        let vocab_size = logits.shape.last().copied().unwrap_or(0).max(1);
+        let valid_frames = lengths.data.first().map_or(usize::MAX, |&len| len as usize);
+        let valid = &logits.data[..(valid_frames * vocab_size).min(logits.data.len())];
+
+        let ids = ctc_collapse(&argmax_frames(valid, vocab_size));
+        let parsed = sensevoice::parse(&self.tokens.decode_sentencepiece(&ids));
+
+        Ok(OnnxOutput {
+            text: parsed.text,
+            language: parsed.language,
+            emotion: parsed.emotion,
+            events: parsed.events,
+        })
+    }
+}
+
+/// 合并各块的结果：文本按顺序拼接，语言取第一个检测结果，情感按 [`sensevoice::dominant_emotion`] 汇总，事件去重
+fn merge_outputs(outputs: Vec<OnnxOutput>) -> OnnxOutput {
+    let mut merged = OnnxOutput::default();
+    let mut emotions: Vec<String> = Vec::new();
+
+    for output in outputs {
+        let text = output.text.trim();
+        if !text.is_empty() {
+            // 英文等以空格分词的文本在块之间补空格
+            let needs_space = merged.text.chars().last().is_some_and(|c| c.is_ascii_alphanumeric())
+                && text.chars().next().is_some_and(|c| c.is_ascii_alphanumeric());
+            if needs_space {
+                merged.text.push(' ');
+            }
+            merged.text.push_str(text);
+        }
+
+        if merged.language.is_none() {
+            merged.language = output.language;
+        }
+        emotions.extend(output.emotion);
+        for event in output.events {
+            if !merged.events.contains(&event) {
+                merged.events.push(event);
+            }
+        }
+    }
+
+    merged.emotion = sensevoice::dominant_emotion(emotions.iter().map(String::as_str));
+    merged
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_find_onnx_model() {
+        assert_eq!(find_onnx_model("paraformer-zh-onnx").unwrap().kind, OnnxModelKind::Paraformer);
+        assert_eq!(find_onnx_model("sensevoice-small-onnx").unwrap().kind, OnnxModelKind::SenseVoice);
+        assert!(find_onnx_model("paraformer-zh").is_none());
+    }
+
+    #[test]
+    fn test_merge_outputs() {
+        let output = |text: &str, language: Option<&str>, emotion: &str, events: &[&str]| OnnxOutput {
+            text: text.to_string(),
+            language: language.map(str::to_string),
+            emotion: Some(emotion.to_string()),
+            events: events.iter().map(|e| e.to_string()).collect(),
+        };
+
+        let merged = merge_outputs(vec![
+            output("今天天气不错。", Some("zh"), "happy", &["bgm"]),
+            output("Let's go", None, "neutral", &[]),
+            output("hiking tomorrow.", Some("en"), "neutral", &["bgm", "laughter"]),
+        ]);
+        assert_eq!(merged.text, "今天天气不错。Let's go hiking tomorrow.");
+        assert_eq!(merged.language.as_deref(), Some("zh"));
+        // 非中性情感优先，与单次识别的汇总规则一致
+        assert_eq!(merged.emotion.as_deref(), Some("happy"));
+        assert_eq!(merged.events, vec!["bgm", "laughter"]);
+
+        let merged = merge_outputs(vec![
+            output("好的", Some("zh"), "neutral", &[]),
+            output("明天见", Some("zh"), "neutral", &[]),
+        ]);
+        assert_eq!(merged.emotion.as_deref(), Some("neutral"));
+
+        assert_eq!(merge_outputs(Vec::new()).text, "");
+    }
+}
@@ -0,0 +1,408 @@
+/// 声学特征提取
+/// 与 FunASR `WavFrontend` 一致：Kaldi 兼容的 80 维 fbank → LFR 拼帧 → CMVN 归一化
+
+use std::f64::consts::PI;
+
+/// 采样率（模型输入固定为 16kHz）
+const SAMPLE_RATE: f64 = 16000.0;
+
+/// 帧长 25ms（400 个采样点）
+const FRAME_LENGTH: usize = 400;
+
+/// 帧移 10ms（160 个采样点）
+const FRAME_SHIFT: usize = 160;
+
+/// FFT 长度（帧长向上取 2 的幂）
+const FFT_SIZE: usize = 512;
+
+/// 预加重系数
+const PREEMPHASIS: f32 = 0.97;
+
+/// mel 滤波器组下限频率（Kaldi 默认 20Hz，上限为奈奎斯特频率）
+const LOW_FREQ: f64 = 20.0;
+
+/// fbank 维数
+pub const NUM_MEL_BINS: usize = 80;
+
+/// LFR 参数：每 6 帧取一次，每次拼接 7 帧（输出 560 维）
+pub const LFR_M: usize = 7;
+pub const LFR_N: usize = 6;
+
+/// Kaldi fbank 提取器（预先计算窗函数和 mel 滤波器组）
+pub struct Fbank {
+    window: Vec<f32>,
+    /// 每个 mel 滤波器：(起始 FFT 频点, 权重)
+    mel_banks: Vec<(usize, Vec<f32>)>,
+}
+
+impl Fbank {
+    pub fn new() -> Self {
+        // Hamming 窗（Kaldi 形式：分母为 N - 1）
+        let window = (0..FRAME_LENGTH)
+            .map(|i| (0.54 - 0.46 * (2.0 * PI * i as f64 / (FRAME_LENGTH - 1) as f64).cos()) as f32)
+            .collect();
+
+        Self {
+            window,
+            mel_banks: mel_banks(NUM_MEL_BINS),
+        }
+    }
+
+    /// 计算 fbank 特征
+    ///
+    /// `audio` 为 [-1, 1] 范围的 16kHz 音频，内部按 Kaldi 习惯放大到 16 位整数幅度。
+    /// 返回每帧 `NUM_MEL_BINS` 维的对数 mel 能量；音频不足一帧时返回空
+    pub fn compute(&self, audio: &[f32]) -> Vec<Vec<f32>> {
+        if audio.len() < FRAME_LENGTH {
+            return Vec::new();
+        }
+
+        let num_frames = 1 + (audio.len() - FRAME_LENGTH) / FRAME_SHIFT;
+        let mut frames = Vec::with_capacity(num_frames);
+        let mut re = vec![0.0f32; FFT_SIZE];
+        let mut im = vec![0.0f32; FFT_SIZE];
+
+        for index in 0..num_frames {
+            let start = index * FRAME_SHIFT;
+            let frame = &audio[start..start + FRAME_LENGTH];
+
+            // 去直流
+            let mean = frame.iter().map(|&s| s * 32768.0).sum::<f32>() / FRAME_LENGTH as f32;
+            for (i, &sample) in frame.iter().enumerate() {
+                re[i] = sample * 32768.0 - mean;
+            }
+
+            // 预加重（从后往前，首个采样点与自身相减）
+            for i in (1..FRAME_LENGTH).rev() {
+                re[i] -= PREEMPHASIS * re[i - 1];
+            }
+            re[0] -= PREEMPHASIS * re[0];
+
+            for (sample, weight) in re.iter_mut().zip(&self.window) {
+                *sample *= weight;
+            }
+            re[FRAME_LENGTH..].fill(0.0);
+            im.fill(0.0);
+
+            fft(&mut re, &mut im);
+
+            let power: Vec<f32> = (0..=FFT_SIZE / 2).map(|k| re[k] * re[k] + im[k] * im[k]).collect();
+            let energies = self
+                .mel_banks
+                .iter()
+                .map(|(offset, weights)| {
+                    let energy: f32 = weights.iter().zip(&power[*offset..]).map(|(w, p)| w * p).sum();
+                    energy.max(f32::EPSILON).ln()
+                })
+                .collect();
+            frames.push(energies);
+        }
+
+        frames
+    }
+}
+
+impl Default for Fbank {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// Kaldi mel 刻度
+pub fn mel_scale(freq: f64) -> f64 {
+    1127.0 * (1.0 + freq / 700.0).ln()
+}
+
+/// Kaldi 三角 mel 滤波器组（在 mel 域等间隔，不含奈奎斯特频点）
+fn mel_banks(num_bins: usize) -> Vec<(usize, Vec<f32>)> {
+    let num_fft_bins = FFT_SIZE / 2;
+    let bin_width = SAMPLE_RATE / FFT_SIZE as f64;
+    let mel_low = mel_scale(LOW_FREQ);
+    let mel_high = mel_scale(SAMPLE_RATE / 2.0);
+    let mel_delta = (mel_high - mel_low) / (num_bins + 1) as f64;
+
+    (0..num_bins)
+        .map(|bin| {
+            let left = mel_low + bin as f64 * mel_delta;
+            let center = left + mel_delta;
+            let right = center + mel_delta;
+
+            let weights: Vec<(usize, f32)> = (0..num_fft_bins)
+                .filter_map(|i| {
+                    let mel = mel_scale(bin_width * i as f64);
+                    if mel <= left || mel >= right {
+                        return None;
+                    }
+                    let weight = if mel <= center {
+                        (mel - left) / (center - left)
+                    } else {
+                        (right - mel) / (right - center)
+                    };
+                    Some((i, weight as f32))
+                })
+                .collect();
+
+            let offset = weights.first().map(|(i, _)| *i).unwrap_or(0);
+            (offset, weights.into_iter().map(|(_, w)| w).collect())
+        })
+        .collect()
+}
+
+/// 原地基 2 FFT（长度必须为 2 的幂）
+fn fft(re: &mut [f32], im: &mut [f32]) {
+    let n = re.len();
+
+    // 位反转置换
+    let mut j = 0;
+    for i in 1..n {
+        let mut bit = n >> 1;
+        while j & bit != 0 {
+            j ^= bit;
+            bit >>= 1;
+        }
+        j |= bit;
+        if i < j {
+            re.swap(i, j);
+            im.swap(i, j);
+        }
+    }
+
+    let mut len = 2;
+    while len <= n {
+        let angle = -2.0 * PI / len as f64;
+        for start in (0..n).step_by(len) {
+            for k in 0..len / 2 {
+                let (sin, cos) = (angle * k as f64).sin_cos();
+                let (w_re, w_im) = (cos as f32, sin as f32);
+                let a = start + k;
+                let b = a + len / 2;
+                let t_re = re[b] * w_re - im[b] * w_im;
+                let t_im = re[b] * w_im + im[b] * w_re;
+                re[b] = re[a] - t_re;
+                im[b] = im[a] - t_im;
+                re[a] += t_re;
+                im[a] += t_im;
+            }
+        }
+        len <<= 1;
+    }
+}
+
+/// 低帧率（LFR）拼帧
+///
+/// 左侧补 `(m - 1) / 2` 个首帧，每 `n` 帧取一次并拼接其后 `m` 帧，末尾不足时重复最后一帧
+pub fn apply_lfr(frames: &[Vec<f32>], m: usize, n: usize) -> Vec<Vec<f32>> {
+    let Some(first) = frames.first() else {
+        return Vec::new();
+    };
+
+    let left_padding = (m - 1) / 2;
+    let padded: Vec<&Vec<f32>> = std::iter::repeat_n(first, left_padding)
+        .chain(frames.iter())
+        .collect();
+    let last = padded[padded.len() - 1];
+    let num_lfr = frames.len().div_ceil(n);
+
+    (0..num_lfr)
+        .map(|i| {
+            let mut stacked = Vec::with_capacity(m * first.len());
+            for k in 0..m {
+                stacked.extend_from_slice(padded.get(i * n + k).copied().unwrap_or(last));
+            }
+            stacked
+        })
+        .collect()
+}
+
+/// 全局均值方差归一化（FunASR 导出的 `am.mvn`）
+#[derive(Debug, Clone, PartialEq)]
+pub struct Cmvn {
+    /// 加到特征上的偏移（负均值）
+    pub shift: Vec<f32>,
+    /// 偏移后的缩放系数（标准差倒数）
+    pub scale: Vec<f32>,
+}
+
+impl Cmvn {
+    /// 解析 Kaldi nnet 文本格式的 `am.mvn`（`<AddShift>` 和 `<Rescale>` 两层）
+    pub fn parse(content: &str) -> Result<Self, String> {
+        let shift = parse_component(content, "<AddShift>")?;
+        let scale = parse_component(content, "<Rescale>")?;
+        if shift.len() != scale.len() {
+            return Err(format!(
+                "Invalid CMVN file: shift has {} dims but scale has {}",
+                shift.len(),
+                scale.len()
+            ));
+        }
+        Ok(Self { shift, scale })
+    }
+
+    pub fn dim(&self) -> usize {
+        self.shift.len()
+    }
+
+    /// 原地归一化：`(x + shift) * scale`
+    pub fn apply(&self, frames: &mut [Vec<f32>]) {
+        for frame in frames {
+            for ((x, shift), scale) in frame.iter_mut().zip(&self.shift).zip(&self.scale) {
+                *x = (*x + shift) * scale;
+            }
+        }
+    }
+}
+
+/// 读取组件标记之后第一个 `[ ... ]` 中的数值
+fn parse_component(content: &str, tag: &str) -> Result<Vec<f32>, String> {
+    let after_tag = content
+        .split_once(tag)
+        .map(|(_, rest)| rest)
+        .ok_or_else(|| format!("Invalid CMVN file: missing {}", tag))?;
+    let values = after_tag
+        .split_once('[')
+        .and_then(|(_, rest)| rest.split_once(']'))
+        .map(|(values, _)| values)
+        .ok_or_else(|| format!("Invalid CMVN file: missing values for {}", tag))?;
+
+    values
+        .split_whitespace()
+        .map(|v| v.parse::<f32>().map_err(|e| format!("Invalid CMVN value '{}': {}", v, e)))
+        .collect()
+}
+
+/// 完整前端：fbank → LFR → CMVN，返回 LFR 帧（每帧 `LFR_M * NUM_MEL_BINS` 维）
+pub fn extract_features(fbank: &Fbank, cmvn: &Cmvn, audio: &[f32]) -> Vec<Vec<f32>> {
+    let mut frames = apply_lfr(&fbank.compute(audio), LFR_M, LFR_N);
+    cmvn.apply(&mut frames);
+    frames
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// 440Hz 正弦波，幅度 0.5
+    fn sine(samples: usize) -> Vec<f32> {
+        (0..samples)
+            .map(|i| 0.5 * (2.0 * PI * 440.0 * i as f64 / SAMPLE_RATE).sin() as f32)
+            .collect()
+    }
+
+    #[test]
+    fn test_mel_scale() {
+        assert_eq!(mel_scale(0.0), 0.0);
+        assert!((mel_scale(1000.0) - 1000.0).abs() < 0.1);
+        assert!((mel_scale(8000.0) - 2840.04).abs() < 0.01);
+    }
+
+    #[test]
+    fn test_fft_matches_dft() {
+        let signal: Vec<f32> = (0..16).map(|i| ((i * 7) % 5) as f32 - 2.0).collect();
+        let mut re = signal.clone();
+        let mut im = vec![0.0; 16];
+        fft(&mut re, &mut im);
+
+        for k in 0..16 {
+            let (mut dft_re, mut dft_im) = (0.0f64, 0.0f64);
+            for (n, &x) in signal.iter().enumerate() {
+                let angle = -2.0 * PI * (k * n) as f64 / 16.0;
+                dft_re += x as f64 * angle.cos();
+                dft_im += x as f64 * angle.sin();
+            }
+            assert!((re[k] as f64 - dft_re).abs() < 1e-4, "re[{}]", k);
+            assert!((im[k] as f64 - dft_im).abs() < 1e-4, "im[{}]", k);
+        }
+    }
+
+    #[test]
+    fn test_mel_banks_layout() {
+        let banks = mel_banks(NUM_MEL_BINS);
+        assert_eq!(banks.len(), NUM_MEL_BINS);
+        // 首个滤波器从 20Hz 附近开始，频点宽度 31.25Hz
+        assert_eq!(banks[0].0, 1);
+        // 滤波器中心依次升高
+        let peaks: Vec<usize> = banks
+            .iter()
+            .map(|(offset, w)| {
+                offset + w.iter().enumerate().fold(0, |best, (i, &v)| if v > w[best] { i } else { best })
+            })
+            .collect();
+        assert!(peaks.windows(2).all(|p| p[0] <= p[1]));
+        assert!(banks.iter().all(|(offset, w)| offset + w.len() <= FFT_SIZE / 2));
+    }
+
+    #[test]
+    fn test_fbank_frame_count() {
+        let fbank = Fbank::new();
+        assert!(fbank.compute(&vec![0.0; 399]).is_empty());
+        assert_eq!(fbank.compute(&vec![0.0; 400]).len(), 1);
+        // 1 秒：1 + (16000 - 400) / 160 = 98 帧
+        let frames = fbank.compute(&sine(16000));
+        assert_eq!(frames.len(), 98);
+        assert!(frames.iter().all(|f| f.len() == NUM_MEL_BINS));
+    }
+
+    #[test]
+    fn test_fbank_silence_is_floored() {
+        let frames = Fbank::new().compute(&vec![0.0; 800]);
+        let floor = f32::EPSILON.ln();
+        assert!(frames.iter().flatten().all(|&v| (v - floor).abs() < 1e-6));
+    }
+
+    #[test]
+    fn test_fbank_matches_reference() {
+        // 参考值由 tests/fixtures/kaldi_fbank_reference.py 生成（按 Kaldi 默认流程的双精度实现，
+        // 直接 DFT）：440Hz 正弦、幅度 0.5、dither 0、hamming 窗、第一帧
+        let frames = Fbank::new().compute(&sine(400));
+        let reference = [
+            (0, 11.8922),
+            (13, 24.4027),
+            (14, 25.2221),
+            (15, 24.1378),
+            (20, 15.2782),
+            (40, 12.8304),
+            (79, 12.2036),
+        ];
+        for (bin, expected) in reference {
+            assert!(
+                (frames[0][bin] - expected).abs() < 1e-2,
+                "bin {}: {} vs {}",
+                bin,
+                frames[0][bin],
+                expected
+            );
+        }
+    }
+
+    #[test]
+    fn test_lfr_stacking() {
+        let frames: Vec<Vec<f32>> = (0..10).map(|i| vec![i as f32]).collect();
+        let lfr = apply_lfr(&frames, 7, 6);
+
+        // ceil(10 / 6) = 2 帧
+        assert_eq!(lfr.len(), 2);
+        // 左侧补 3 个首帧
+        assert_eq!(lfr[0], vec![0.0, 0.0, 0.0, 0.0, 1.0, 2.0, 3.0]);
+        // 末尾不足时重复最后一帧
+        assert_eq!(lfr[1], vec![3.0, 4.0, 5.0, 6.0, 7.0, 8.0, 9.0]);
+
+        let frames: Vec<Vec<f32>> = (0..8).map(|i| vec![i as f32]).collect();
+        assert_eq!(apply_lfr(&frames, 7, 6)[1], vec![3.0, 4.0, 5.0, 6.0, 7.0, 7.0, 7.0]);
+        assert!(apply_lfr(&[], 7, 6).is_empty());
+    }
+
+    #[test]
+    fn test_cmvn_parse_and_apply() {
+        let content = "<Nnet>\n<Splice> 3 3\n[ 0 ]\n<AddShift> 3 3\n<LearnRateCoef> 0 [ -1.0 -2.5 0.5 ]\n\
+                       <Rescale> 3 3\n<LearnRateCoef> 0 [ 2.0 0.5 1.0 ]\n</Nnet>\n";
+        let cmvn = Cmvn::parse(content).unwrap();
+        assert_eq!(cmvn.dim(), 3);
+
+        let mut frames = vec![vec![1.0, 2.5, 0.0]];
+        cmvn.apply(&mut frames);
+        assert_eq!(frames[0], vec![0.0, 0.0, 0.5]);
+
+        assert!(Cmvn::parse("<AddShift> 1 1 [ 1.0 ]").is_err());
+        assert!(Cmvn::parse("<AddShift> [ 1.0 2.0 ] <Rescale> [ 1.0 ]").is_err());
+    }
+}
@@ -0,0 +1,21 @@
+/// 原生 ONNX 语音识别
+/// 特征提取、解码和标点恢复都在 Rust 中完成，模型由 ONNX Runtime 在 CPU 上运行
+
+pub mod engine;
+pub mod features;
+pub mod punctuation;
+pub mod session;
+pub mod tokens;
+
+pub use engine::{find_onnx_model, is_model_present, OnnxAsrEngine, OnnxModelKind, OnnxModelSpec, ONNX_MODELS};
+
+use std::path::{Path, PathBuf};
+
+/// 导出目录中的模型文件，优先使用量化版本
+pub fn model_file(dir: &Path) -> Result<PathBuf, String> {
+    ["model_quant.onnx", "model.onnx"]
+        .iter()
+        .map(|name| dir.join(name))
+        .find(|path| path.exists())
+        .ok_or_else(|| format!("No model.onnx or model_quant.onnx found in {}", dir.display()))
+}
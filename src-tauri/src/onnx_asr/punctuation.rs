@@ -0,0 +1,286 @@
+/// CT-Transformer 标点恢复
+/// Paraformer 输出不带标点，按 FunASR 的做法逐段预测每个词后的标点，
+/// 段尾未完结的句子带入下一段重新预测
+
+use std::path::Path;
+
+use super::session::{Input, OnnxSession};
+use super::tokens::{argmax_frames, TokenTable};
+
+/// 每次送入模型的词数
+const SPLIT_SIZE: usize = 20;
+
+/// 缓存超过该词数仍无句号时，在最后一个逗号处强制断句
+const CACHE_POP_TRIGGER_LIMIT: usize = 200;
+
+/// 模型配置缺失时使用的标点表（与 punc_ct-transformer_zh-cn 一致）
+const DEFAULT_PUNC_LIST: &[&str] = &["<unk>", "_", "，", "。", "？", "、"];
+
+/// 标点模型
+pub struct CtPunctuation {
+    session: OnnxSession,
+    tokens: TokenTable,
+    punc_list: Vec<String>,
+}
+
+impl CtPunctuation {
+    /// 从导出目录加载（`model_quant.onnx` / `model.onnx`、`tokens.json`、`config.yaml`）
+    pub fn load(dir: &Path) -> Result<Self, String> {
+        let session = OnnxSession::load(&super::model_file(dir)?)?;
+        let tokens = TokenTable::load(&dir.join("tokens.json"))?;
+        let punc_list = std::fs::read_to_string(dir.join("config.yaml"))
+            .ok()
+            .and_then(|config| parse_punc_list(&config))
+            .unwrap_or_else(|| DEFAULT_PUNC_LIST.iter().map(|p| p.to_string()).collect());
+
+        Ok(Self {
+            session,
+            tokens,
+            punc_list,
+        })
+    }
+
+    /// 为不带标点的文本添加标点
+    pub fn punctuate(&self, text: &str) -> Result<String, String> {
+        add_punctuation(text, &self.punc_list, |words| self.predict(words))
+    }
+
+    /// 预测每个词之后的标点编号
+    fn predict(&self, words: &[String]) -> Result<Vec<usize>, String> {
+        let unk = self.tokens.id("<unk>").unwrap_or(0);
+        let ids: Vec<i32> = words
+            .iter()
+            .map(|word| self.tokens.id(&word.to_lowercase()).unwrap_or(unk) as i32)
+            .collect();
+        let len = ids.len();
+
+        let outputs = self.session.run(
+            vec![
+                ("inputs", Input::I32 { shape: vec![1, len], data: ids }),
+                ("text_lengths", Input::I32 { shape: vec![1], data: vec![len as i32] }),
+            ],
+            &["logits"],
+        )?;
+        let logits = &outputs[0];
+        let num_punc = logits.shape.last().copied().unwrap_or(self.punc_list.len()).max(1);
+
+        Ok(argmax_frames(&logits.data, num_punc))
+    }
+}
+
+/// 中英混合分词：非 ASCII 字符逐字切分，连续 ASCII 字符作为一个词
+pub fn split_words(text: &str) -> Vec<String> {
+    let mut words = Vec::new();
+    for segment in text.split_whitespace() {
+        let mut current = String::new();
+        for c in segment.chars() {
+            if c.is_ascii() {
+                current.push(c);
+            } else {
+                if !current.is_empty() {
+                    words.push(std::mem::take(&mut current));
+                }
+                words.push(c.to_string());
+            }
+        }
+        if !current.is_empty() {
+            words.push(current);
+        }
+    }
+    words
+}
+
+/// 按 FunASR CT-Transformer 的分段策略添加标点
+///
+/// `predict` 返回每个词之后的标点在 `punc_list` 中的编号（`_` 表示无标点）
+pub fn add_punctuation<F>(text: &str, punc_list: &[String], mut predict: F) -> Result<String, String>
+where
+    F: FnMut(&[String]) -> Result<Vec<usize>, String>,
+{
+    let words = split_words(text);
+    if words.is_empty() {
+        return Ok(String::new());
+    }
+
+    let is_punc = |id: usize, punc: &str| punc_list.get(id).is_some_and(|p| p == punc);
+    let period = punc_list.iter().position(|p| p == "。").unwrap_or(0);
+    let chunks: Vec<&[String]> = words.chunks(SPLIT_SIZE).collect();
+
+    let mut output = String::new();
+    let mut cache: Vec<String> = Vec::new();
+    let mut prev_ascii = false;
+
+    for (index, chunk) in chunks.iter().enumerate() {
+        let mut sentence: Vec<String> = cache.drain(..).chain(chunk.iter().cloned()).collect();
+        let mut puncs = predict(&sentence)?;
+        if puncs.len() != sentence.len() {
+            return Err(format!(
+                "Punctuation model returned {} labels for {} words",
+                puncs.len(),
+                sentence.len()
+            ));
+        }
+
+        // 非最后一段：只输出到最后一个句号 / 问号，其后的词留到下一段
+        if index + 1 < chunks.len() {
+            let mut sentence_end = None;
+            let mut last_comma = None;
+            for i in (2..sentence.len().saturating_sub(1)).rev() {
+                if is_punc(puncs[i], "。") || is_punc(puncs[i], "？") {
+                    sentence_end = Some(i);
+                    break;
+                }
+                if last_comma.is_none() && is_punc(puncs[i], "，") {
+                    last_comma = Some(i);
+                }
+            }
+            if sentence_end.is_none() && sentence.len() > CACHE_POP_TRIGGER_LIMIT {
+                if let Some(comma) = last_comma {
+                    puncs[comma] = period;
+                    sentence_end = Some(comma);
+                }
+            }
+
+            let split = sentence_end.map_or(0, |end| end + 1);
+            cache = sentence.split_off(split);
+            puncs.truncate(split);
+        }
+
+        for (word, &punc) in sentence.iter().zip(&puncs) {
+            // 相邻的英文单词之间保留空格
+            let ascii = word.is_ascii();
+            if ascii && prev_ascii {
+                output.push(' ');
+            }
+            output.push_str(word);
+            prev_ascii = ascii;
+
+            if let Some(p) = punc_list.get(punc).filter(|p| !matches!(p.as_str(), "_" | "<unk>")) {
+                output.push_str(p);
+                prev_ascii = false;
+            }
+        }
+    }
+
+    // 句末补句号
+    if output.ends_with('，') || output.ends_with('、') {
+        output.pop();
+        output.push('。');
+    } else if !output.ends_with('。') && !output.ends_with('？') {
+        output.push('。');
+    }
+
+    Ok(output)
+}
+
+/// 从 `config.yaml` 读取 `punc_list`（支持 `- item` 列表和 `[a, b]` 行内写法），半角逗号和问号换成全角
+fn parse_punc_list(config: &str) -> Option<Vec<String>> {
+    let mut lines = config.lines().skip_while(|line| !line.trim_start().starts_with("punc_list:"));
+    let header = lines.next()?;
+    let inline = header.trim_start().trim_start_matches("punc_list:").trim();
+
+    let items: Vec<String> = if let Some(inline) = inline.strip_prefix('[') {
+        inline
+            .trim_end_matches(']')
+            .split(',')
+            .map(|item| item.trim().trim_matches(|c| c == '\'' || c == '"').to_string())
+            .filter(|item| !item.is_empty())
+            .collect()
+    } else {
+        lines
+            .map(str::trim)
+            .take_while(|line| line.starts_with("- "))
+            .map(|line| line[2..].trim().trim_matches(|c| c == '\'' || c == '"').to_string())
+            .collect()
+    };
+
+    if items.is_empty() {
+        return None;
+    }
+
+    Some(
+        items
+            .into_iter()
+            .map(|item| match item.as_str() {
+                "," => "，".to_string(),
+                "?" => "？".to_string(),
+                _ => item,
+            })
+            .collect(),
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn punc_list() -> Vec<String> {
+        DEFAULT_PUNC_LIST.iter().map(|p| p.to_string()).collect()
+    }
+
+    /// 模拟模型："好" 后加逗号，"吧" 后加句号
+    fn fake_predict(words: &[String]) -> Result<Vec<usize>, String> {
+        Ok(words
+            .iter()
+            .map(|w| match w.as_str() {
+                "好" => 2,
+                "吧" => 3,
+                _ => 1,
+            })
+            .collect())
+    }
+
+    #[test]
+    fn test_split_words() {
+        assert_eq!(
+            split_words("你好 hello world测试 ok"),
+            vec!["你", "好", "hello", "world", "测", "试", "ok"]
+        );
+        assert!(split_words("  ").is_empty());
+    }
+
+    #[test]
+    fn test_add_punctuation() {
+        let list = punc_list();
+        assert_eq!(add_punctuation("你好hello world", &list, fake_predict).unwrap(), "你好，hello world。");
+        // 句末逗号换成句号
+        assert_eq!(add_punctuation("很好", &list, fake_predict).unwrap(), "很好。");
+        assert_eq!(add_punctuation("", &list, fake_predict).unwrap(), "");
+    }
+
+    #[test]
+    fn test_add_punctuation_carries_unfinished_sentence() {
+        let sentence = "今天天气很好我们去公园吧";
+        let text = sentence.repeat(4);
+        let mut calls = Vec::new();
+
+        let output = add_punctuation(&text, &punc_list(), |words| {
+            calls.push(words.len());
+            fake_predict(words)
+        })
+        .unwrap();
+
+        assert_eq!(output, "今天天气很好，我们去公园吧。".repeat(4));
+        // 48 个字分三段，每段最后一个句号之后的字带入下一段
+        assert_eq!(calls, vec![20, 8 + 20, 4 + 8]);
+    }
+
+    #[test]
+    fn test_label_count_mismatch() {
+        assert!(add_punctuation("你好", &punc_list(), |_| Ok(vec![1])).is_err());
+    }
+
+    #[test]
+    fn test_parse_punc_list() {
+        let config = "model: CTTransformer\nmodel_conf:\n    ignore_id: 0\n    punc_list:\n    - <unk>\n    - _\n    - ','\n    - 。\n    - '?'\n    - 、\nencoder: SANMEncoder\n";
+        assert_eq!(
+            parse_punc_list(config).unwrap(),
+            vec!["<unk>", "_", "，", "。", "？", "、"]
+        );
+        assert_eq!(
+            parse_punc_list("punc_list: [<unk>, _, ，, 。]").unwrap(),
+            vec!["<unk>", "_", "，", "。"]
+        );
+        assert!(parse_punc_list("model: x").is_none());
+    }
+}
@@ -0,0 +1,97 @@
+/// ONNX Runtime 会话封装
+/// 只暴露按名称输入输出的 f32 / i32 张量，模型代码不直接依赖 ort 的类型
+
+use ndarray::{ArrayD, IxDyn};
+use ort::session::builder::GraphOptimizationLevel;
+use ort::session::Session;
+use ort::value::{DynValue, Tensor};
+use std::path::Path;
+
+/// 输入张量
+pub enum Input {
+    F32 { shape: Vec<usize>, data: Vec<f32> },
+    I32 { shape: Vec<usize>, data: Vec<i32> },
+}
+
+/// 输出张量（按行展开）
+#[derive(Debug, Clone)]
+pub struct Output {
+    pub shape: Vec<usize>,
+    pub data: Vec<f32>,
+}
+
+pub struct OnnxSession {
+    session: Session,
+    name: String,
+}
+
+impl OnnxSession {
+    /// 在 CPU 上加载模型
+    pub fn load(path: &Path) -> Result<Self, String> {
+        let threads = num_cpus::get_physical().clamp(1, 8);
+        let session = Session::builder()
+            .and_then(|builder| builder.with_optimization_level(GraphOptimizationLevel::Level3))
+            .and_then(|builder| builder.with_intra_threads(threads))
+            .and_then(|builder| builder.commit_from_file(path))
+            .map_err(|e| format!("Failed to load ONNX model {}: {}", path.display(), e))?;
+
+        Ok(Self {
+            session,
+            name: path.display().to_string(),
+        })
+    }
+
+    /// 运行模型，按 `outputs` 中的名称取出结果（整数输出转换为 f32）
+    pub fn run(&self, inputs: Vec<(&'static str, Input)>, outputs: &[&str]) -> Result<Vec<Output>, String> {
+        let error = |e: ort::Error| format!("ONNX inference failed ({}): {}", self.name, e);
+
+        let values = inputs
+            .into_iter()
+            .map(|(name, input)| Ok((name, to_value(input)?)))
+            .collect::<Result<Vec<(&str, DynValue)>, String>>()?;
+        let results = self.session.run(values).map_err(error)?;
+
+        outputs
+            .iter()
+            .map(|&name| {
+                let value = results
+                    .get(name)
+                    .ok_or_else(|| format!("ONNX model {} has no output '{}'", self.name, name))?;
+
+                if let Ok(array) = value.try_extract_tensor::<f32>() {
+                    return Ok(Output {
+                        shape: array.shape().to_vec(),
+                        data: array.iter().copied().collect(),
+                    });
+                }
+                if let Ok(array) = value.try_extract_tensor::<i32>() {
+                    return Ok(Output {
+                        shape: array.shape().to_vec(),
+                        data: array.iter().map(|&v| v as f32).collect(),
+                    });
+                }
+                let array = value.try_extract_tensor::<i64>().map_err(error)?;
+                Ok(Output {
+                    shape: array.shape().to_vec(),
+                    data: array.iter().map(|&v| v as f32).collect(),
+                })
+            })
+            .collect()
+    }
+}
+
+fn to_value(input: Input) -> Result<DynValue, String> {
+    let shape_error = |e: ndarray::ShapeError| format!("Invalid input shape: {}", e);
+    let tensor_error = |e: ort::Error| format!("Failed to create input tensor: {}", e);
+
+    match input {
+        Input::F32 { shape, data } => {
+            let array = ArrayD::from_shape_vec(IxDyn(&shape), data).map_err(shape_error)?;
+            Ok(Tensor::from_array(array).map_err(tensor_error)?.into_dyn())
+        }
+        Input::I32 { shape, data } => {
+            let array = ArrayD::from_shape_vec(IxDyn(&shape), data).map_err(shape_error)?;
+            Ok(Tensor::from_array(array).map_err(tensor_error)?.into_dyn())
+        }
+    }
+}
@@ -0,0 +1,195 @@
+/// 词表与解码
+/// 把模型输出的 token id 还原为文本：Paraformer 使用字 + `@@` 子词，SenseVoice 使用 SentencePiece（`▁` 表示空格）
+
+use std::collections::HashMap;
+use std::path::Path;
+
+/// CTC 空白符 / Paraformer 的 blank
+pub const BLANK_ID: usize = 0;
+
+/// 不输出到文本的特殊 token
+const SPECIAL_TOKENS: &[&str] = &["<blank>", "<blk>", "<s>", "</s>", "<sos/eos>", "<unk>", "<pad>"];
+
+/// 词表
+#[derive(Debug, Clone)]
+pub struct TokenTable {
+    tokens: Vec<String>,
+    index: HashMap<String, usize>,
+}
+
+impl TokenTable {
+    pub fn new(tokens: Vec<String>) -> Self {
+        let index = tokens.iter().enumerate().map(|(i, t)| (t.clone(), i)).collect();
+        Self { tokens, index }
+    }
+
+    /// 读取模型目录中的词表：FunASR 导出的 `tokens.json`（字符串数组），
+    /// 或 `tokens.txt`（每行 `token id`）
+    pub fn load_dir(dir: &Path) -> Result<Self, String> {
+        let json_path = dir.join("tokens.json");
+        if json_path.exists() {
+            return Self::load(&json_path);
+        }
+
+        let txt_path = dir.join("tokens.txt");
+        let content = std::fs::read_to_string(&txt_path)
+            .map_err(|e| format!("No token list found in {}: {}", dir.display(), e))?;
+        Self::parse_txt(&content).map_err(|e| format!("Invalid token list {}: {}", txt_path.display(), e))
+    }
+
+    /// 读取 `tokens.json`
+    pub fn load(path: &Path) -> Result<Self, String> {
+        let content = std::fs::read_to_string(path)
+            .map_err(|e| format!("Failed to read token list {}: {}", path.display(), e))?;
+        let tokens: Vec<String> = serde_json::from_str(&content)
+            .map_err(|e| format!("Invalid token list {}: {}", path.display(), e))?;
+        Ok(Self::new(tokens))
+    }
+
+    /// 解析 `token id` 格式的词表（token 本身可能包含空格，以最后一个空白分隔）
+    fn parse_txt(content: &str) -> Result<Self, String> {
+        let mut entries = content
+            .lines()
+            .filter(|line| !line.trim().is_empty())
+            .map(|line| {
+                let (token, id) = line
+                    .rsplit_once(char::is_whitespace)
+                    .ok_or_else(|| format!("malformed line '{}'", line))?;
+                let id = id.parse::<usize>().map_err(|e| format!("malformed id in '{}': {}", line, e))?;
+                Ok((id, token.to_string()))
+            })
+            .collect::<Result<Vec<_>, String>>()?;
+        entries.sort_by_key(|(id, _)| *id);
+
+        let mut tokens = vec![String::new(); entries.last().map_or(0, |(id, _)| id + 1)];
+        for (id, token) in entries {
+            tokens[id] = token;
+        }
+        Ok(Self::new(tokens))
+    }
+
+    pub fn vocab_size(&self) -> usize {
+        self.tokens.len()
+    }
+
+    pub fn token(&self, id: usize) -> Option<&str> {
+        self.tokens.get(id).map(String::as_str)
+    }
+
+    pub fn id(&self, token: &str) -> Option<usize> {
+        self.index.get(token).copied()
+    }
+
+    fn tokens_for(&self, ids: &[usize]) -> Vec<&str> {
+        ids.iter()
+            .filter_map(|&id| self.token(id))
+            .filter(|token| !SPECIAL_TOKENS.contains(token))
+            .collect()
+    }
+
+    /// Paraformer 输出：中文逐字拼接，英文单词之间加空格，`@@` 结尾的子词与后一个 token 相连
+    pub fn decode_paraformer(&self, ids: &[usize]) -> String {
+        let mut text = String::new();
+        let mut prev_is_word = false;
+        let mut in_subword = false;
+
+        for token in self.tokens_for(ids) {
+            let (piece, continues) = match token.strip_suffix("@@") {
+                Some(piece) => (piece, true),
+                None => (token, false),
+            };
+            let is_word = piece.chars().all(|c| c.is_ascii_alphanumeric() || c == '\'');
+
+            if is_word && prev_is_word && !in_subword {
+                text.push(' ');
+            }
+            text.push_str(piece);
+            prev_is_word = is_word;
+            in_subword = continues;
+        }
+
+        text
+    }
+
+    /// SentencePiece 输出：`▁` 还原为空格
+    pub fn decode_sentencepiece(&self, ids: &[usize]) -> String {
+        self.tokens_for(ids).concat().replace('▁', " ").trim().to_string()
+    }
+}
+
+/// 逐帧取最大概率的 token id（`logits` 为按帧展开的 `[frames, vocab]`）
+pub fn argmax_frames(logits: &[f32], vocab_size: usize) -> Vec<usize> {
+    logits
+        .chunks_exact(vocab_size)
+        .map(|frame| {
+            frame
+                .iter()
+                .enumerate()
+                .fold((0, f32::NEG_INFINITY), |best, (i, &v)| if v > best.1 { (i, v) } else { best })
+                .0
+        })
+        .collect()
+}
+
+/// CTC 贪心解码：合并连续重复，去掉空白符
+pub fn ctc_collapse(ids: &[usize]) -> Vec<usize> {
+    let mut output = Vec::new();
+    let mut prev = None;
+    for &id in ids {
+        if Some(id) != prev && id != BLANK_ID {
+            output.push(id);
+        }
+        prev = Some(id);
+    }
+    output
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn table(tokens: &[&str]) -> TokenTable {
+        TokenTable::new(tokens.iter().map(|t| t.to_string()).collect())
+    }
+
+    #[test]
+    fn test_decode_paraformer_mixed() {
+        let table = table(&["<blank>", "<s>", "</s>", "你", "好", "hello", "wor@@", "ld", "<unk>", "ok"]);
+        assert_eq!(table.decode_paraformer(&[3, 4]), "你好");
+        assert_eq!(table.decode_paraformer(&[3, 4, 5, 6, 7, 9]), "你好hello world ok");
+        assert_eq!(table.decode_paraformer(&[1, 3, 8, 4, 2]), "你好");
+        assert_eq!(table.token(5), Some("hello"));
+        assert_eq!(table.id("ld"), Some(7));
+    }
+
+    #[test]
+    fn test_parse_txt() {
+        let table = TokenTable::parse_txt("<blk> 0\n▁the 2\n<sos/eos> 1\n\n").unwrap();
+        assert_eq!(table.vocab_size(), 3);
+        assert_eq!(table.token(1), Some("<sos/eos>"));
+        assert_eq!(table.id("▁the"), Some(2));
+        assert!(TokenTable::parse_txt("token").is_err());
+    }
+
+    #[test]
+    fn test_decode_sentencepiece() {
+        let table = table(&["<blank>", "▁hello", "▁wor", "ld", "你好"]);
+        assert_eq!(table.decode_sentencepiece(&[1, 2, 3]), "hello world");
+        assert_eq!(table.decode_sentencepiece(&[4]), "你好");
+    }
+
+    #[test]
+    fn test_ctc_greedy() {
+        let logits = [
+            0.9, 0.1, 0.0, // blank
+            0.1, 0.8, 0.1, // 1
+            0.1, 0.7, 0.2, // 1（重复）
+            0.9, 0.0, 0.1, // blank
+            0.0, 0.6, 0.4, // 1（空白符后重新出现）
+            0.0, 0.1, 0.9, // 2
+        ];
+        let ids = argmax_frames(&logits, 3);
+        assert_eq!(ids, vec![0, 1, 1, 0, 1, 2]);
+        assert_eq!(ctc_collapse(&ids), vec![1, 1, 2]);
+    }
+}
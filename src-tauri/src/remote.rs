@@ -0,0 +1,501 @@
+/// OpenAI 兼容的远程转录引擎
+/// 把音频以 multipart 表单 POST 到 `/v1/audio/transcriptions` 风格的接口
+/// （whisper.cpp server、faster-whisper-server、局域网 GPU 服务器等），响应映射为段落
+
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::time::Duration;
+
+use reqwest::multipart::{Form, Part};
+
+use crate::whisper::{TranscriptionSegment, WordTiming};
+
+/// 默认请求超时（包含上传和服务器解码时间）
+pub const DEFAULT_TIMEOUT_SECS: u64 = 120;
+
+/// 建立连接的超时，服务器不可达时尽快失败以便回退到本地引擎
+const CONNECT_TIMEOUT: Duration = Duration::from_secs(10);
+
+/// 取消标志的检查间隔
+const CANCEL_POLL_INTERVAL: Duration = Duration::from_millis(50);
+
+/// 远程引擎配置（保存在设置 `remoteEngine` 中）
+#[derive(Debug, Clone, PartialEq, serde::Serialize, serde::Deserialize)]
+#[serde(default)]
+pub struct RemoteConfig {
+    /// 服务地址：可以是根地址（自动补全 `/v1/audio/transcriptions`）或完整接口路径
+    pub endpoint: String,
+    /// 认证凭据，为空时不发送认证头
+    pub api_key: Option<String>,
+    /// 认证头名称，默认 `Authorization`（值为 `Bearer <api_key>`）；
+    /// 设为其他名称（如 `X-API-Key`）时直接发送 api_key
+    pub auth_header: Option<String>,
+    /// 服务器端模型名
+    pub model: String,
+    pub timeout_secs: u64,
+}
+
+impl Default for RemoteConfig {
+    fn default() -> Self {
+        Self {
+            endpoint: String::new(),
+            api_key: None,
+            auth_header: None,
+            model: "whisper-1".to_string(),
+            timeout_secs: DEFAULT_TIMEOUT_SECS,
+        }
+    }
+}
+
+impl RemoteConfig {
+    /// 转录（或翻译）接口的完整地址
+    pub fn request_url(&self, translate: bool) -> Result<String, String> {
+        let endpoint = self.endpoint.trim().trim_end_matches('/');
+        let Some((scheme, rest)) = endpoint.split_once("://") else {
+            return Err(format!("Invalid remote endpoint (missing http:// or https://): {}", self.endpoint));
+        };
+        if !matches!(scheme, "http" | "https") || rest.is_empty() {
+            return Err(format!("Invalid remote endpoint: {}", self.endpoint));
+        }
+
+        let url = if !rest.contains('/') {
+            format!("{}/v1/audio/transcriptions", endpoint)
+        } else if endpoint.ends_with("/v1") {
+            format!("{}/audio/transcriptions", endpoint)
+        } else {
+            endpoint.to_string()
+        };
+
+        // OpenAI 的翻译接口与转录接口并列
+        if translate && url.ends_with("/audio/transcriptions") {
+            Ok(format!("{}/audio/translations", url.trim_end_matches("/audio/transcriptions")))
+        } else {
+            Ok(url)
+        }
+    }
+}
+
+/// 单次请求参数
+#[derive(Debug, Clone, Default)]
+pub struct RemoteRequest<'a> {
+    pub language: Option<&'a str>,
+    pub translate: bool,
+    /// 提示词（用户词汇表）
+    pub prompt: Option<&'a str>,
+}
+
+/// 远程转录结果
+#[derive(Debug, Clone, PartialEq)]
+pub struct RemoteTranscription {
+    pub text: String,
+    pub language: Option<String>,
+    /// 是否经翻译接口输出英文译文
+    pub translated: bool,
+    pub segments: Vec<TranscriptionSegment>,
+}
+
+/// OpenAI 兼容接口客户端
+pub struct RemoteClient {
+    client: reqwest::Client,
+    config: RemoteConfig,
+}
+
+impl RemoteClient {
+    pub fn new(config: RemoteConfig) -> Result<Self, String> {
+        config.request_url(false)?;
+
+        let client = reqwest::Client::builder()
+            .connect_timeout(CONNECT_TIMEOUT)
+            .timeout(Duration::from_secs(config.timeout_secs.max(1)))
+            .build()
+            .map_err(|e| format!("Failed to create HTTP client: {}", e))?;
+
+        Ok(Self { client, config })
+    }
+
+    pub fn config(&self) -> &RemoteConfig {
+        &self.config
+    }
+
+    /// 上传 16kHz 单声道音频并解析结果；`cancel` 置位时放弃请求
+    pub async fn transcribe(
+        &self,
+        audio: &[f32],
+        request: &RemoteRequest<'_>,
+        cancel: Option<&AtomicBool>,
+    ) -> Result<RemoteTranscription, String> {
+        use tracing::info;
+
+        let url = self.config.request_url(request.translate)?;
+        let wav = encode_wav_16k(audio)?;
+        let duration_ms = audio.len() as u64 * 1000 / 16000;
+
+        let file = Part::bytes(wav)
+            .file_name("audio.wav")
+            .mime_str("audio/wav")
+            .map_err(|e| format!("Failed to build request: {}", e))?;
+        let mut form = Form::new()
+            .part("file", file)
+            .text("model", self.config.model.clone())
+            .text("response_format", "verbose_json")
+            .text("timestamp_granularities[]", "segment")
+            .text("timestamp_granularities[]", "word");
+        if let Some(language) = request.language.filter(|_| !request.translate) {
+            form = form.text("language", language.to_string());
+        }
+        if let Some(prompt) = request.prompt.filter(|p| !p.is_empty()) {
+            form = form.text("prompt", prompt.to_string());
+        }
+
+        let mut builder = self.client.post(&url).multipart(form);
+        if let Some(api_key) = self.config.api_key.as_deref().filter(|k| !k.is_empty()) {
+            builder = match self.config.auth_header.as_deref().filter(|h| !h.is_empty()) {
+                Some(header) if !header.eq_ignore_ascii_case("authorization") => builder.header(header, api_key),
+                _ => builder.bearer_auth(api_key),
+            };
+        }
+
+        info!("🌐 [Remote] Uploading {:.1}s of audio to {}", duration_ms as f32 / 1000.0, url);
+
+        let response = async {
+            let response = builder
+                .send()
+                .await
+                .map_err(|e| format!("Remote request failed: {}", describe_error(&e)))?;
+            let status = response.status();
+            let body = response
+                .bytes()
+                .await
+                .map_err(|e| format!("Failed to read remote response: {}", describe_error(&e)))?;
+            Ok::<_, String>((status, body))
+        };
+        let (status, body) = with_cancel(response, cancel).await?;
+
+        if !status.is_success() {
+            return Err(format!("Remote server returned {}: {}", status, error_message(&body)));
+        }
+
+        let mut result = parse_response(&body, duration_ms)?;
+        result.translated = request.translate && url != self.config.request_url(false)?;
+        info!("✅ [Remote] Received {} segments", result.segments.len());
+        Ok(result)
+    }
+}
+
+/// 等待请求完成，期间定期检查取消标志
+async fn with_cancel<T>(
+    future: impl std::future::Future<Output = Result<T, String>>,
+    cancel: Option<&AtomicBool>,
+) -> Result<T, String> {
+    tokio::pin!(future);
+    loop {
+        tokio::select! {
+            result = &mut future => return result,
+            _ = tokio::time::sleep(CANCEL_POLL_INTERVAL) => {
+                if cancel.is_some_and(|flag| flag.load(Ordering::SeqCst)) {
+                    return Err(crate::jobs::CANCELLED_MESSAGE.to_string());
+                }
+            }
+        }
+    }
+}
+
+fn describe_error(error: &reqwest::Error) -> String {
+    if error.is_timeout() {
+        format!("timed out ({})", error)
+    } else if error.is_connect() {
+        format!("cannot connect to server ({})", error)
+    } else {
+        error.to_string()
+    }
+}
+
+/// 提取错误响应中的信息（OpenAI 格式为 `{"error": {"message": ...}}`）
+fn error_message(body: &[u8]) -> String {
+    #[derive(serde::Deserialize)]
+    struct ErrorBody {
+        error: serde_json::Value,
+    }
+
+    if let Ok(ErrorBody { error }) = serde_json::from_slice::<ErrorBody>(body) {
+        if let Some(message) = error.get("message").and_then(|m| m.as_str()) {
+            return message.to_string();
+        }
+        if let Some(message) = error.as_str() {
+            return message.to_string();
+        }
+    }
+
+    let text = String::from_utf8_lossy(body);
+    text.trim().chars().take(300).collect()
+}
+
+/// `verbose_json` 响应（`json` / `text` 格式同样兼容）
+#[derive(Debug, serde::Deserialize)]
+struct VerboseResponse {
+    #[serde(default)]
+    text: String,
+    #[serde(default)]
+    language: Option<String>,
+    #[serde(default)]
+    segments: Vec<ResponseSegment>,
+    #[serde(default)]
+    words: Vec<ResponseWord>,
+}
+
+#[derive(Debug, serde::Deserialize)]
+struct ResponseSegment {
+    start: f64,
+    end: f64,
+    text: String,
+    /// 部分服务器（如 whisper.cpp）把词放在段落内
+    #[serde(default)]
+    words: Vec<ResponseWord>,
+}
+
+#[derive(Debug, serde::Deserialize)]
+struct ResponseWord {
+    word: String,
+    start: f64,
+    end: f64,
+}
+
+/// 把响应映射为段落；没有段落信息时整段音频作为一个段落
+pub fn parse_response(body: &[u8], duration_ms: u64) -> Result<RemoteTranscription, String> {
+    let trimmed = String::from_utf8_lossy(body);
+    let trimmed = trimmed.trim();
+
+    let response = if trimmed.starts_with('{') {
+        serde_json::from_str::<VerboseResponse>(trimmed)
+            .map_err(|e| format!("Failed to parse remote response: {}", e))?
+    } else {
+        // response_format=text
+        VerboseResponse {
+            text: trimmed.to_string(),
+            language: None,
+            segments: Vec::new(),
+            words: Vec::new(),
+        }
+    };
+
+    let mut segments: Vec<TranscriptionSegment> = response
+        .segments
+        .iter()
+        .filter(|segment| !segment.text.trim().is_empty())
+        .map(|segment| TranscriptionSegment {
+            text: segment.text.trim().to_string(),
+            start_ms: seconds_to_ms(segment.start),
+            end_ms: seconds_to_ms(segment.end).max(seconds_to_ms(segment.start)),
+            words: segment.words.iter().map(word_timing).collect(),
+            speaker: None,
+        })
+        .collect();
+
+    if segments.is_empty() && !response.text.trim().is_empty() {
+        segments.push(TranscriptionSegment {
+            text: response.text.trim().to_string(),
+            start_ms: 0,
+            end_ms: duration_ms,
+            words: Vec::new(),
+            speaker: None,
+        });
+    }
+
+    // 顶层词列表按时间归入段落（段落内已带词时忽略，避免重复）
+    if segments.iter().all(|segment| segment.words.is_empty()) {
+        for word in response.words.iter().map(word_timing) {
+            let midpoint = (word.start_ms + word.end_ms) / 2;
+            let index = segments.iter().rposition(|s| s.start_ms <= midpoint).unwrap_or(0);
+            if let Some(segment) = segments.get_mut(index) {
+                segment.words.push(word);
+            }
+        }
+    }
+
+    let text = if response.text.trim().is_empty() {
+        segments.iter().map(|s| s.text.as_str()).collect::<Vec<_>>().join(" ")
+    } else {
+        response.text.trim().to_string()
+    };
+
+    Ok(RemoteTranscription {
+        text,
+        language: response.language.filter(|l| !l.is_empty()),
+        translated: false,
+        segments,
+    })
+}
+
+fn word_timing(word: &ResponseWord) -> WordTiming {
+    let start_ms = seconds_to_ms(word.start);
+    WordTiming {
+        text: word.word.trim().to_string(),
+        start_ms,
+        end_ms: seconds_to_ms(word.end).max(start_ms),
+    }
+}
+
+fn seconds_to_ms(seconds: f64) -> u64 {
+    (seconds.max(0.0) * 1000.0).round() as u64
+}
+
+/// 16kHz f32 音频编码为内存中的 16bit WAV
+fn encode_wav_16k(audio: &[f32]) -> Result<Vec<u8>, String> {
+    let spec = hound::WavSpec {
+        channels: 1,
+        sample_rate: 16000,
+        bits_per_sample: 16,
+        sample_format: hound::SampleFormat::Int,
+    };
+
+    let mut cursor = std::io::Cursor::new(Vec::with_capacity(44 + audio.len() * 2));
+    {
+        let mut writer = hound::WavWriter::new(&mut cursor, spec)
+            .map_err(|e| format!("Failed to encode WAV: {}", e))?;
+        for &sample in audio {
+            writer
+                .write_sample((sample.clamp(-1.0, 1.0) * i16::MAX as f32) as i16)
+                .map_err(|e| format!("Failed to encode WAV: {}", e))?;
+        }
+        writer
+            .finalize()
+            .map_err(|e| format!("Failed to encode WAV: {}", e))?;
+    }
+    Ok(cursor.into_inner())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::io::{BufRead, BufReader, Read, Write};
+    use std::net::TcpListener;
+    use std::sync::mpsc;
+
+    /// 只处理一个请求的本地 HTTP 服务器，返回 (地址, 收到的请求)
+    fn mock_server(status: &'static str, body: &'static str) -> (String, mpsc::Receiver<String>) {
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let address = format!("http://{}", listener.local_addr().unwrap());
+        let (tx, rx) = mpsc::channel();
+
+        std::thread::spawn(move || {
+            let (stream, _) = listener.accept().unwrap();
+            let mut reader = BufReader::new(stream.try_clone().unwrap());
+
+            let mut head = String::new();
+            let mut content_length = 0;
+            loop {
+                let mut line = String::new();
+                reader.read_line(&mut line).unwrap();
+                if let Some(value) = line.to_ascii_lowercase().strip_prefix("content-length:") {
+                    content_length = value.trim().parse().unwrap();
+                }
+                if line == "\r\n" {
+                    break;
+                }
+                head.push_str(&line);
+            }
+            let mut request_body = vec![0; content_length];
+            reader.read_exact(&mut request_body).unwrap();
+            head.push_str(&String::from_utf8_lossy(&request_body));
+            tx.send(head).unwrap();
+
+            let mut stream = stream;
+            write!(
+                stream,
+                "HTTP/1.1 {}\r\nContent-Type: application/json\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{}",
+                status,
+                body.len(),
+                body
+            )
+            .unwrap();
+        });
+
+        (address, rx)
+    }
+
+    fn config(endpoint: &str) -> RemoteConfig {
+        RemoteConfig {
+            endpoint: endpoint.to_string(),
+            api_key: Some("secret".to_string()),
+            model: "large-v3".to_string(),
+            ..RemoteConfig::default()
+        }
+    }
+
+    #[test]
+    fn test_request_url() {
+        let url = |endpoint: &str, translate| config(endpoint).request_url(translate);
+        assert_eq!(url("http://gpu:8000", false).unwrap(), "http://gpu:8000/v1/audio/transcriptions");
+        assert_eq!(url("http://gpu:8000/v1/", false).unwrap(), "http://gpu:8000/v1/audio/transcriptions");
+        assert_eq!(url("http://gpu:8000/v1", true).unwrap(), "http://gpu:8000/v1/audio/translations");
+        assert_eq!(url("http://127.0.0.1:8080/inference", true).unwrap(), "http://127.0.0.1:8080/inference");
+        assert!(url("gpu:8000", false).is_err());
+    }
+
+    #[test]
+    fn test_parse_verbose_json() {
+        let body = br#"{
+            "text": "Hello world. Second part.",
+            "language": "en",
+            "segments": [
+                {"start": 0.0, "end": 1.5, "text": " Hello world."},
+                {"start": 1.5, "end": 3.2, "text": " Second part."}
+            ],
+            "words": [
+                {"word": "Hello", "start": 0.0, "end": 0.6},
+                {"word": "world.", "start": 0.6, "end": 1.4},
+                {"word": "Second", "start": 1.6, "end": 2.2}
+            ]
+        }"#;
+        let result = parse_response(body, 3200).unwrap();
+        assert_eq!(result.language.as_deref(), Some("en"));
+        assert_eq!(result.segments.len(), 2);
+        assert_eq!(result.segments[0].text, "Hello world.");
+        assert_eq!((result.segments[1].start_ms, result.segments[1].end_ms), (1500, 3200));
+        assert_eq!(result.segments[0].words.len(), 2);
+        assert_eq!(result.segments[1].words[0].text, "Second");
+    }
+
+    #[test]
+    fn test_parse_plain_text_spans_audio() {
+        let result = parse_response(b"  just text\n", 2000).unwrap();
+        assert_eq!(result.text, "just text");
+        assert_eq!(result.segments.len(), 1);
+        assert_eq!(result.segments[0].end_ms, 2000);
+    }
+
+    #[tokio::test]
+    async fn test_transcribe_against_mock_server() {
+        let (address, requests) = mock_server(
+            "200 OK",
+            r#"{"text":"你好","segments":[{"start":0.0,"end":0.8,"text":"你好"}]}"#,
+        );
+        let client = RemoteClient::new(config(&address)).unwrap();
+
+        let request = RemoteRequest {
+            language: Some("zh"),
+            ..RemoteRequest::default()
+        };
+        let result = client.transcribe(&vec![0.0; 16000], &request, None).await.unwrap();
+        assert_eq!(result.text, "你好");
+        assert_eq!(result.segments[0].end_ms, 800);
+
+        let received = requests.recv().unwrap();
+        assert!(received.starts_with("POST /v1/audio/transcriptions "));
+        assert!(received.to_ascii_lowercase().contains("authorization: bearer secret"));
+        assert!(received.contains("name=\"model\"\r\n\r\nlarge-v3"));
+        assert!(received.contains("name=\"language\"\r\n\r\nzh"));
+    }
+
+    #[tokio::test]
+    async fn test_server_error_message() {
+        let (address, _requests) = mock_server("401 Unauthorized", r#"{"error":{"message":"Invalid API key"}}"#);
+        let client = RemoteClient::new(config(&address)).unwrap();
+
+        let error = client
+            .transcribe(&vec![0.0; 1600], &RemoteRequest::default(), None)
+            .await
+            .unwrap_err();
+        assert!(error.contains("401"));
+        assert!(error.contains("Invalid API key"));
+    }
+}
@@ -0,0 +1,181 @@
+/// SenseVoice 富文本标签
+/// SenseVoice 在每段识别结果前输出 `<|zh|><|HAPPY|><|Laughter|><|withitn|>` 形式的标签
+/// （语言、情感、音频事件、是否逆文本正则化），这里把标签从文本中拆出并汇总为结构化数据
+
+/// 情感标签（`EMO_UNKNOWN` 视为未识别）
+const EMOTIONS: &[&str] = &[
+    "HAPPY",
+    "SAD",
+    "ANGRY",
+    "NEUTRAL",
+    "FEARFUL",
+    "DISGUSTED",
+    "SURPRISED",
+    "EMO_UNKNOWN",
+];
+
+/// 音频事件标签（`Speech` 是普通语音，`Event_UNK` 为未识别事件，都不记录）
+const EVENTS: &[&str] = &[
+    "Speech",
+    "BGM",
+    "Applause",
+    "Laughter",
+    "Cry",
+    "Sneeze",
+    "Breath",
+    "Cough",
+    "Event_UNK",
+];
+
+/// 语言标签（`nospeech` 表示该段没有语音）
+const LANGUAGES: &[&str] = &["zh", "en", "yue", "ja", "ko", "nospeech"];
+
+/// 拆分后的识别结果，标签值统一为小写
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct RichTranscript {
+    /// 去掉标签后的文本
+    pub text: String,
+    /// 出现最多的语言
+    pub language: Option<String>,
+    /// 主要情感：出现最多的非中性情感，没有时为 `neutral`
+    pub emotion: Option<String>,
+    /// 出现过的音频事件（按首次出现顺序去重）
+    pub events: Vec<String>,
+}
+
+/// 拆出文本中所有 `<|...|>` 标签
+///
+/// 分段识别的结果每段都带一组标签，标签可能出现在文本中间
+pub fn parse(text: &str) -> RichTranscript {
+    let mut pieces: Vec<&str> = Vec::new();
+    let mut languages: Vec<(&str, usize)> = Vec::new();
+    let mut emotions: Vec<&str> = Vec::new();
+    let mut events: Vec<String> = Vec::new();
+
+    let mut rest = text;
+    while let Some(start) = rest.find("<|") {
+        let Some(len) = rest[start + 2..].find("|>") else {
+            break;
+        };
+        let tag = &rest[start + 2..start + 2 + len];
+        pieces.push(&rest[..start]);
+        rest = &rest[start + 2 + len + 2..];
+
+        if LANGUAGES.contains(&tag) {
+            if tag != "nospeech" {
+                count(&mut languages, tag);
+            }
+        } else if EMOTIONS.contains(&tag) {
+            if tag != "EMO_UNKNOWN" {
+                emotions.push(tag);
+            }
+        } else if EVENTS.contains(&tag) {
+            let event = tag.to_lowercase();
+            if tag != "Speech" && tag != "Event_UNK" && !events.contains(&event) {
+                events.push(event);
+            }
+        }
+    }
+    pieces.push(rest);
+
+    RichTranscript {
+        text: join_pieces(&pieces),
+        language: most_frequent(languages.iter()).map(str::to_lowercase),
+        emotion: dominant_emotion(emotions),
+        events,
+    }
+}
+
+/// 汇总多段的情感：出现最多的非中性情感，没有时为 `neutral`（不区分大小写，返回小写）
+pub fn dominant_emotion<'a>(emotions: impl IntoIterator<Item = &'a str>) -> Option<String> {
+    let mut counts: Vec<(&str, usize)> = Vec::new();
+    for emotion in emotions {
+        count(&mut counts, emotion);
+    }
+
+    most_frequent(counts.iter().filter(|(tag, _)| !tag.eq_ignore_ascii_case("NEUTRAL")))
+        .or_else(|| most_frequent(counts.iter()))
+        .map(str::to_lowercase)
+}
+
+fn count<'a>(counts: &mut Vec<(&'a str, usize)>, tag: &'a str) {
+    match counts.iter_mut().find(|(seen, _)| *seen == tag) {
+        Some((_, n)) => *n += 1,
+        None => counts.push((tag, 1)),
+    }
+}
+
+/// 次数最多的标签，次数相同时取先出现的
+fn most_frequent<'a, 'b>(counts: impl Iterator<Item = &'b (&'a str, usize)>) -> Option<&'a str>
+where
+    'a: 'b,
+{
+    counts
+        .fold(None, |best: Option<(&str, usize)>, &(tag, n)| match best {
+            Some((_, best_n)) if best_n >= n => best,
+            _ => Some((tag, n)),
+        })
+        .map(|(tag, _)| tag)
+}
+
+/// 拼接去掉标签后的各段文本，两侧都是拉丁字母/数字时补空格
+fn join_pieces(pieces: &[&str]) -> String {
+    let mut joined = String::new();
+    for piece in pieces
+        .iter()
+        .map(|piece| piece.trim())
+        .filter(|piece| !piece.is_empty())
+    {
+        let needs_space = joined
+            .chars()
+            .last()
+            .is_some_and(|c| c.is_ascii_alphanumeric())
+            && piece
+                .chars()
+                .next()
+                .is_some_and(|c| c.is_ascii_alphanumeric());
+        if needs_space {
+            joined.push(' ');
+        }
+        joined.push_str(piece);
+    }
+    joined
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_single_segment() {
+        let parsed = parse("<|zh|><|NEUTRAL|><|Speech|><|withitn|>你好，世界。");
+        assert_eq!(parsed.text, "你好，世界。");
+        assert_eq!(parsed.language.as_deref(), Some("zh"));
+        assert_eq!(parsed.emotion.as_deref(), Some("neutral"));
+        assert!(parsed.events.is_empty());
+    }
+
+    #[test]
+    fn test_parse_multiple_segments() {
+        let parsed = parse(
+            "<|en|><|NEUTRAL|><|Speech|><|withitn|>Hello<|en|><|HAPPY|><|Laughter|><|withitn|>that was fun\
+             <|zh|><|HAPPY|><|Applause|><|Laughter|><|withitn|>太好了",
+        );
+        assert_eq!(parsed.text, "Hello that was fun太好了");
+        assert_eq!(parsed.language.as_deref(), Some("en"));
+        // 非中性情感优先
+        assert_eq!(parsed.emotion.as_deref(), Some("happy"));
+        assert_eq!(parsed.events, vec!["laughter", "applause"]);
+    }
+
+    #[test]
+    fn test_parse_plain_text() {
+        let parsed = parse("没有标签 <|unterminated");
+        assert_eq!(parsed.text, "没有标签 <|unterminated");
+        assert_eq!(parsed.language, None);
+        assert_eq!(parsed.emotion, None);
+
+        let parsed = parse("<|nospeech|><|EMO_UNKNOWN|><|Event_UNK|><|woitn|>");
+        assert_eq!(parsed, RichTranscript::default());
+    }
+}
@@ -2,11 +2,15 @@ use tauri::{AppHandle, Emitter, Manager, Runtime};
 use tauri_plugin_global_shortcut::{Code, GlobalShortcutExt, Modifiers, Shortcut, ShortcutState};
 use std::sync::{Arc, Mutex};
 use std::time::{Duration, Instant};
+use crate::config::OutputMode;
 use crate::db::{Database, SettingsRepository};
 
 // Recording state tracking
 static RECORDING_STATE: Mutex<RecordingState> = Mutex::new(RecordingState::Idle);
 
+// Output mode requested by the shortcut that started the current recording
+static ACTIVE_OUTPUT_MODE: Mutex<Option<OutputMode>> = Mutex::new(None);
+
 // Track last frontend ready notification time to prevent duplicates
 static LAST_FRONTEND_READY: Mutex<Option<Instant>> = Mutex::new(None);
 const FRONTEND_READY_DEBOUNCE_MS: u64 = 500; // 500ms debounce window
@@ -36,6 +40,18 @@ fn get_operation_mode(app: &AppHandle<impl Runtime>) -> String {
     "preview".to_string()
 }
 
+fn set_active_output_mode(mode: Option<OutputMode>) {
+    *ACTIVE_OUTPUT_MODE.lock().unwrap() = mode;
+}
+
+/// Output mode requested by the shortcut that started the last recording
+///
+/// Returns `None` for the regular dictation shortcut so that per-app profiles
+/// and the default setting still apply.
+pub fn active_output_mode() -> Option<OutputMode> {
+    *ACTIVE_OUTPUT_MODE.lock().unwrap()
+}
+
 pub fn register_shortcuts<R: Runtime>(app: &AppHandle<R>) -> Result<(), String> {
     // Register default shortcut: Cmd+Shift+S (macOS) or Ctrl+Shift+S (other platforms)
     let modifiers = if cfg!(target_os = "macos") {
@@ -49,83 +65,114 @@ pub fn register_shortcuts<R: Runtime>(app: &AppHandle<R>) -> Result<(), String>
 
     let app_handle = app.clone();
     app.global_shortcut()
-        .on_shortcut(shortcut, move |app, _shortcut, event| {
-            // Get operation mode
-            let operation_mode = get_operation_mode(&app_handle);
-            let is_direct_mode = operation_mode == "direct";
-
-            println!("[Shortcut] Shortcut event: {:?}, Mode: {}", event.state, operation_mode);
-
-            // Get current state
-            let mut state = RECORDING_STATE.lock().unwrap();
-            let current_state = *state;
-
-            // Handle different modes
-            if is_direct_mode {
-                // Direct mode: Press to start, Release to stop
-                match (event.state, current_state) {
-                    (ShortcutState::Pressed, RecordingState::Idle) => {
-                        // Start recording on press
-                        println!("[Shortcut] Direct mode: Press detected -> Start recording");
-                        *state = RecordingState::Recording;
-                        drop(state);
-
-                        // Create/show window and start recording
-                        handle_start_recording(&app_handle);
-                    }
-                    (ShortcutState::Released, RecordingState::Recording) => {
-                        // Stop recording on release
-                        println!("[Shortcut] Direct mode: Release detected -> Stop recording");
-                        *state = RecordingState::Processing;
-                        drop(state);
-
-                        // Stop recording
-                        handle_stop_recording(&app_handle);
-                    }
-                    _ => {
-                        // Ignore other combinations
-                        drop(state);
-                    }
-                }
-            } else {
-                // Preview mode: Toggle window visibility on press
-                if event.state != ShortcutState::Pressed {
-                    drop(state);
-                    return;
-                }
-
-                println!("[Shortcut] Preview mode: Toggle window visibility");
-
-                // Check if window is visible
-                if let Some(window) = app_handle.get_webview_window("recording-float") {
-                    // Window exists - toggle visibility
-                    if window.is_visible().unwrap_or(false) {
-                        println!("[Shortcut] Window visible - hiding and stopping recording");
-                        let _ = window.hide();
-                        let _ = window.emit("shortcut-stop-recording", ());
-                        *state = RecordingState::Idle;
-                        drop(state);
-                    } else {
-                        println!("[Shortcut] Window hidden - showing and starting recording");
-                        *state = RecordingState::Recording;
-                        drop(state);
-                        handle_start_recording(&app_handle);
-                    }
-                } else {
-                    // Window doesn't exist - create and start recording
-                    println!("[Shortcut] Window doesn't exist - creating and starting recording");
-                    *state = RecordingState::Recording;
-                    drop(state);
-                    handle_start_recording(&app_handle);
-                }
-            }
+        .on_shortcut(shortcut, move |_app, _shortcut, event| {
+            handle_shortcut_event(&app_handle, event.state, None);
         })
         .map_err(|e| e.to_string())?;
 
+    // 翻译快捷键是可选的，注册失败不影响主快捷键
+    if let Err(e) = register_translate_shortcut(app) {
+        println!("[Shortcut] ⚠️  Failed to register translate shortcut: {}", e);
+    }
+
     println!("[Shortcut] Shortcut registration complete");
     Ok(())
 }
 
+/// Shared press/release handling for all dictation shortcuts
+///
+/// `output_mode` is the override carried by the shortcut that started the
+/// recording (`None` for the regular dictation shortcut).
+fn handle_shortcut_event<R: Runtime>(
+    app_handle: &AppHandle<R>,
+    event_state: ShortcutState,
+    output_mode: Option<OutputMode>,
+) {
+    // Get operation mode
+    let operation_mode = get_operation_mode(app_handle);
+    let is_direct_mode = operation_mode == "direct";
+
+    println!("[Shortcut] Shortcut event: {:?}, Mode: {}", event_state, operation_mode);
+
+    // Get current state
+    let mut state = RECORDING_STATE.lock().unwrap();
+    let current_state = *state;
+
+    // Handle different modes
+    if is_direct_mode {
+        // Direct mode: Press to start, Release to stop
+        match (event_state, current_state) {
+            (ShortcutState::Pressed, RecordingState::Idle) => {
+                // Start recording on press
+                println!("[Shortcut] Direct mode: Press detected -> Start recording");
+                *state = RecordingState::Recording;
+                drop(state);
+
+                // Create/show window and start recording
+                set_active_output_mode(output_mode);
+                handle_start_recording(app_handle);
+            }
+            (ShortcutState::Pressed, RecordingState::Processing) if !crate::jobs::is_busy() => {
+                // The frontend never started the reserved transcription (timed out), treat as idle
+                println!("[Shortcut] Direct mode: Pending transcription expired -> Start recording");
+                *state = RecordingState::Recording;
+                drop(state);
+
+                set_active_output_mode(output_mode);
+                handle_start_recording(app_handle);
+            }
+            (ShortcutState::Released, RecordingState::Recording) => {
+                // Stop recording on release
+                println!("[Shortcut] Direct mode: Release detected -> Stop recording");
+                *state = RecordingState::Processing;
+                // Reserve the transcription job so Processing lasts until the job ends
+                crate::jobs::expect_job();
+                drop(state);
+
+                // Stop recording
+                handle_stop_recording(app_handle);
+            }
+            _ => {
+                // Ignore other combinations
+                drop(state);
+            }
+        }
+    } else {
+        // Preview mode: Toggle window visibility on press
+        if event_state != ShortcutState::Pressed {
+            drop(state);
+            return;
+        }
+
+        println!("[Shortcut] Preview mode: Toggle window visibility");
+
+        // Check if window is visible
+        if let Some(window) = app_handle.get_webview_window("recording-float") {
+            // Window exists - toggle visibility
+            if window.is_visible().unwrap_or(false) {
+                println!("[Shortcut] Window visible - hiding and stopping recording");
+                let _ = window.hide();
+                let _ = window.emit("shortcut-stop-recording", ());
+                *state = RecordingState::Idle;
+                drop(state);
+            } else {
+                println!("[Shortcut] Window hidden - showing and starting recording");
+                *state = RecordingState::Recording;
+                drop(state);
+                set_active_output_mode(output_mode);
+                handle_start_recording(app_handle);
+            }
+        } else {
+            // Window doesn't exist - create and start recording
+            println!("[Shortcut] Window doesn't exist - creating and starting recording");
+            *state = RecordingState::Recording;
+            drop(state);
+            set_active_output_mode(output_mode);
+            handle_start_recording(app_handle);
+        }
+    }
+}
+
 fn handle_start_recording<R: Runtime>(app_handle: &AppHandle<R>) {
     // 🚨 CRITICAL: 首先检查麦克风权限，如果没有授权则阻止录音
     use crate::audio::check_permission;
@@ -143,6 +190,9 @@ fn handle_start_recording<R: Runtime>(app_handle: &AppHandle<R>) {
 
     println!("[Shortcut] ✅ Microphone permission granted, proceeding with recording");
 
+    // 模型可能因空闲已被卸载，趁用户说话时提前加载
+    crate::memory_governor::prewarm_on_shortcut(app_handle);
+
     // 🔑 关键：在显示窗口前，先保存当前活跃的应用
     #[cfg(target_os = "macos")]
     {
@@ -338,9 +388,54 @@ fn handle_stop_recording<R: Runtime>(app_handle: &AppHandle<R>) {
         let _ = window.emit("shortcut-stop-recording", ());
     }
 
-    // Reset to idle state after processing
-    // In a real app, this would be done after transcription completes
-    *RECORDING_STATE.lock().unwrap() = RecordingState::Idle;
+    // Stay in Processing until the transcription job finishes (see finish_processing)
+    // or the frontend abandons it (see abandon_recording)
+}
+
+/// Called when the transcription job that took over the shortcut's reservation ends
+/// (completed, failed or cancelled); the job itself reports the outcome to the frontend
+///
+/// Returns the state machine to `Idle`.
+pub fn finish_processing() {
+    {
+        let mut state = RECORDING_STATE.lock().unwrap();
+        if *state == RecordingState::Processing {
+            *state = RecordingState::Idle;
+        }
+    }
+
+    // The shortcut's output mode only applies to the recording it started
+    set_active_output_mode(None);
+    println!("[Shortcut] Reserved transcription finished");
+}
+
+/// Leaves Processing when the reserved transcription will never start
+fn abandon_processing<R: Runtime>(app_handle: &AppHandle<R>) {
+    let mut state = RECORDING_STATE.lock().unwrap();
+    if !crate::jobs::abandon_pending() {
+        // A transcription job already owns the Processing state
+        return;
+    }
+
+    if *state == RecordingState::Processing {
+        *state = RecordingState::Idle;
+    }
+    drop(state);
+
+    set_active_output_mode(None);
+    println!("[Shortcut] Pending transcription abandoned");
+    let _ = app_handle.emit(
+        "transcription-outcome",
+        serde_json::json!({ "job_id": null, "outcome": crate::jobs::JobOutcome::Cancelled }),
+    );
+}
+
+/// Command called by frontend when a stopped recording will not be transcribed
+/// (capture failed, recording too short, ...)
+#[tauri::command]
+pub fn abandon_recording(app: AppHandle) -> Result<(), String> {
+    abandon_processing(&app);
+    Ok(())
 }
 
 pub fn unregister_all<R: Runtime>(app: &AppHandle<R>) -> Result<(), String> {
@@ -380,6 +475,57 @@ pub fn register_shortcuts_cmd(app: AppHandle) -> Result<(), String> {
 
 /// Register a custom shortcut from string format (e.g. "Cmd+Shift+S")
 fn register_custom_shortcut<R: Runtime>(app: &AppHandle<R>, shortcut_str: &str) -> Result<(), String> {
+    let shortcut = parse_shortcut(shortcut_str)?;
+    println!("[Shortcut] Registering custom shortcut: {:?}", shortcut);
+
+    let app_handle = app.clone();
+    app.global_shortcut()
+        .on_shortcut(shortcut, move |_app, _shortcut, event| {
+            handle_shortcut_event(&app_handle, event.state, None);
+        })
+        .map_err(|e| e.to_string())?;
+
+    // 翻译快捷键是可选的，注册失败不影响主快捷键
+    if let Err(e) = register_translate_shortcut(app) {
+        println!("[Shortcut] ⚠️  Failed to register translate shortcut: {}", e);
+    }
+
+    println!("[Shortcut] Custom shortcut registration complete");
+    Ok(())
+}
+
+/// Register the optional translate-to-English shortcut from settings
+///
+/// Recordings started with this shortcut are decoded with Whisper's translate task.
+fn register_translate_shortcut<R: Runtime>(app: &AppHandle<R>) -> Result<(), String> {
+    let Some(db) = app.try_state::<Arc<Database>>() else {
+        return Ok(());
+    };
+
+    let repo = SettingsRepository::new(db.connection());
+    let shortcut_str = match repo.get("translateShortcut") {
+        Ok(Some(value)) => match serde_json::from_str::<String>(&value) {
+            Ok(shortcut_str) if !shortcut_str.is_empty() => shortcut_str,
+            _ => return Ok(()),
+        },
+        _ => return Ok(()),
+    };
+
+    println!("[Shortcut] Registering translate shortcut: {}", shortcut_str);
+    let shortcut = parse_shortcut(&shortcut_str)?;
+
+    let app_handle = app.clone();
+    app.global_shortcut()
+        .on_shortcut(shortcut, move |_app, _shortcut, event| {
+            handle_shortcut_event(&app_handle, event.state, Some(OutputMode::Translate));
+        })
+        .map_err(|e| e.to_string())?;
+
+    Ok(())
+}
+
+/// Parse a shortcut string (e.g. "Cmd+Shift+S") into a Shortcut
+fn parse_shortcut(shortcut_str: &str) -> Result<Shortcut, String> {
     use tauri_plugin_global_shortcut::{Code, Modifiers};
 
     println!("[Shortcut] Parsing shortcut string: {}", shortcut_str);
@@ -419,86 +565,7 @@ fn register_custom_shortcut<R: Runtime>(app: &AppHandle<R>, shortcut_str: &str)
         return Err("无效的快捷键格式".to_string());
     };
 
-    let shortcut = Shortcut::new(Some(modifiers), code);
-    println!("[Shortcut] Registering custom shortcut: {:?}", shortcut);
-
-    let app_handle = app.clone();
-    app.global_shortcut()
-        .on_shortcut(shortcut, move |app, _shortcut, event| {
-            // Get operation mode
-            let operation_mode = get_operation_mode(&app_handle);
-            let is_direct_mode = operation_mode == "direct";
-
-            println!("[Shortcut] Shortcut event: {:?}, Mode: {}", event.state, operation_mode);
-
-            // Get current state
-            let mut state = RECORDING_STATE.lock().unwrap();
-            let current_state = *state;
-
-            // Handle different modes
-            if is_direct_mode {
-                // Direct mode: Press to start, Release to stop
-                match (event.state, current_state) {
-                    (ShortcutState::Pressed, RecordingState::Idle) => {
-                        // Start recording on press
-                        println!("[Shortcut] Direct mode: Press detected -> Start recording");
-                        *state = RecordingState::Recording;
-                        drop(state);
-
-                        // Create/show window and start recording
-                        handle_start_recording(&app_handle);
-                    }
-                    (ShortcutState::Released, RecordingState::Recording) => {
-                        // Stop recording on release
-                        println!("[Shortcut] Direct mode: Release detected -> Stop recording");
-                        *state = RecordingState::Processing;
-                        drop(state);
-
-                        // Stop recording
-                        handle_stop_recording(&app_handle);
-                    }
-                    _ => {
-                        // Ignore other combinations
-                        drop(state);
-                    }
-                }
-            } else {
-                // Preview mode: Toggle window visibility on press
-                if event.state != ShortcutState::Pressed {
-                    drop(state);
-                    return;
-                }
-
-                println!("[Shortcut] Preview mode: Toggle window visibility");
-
-                // Check if window is visible
-                if let Some(window) = app_handle.get_webview_window("recording-float") {
-                    // Window exists - toggle visibility
-                    if window.is_visible().unwrap_or(false) {
-                        println!("[Shortcut] Window visible - hiding and stopping recording");
-                        let _ = window.hide();
-                        let _ = window.emit("shortcut-stop-recording", ());
-                        *state = RecordingState::Idle;
-                        drop(state);
-                    } else {
-                        println!("[Shortcut] Window hidden - showing and starting recording");
-                        *state = RecordingState::Recording;
-                        drop(state);
-                        handle_start_recording(&app_handle);
-                    }
-                } else {
-                    // Window doesn't exist - create and start recording
-                    println!("[Shortcut] Window doesn't exist - creating and starting recording");
-                    *state = RecordingState::Recording;
-                    drop(state);
-                    handle_start_recording(&app_handle);
-                }
-            }
-        })
-        .map_err(|e| e.to_string())?;
-
-    println!("[Shortcut] Custom shortcut registration complete");
-    Ok(())
+    Ok(Shortcut::new(Some(modifiers), code))
 }
 
 /// Parse a key string to Code enum
@@ -607,9 +674,12 @@ pub fn recording_window_ready(app: AppHandle) -> Result<(), String> {
             }
             RecordingState::Processing | RecordingState::Idle => {
                 // User released the key before window was ready
-                // Reset state to idle (it's already Processing or Idle)
                 // Note: We don't emit stop event here - user already released, no recording happened
                 println!("[Shortcut] ⚠️  User released key too quickly (before window ready), window will auto-hide");
+                drop(state);
+
+                // Nothing was recorded, so the reserved transcription never starts
+                abandon_processing(&app);
             }
         }
     }
@@ -0,0 +1,131 @@
+/// 统一的语音识别引擎接口
+/// Whisper 和 FunASR 以相同的形式提供初始化、转录、能力查询和卸载，
+/// 命令层按 `AppConfig::model_type` 选择引擎，新增引擎无需再增加一套命令
+
+use std::future::Future;
+use tauri::AppHandle;
+
+use crate::config::ModelType;
+use crate::jobs::TranscriptionJob;
+use crate::whisper::TranscriptionSegment;
+
+/// 引擎能力（前端据此决定显示哪些选项）
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, serde::Serialize)]
+pub struct EngineCapabilities {
+    /// 段落时间戳
+    pub timestamps: bool,
+    /// 词级时间戳
+    pub word_timestamps: bool,
+    /// 翻译为英文
+    pub translation: bool,
+    /// 自动检测语言
+    pub language_detection: bool,
+    /// 用户词汇表（提示词 / 热词）
+    pub vocabulary: bool,
+}
+
+/// 转录请求
+#[derive(Clone, Copy)]
+pub struct SpeechRequest<'a> {
+    /// 16kHz 单声道音频
+    pub audio: &'a [f32],
+    /// 语言代码，`None` 表示自动检测
+    pub language: Option<&'a str>,
+    /// 输出英文译文（引擎不支持时忽略）
+    pub translate: bool,
+    /// 同时返回带时间戳的段落（引擎不支持时段落为空）
+    pub timestamps: bool,
+    /// 音频来自的文件（导入的音频），引擎可以直接读取时不再另写临时文件
+    pub source_file: Option<&'a str>,
+}
+
+/// 转录结果
+#[derive(Debug, Clone, Default)]
+pub struct SpeechOutput {
+    pub text: String,
+    /// 源语言（引擎能检测时）
+    pub source_language: Option<String>,
+    /// 文本是否为英文译文
+    pub translated: bool,
+    /// 带时间戳的段落（请求了时间戳或引擎顺带给出时）
+    pub segments: Vec<TranscriptionSegment>,
+    /// 说话人情感（SenseVoice）
+    pub emotion: Option<String>,
+    /// 音频事件，如笑声、掌声（SenseVoice）
+    pub events: Vec<String>,
+}
+
+/// 语音识别引擎
+///
+/// 实现者是 Tauri 托管的引擎状态，方法都以 `&self` 调用，内部自行加锁
+pub trait SpeechEngine: Send + Sync {
+    /// 引擎对应的模型类型
+    fn model_type(&self) -> ModelType;
+
+    /// 当前选择的模型
+    fn current_model(&self) -> impl Future<Output = Option<String>> + Send;
+
+    /// 加载模型（切换模型时重复调用）
+    fn init(&self, app: &AppHandle, model_name: &str) -> impl Future<Output = Result<(), String>> + Send;
+
+    /// 转录音频；取消和进度通过 `job` 传递
+    fn transcribe(
+        &self,
+        app: &AppHandle,
+        request: SpeechRequest<'_>,
+        job: &TranscriptionJob,
+    ) -> impl Future<Output = Result<SpeechOutput, String>> + Send;
+
+    fn capabilities(&self) -> EngineCapabilities;
+
+    /// 释放模型占用的内存，返回是否实际释放；下次转录时自动重新加载
+    fn unload(&self) -> impl Future<Output = Result<bool, String>> + Send;
+}
+
+/// 回退链：当前模型在前，随后是去重后的备用模型
+pub fn fallback_chain(primary: &str, fallbacks: &[String]) -> Vec<String> {
+    let mut chain = vec![primary.to_string()];
+    for model in fallbacks {
+        if !chain.contains(model) {
+            chain.push(model.clone());
+        }
+    }
+    chain
+}
+
+/// 回退链全部失败时的错误信息：只有当前模型时原样返回，否则按尝试顺序列出各模型的错误
+pub fn fallback_error(errors: &[(String, String)]) -> String {
+    match errors {
+        [] => "No speech engine available".to_string(),
+        [(_, error)] => error.clone(),
+        _ => errors
+            .iter()
+            .map(|(model, error)| format!("{}: {}", model, error))
+            .collect::<Vec<_>>()
+            .join("; "),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_fallback_chain_dedups() {
+        let fallbacks = vec!["small".to_string(), "paraformer-zh".to_string(), "small".to_string()];
+        assert_eq!(fallback_chain("paraformer-zh", &fallbacks), vec!["paraformer-zh", "small"]);
+    }
+
+    #[test]
+    fn test_fallback_error_keeps_primary_error() {
+        let errors = vec![("large-v3".to_string(), "model file corrupted".to_string())];
+        assert_eq!(fallback_error(&errors), "model file corrupted");
+
+        let errors = vec![
+            ("large-v3".to_string(), "model file corrupted".to_string()),
+            ("small".to_string(), "out of memory".to_string()),
+        ];
+        assert_eq!(fallback_error(&errors), "large-v3: model file corrupted; small: out of memory");
+        assert_eq!(fallback_error(&[]), "No speech engine available");
+    }
+}
@@ -0,0 +1,177 @@
+/// 自定义词汇表
+/// 将用户维护的产品名、人名、技术术语转换为 FunASR 热词和 Whisper 提示词
+
+use crate::db::{Database, VocabularyRepository};
+use std::collections::HashSet;
+use std::sync::Arc;
+use tauri::{AppHandle, Manager};
+
+/// Whisper initial prompt 的最大 token 数
+/// whisper.cpp 只保留 prompt 末尾 n_text_ctx/2 (224) 个 token，超出部分（连同开头的基础提示）会被丢弃
+const MAX_PROMPT_TOKENS: usize = 224;
+
+/// 单个词条的最大长度
+const MAX_TERM_CHARS: usize = 64;
+
+/// 从数据库加载词汇表（失败时返回空列表，不影响转录）
+pub fn load_terms(app: &AppHandle) -> Vec<String> {
+    use tracing::warn;
+
+    let Some(db) = app.try_state::<Arc<Database>>() else {
+        return Vec::new();
+    };
+
+    match VocabularyRepository::new(db.connection()).get_terms() {
+        Ok(terms) => terms,
+        Err(e) => {
+            warn!("⚠️  Failed to load vocabulary: {}", e);
+            Vec::new()
+        }
+    }
+}
+
+/// 解析词汇文本（每行一个词条）
+///
+/// - 忽略空行和以 `#` 开头的注释行
+/// - 去除首尾空白，过滤过长的词条
+/// - 按首次出现顺序去重
+pub fn parse_vocabulary_text(content: &str) -> Vec<String> {
+    let mut seen = HashSet::new();
+    let mut terms = Vec::new();
+
+    for line in content.lines() {
+        let term = line.trim().trim_start_matches('\u{feff}').trim();
+        if term.is_empty() || term.starts_with('#') || term.contains('\0') {
+            continue;
+        }
+        if term.chars().count() > MAX_TERM_CHARS {
+            continue;
+        }
+        if seen.insert(term.to_string()) {
+            terms.push(term.to_string());
+        }
+    }
+
+    terms
+}
+
+/// 生成 FunASR 热词参数（换行分隔，每行一个完整词条）
+///
+/// 多词术语内部含空格，不能用空格分隔；服务端把每行作为一个热词传给 FunASR
+pub fn funasr_hotwords(terms: &[String]) -> Option<String> {
+    if terms.is_empty() {
+        return None;
+    }
+
+    Some(terms.join("\n"))
+}
+
+/// 生成 Whisper initial prompt
+///
+/// 中文沿用 "以下是普通话的句子。" 的风格并附上词表，其他语言使用英文词表提示。
+/// `count_tokens` 返回文本在模型分词器下的 token 数；词表按顺序加入，
+/// 整个 prompt 超过 `MAX_PROMPT_TOKENS` 时停止，保证 prompt 不被 whisper.cpp 截断。
+pub fn whisper_prompt(
+    base: Option<&str>,
+    terms: &[String],
+    language: Option<&str>,
+    count_tokens: impl Fn(&str) -> usize,
+) -> Option<String> {
+    let is_chinese = language.map(|l| l.starts_with("zh")).unwrap_or(false);
+    let (prefix, separator, suffix) = if is_chinese {
+        ("可能出现的词语：", "、", "。")
+    } else {
+        ("Glossary: ", ", ", ".")
+    };
+
+    let assemble = |glossary: &str| {
+        let mut prompt = String::new();
+        if let Some(base) = base {
+            prompt.push_str(base);
+        }
+        prompt.push_str(prefix);
+        prompt.push_str(glossary);
+        prompt.push_str(suffix);
+        prompt
+    };
+
+    let mut glossary = String::new();
+    for term in terms {
+        let mut candidate = glossary.clone();
+        if !candidate.is_empty() {
+            candidate.push_str(separator);
+        }
+        candidate.push_str(term);
+        if count_tokens(&assemble(&candidate)) > MAX_PROMPT_TOKENS {
+            break;
+        }
+        glossary = candidate;
+    }
+
+    if glossary.is_empty() {
+        return base.map(|b| b.to_string());
+    }
+
+    Some(assemble(&glossary))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_vocabulary_text() {
+        let content = "\u{feff}聆码\n# comment\n\n  FunASR  \nKubernetes\n聆码\n";
+        let terms = parse_vocabulary_text(content);
+        assert_eq!(terms, vec!["聆码", "FunASR", "Kubernetes"]);
+    }
+
+    #[test]
+    fn test_funasr_hotwords() {
+        assert_eq!(funasr_hotwords(&[]), None);
+        let terms = vec!["聆码".to_string(), "达摩院".to_string()];
+        assert_eq!(funasr_hotwords(&terms), Some("聆码\n达摩院".to_string()));
+
+        // 多词术语保持完整
+        let terms = vec!["Visual Studio Code".to_string(), "聆码".to_string()];
+        assert_eq!(funasr_hotwords(&terms), Some("Visual Studio Code\n聆码".to_string()));
+    }
+
+    /// 测试用分词器：每个字符（空白除外）算一个 token
+    fn count_chars(text: &str) -> usize {
+        text.chars().filter(|c| !c.is_whitespace()).count()
+    }
+
+    #[test]
+    fn test_whisper_prompt() {
+        let terms = vec!["聆码".to_string(), "FunASR".to_string()];
+
+        assert_eq!(
+            whisper_prompt(Some("以下是普通话的句子。"), &[], Some("zh"), count_chars),
+            Some("以下是普通话的句子。".to_string())
+        );
+        assert_eq!(
+            whisper_prompt(Some("以下是普通话的句子。"), &terms, Some("zh"), count_chars),
+            Some("以下是普通话的句子。可能出现的词语：聆码、FunASR。".to_string())
+        );
+        assert_eq!(
+            whisper_prompt(None, &terms, None, count_chars),
+            Some("Glossary: 聆码, FunASR.".to_string())
+        );
+    }
+
+    #[test]
+    fn test_whisper_prompt_truncates() {
+        let terms: Vec<String> = (0..500).map(|i| format!("term{}", i)).collect();
+        let prompt = whisper_prompt(None, &terms, Some("en"), count_chars).unwrap();
+        assert!(count_chars(&prompt) <= MAX_PROMPT_TOKENS);
+        assert!(prompt.starts_with("Glossary: term0, term1"));
+        assert!(prompt.ends_with('.'));
+
+        // 基础提示计入预算，始终保留在开头
+        let base = "以下是普通话的句子。";
+        let prompt = whisper_prompt(Some(base), &terms, Some("zh"), count_chars).unwrap();
+        assert!(count_chars(&prompt) <= MAX_PROMPT_TOKENS);
+        assert!(prompt.starts_with(base));
+    }
+}
@@ -0,0 +1,369 @@
+/// Whisper 模型目录
+/// 集中维护可下载的 ggml 模型（多语言、英文专用、量化、turbo、distil），
+/// 供模型列表、下载和校验共用
+
+/// 模型下载镜像（国内可访问的 Hugging Face 镜像）
+const MIRROR_BASE: &str = "https://hf-mirror.com";
+
+/// whisper.cpp 官方 ggml 模型仓库
+const WHISPER_CPP_REPO: &str = "ggerganov/whisper.cpp";
+
+/// distil-whisper 的 ggml 模型仓库
+const DISTIL_LARGE_V3_REPO: &str = "distil-whisper/distil-large-v3-ggml";
+
+/// 目录中的单个 Whisper 模型
+#[derive(Debug, Clone, Copy)]
+pub struct WhisperModelSpec {
+    /// 模型名（同时决定本地文件名 `ggml-{name}.bin`）
+    pub name: &'static str,
+    /// Hugging Face 仓库
+    pub repo: &'static str,
+    /// 文件大小（字节）
+    pub size_bytes: u64,
+    /// 文件 SHA-256（来自 Hugging Face LFS 元数据；为 None 时下载前从 LFS 元数据获取）
+    pub sha256: Option<&'static str>,
+    /// 推理时大致需要的内存（MB）
+    pub ram_mb: u64,
+    pub speed: &'static str,
+    pub accuracy: &'static str,
+    pub description: &'static str,
+    /// 是否为英文专用模型（`.en`）
+    pub english_only: bool,
+    pub is_recommended: bool,
+}
+
+impl WhisperModelSpec {
+    /// 本地文件名
+    pub fn file_name(&self) -> String {
+        format!("ggml-{}.bin", self.name)
+    }
+
+    /// 下载地址
+    pub fn download_url(&self) -> String {
+        format!("{}/{}/resolve/main/{}", MIRROR_BASE, self.repo, self.file_name())
+    }
+
+    /// 人类可读的文件大小（如 "142MB"、"1.5GB"）
+    pub fn size_label(&self) -> String {
+        format_size(self.size_bytes)
+    }
+}
+
+/// 所有可下载的 Whisper 模型
+pub const WHISPER_MODELS: &[WhisperModelSpec] = &[
+    WhisperModelSpec {
+        name: "tiny",
+        repo: WHISPER_CPP_REPO,
+        size_bytes: 77_691_713,
+        sha256: Some("be07e048e1e599ad46341c8d2a135645097a538221678b7acdd1b1919c6e1b21"),
+        ram_mb: 273,
+        speed: "极快",
+        accuracy: "较低精度",
+        description: "Whisper 微型模型，适合低配置设备",
+        english_only: false,
+        is_recommended: false,
+    },
+    WhisperModelSpec {
+        name: "tiny.en",
+        repo: WHISPER_CPP_REPO,
+        size_bytes: 77_704_715,
+        sha256: None,
+        ram_mb: 273,
+        speed: "极快",
+        accuracy: "较低精度（英文）",
+        description: "Whisper 微型英文模型",
+        english_only: true,
+        is_recommended: false,
+    },
+    WhisperModelSpec {
+        name: "base",
+        repo: WHISPER_CPP_REPO,
+        size_bytes: 147_951_465,
+        sha256: Some("60ed5bc3dd14eea856493d334349b405782ddcaf0028d4b5df4088345fba2efe"),
+        ram_mb: 388,
+        speed: "快速",
+        accuracy: "一般精度",
+        description: "Whisper 基础模型，支持多语言",
+        english_only: false,
+        is_recommended: false,
+    },
+    WhisperModelSpec {
+        name: "base.en",
+        repo: WHISPER_CPP_REPO,
+        size_bytes: 147_964_211,
+        sha256: None,
+        ram_mb: 388,
+        speed: "快速",
+        accuracy: "一般精度（英文）",
+        description: "Whisper 基础英文模型，英文效果优于同尺寸多语言模型",
+        english_only: true,
+        is_recommended: false,
+    },
+    WhisperModelSpec {
+        name: "base-q5_1",
+        repo: WHISPER_CPP_REPO,
+        size_bytes: 59_707_625,
+        sha256: None,
+        ram_mb: 200,
+        speed: "快速",
+        accuracy: "一般精度",
+        description: "Whisper 基础模型 5-bit 量化版，体积更小",
+        english_only: false,
+        is_recommended: false,
+    },
+    WhisperModelSpec {
+        name: "small",
+        repo: WHISPER_CPP_REPO,
+        size_bytes: 487_601_967,
+        sha256: Some("1be3a9b2063867b937e64e2ec7483364a79917e157fa98c5d94b5c1fffea987b"),
+        ram_mb: 852,
+        speed: "较快",
+        accuracy: "较高精度",
+        description: "Whisper 小型模型，平衡速度和精度",
+        english_only: false,
+        is_recommended: false,
+    },
+    WhisperModelSpec {
+        name: "small.en",
+        repo: WHISPER_CPP_REPO,
+        size_bytes: 487_614_201,
+        sha256: None,
+        ram_mb: 852,
+        speed: "较快",
+        accuracy: "较高精度（英文）",
+        description: "Whisper 小型英文模型",
+        english_only: true,
+        is_recommended: false,
+    },
+    WhisperModelSpec {
+        name: "small-q5_1",
+        repo: WHISPER_CPP_REPO,
+        size_bytes: 190_085_487,
+        sha256: None,
+        ram_mb: 450,
+        speed: "较快",
+        accuracy: "较高精度",
+        description: "Whisper 小型模型 5-bit 量化版，适合 8GB 内存设备",
+        english_only: false,
+        is_recommended: false,
+    },
+    WhisperModelSpec {
+        name: "medium",
+        repo: WHISPER_CPP_REPO,
+        size_bytes: 1_533_763_059,
+        sha256: Some("6c14d5adee5f86394037b4e4e8b59f1673b6cee10e3cf0b11bbdbee79c156208"),
+        ram_mb: 2100,
+        speed: "较慢",
+        accuracy: "高精度",
+        description: "Whisper 中型模型，高精度",
+        english_only: false,
+        is_recommended: false,
+    },
+    WhisperModelSpec {
+        name: "medium.en",
+        repo: WHISPER_CPP_REPO,
+        size_bytes: 1_533_774_781,
+        sha256: None,
+        ram_mb: 2100,
+        speed: "较慢",
+        accuracy: "高精度（英文）",
+        description: "Whisper 中型英文模型",
+        english_only: true,
+        is_recommended: false,
+    },
+    WhisperModelSpec {
+        name: "medium-q5_0",
+        repo: WHISPER_CPP_REPO,
+        size_bytes: 539_212_467,
+        sha256: None,
+        ram_mb: 1000,
+        speed: "较慢",
+        accuracy: "高精度",
+        description: "Whisper 中型模型 5-bit 量化版，内存占用约为原版一半",
+        english_only: false,
+        is_recommended: false,
+    },
+    WhisperModelSpec {
+        name: "large-v2",
+        repo: WHISPER_CPP_REPO,
+        size_bytes: 3_094_623_691,
+        sha256: None,
+        ram_mb: 3900,
+        speed: "慢",
+        accuracy: "最高精度",
+        description: "Whisper large-v2 模型",
+        english_only: false,
+        is_recommended: false,
+    },
+    WhisperModelSpec {
+        name: "large-v3",
+        repo: WHISPER_CPP_REPO,
+        size_bytes: 3_095_033_483,
+        sha256: Some("64d182b440b98d5203c4f9bd541544d84c605196c4f7b845dfa11fb23594d1e2"),
+        ram_mb: 3900,
+        speed: "慢",
+        accuracy: "最高精度",
+        description: "Whisper large-v3 模型，最高精度",
+        english_only: false,
+        is_recommended: false,
+    },
+    WhisperModelSpec {
+        name: "large-v3-q5_0",
+        repo: WHISPER_CPP_REPO,
+        size_bytes: 1_081_140_203,
+        sha256: None,
+        ram_mb: 1700,
+        speed: "慢",
+        accuracy: "最高精度",
+        description: "Whisper large-v3 5-bit 量化版",
+        english_only: false,
+        is_recommended: false,
+    },
+    WhisperModelSpec {
+        name: "large-v3-turbo",
+        repo: WHISPER_CPP_REPO,
+        size_bytes: 1_624_555_275,
+        sha256: Some("1fc70f774d38eb169993ac391eea357ef47c88757ef72ee5943879b7e8e2bc69"),
+        ram_mb: 1800,
+        speed: "较快",
+        accuracy: "高精度",
+        description: "Whisper large-v3-turbo，精度接近 large-v3，速度快数倍",
+        english_only: false,
+        is_recommended: true,
+    },
+    WhisperModelSpec {
+        name: "large-v3-turbo-q5_0",
+        repo: WHISPER_CPP_REPO,
+        size_bytes: 574_041_195,
+        sha256: None,
+        ram_mb: 900,
+        speed: "较快",
+        accuracy: "高精度",
+        description: "large-v3-turbo 5-bit 量化版，适合 8GB 内存设备",
+        english_only: false,
+        is_recommended: false,
+    },
+    WhisperModelSpec {
+        name: "large-v3-turbo-q8_0",
+        repo: WHISPER_CPP_REPO,
+        size_bytes: 874_188_075,
+        sha256: None,
+        ram_mb: 1200,
+        speed: "较快",
+        accuracy: "高精度",
+        description: "large-v3-turbo 8-bit 量化版，精度损失极小",
+        english_only: false,
+        is_recommended: false,
+    },
+    WhisperModelSpec {
+        name: "distil-large-v3",
+        repo: DISTIL_LARGE_V3_REPO,
+        size_bytes: 1_519_521_155,
+        sha256: None,
+        ram_mb: 1800,
+        speed: "较快",
+        accuracy: "高精度（英文）",
+        description: "distil-whisper large-v3 蒸馏模型，英文速度和精度俱佳",
+        english_only: true,
+        is_recommended: false,
+    },
+];
+
+/// 按名称查找 Whisper 模型
+pub fn find_whisper_model(name: &str) -> Option<&'static WhisperModelSpec> {
+    WHISPER_MODELS.iter().find(|m| m.name == name)
+}
+
+/// 已从目录移除的旧模型名 -> 可能对应的目录模型（第一个为无法按大小判断时的默认值）
+///
+/// 旧版本的 `large` 指向 whisper.cpp 仓库当时的 `ggml-large.bin`，实际是 large-v2 或 large-v3
+const LEGACY_ALIASES: &[(&str, &[&str])] = &[("large", &["large-v3", "large-v2"])];
+
+/// 旧模型名对应的目录模型
+///
+/// `file_size` 为本地旧文件的大小，与候选模型的文件大小一致时选中该模型
+pub fn legacy_replacement(name: &str, file_size: Option<u64>) -> Option<&'static WhisperModelSpec> {
+    let (_, candidates) = LEGACY_ALIASES.iter().find(|(legacy, _)| *legacy == name)?;
+    let specs = candidates.iter().filter_map(|candidate| find_whisper_model(candidate));
+
+    file_size
+        .and_then(|size| specs.clone().find(|spec| spec.size_bytes == size))
+        .or_else(|| specs.clone().next())
+}
+
+/// 所有已移除的旧模型名
+pub fn legacy_model_names() -> impl Iterator<Item = &'static str> {
+    LEGACY_ALIASES.iter().map(|(name, _)| *name)
+}
+
+/// 格式化文件大小
+fn format_size(bytes: u64) -> String {
+    const MB: u64 = 1024 * 1024;
+    const GB: u64 = 1024 * MB;
+
+    if bytes >= GB {
+        format!("{:.1}GB", bytes as f64 / GB as f64)
+    } else {
+        format!("{}MB", (bytes + MB / 2) / MB)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::collections::HashSet;
+
+    #[test]
+    fn test_catalog_names_are_unique() {
+        let names: HashSet<&str> = WHISPER_MODELS.iter().map(|m| m.name).collect();
+        assert_eq!(names.len(), WHISPER_MODELS.len());
+    }
+
+    #[test]
+    fn test_catalog_entries_are_well_formed() {
+        for model in WHISPER_MODELS {
+            assert!(model.size_bytes > 0, "{} has no size", model.name);
+            assert!(model.ram_mb > 0, "{} has no RAM requirement", model.name);
+            if let Some(sha256) = model.sha256 {
+                assert_eq!(sha256.len(), 64, "{} has malformed sha256", model.name);
+                assert!(sha256.chars().all(|c| c.is_ascii_hexdigit()));
+            }
+            assert_eq!(model.english_only, model.name.ends_with(".en") || model.name.starts_with("distil-"));
+        }
+    }
+
+    #[test]
+    fn test_download_url() {
+        let turbo = find_whisper_model("large-v3-turbo-q5_0").unwrap();
+        assert_eq!(
+            turbo.download_url(),
+            "https://hf-mirror.com/ggerganov/whisper.cpp/resolve/main/ggml-large-v3-turbo-q5_0.bin"
+        );
+
+        let distil = find_whisper_model("distil-large-v3").unwrap();
+        assert_eq!(
+            distil.download_url(),
+            "https://hf-mirror.com/distil-whisper/distil-large-v3-ggml/resolve/main/ggml-distil-large-v3.bin"
+        );
+
+        assert!(find_whisper_model("large").is_none());
+    }
+
+    #[test]
+    fn test_legacy_replacement() {
+        assert_eq!(legacy_replacement("large", None).unwrap().name, "large-v3");
+        assert_eq!(legacy_replacement("large", Some(3_094_623_691)).unwrap().name, "large-v2");
+        assert_eq!(legacy_replacement("large", Some(1)).unwrap().name, "large-v3");
+        assert!(legacy_replacement("base", None).is_none());
+
+        for name in legacy_model_names() {
+            assert!(find_whisper_model(name).is_none(), "{} is still in the catalog", name);
+        }
+    }
+
+    #[test]
+    fn test_size_label() {
+        assert_eq!(find_whisper_model("base").unwrap().size_label(), "141MB");
+        assert_eq!(find_whisper_model("large-v3").unwrap().size_label(), "2.9GB");
+    }
+}
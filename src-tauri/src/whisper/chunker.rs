@@ -0,0 +1,400 @@
+/// 长音频分块
+/// 优先在 VAD 检测到的停顿处切分，没有合适停顿时按固定窗口切分并保留重叠；
+/// 各块解码后合并段落、去除重叠部分的重复内容并修正时间戳
+
+use super::engine::TranscriptionSegment;
+use super::vad::SpeechRegion;
+use super::words::WordTiming;
+
+/// 每毫秒的采样数（16kHz）
+const SAMPLES_PER_MS: usize = 16;
+
+/// 重复段落与上一段之间允许的最大间隔
+const DUPLICATE_MAX_GAP_MS: u64 = 1_000;
+
+/// 文本相似度达到该值时视为同一句话的重复识别
+const DUPLICATE_SIMILARITY: f32 = 0.8;
+
+/// 分块参数
+#[derive(Debug, Clone)]
+pub struct ChunkConfig {
+    /// 理想块长度
+    pub target_ms: u64,
+    /// 在停顿处切分时允许的最短块长度
+    pub min_ms: u64,
+    /// 块的最大长度（Whisper 单窗口为 30 秒）
+    pub max_ms: u64,
+    /// 固定窗口切分时相邻块的重叠长度
+    pub overlap_ms: u64,
+}
+
+impl Default for ChunkConfig {
+    fn default() -> Self {
+        Self {
+            target_ms: 28_000,
+            min_ms: 15_000,
+            max_ms: 30_000,
+            overlap_ms: 2_000,
+        }
+    }
+}
+
+/// 音频块（毫秒）
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct AudioChunk {
+    pub start_ms: u64,
+    pub end_ms: u64,
+}
+
+impl AudioChunk {
+    /// 块对应的采样区间
+    pub fn sample_range(&self, total_samples: usize) -> std::ops::Range<usize> {
+        let start = (self.start_ms as usize * SAMPLES_PER_MS).min(total_samples);
+        let end = (self.end_ms as usize * SAMPLES_PER_MS).min(total_samples);
+        start..end
+    }
+}
+
+/// 规划分块
+///
+/// # 参数
+/// * `duration_ms` - 音频总时长
+/// * `regions` - VAD 语音区间（用于寻找停顿）
+pub fn plan_chunks(duration_ms: u64, regions: &[SpeechRegion], config: &ChunkConfig) -> Vec<AudioChunk> {
+    let mut chunks = Vec::new();
+    let mut start = 0u64;
+
+    while start < duration_ms {
+        if duration_ms - start <= config.max_ms {
+            chunks.push(AudioChunk { start_ms: start, end_ms: duration_ms });
+            break;
+        }
+
+        match find_pause(regions, start + config.min_ms, start + config.max_ms, start + config.target_ms) {
+            // 在停顿中点切分，静音处不需要重叠
+            Some(cut) => {
+                chunks.push(AudioChunk { start_ms: start, end_ms: cut });
+                start = cut;
+            }
+            // 没有停顿（连续说话或音乐）：固定窗口 + 重叠
+            None => {
+                let end = start + config.target_ms;
+                chunks.push(AudioChunk { start_ms: start, end_ms: end });
+                start = end - config.overlap_ms;
+            }
+        }
+    }
+
+    chunks
+}
+
+/// 在 [from, to] 内寻找离 `target` 最近的停顿中点
+fn find_pause(regions: &[SpeechRegion], from: u64, to: u64, target: u64) -> Option<u64> {
+    // 相邻语音区间之间的空隙即为停顿（VAD 已合并了过短的空隙）
+    let gaps = regions.windows(2).map(|pair| (pair[0].end_ms, pair[1].start_ms));
+    let trailing = regions.last().map(|last| (last.end_ms, u64::MAX));
+
+    gaps.chain(trailing)
+        .map(|(gap_start, gap_end)| {
+            let gap_end = gap_end.min(to);
+            gap_start + gap_end.saturating_sub(gap_start) / 2
+        })
+        .filter(|&mid| mid >= from && mid <= to)
+        .min_by_key(|&mid| mid.abs_diff(target))
+}
+
+/// 合并各块的段落
+///
+/// `results[i]` 为 `chunks[i]` 的解码结果，时间戳相对于块起点。
+/// 重叠区间以中点为界，前一块保留中点之前的段落，后一块保留中点之后的段落；
+/// 块边界两侧对同一句话的重复识别（时间相邻，文本相近或一方包含另一方）只保留较完整的一次，
+/// 并保证时间戳单调递增。
+pub fn merge_chunk_segments(chunks: &[AudioChunk], results: Vec<Vec<TranscriptionSegment>>) -> Vec<TranscriptionSegment> {
+    let mut merged: Vec<TranscriptionSegment> = Vec::new();
+    // 最后一个已合并段落所属的块
+    let mut last_chunk = None;
+
+    for (i, (chunk, segments)) in chunks.iter().zip(results).enumerate() {
+        let lower = match i.checked_sub(1).map(|prev| chunks[prev]) {
+            Some(prev) if prev.end_ms > chunk.start_ms => (chunk.start_ms + prev.end_ms) / 2,
+            _ => 0,
+        };
+        let upper = match chunks.get(i + 1) {
+            Some(next) if chunk.end_ms > next.start_ms => (next.start_ms + chunk.end_ms) / 2,
+            _ => u64::MAX,
+        };
+
+        for segment in segments {
+            let text = segment.text.trim();
+            if text.is_empty() {
+                continue;
+            }
+
+            // 转为绝对时间，并限制在块范围内（防止时间戳漂移到块外）
+            let mut start_ms = (chunk.start_ms + segment.start_ms).min(chunk.end_ms);
+            let mut end_ms = (chunk.start_ms + segment.end_ms).min(chunk.end_ms);
+
+            let mid = (start_ms + end_ms) / 2;
+            if mid < lower || mid >= upper {
+                continue;
+            }
+
+            if let Some(last) = merged.last_mut() {
+                // 块边界附近重复识别出的同一句话（边界处常只识别出半句或略有差异）
+                if last_chunk != Some(i) && is_duplicate(last, text, start_ms) {
+                    if normalized_len(text) > normalized_len(&last.text) {
+                        // 保留更完整的一次，词级时间同样取这一次的结果
+                        let (start, end) = (last.start_ms.min(start_ms), last.end_ms.max(end_ms));
+                        last.text = text.to_string();
+                        last.words = absolute_words(segment.words, chunk.start_ms, start, end);
+                        last.start_ms = start;
+                        last.end_ms = end;
+                    } else {
+                        last.end_ms = last.end_ms.max(end_ms);
+                    }
+                    continue;
+                }
+                start_ms = start_ms.max(last.end_ms);
+            }
+            end_ms = end_ms.max(start_ms);
+
+            let words = absolute_words(segment.words, chunk.start_ms, start_ms, end_ms);
+
+            merged.push(TranscriptionSegment {
+                text: text.to_string(),
+                start_ms,
+                end_ms,
+                words,
+                speaker: None,
+            });
+            last_chunk = Some(i);
+        }
+    }
+
+    merged
+}
+
+/// 词级时间转为绝对时间，并限制在段落范围内
+fn absolute_words(words: Vec<WordTiming>, offset_ms: u64, start_ms: u64, end_ms: u64) -> Vec<WordTiming> {
+    words
+        .into_iter()
+        .map(|mut word| {
+            word.start_ms = (offset_ms + word.start_ms).clamp(start_ms, end_ms);
+            word.end_ms = (offset_ms + word.end_ms).clamp(word.start_ms, end_ms);
+            word
+        })
+        .collect()
+}
+
+/// 新段落是否为上一段的重复识别：时间上紧邻或重叠，
+/// 且文本高度相似（标点、个别字不同）或一方包含另一方（块边界截断的半句）
+fn is_duplicate(last: &TranscriptionSegment, text: &str, start_ms: u64) -> bool {
+    if start_ms > last.end_ms + DUPLICATE_MAX_GAP_MS {
+        return false;
+    }
+
+    let previous = normalize_text(&last.text);
+    let current = normalize_text(text);
+    if previous.is_empty() || current.is_empty() {
+        return false;
+    }
+
+    text_similarity(&previous, &current) >= DUPLICATE_SIMILARITY
+        || contains(&previous, &current)
+        || contains(&current, &previous)
+}
+
+/// 去掉标点、空白并统一大小写后的字符序列
+fn normalize_text(text: &str) -> Vec<char> {
+    text.chars()
+        .filter(|c| c.is_alphanumeric())
+        .flat_map(|c| c.to_lowercase())
+        .collect()
+}
+
+fn normalized_len(text: &str) -> usize {
+    normalize_text(text).len()
+}
+
+/// `haystack` 是否包含连续的 `needle`
+fn contains(haystack: &[char], needle: &[char]) -> bool {
+    needle.len() <= haystack.len() && haystack.windows(needle.len()).any(|window| window == needle)
+}
+
+/// 基于编辑距离的相似度（1.0 表示相同）
+fn text_similarity(a: &[char], b: &[char]) -> f32 {
+    let longest = a.len().max(b.len());
+    if longest == 0 {
+        return 1.0;
+    }
+
+    let mut previous: Vec<usize> = (0..=b.len()).collect();
+    let mut current = vec![0; b.len() + 1];
+    for (i, ca) in a.iter().enumerate() {
+        current[0] = i + 1;
+        for (j, cb) in b.iter().enumerate() {
+            let substitution = previous[j] + usize::from(ca != cb);
+            current[j + 1] = substitution.min(previous[j + 1] + 1).min(current[j] + 1);
+        }
+        std::mem::swap(&mut previous, &mut current);
+    }
+
+    1.0 - previous[b.len()] as f32 / longest as f32
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn segment(text: &str, start_ms: u64, end_ms: u64) -> TranscriptionSegment {
+        TranscriptionSegment {
+            text: text.to_string(),
+            start_ms,
+            end_ms,
+            words: Vec::new(),
+            speaker: None,
+        }
+    }
+
+    #[test]
+    fn test_short_audio_is_single_chunk() {
+        let chunks = plan_chunks(20_000, &[], &ChunkConfig::default());
+        assert_eq!(chunks, vec![AudioChunk { start_ms: 0, end_ms: 20_000 }]);
+    }
+
+    #[test]
+    fn test_splits_at_pauses() {
+        // 每 10 秒一句话，句间停顿 1 秒
+        let regions: Vec<SpeechRegion> = (0..9)
+            .map(|i| SpeechRegion { start_ms: i * 10_000, end_ms: i * 10_000 + 9_000 })
+            .collect();
+        let chunks = plan_chunks(90_000, &regions, &ChunkConfig::default());
+
+        assert_eq!(chunks[0], AudioChunk { start_ms: 0, end_ms: 29_500 });
+        assert_eq!(chunks[1].start_ms, 29_500);
+        // 停顿处切分，块之间无重叠
+        for pair in chunks.windows(2) {
+            assert_eq!(pair[0].end_ms, pair[1].start_ms);
+        }
+        assert_eq!(chunks.last().unwrap().end_ms, 90_000);
+    }
+
+    #[test]
+    fn test_fixed_windows_overlap_without_pauses() {
+        let regions = vec![SpeechRegion { start_ms: 0, end_ms: 70_000 }];
+        let config = ChunkConfig::default();
+        let chunks = plan_chunks(70_000, &regions, &config);
+
+        assert_eq!(chunks[0], AudioChunk { start_ms: 0, end_ms: 28_000 });
+        assert_eq!(chunks[1], AudioChunk { start_ms: 26_000, end_ms: 54_000 });
+        assert_eq!(chunks[2], AudioChunk { start_ms: 52_000, end_ms: 70_000 });
+    }
+
+    #[test]
+    fn test_merge_offsets_timestamps() {
+        let chunks = vec![
+            AudioChunk { start_ms: 0, end_ms: 29_500 },
+            AudioChunk { start_ms: 29_500, end_ms: 50_000 },
+        ];
+        let merged = merge_chunk_segments(
+            &chunks,
+            vec![
+                vec![segment("第一句", 0, 9_000), segment("第二句", 10_000, 19_000)],
+                vec![segment("第三句", 500, 9_500)],
+            ],
+        );
+
+        assert_eq!(merged.len(), 3);
+        assert_eq!(merged[2].text, "第三句");
+        assert_eq!((merged[2].start_ms, merged[2].end_ms), (30_000, 39_000));
+    }
+
+    #[test]
+    fn test_merge_deduplicates_overlap() {
+        let chunks = vec![
+            AudioChunk { start_ms: 0, end_ms: 28_000 },
+            AudioChunk { start_ms: 26_000, end_ms: 54_000 },
+        ];
+        let merged = merge_chunk_segments(
+            &chunks,
+            vec![
+                // 最后一句落在重叠区前半部分
+                vec![segment("前面的内容", 0, 20_000), segment("重叠的一句话", 24_000, 26_800)],
+                // 后一块在开头又识别了一次同一句话
+                vec![segment("重叠的一句话", 0, 900), segment("后面的内容", 1_200, 10_000)],
+            ],
+        );
+
+        let texts: Vec<&str> = merged.iter().map(|s| s.text.as_str()).collect();
+        assert_eq!(texts, vec!["前面的内容", "重叠的一句话", "后面的内容"]);
+
+        for pair in merged.windows(2) {
+            assert!(pair[0].end_ms <= pair[1].start_ms);
+        }
+    }
+
+    #[test]
+    fn test_merge_deduplicates_near_duplicates() {
+        let chunks = vec![
+            AudioChunk { start_ms: 0, end_ms: 28_000 },
+            AudioChunk { start_ms: 26_000, end_ms: 54_000 },
+        ];
+        let merged = merge_chunk_segments(
+            &chunks,
+            vec![
+                vec![segment("前面的内容", 0, 20_000), segment("我们明天下午开会。", 24_000, 26_900)],
+                // 后一块识别结果标点和个别字不同
+                vec![segment("我们明天下午开会", 0, 950), segment("后面的内容", 1_200, 10_000)],
+            ],
+        );
+
+        let texts: Vec<&str> = merged.iter().map(|s| s.text.as_str()).collect();
+        assert_eq!(texts, vec!["前面的内容", "我们明天下午开会。", "后面的内容"]);
+    }
+
+    #[test]
+    fn test_merge_keeps_complete_version_of_cut_sentence() {
+        let chunks = vec![
+            AudioChunk { start_ms: 0, end_ms: 28_000 },
+            AudioChunk { start_ms: 26_000, end_ms: 54_000 },
+        ];
+        let merged = merge_chunk_segments(
+            &chunks,
+            vec![
+                // 前一块在边界处只识别出半句
+                vec![segment("前面的内容", 0, 20_000), segment("这个方案", 25_000, 26_500)],
+                vec![segment("这个方案需要再讨论一下", 0, 2_000), segment("后面的内容", 2_200, 10_000)],
+            ],
+        );
+
+        let texts: Vec<&str> = merged.iter().map(|s| s.text.as_str()).collect();
+        assert_eq!(texts, vec!["前面的内容", "这个方案需要再讨论一下", "后面的内容"]);
+        assert_eq!((merged[1].start_ms, merged[1].end_ms), (25_000, 28_000));
+    }
+
+    #[test]
+    fn test_distinct_adjacent_segments_are_kept() {
+        let last = segment("今天天气不错", 0, 2_000);
+        assert!(!is_duplicate(&last, "我们出去走走", 2_100));
+        assert!(!is_duplicate(&last, "今天天气不错", 5_000));
+        assert!(is_duplicate(&last, "今天天气不错！", 2_100));
+        assert!(is_duplicate(&last, "天气不错", 1_500));
+    }
+
+    #[test]
+    fn test_repeats_within_a_chunk_are_kept() {
+        let chunks = vec![AudioChunk { start_ms: 0, end_ms: 20_000 }];
+        let merged = merge_chunk_segments(
+            &chunks,
+            vec![vec![segment("好的", 0, 1_000), segment("好的", 1_200, 2_000)]],
+        );
+        assert_eq!(merged.len(), 2);
+    }
+
+    #[test]
+    fn test_sample_range() {
+        let chunk = AudioChunk { start_ms: 1_000, end_ms: 2_000 };
+        assert_eq!(chunk.sample_range(100_000), 16_000..32_000);
+        assert_eq!(chunk.sample_range(20_000), 16_000..20_000);
+    }
+}
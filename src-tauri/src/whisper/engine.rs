@@ -4,9 +4,32 @@
 use std::error::Error;
 use std::fmt;
 use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Arc;
 use whisper_rs::{FullParams, SamplingStrategy, WhisperContext, WhisperContextParameters};
 
+use super::chunker::{merge_chunk_segments, plan_chunks, ChunkConfig};
+use super::hallucination::{HallucinationFilter, SegmentSignals};
 use super::preprocessor::{validate_audio_data, PreprocessError};
+use super::progress::{ChunkScope, ProgressCallback, ProgressTracker};
+use super::vad::{detect_speech, speech_coverage};
+use super::words::{group_words, TimedToken, WordTiming};
+use crate::vocabulary::whisper_prompt;
+
+/// 单次解码的最大时长（超过后 `transcribe` 改走分块长音频路径）
+const MAX_SINGLE_PASS_SECS: f32 = 600.0;
+
+/// 长音频转录的最大时长（3 小时）
+const MAX_LONG_FORM_MS: u64 = 3 * 60 * 60 * 1000;
+
+/// 长音频并行解码的最大工作线程数（每个线程持有一份解码缓冲区）
+const MAX_LONG_FORM_WORKERS: usize = 3;
+
+/// Beam search 的候选数（基准测试随结果记录）
+pub const BEAM_SIZE: i32 = 5;
+
+/// 计算 initial prompt 长度时的分词上限（远大于 prompt 预算，超出即视为过长）
+const TOKENIZE_LIMIT: usize = 1024;
 
 #[derive(Debug)]
 pub enum WhisperError {
@@ -16,6 +39,8 @@ pub enum WhisperError {
     PreprocessError(PreprocessError),
     AudioTooShort,
     AudioTooLong,
+    /// 解码被用户取消
+    Cancelled,
 }
 
 impl fmt::Display for WhisperError {
@@ -37,7 +62,10 @@ impl fmt::Display for WhisperError {
                 write!(f, "Audio too short (minimum 0.1 seconds)")
             }
             WhisperError::AudioTooLong => {
-                write!(f, "Audio too long (maximum 10 minutes)")
+                write!(f, "Audio too long (maximum 3 hours)")
+            }
+            WhisperError::Cancelled => {
+                write!(f, "{}", crate::jobs::CANCELLED_MESSAGE)
             }
         }
     }
@@ -51,11 +79,53 @@ impl From<PreprocessError> for WhisperError {
     }
 }
 
+/// Whisper 解码任务
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum WhisperTask {
+    /// 按原语言转写
+    Transcribe,
+    /// 使用 Whisper 内置的翻译任务输出英文
+    Translate,
+}
+
+/// 转录输出
+#[derive(Debug, Clone)]
+pub struct WhisperOutput {
+    pub text: String,
+    /// Whisper 识别出的源语言（如 "zh"）
+    pub language: Option<String>,
+    pub translated: bool,
+}
+
+/// 长音频转录输出
+#[derive(Debug, Clone)]
+pub struct LongFormOutput {
+    pub segments: Vec<TranscriptionSegment>,
+    /// 识别出的源语言（自动检测时来自第一块）
+    pub language: Option<String>,
+}
+
+impl LongFormOutput {
+    /// 各段拼接成的全文
+    pub fn text(&self) -> String {
+        let texts: Vec<&str> = self.segments.iter().map(|segment| segment.text.as_str()).collect();
+        join_segment_texts(&texts)
+    }
+}
+
 /// Whisper 转录引擎
 pub struct WhisperEngine {
     context: WhisperContext,
     model_path: PathBuf,
     n_threads: usize,
+    /// 用户词汇表，作为 initial prompt 偏置解码结果
+    vocabulary: Vec<String>,
+    /// 幻觉过滤阈值
+    hallucination_filter: HallucinationFilter,
+    /// 当前任务的取消标志，置位后 whisper.cpp 在下一次检查时中止解码
+    cancel: Option<Arc<AtomicBool>>,
+    /// 当前任务的进度回调
+    progress: Option<ProgressCallback>,
 }
 
 impl WhisperEngine {
@@ -92,6 +162,10 @@ impl WhisperEngine {
             context,
             model_path,
             n_threads,
+            vocabulary: Vec::new(),
+            hallucination_filter: HallucinationFilter::default(),
+            cancel: None,
+            progress: None,
         })
     }
 
@@ -105,19 +179,103 @@ impl WhisperEngine {
         &self.model_path
     }
 
+    /// 推理使用的线程数
+    pub fn n_threads(&self) -> usize {
+        self.n_threads
+    }
+
+    /// 设置用户词汇表（产品名、人名、术语），用于偏置识别结果
+    pub fn set_vocabulary(&mut self, terms: Vec<String>) {
+        self.vocabulary = terms;
+    }
+
+    /// 基础提示加用户词汇表组成的 initial prompt，按模型分词器的 token 数截断
+    fn initial_prompt(&self, base: Option<&str>, language: Option<&str>) -> Option<String> {
+        whisper_prompt(base, &self.vocabulary, language, |text| {
+            self.context
+                .tokenize(text, TOKENIZE_LIMIT)
+                .map(|tokens| tokens.len())
+                .unwrap_or(usize::MAX)
+        })
+    }
+
+    /// 设置当前任务的取消标志（`None` 表示不可取消）
+    pub fn set_cancel_flag(&mut self, cancel: Option<Arc<AtomicBool>>) {
+        self.cancel = cancel;
+    }
+
+    fn is_cancelled(&self) -> bool {
+        self.cancel.as_ref().is_some_and(|cancel| cancel.load(Ordering::SeqCst))
+    }
+
+    /// 设置当前任务的进度回调（`None` 表示不上报进度）
+    pub fn set_progress_callback(&mut self, progress: Option<ProgressCallback>) {
+        self.progress = progress;
+    }
+
+    /// 为一次转录创建进度汇总（`n_chunks` 为并行解码的块数）
+    fn progress_tracker(&self, n_chunks: usize) -> Option<std::sync::Arc<ProgressTracker>> {
+        self.progress
+            .clone()
+            .map(|callback| ProgressTracker::new(callback, n_chunks))
+    }
+
+    /// 注册 whisper.cpp 回调：中止回调轮询取消标志，进度回调上报进度
+    ///
+    /// 不注册新段落回调：段落要经过幻觉过滤后才上报（见 `collect_segments`）
+    fn install_callbacks(&self, params: &mut FullParams, scope: Option<&ChunkScope>) {
+        if let Some(cancel) = self.cancel.clone() {
+            params.set_abort_callback_safe(move || cancel.load(Ordering::SeqCst));
+        }
+
+        if let Some(scope) = scope {
+            let progress_scope = scope.clone();
+            params.set_progress_callback_safe(move |percent| progress_scope.report_percent(percent));
+        }
+    }
+
+    /// 执行解码，被取消时返回 `WhisperError::Cancelled`
+    fn run_full(
+        &self,
+        state: &mut whisper_rs::WhisperState,
+        params: FullParams,
+        audio_data: &[f32],
+        scope: Option<&ChunkScope>,
+    ) -> Result<(), WhisperError> {
+        if self.is_cancelled() {
+            return Err(WhisperError::Cancelled);
+        }
+
+        let result = state.full(params, audio_data);
+
+        // 中止后 whisper.cpp 返回错误码，也可能在最后一个窗口结束后才生效
+        if self.is_cancelled() {
+            return Err(WhisperError::Cancelled);
+        }
+        result.map_err(|e| WhisperError::TranscriptionFailed(e.to_string()))?;
+
+        // whisper.cpp 不一定会回调 100%
+        if let Some(scope) = scope {
+            scope.report_percent(100);
+        }
+        Ok(())
+    }
+
     /// 转录音频
     ///
     /// # 参数
     /// * `audio_data` - f32 格式的音频数据（16kHz, 单声道）
     /// * `language` - 语言代码（如 "zh", "en"），None 表示自动检测
+    /// * `task` - 转写原文或翻译为英文
     ///
     /// # 返回
-    /// 转录后的文本
+    /// 转录后的文本及识别出的源语言
     pub fn transcribe(
         &self,
         audio_data: &[f32],
         language: Option<&str>,
-    ) -> Result<String, WhisperError> {
+        task: WhisperTask,
+    ) -> Result<WhisperOutput, WhisperError> {
         use tracing::info;
 
         // 验证音频数据
@@ -130,21 +288,37 @@ impl WhisperEngine {
         if duration_secs < 0.1 {
             return Err(WhisperError::AudioTooShort);
         }
-        if duration_secs > 600.0 {
-            // 10 分钟
-            return Err(WhisperError::AudioTooLong);
+        if duration_secs > MAX_SINGLE_PASS_SECS {
+            // 超过 10 分钟的音频单次解码慢且容易漂移，改为分块并行转录
+            let output = self.transcribe_long(audio_data, language, task)?;
+
+            return Ok(WhisperOutput {
+                text: output.text(),
+                language: output.language,
+                translated: task == WhisperTask::Translate,
+            });
         }
 
         // 创建转录参数 - 针对中文优化
         // 使用 BeamSearch 策略以提高准确度（虽然会稍微慢一点）
         let mut params = FullParams::new(SamplingStrategy::BeamSearch {
-            beam_size: 5,      // 使用 5 个候选
+            beam_size: BEAM_SIZE, // 使用 5 个候选
             patience: -1.0     // 默认值
         });
 
         // 设置语言
-        info!("🎯 [Whisper] Language setting: {:?}", language);
-        if let Some(lang) = language {
+        info!("🎯 [Whisper] Language setting: {:?}, task: {:?}", language, task);
+        if task == WhisperTask::Translate {
+            // 翻译模式：源语言交给 Whisper 检测（或使用显式指定的语言），输出英文
+            // 不设置中文 initial prompt，否则会把输出拉回中文
+            params.set_language(Some(language.unwrap_or("auto")));
+            params.set_translate(true);
+            params.set_temperature(0.0);
+            params.set_suppress_blank(true);
+            if let Some(prompt) = self.initial_prompt(None, Some("en")) {
+                params.set_initial_prompt(&prompt);
+            }
+        } else if let Some(lang) = language {
             info!("🎯 [Whisper] Setting explicit language: {}", lang);
             params.set_language(Some(lang));
             params.set_translate(false);
@@ -154,10 +328,15 @@ impl WhisperEngine {
                 // 对中文的特殊设置
                 params.set_temperature(0.0);  // 使用确定性解码
                 params.set_suppress_blank(true);  // 抑制空白输出
-                params.set_initial_prompt("以下是普通话的句子。");  // 提示这是中文
+                // 提示这是中文，并附上用户词汇表
+                if let Some(prompt) = self.initial_prompt(Some("以下是普通话的句子。"), Some(lang)) {
+                    params.set_initial_prompt(&prompt);
+                }
                 params.set_single_segment(false);  // 允许多段
                 params.set_print_special(false);  // 不打印特殊 token
                 params.set_token_timestamps(false); // 不需要 token 时间戳
+            } else if let Some(prompt) = self.initial_prompt(None, Some(lang)) {
+                params.set_initial_prompt(&prompt);
             }
         } else {
             info!("🎯 [Whisper] Using auto language detection");
@@ -165,7 +344,9 @@ impl WhisperEngine {
             // Whisper 的自动检测有时候会偏向英文，特别是短音频
             params.set_translate(false);
             params.set_suppress_blank(true);
-            // params.set_suppress_non_speech_tokens(true); // 某些版本可能没有这个方法
+            if let Some(prompt) = self.initial_prompt(None, None) {
+                params.set_initial_prompt(&prompt);
+            }
         }
 
         // 设置线程数
@@ -177,60 +358,177 @@ impl WhisperEngine {
         params.set_print_timestamps(false);
         params.set_print_special(false);  // 不打印特殊token
         params.set_token_timestamps(false);  // 不需要 token 级时间戳
+        params.set_suppress_nst(true);  // 抑制非语音 token（音乐符号、[掌声] 等）
+        let scope = self.progress_tracker(1).map(|tracker| tracker.scope(0, 0));
+        self.install_callbacks(&mut params, scope.as_ref());
 
         // 创建 state 并执行转录
         let mut state = self.context
             .create_state()
             .map_err(|e| WhisperError::TranscriptionFailed(format!("Failed to create state: {}", e)))?;
 
-        state
-            .full(params, audio_data)
-            .map_err(|e| WhisperError::TranscriptionFailed(e.to_string()))?;
+        self.run_full(&mut state, params, audio_data, scope.as_ref())?;
 
-        // 获取转录结果 - 使用迭代器方式
+        // 获取转录结果（已过滤幻觉段落）
         let mut result = String::new();
-        let mut segment_count = 0;
-        for segment in state.as_iter() {
-            let text = segment
-                .to_str()
-                .map_err(|e| WhisperError::TranscriptionFailed(format!("Failed to get segment text: {:?}", e)))?;
-            info!("🎯 [Whisper] Segment {}: {}", segment_count, text);
-            result.push_str(text);
-            segment_count += 1;
+        for (segment_count, segment) in self.collect_segments(&state, audio_data, false, scope.as_ref())?.iter().enumerate() {
+            info!("🎯 [Whisper] Segment {}: {}", segment_count, segment.text);
+            result.push_str(&segment.text);
         }
 
         let final_result = result.trim().to_string();
-        info!("🎯 [Whisper] Final transcription result: {}", final_result);
+        let detected_language = detected_language(&state, language);
+        info!(
+            "🎯 [Whisper] Final transcription result: {} (source language: {:?})",
+            final_result, detected_language
+        );
 
         // 去除首尾空格
-        Ok(final_result)
+        Ok(WhisperOutput {
+            text: final_result,
+            language: detected_language,
+            translated: task == WhisperTask::Translate,
+        })
     }
 
-    /// 转录音频并返回带时间戳的结果
+    /// 长音频转录（归档录音、导入的文件）
     ///
-    /// # 参数
-    /// * `audio_data` - f32 格式的音频数据（16kHz, 单声道）
-    /// * `language` - 语言代码（如 "zh", "en"），None 表示自动检测
+    /// 在 VAD 停顿处（或按带重叠的固定窗口）切分音频，多个工作线程并行解码，
+    /// 再合并段落、去除重叠部分的重复内容并修正时间戳。
+    ///
+    /// 自动检测语言时先解码第一块确定源语言，其余块沿用该语言，避免各块检测结果不一致。
     ///
     /// # 返回
-    /// 转录后的段落列表，每个段落包含文本和时间戳
-    pub fn transcribe_with_timestamps(
+    /// 合并后的段落列表（时间戳相对于整段音频）及识别出的源语言
+    pub fn transcribe_long(
         &self,
         audio_data: &[f32],
         language: Option<&str>,
-    ) -> Result<Vec<TranscriptionSegment>, WhisperError> {
+        task: WhisperTask,
+    ) -> Result<LongFormOutput, WhisperError> {
+        use parking_lot::Mutex;
+        use std::sync::atomic::{AtomicUsize, Ordering};
+        use tracing::info;
+
         // 验证音频数据
         validate_audio_data(audio_data)?;
 
+        let duration_ms = audio_data.len() as u64 / 16;
+        if duration_ms > MAX_LONG_FORM_MS {
+            return Err(WhisperError::AudioTooLong);
+        }
+
+        let chunks = plan_chunks(duration_ms, &detect_speech(audio_data), &ChunkConfig::default());
+        let tracker = self.progress_tracker(chunks.len());
+        if chunks.len() <= 1 {
+            let scope = tracker.map(|tracker| tracker.scope(0, 0));
+            let (segments, language) =
+                self.decode_with_timestamps(audio_data, language, task, self.n_threads, scope.as_ref())?;
+            return Ok(LongFormOutput { segments, language });
+        }
+
+        // 自动检测：第一块单独解码（使用全部线程）并读取检测到的语言
+        let mut first_chunk = None;
+        let mut detected_language = language.map(|lang| lang.to_string());
+        if language.is_none() {
+            let chunk = chunks[0];
+            let scope = tracker.as_ref().map(|tracker| tracker.scope(0, chunk.start_ms));
+            let (segments, language) = self.decode_with_timestamps(
+                &audio_data[chunk.sample_range(audio_data.len())],
+                None,
+                task,
+                self.n_threads,
+                scope.as_ref(),
+            )?;
+            info!("🎯 [Whisper] Detected language from first chunk: {:?}", language);
+            detected_language = language;
+            first_chunk = Some(segments);
+        }
+        let chunk_language = detected_language.as_deref();
+
+        // 每个工作线程持有独立的 state（各自分配解码缓冲区），线程数在工作线程间平分
+        let total_threads = num_cpus::get().max(1);
+        let workers = chunks.len().min(MAX_LONG_FORM_WORKERS).min(total_threads);
+        let threads_per_worker = (total_threads / workers).clamp(1, self.n_threads);
+        info!(
+            "🎯 [Whisper] Long-form transcription: {:.1}s, {} chunks, {} workers x {} threads",
+            duration_ms as f32 / 1000.0,
+            chunks.len(),
+            workers,
+            threads_per_worker
+        );
+
+        let next_chunk = AtomicUsize::new(usize::from(first_chunk.is_some()));
+        let mut decoded: Vec<Option<Result<Vec<TranscriptionSegment>, WhisperError>>> =
+            (0..chunks.len()).map(|_| None).collect();
+        decoded[0] = first_chunk.map(Ok);
+        let results = Mutex::new(decoded);
+
+        std::thread::scope(|scope| {
+            for _ in 0..workers {
+                scope.spawn(|| loop {
+                    let index = next_chunk.fetch_add(1, Ordering::SeqCst);
+                    let Some(chunk) = chunks.get(index) else {
+                        break;
+                    };
+                    if self.is_cancelled() {
+                        results.lock()[index] = Some(Err(WhisperError::Cancelled));
+                        continue;
+                    }
+
+                    let samples = &audio_data[chunk.sample_range(audio_data.len())];
+                    let scope = tracker.as_ref().map(|tracker| tracker.scope(index, chunk.start_ms));
+                    let result = self
+                        .decode_with_timestamps(samples, chunk_language, task, threads_per_worker, scope.as_ref())
+                        .map(|(segments, _)| segments);
+                    info!(
+                        "🎯 [Whisper] Chunk {}/{} [{}ms - {}ms] decoded",
+                        index + 1,
+                        chunks.len(),
+                        chunk.start_ms,
+                        chunk.end_ms
+                    );
+                    results.lock()[index] = Some(result);
+                });
+            }
+        });
+
+        let mut chunk_segments = Vec::with_capacity(chunks.len());
+        for result in results.into_inner() {
+            let segments = result.ok_or_else(|| {
+                WhisperError::TranscriptionFailed("Chunk was not decoded".to_string())
+            })??;
+            chunk_segments.push(segments);
+        }
+
+        Ok(LongFormOutput {
+            segments: merge_chunk_segments(&chunks, chunk_segments),
+            language: detected_language,
+        })
+    }
+
+    /// 创建带时间戳转录的解码参数
+    fn timestamp_params<'a>(
+        &self,
+        language: Option<&'a str>,
+        task: WhisperTask,
+        n_threads: usize,
+        scope: Option<&ChunkScope>,
+    ) -> FullParams<'a, 'a> {
         // 创建转录参数 - 针对中文优化
         // 使用 BeamSearch 策略以提高准确度（虽然会稍微慢一点）
         let mut params = FullParams::new(SamplingStrategy::BeamSearch {
-            beam_size: 5,      // 使用 5 个候选
+            beam_size: BEAM_SIZE, // 使用 5 个候选
             patience: -1.0     // 默认值
         });
 
         // 设置语言
-        if let Some(lang) = language {
+        if task == WhisperTask::Translate {
+            params.set_language(Some(language.unwrap_or("auto")));
+            params.set_translate(true);
+            params.set_temperature(0.0);
+            params.set_suppress_blank(true);
+        } else if let Some(lang) = language {
             params.set_language(Some(lang));
             params.set_translate(false);
 
@@ -238,58 +536,198 @@ impl WhisperEngine {
             if lang == "zh" {
                 params.set_temperature(0.0);
                 params.set_suppress_blank(true);
-                // params.set_suppress_non_speech_tokens(true); // 某些版本可能没有这个方法
             }
         } else {
             params.set_translate(false);
             params.set_suppress_blank(true);
-            // params.set_suppress_non_speech_tokens(true); // 某些版本可能没有这个方法
+        }
+
+        // 用户词汇表作为提示词（翻译模式输出英文，使用英文提示）
+        let prompt_language = if task == WhisperTask::Translate { Some("en") } else { language };
+        if let Some(prompt) = self.initial_prompt(None, prompt_language) {
+            params.set_initial_prompt(&prompt);
         }
 
         // 设置线程数
-        params.set_n_threads(self.n_threads as i32);
+        params.set_n_threads(n_threads as i32);
 
-        // 启用时间戳
+        // 启用时间戳（token 时间戳用于词级时间）
         params.set_print_timestamps(true);
         params.set_print_special(false);
-        params.set_token_timestamps(false);
+        params.set_token_timestamps(true);
+        params.set_suppress_nst(true);
+        self.install_callbacks(&mut params, scope);
+
+        params
+    }
+
+    /// 解码一段音频，返回带时间戳的段落（时间戳相对于该段音频起点）及源语言
+    fn decode_with_timestamps(
+        &self,
+        audio_data: &[f32],
+        language: Option<&str>,
+        task: WhisperTask,
+        n_threads: usize,
+        scope: Option<&ChunkScope>,
+    ) -> Result<(Vec<TranscriptionSegment>, Option<String>), WhisperError> {
+        let params = self.timestamp_params(language, task, n_threads, scope);
 
         // 创建 state 并执行转录
         let mut state = self.context
             .create_state()
             .map_err(|e| WhisperError::TranscriptionFailed(format!("Failed to create state: {}", e)))?;
 
-        state
-            .full(params, audio_data)
-            .map_err(|e| WhisperError::TranscriptionFailed(e.to_string()))?;
+        self.run_full(&mut state, params, audio_data, scope)?;
+
+        // 获取转录结果（已过滤幻觉段落）
+        let segments = self
+            .collect_segments(&state, audio_data, true, scope)?
+            .into_iter()
+            .map(|segment| TranscriptionSegment {
+                text: segment.text.trim().to_string(),
+                ..segment
+            })
+            .collect();
+
+        Ok((segments, detected_language(&state, language)))
+    }
+
+    /// 读取解码后的段落，并按解码信号逐段丢弃幻觉内容
+    ///
+    /// 每个段落结合 Whisper 的无语音概率、token 平均对数概率、文本重复度
+    /// 以及该时间范围内的 VAD 语音覆盖率判断，被丢弃的段落会记录原因。
+    /// `with_words` 时由 token 时间戳合并出词级时间（需要解码时开启 token 时间戳）。
+    /// 通过过滤的段落经 `scope` 上报给前端，被丢弃的段落不会出现在实时结果中。
+    fn collect_segments(
+        &self,
+        state: &whisper_rs::WhisperState,
+        audio_data: &[f32],
+        with_words: bool,
+        scope: Option<&ChunkScope>,
+    ) -> Result<Vec<TranscriptionSegment>, WhisperError> {
+        use tracing::warn;
+
+        let speech_regions = detect_speech(audio_data);
+        let token_eot = self.context.token_eot();
 
-        // 获取转录结果 - 使用迭代器方式
         let mut segments = Vec::new();
         for segment in state.as_iter() {
             let text = segment
                 .to_str()
                 .map_err(|e| WhisperError::TranscriptionFailed(format!("Failed to get segment text: {:?}", e)))?;
 
-            let start_time = segment.start_timestamp();
-            let end_time = segment.end_timestamp();
+            // whisper 时间戳单位是厘秒（10ms）
+            let start_ms = segment.start_timestamp().max(0) as u64 * 10;
+            let end_ms = segment.end_timestamp().max(0) as u64 * 10;
+
+            // 只统计文本 token（特殊 token 的 id 都不小于 EOT）
+            let logprobs: Vec<f32> = (0..segment.n_tokens())
+                .filter_map(|i| segment.get_token(i))
+                .filter(|token| token.token_id() < token_eot)
+                .map(|token| token.token_data().plog)
+                .collect();
+            let avg_logprob = if logprobs.is_empty() {
+                0.0
+            } else {
+                logprobs.iter().sum::<f32>() / logprobs.len() as f32
+            };
+
+            let signals = SegmentSignals {
+                no_speech_prob: segment.no_speech_probability(),
+                avg_logprob,
+                speech_coverage: speech_coverage(&speech_regions, start_ms, end_ms),
+            };
+
+            if let Some(reason) = self.hallucination_filter.check(text, &signals) {
+                warn!(
+                    "⚠️  [Whisper] Dropped suspected hallucination ({:?}) [{}ms - {}ms]: {} ({:?})",
+                    reason, start_ms, end_ms, text, signals
+                );
+                continue;
+            }
 
-            segments.push(TranscriptionSegment {
-                text: text.trim().to_string(),
-                start_ms: start_time as u64 * 10, // whisper 时间戳单位是厘秒（10ms）
-                end_ms: end_time as u64 * 10,
-            });
+            let words = if with_words {
+                let tokens: Vec<_> = (0..segment.n_tokens())
+                    .filter_map(|i| segment.get_token(i))
+                    .filter(|token| token.token_id() < token_eot)
+                    .collect();
+                let timed: Vec<TimedToken> = tokens
+                    .iter()
+                    .filter_map(|token| {
+                        let data = token.token_data();
+                        token.to_bytes().ok().map(|bytes| TimedToken { bytes, t0: data.t0, t1: data.t1 })
+                    })
+                    .collect();
+                group_words(&timed)
+            } else {
+                Vec::new()
+            };
+
+            let segment = TranscriptionSegment {
+                text: text.to_string(),
+                start_ms,
+                end_ms,
+                words,
+                speaker: None,
+            };
+            if let Some(scope) = scope {
+                scope.report_segment(&segment);
+            }
+            segments.push(segment);
         }
 
         Ok(segments)
     }
 }
 
+/// 读取解码时使用的源语言
+///
+/// 显式指定语言时直接返回该语言；自动检测时从 state 中读取 Whisper 检测到的语言
+fn detected_language(state: &whisper_rs::WhisperState, requested: Option<&str>) -> Option<String> {
+    if let Some(lang) = requested {
+        return Some(lang.to_string());
+    }
+
+    whisper_rs::get_lang_str(state.full_lang_id_from_state()).map(|lang| lang.to_string())
+}
+
+/// 拼接各段文本：中日文之间直接相连，其余（英文等以空格分词的文字）之间补空格
+fn join_segment_texts(texts: &[&str]) -> String {
+    let mut joined = String::new();
+    for text in texts.iter().map(|text| text.trim()).filter(|text| !text.is_empty()) {
+        let needs_space = joined.chars().last().is_some_and(|c| !is_unspaced_script(c))
+            && text.chars().next().is_some_and(|c| !is_unspaced_script(c));
+        if needs_space {
+            joined.push(' ');
+        }
+        joined.push_str(text);
+    }
+    joined
+}
+
+/// 词之间不加空格的文字：汉字、假名以及全角标点
+fn is_unspaced_script(c: char) -> bool {
+    matches!(c,
+        '\u{3000}'..='\u{30FF}'   // 全角标点、假名
+        | '\u{3400}'..='\u{4DBF}' // 扩展 A
+        | '\u{4E00}'..='\u{9FFF}' // 基本汉字
+        | '\u{F900}'..='\u{FAFF}' // 兼容汉字
+        | '\u{FF00}'..='\u{FFEF}' // 全角字符
+    )
+}
+
 /// 转录段落（带时间戳）
-#[derive(Debug, Clone, serde::Serialize, serde::Deserialize)]
+#[derive(Debug, Clone, PartialEq, serde::Serialize, serde::Deserialize)]
 pub struct TranscriptionSegment {
     pub text: String,
     pub start_ms: u64,
     pub end_ms: u64,
+    /// 词级时间戳（仅带时间戳的转录路径提供）
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub words: Vec<WordTiming>,
+    /// 说话人编号（从 0 开始，开启说话人分离时提供）
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub speaker: Option<u32>,
 }
 
 #[cfg(test)]
@@ -318,4 +756,12 @@ mod tests {
         let duration = short_audio.len() as f32 / 16000.0;
         assert!(duration < 0.1);
     }
+
+    #[test]
+    fn test_join_segment_texts() {
+        assert_eq!(join_segment_texts(&[" Hello world.", " How are you?"]), "Hello world. How are you?");
+        assert_eq!(join_segment_texts(&["今天天气不错，", "我们出去走走。"]), "今天天气不错，我们出去走走。");
+        assert_eq!(join_segment_texts(&["打开 VS Code。", "Then run it", ""]), "打开 VS Code。Then run it");
+        assert_eq!(join_segment_texts(&["version 2", "3 items"]), "version 2 3 items");
+    }
 }
@@ -0,0 +1,183 @@
+/// Whisper 幻觉检测
+/// 基于解码信号（无语音概率、平均对数概率、压缩率、重复度）和 VAD 语音覆盖率逐段判断，
+/// 取代原先的固定短语黑名单
+
+use std::io::Write;
+
+/// 段落的解码信号
+#[derive(Debug, Clone, Copy)]
+pub struct SegmentSignals {
+    /// Whisper 给出的无语音概率
+    pub no_speech_prob: f32,
+    /// 文本 token 的平均对数概率
+    pub avg_logprob: f32,
+    /// 段落时间范围内的 VAD 语音覆盖率（0.0 - 1.0）
+    pub speech_coverage: f32,
+}
+
+/// 可疑原因
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SuspectReason {
+    /// Whisper 自身判断为无语音且置信度低
+    NoSpeech,
+    /// 段落对应的音频几乎没有人声，且解码置信度不高
+    NoVoiceActivity,
+    /// 文本高度重复（压缩率过高或 n-gram 重复）
+    Repetitive,
+}
+
+/// 幻觉过滤阈值
+#[derive(Debug, Clone)]
+pub struct HallucinationFilter {
+    /// 无语音概率阈值（与 logprob_threshold 同时满足时判为无语音）
+    pub no_speech_threshold: f32,
+    /// 平均对数概率阈值
+    pub logprob_threshold: f32,
+    /// zlib 压缩率阈值
+    pub compression_ratio_threshold: f32,
+    /// 4-gram 重复比例阈值
+    pub repetition_threshold: f32,
+    /// 最低语音覆盖率
+    pub min_speech_coverage: f32,
+}
+
+impl Default for HallucinationFilter {
+    fn default() -> Self {
+        // 与 OpenAI Whisper 参考实现的默认阈值保持一致
+        Self {
+            no_speech_threshold: 0.6,
+            logprob_threshold: -1.0,
+            compression_ratio_threshold: 2.4,
+            repetition_threshold: 0.6,
+            min_speech_coverage: 0.1,
+        }
+    }
+}
+
+impl HallucinationFilter {
+    /// 检查段落是否可疑，返回可疑原因
+    pub fn check(&self, text: &str, signals: &SegmentSignals) -> Option<SuspectReason> {
+        let text = text.trim();
+        if text.is_empty() {
+            return None;
+        }
+
+        // 1. Whisper 自身的无语音判断
+        if signals.no_speech_prob > self.no_speech_threshold
+            && signals.avg_logprob < self.logprob_threshold
+        {
+            return Some(SuspectReason::NoSpeech);
+        }
+
+        // 2. 音频几乎没有人声：只要解码信号稍有可疑就丢弃
+        if signals.speech_coverage < self.min_speech_coverage
+            && (signals.no_speech_prob > self.no_speech_threshold / 2.0
+                || signals.avg_logprob < self.logprob_threshold * 0.7)
+        {
+            return Some(SuspectReason::NoVoiceActivity);
+        }
+
+        // 3. 重复输出（解码陷入循环）
+        if compression_ratio(text) > self.compression_ratio_threshold
+            || repetition_ratio(text) > self.repetition_threshold
+        {
+            return Some(SuspectReason::Repetitive);
+        }
+
+        None
+    }
+}
+
+/// 计算文本的 zlib 压缩率（原始字节数 / 压缩后字节数）
+///
+/// 短文本的压缩头开销大于收益，直接返回 1.0
+pub fn compression_ratio(text: &str) -> f32 {
+    let bytes = text.as_bytes();
+    if bytes.len() < 32 {
+        return 1.0;
+    }
+
+    let mut encoder = flate2::write::ZlibEncoder::new(Vec::new(), flate2::Compression::default());
+    if encoder.write_all(bytes).is_err() {
+        return 1.0;
+    }
+    match encoder.finish() {
+        Ok(compressed) if !compressed.is_empty() => bytes.len() as f32 / compressed.len() as f32,
+        _ => 1.0,
+    }
+}
+
+/// 计算字符 4-gram 的重复比例（0.0 表示没有重复）
+pub fn repetition_ratio(text: &str) -> f32 {
+    const N: usize = 4;
+
+    let chars: Vec<char> = text.chars().filter(|c| !c.is_whitespace()).collect();
+    if chars.len() < N * 3 {
+        return 0.0;
+    }
+
+    let grams: Vec<&[char]> = chars.windows(N).collect();
+    let unique: std::collections::HashSet<&[char]> = grams.iter().cloned().collect();
+    1.0 - unique.len() as f32 / grams.len() as f32
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn confident(coverage: f32) -> SegmentSignals {
+        SegmentSignals {
+            no_speech_prob: 0.05,
+            avg_logprob: -0.3,
+            speech_coverage: coverage,
+        }
+    }
+
+    #[test]
+    fn test_legitimate_dictation_is_kept() {
+        let filter = HallucinationFilter::default();
+        // 以前会被黑名单误杀的正常句子
+        assert_eq!(filter.check("请帮我把这段字幕翻译成英文", &confident(0.9)), None);
+        assert_eq!(filter.check("Remember to subscribe the webhook", &confident(0.8)), None);
+    }
+
+    #[test]
+    fn test_no_speech_segment_is_dropped() {
+        let filter = HallucinationFilter::default();
+        let signals = SegmentSignals {
+            no_speech_prob: 0.8,
+            avg_logprob: -1.4,
+            speech_coverage: 0.5,
+        };
+        assert_eq!(filter.check("字幕由 Amara.org 社区提供", &signals), Some(SuspectReason::NoSpeech));
+    }
+
+    #[test]
+    fn test_silent_audio_segment_is_dropped() {
+        let filter = HallucinationFilter::default();
+        let signals = SegmentSignals {
+            no_speech_prob: 0.4,
+            avg_logprob: -0.5,
+            speech_coverage: 0.0,
+        };
+        assert_eq!(filter.check("Thanks for watching!", &signals), Some(SuspectReason::NoVoiceActivity));
+
+        // 覆盖率低但解码非常自信（例如小声说话）时保留
+        assert_eq!(filter.check("你好", &confident(0.0)), None);
+    }
+
+    #[test]
+    fn test_repetitive_segment_is_dropped() {
+        let filter = HallucinationFilter::default();
+        let text = "谢谢大家".repeat(12);
+        assert!(compression_ratio(&text) > 2.4);
+        assert_eq!(filter.check(&text, &confident(0.9)), Some(SuspectReason::Repetitive));
+    }
+
+    #[test]
+    fn test_repetition_ratio() {
+        assert_eq!(repetition_ratio("短句"), 0.0);
+        assert!(repetition_ratio("今天天气很好我们去公园散步吧") < 0.1);
+        assert!(repetition_ratio(&"哈哈哈哈".repeat(5)) > 0.8);
+    }
+}
@@ -1,5 +1,13 @@
+pub mod catalog;
+pub mod chunker;
 pub mod engine;
+pub mod hallucination;
 pub mod preprocessor;
+pub mod progress;
+pub mod vad;
+pub mod words;
 
-pub use engine::WhisperEngine;
+pub use engine::{TranscriptionSegment, WhisperEngine, WhisperOutput, WhisperTask, BEAM_SIZE};
+pub use words::WordTiming;
+pub use progress::{DecodeProgress, ProgressCallback};
 pub use preprocessor::*;
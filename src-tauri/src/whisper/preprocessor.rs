@@ -47,6 +47,104 @@ pub fn resample_48khz_to_16khz(samples: &[f32]) -> Vec<f32> {
     samples.iter().step_by(3).copied().collect()
 }
 
+/// 将多声道交错采样混合为单声道
+pub fn mix_to_mono(samples: &[f32], channels: u16) -> Vec<f32> {
+    if channels <= 1 {
+        return samples.to_vec();
+    }
+
+    samples
+        .chunks(channels as usize)
+        .map(|frame| frame.iter().sum::<f32>() / frame.len() as f32)
+        .collect()
+}
+
+/// 重采样滤波器每侧的过零点数（越大过渡带越窄）
+const RESAMPLE_ZERO_CROSSINGS: f64 = 16.0;
+
+/// 低通截止频率相对目标奈奎斯特频率的比例（留出过渡带）
+const RESAMPLE_ROLLOFF: f64 = 0.95;
+
+/// 分数采样位置量化的相位数
+const RESAMPLE_PHASES: usize = 256;
+
+/// 任意采样率重采样到 16kHz（带限插值）
+///
+/// 用于导入的音频文件，采样率不一定是 48kHz 的整数倍。
+/// 使用 Blackman 窗的 sinc 低通滤波器：降采样时截止频率随目标奈奎斯特频率（8kHz）降低，
+/// 高于 8kHz 的成分被滤除而不会混叠到语音频段。
+pub fn resample_to_16khz(samples: &[f32], from_rate: u32) -> Vec<f32> {
+    if from_rate == 16000 || samples.is_empty() {
+        return samples.to_vec();
+    }
+
+    // 输入采样间隔为单位：每个输出采样前进 ratio 个输入采样
+    let ratio = from_rate as f64 / 16000.0;
+    let cutoff = RESAMPLE_ROLLOFF * 0.5 / ratio.max(1.0);
+    let half_width = (RESAMPLE_ZERO_CROSSINGS / (2.0 * cutoff)).ceil() as isize;
+
+    // 每个分数相位一组归一化系数（直流增益为 1）
+    let table: Vec<Vec<f32>> = (0..RESAMPLE_PHASES)
+        .map(|phase| {
+            let frac = phase as f64 / RESAMPLE_PHASES as f64;
+            let weights: Vec<f64> = (-half_width..=half_width)
+                .map(|k| windowed_sinc(k as f64 - frac, cutoff, half_width as f64))
+                .collect();
+            let sum: f64 = weights.iter().sum();
+            weights.iter().map(|w| (w / sum) as f32).collect()
+        })
+        .collect();
+
+    let new_len = (samples.len() as f64 / ratio) as usize;
+    (0..new_len)
+        .map(|i| {
+            let pos = i as f64 * ratio;
+            let mut base = pos.floor() as isize;
+            let mut phase = ((pos - pos.floor()) * RESAMPLE_PHASES as f64).round() as usize;
+            if phase == RESAMPLE_PHASES {
+                base += 1;
+                phase = 0;
+            }
+            let coeffs = &table[phase];
+            let first = base - half_width;
+
+            if first >= 0 && (first as usize + coeffs.len()) <= samples.len() {
+                let window = &samples[first as usize..first as usize + coeffs.len()];
+                return window.iter().zip(coeffs).map(|(s, c)| s * c).sum();
+            }
+
+            // 首尾不足一个滤波器宽度，只用范围内的采样并重新归一化
+            let (mut acc, mut weight) = (0.0f32, 0.0f32);
+            for (k, c) in coeffs.iter().enumerate() {
+                let idx = first + k as isize;
+                if idx >= 0 && (idx as usize) < samples.len() {
+                    acc += samples[idx as usize] * c;
+                    weight += c;
+                }
+            }
+            if weight.abs() > 1e-3 {
+                acc / weight
+            } else {
+                0.0
+            }
+        })
+        .collect()
+}
+
+/// Blackman 窗 sinc 低通核，`t` 为到中心的距离（输入采样间隔），`cutoff` 为每采样周期数
+fn windowed_sinc(t: f64, cutoff: f64, half_width: f64) -> f64 {
+    use std::f64::consts::PI;
+
+    if t.abs() > half_width {
+        return 0.0;
+    }
+    let x = 2.0 * cutoff * t;
+    let sinc = if x.abs() < 1e-12 { 1.0 } else { (PI * x).sin() / (PI * x) };
+    let u = t / half_width;
+    let window = 0.42 + 0.5 * (PI * u).cos() + 0.08 * (2.0 * PI * u).cos();
+    2.0 * cutoff * sinc * window
+}
+
 /// 验证采样率是否为 16kHz
 pub fn validate_sample_rate(rate: u32) -> Result<(), PreprocessError> {
     if rate != 16000 {
@@ -101,6 +199,50 @@ pub fn validate_audio_data(samples: &[f32]) -> Result<(), PreprocessError> {
 mod tests {
     use super::*;
 
+    #[test]
+    fn test_mix_to_mono_and_resample() {
+        let stereo = vec![0.2f32, 0.4, -0.2, -0.4];
+        assert_eq!(mix_to_mono(&stereo, 2), vec![0.3, -0.3]);
+
+        let audio_44k = vec![0.5f32; 44100];
+        let resampled = resample_to_16khz(&audio_44k, 44100);
+        assert_eq!(resampled.len(), 16000);
+        assert!(resampled.iter().all(|&s| (s - 0.5).abs() < 1e-6));
+    }
+
+    fn sine(freq: f32, rate: u32, secs: f32) -> Vec<f32> {
+        (0..(rate as f32 * secs) as usize)
+            .map(|i| (2.0 * std::f32::consts::PI * freq * i as f32 / rate as f32).sin())
+            .collect()
+    }
+
+    fn rms(samples: &[f32]) -> f32 {
+        (samples.iter().map(|s| s * s).sum::<f32>() / samples.len() as f32).sqrt()
+    }
+
+    #[test]
+    fn test_resample_filters_aliasing() {
+        for rate in [44100, 48000, 22050] {
+            // 语音频段内的信号保持不变（跳过首尾的滤波器边缘）
+            let speech = resample_to_16khz(&sine(1000.0, rate, 1.0), rate);
+            let level = rms(&speech[800..15200]);
+            assert!((level - std::f32::consts::FRAC_1_SQRT_2).abs() < 0.02, "{}Hz: rms {}", rate, level);
+
+            // 高于 8kHz 的成分被滤除，不会混叠到 16kHz 信号中
+            let high = resample_to_16khz(&sine(10_000.0, rate, 1.0), rate);
+            let level = rms(&high[800..15200]);
+            assert!(level < 0.01, "{}Hz: aliased rms {}", rate, level);
+        }
+    }
+
+    #[test]
+    fn test_resample_upsamples() {
+        let upsampled = resample_to_16khz(&sine(1000.0, 8000, 1.0), 8000);
+        assert_eq!(upsampled.len(), 16000);
+        let level = rms(&upsampled[800..15200]);
+        assert!((level - std::f32::consts::FRAC_1_SQRT_2).abs() < 0.02, "rms {}", level);
+    }
+
     #[test]
     fn test_convert_i16_to_f32() {
         let i16_samples = vec![0i16, 16384, -16384, 32767, -32768];
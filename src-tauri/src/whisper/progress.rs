@@ -0,0 +1,151 @@
+/// 解码进度
+/// 把 whisper.cpp 的进度回调、过滤后的段落换算为整段音频上的进度事件；
+/// 长音频并行解码时按块汇总进度并修正段落时间戳
+
+use std::sync::atomic::{AtomicU8, Ordering};
+use std::sync::Arc;
+
+use super::engine::TranscriptionSegment;
+
+/// 解码进度事件
+#[derive(Debug, Clone, PartialEq)]
+pub enum DecodeProgress {
+    /// 整体进度（0-100）
+    Percent(u8),
+    /// 新解码出的段落（已经过幻觉过滤，时间戳相对于整段音频）
+    Segment(TranscriptionSegment),
+}
+
+/// 进度回调
+pub type ProgressCallback = Arc<dyn Fn(DecodeProgress) + Send + Sync>;
+
+/// 一次转录的进度汇总
+pub struct ProgressTracker {
+    callback: ProgressCallback,
+    /// 各块的解码进度
+    chunks: Vec<AtomicU8>,
+    /// 已上报的整体进度（只增不减）
+    reported: AtomicU8,
+}
+
+impl ProgressTracker {
+    pub fn new(callback: ProgressCallback, n_chunks: usize) -> Arc<Self> {
+        Arc::new(Self {
+            callback,
+            chunks: (0..n_chunks.max(1)).map(|_| AtomicU8::new(0)).collect(),
+            reported: AtomicU8::new(0),
+        })
+    }
+
+    /// 第 `index` 块的解码范围
+    pub fn scope(self: &Arc<Self>, index: usize, offset_ms: u64) -> ChunkScope {
+        ChunkScope {
+            tracker: self.clone(),
+            index,
+            offset_ms,
+        }
+    }
+
+    fn update(&self, index: usize, percent: u8) {
+        let Some(chunk) = self.chunks.get(index) else {
+            return;
+        };
+        chunk.fetch_max(percent.min(100), Ordering::SeqCst);
+
+        let total: usize = self.chunks.iter().map(|c| c.load(Ordering::SeqCst) as usize).sum();
+        let overall = (total / self.chunks.len()) as u8;
+
+        // 多个工作线程并发更新时只上报更大的进度
+        if self.reported.fetch_max(overall, Ordering::SeqCst) < overall {
+            (self.callback)(DecodeProgress::Percent(overall));
+        }
+    }
+}
+
+/// 单次解码（整段音频或其中一块）在整体进度中的位置
+#[derive(Clone)]
+pub struct ChunkScope {
+    tracker: Arc<ProgressTracker>,
+    index: usize,
+    offset_ms: u64,
+}
+
+impl ChunkScope {
+    /// whisper.cpp 进度回调（0-100）
+    pub fn report_percent(&self, percent: i32) {
+        self.tracker.update(self.index, percent.clamp(0, 100) as u8);
+    }
+
+    /// 上报通过幻觉过滤的段落（时间戳相对于本块）
+    pub fn report_segment(&self, segment: &TranscriptionSegment) {
+        let text = segment.text.trim();
+        if text.is_empty() {
+            return;
+        }
+
+        (self.tracker.callback)(DecodeProgress::Segment(TranscriptionSegment {
+            text: text.to_string(),
+            start_ms: self.offset_ms + segment.start_ms,
+            end_ms: self.offset_ms + segment.end_ms,
+            words: Vec::new(),
+            speaker: None,
+        }));
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use parking_lot::Mutex;
+
+    fn recording_tracker(n_chunks: usize) -> (Arc<ProgressTracker>, Arc<Mutex<Vec<DecodeProgress>>>) {
+        let events = Arc::new(Mutex::new(Vec::new()));
+        let sink = events.clone();
+        let tracker = ProgressTracker::new(Arc::new(move |event| sink.lock().push(event)), n_chunks);
+        (tracker, events)
+    }
+
+    #[test]
+    fn test_chunk_progress_is_averaged_and_monotonic() {
+        let (tracker, events) = recording_tracker(2);
+        let first = tracker.scope(0, 0);
+        let second = tracker.scope(1, 30_000);
+
+        first.report_percent(50);
+        second.report_percent(100);
+        // 回退的进度不会上报
+        first.report_percent(20);
+        first.report_percent(100);
+
+        assert_eq!(
+            *events.lock(),
+            vec![
+                DecodeProgress::Percent(25),
+                DecodeProgress::Percent(75),
+                DecodeProgress::Percent(100),
+            ]
+        );
+    }
+
+    fn segment(text: &str, start_ms: u64, end_ms: u64) -> TranscriptionSegment {
+        TranscriptionSegment {
+            text: text.to_string(),
+            start_ms,
+            end_ms,
+            words: Vec::new(),
+            speaker: None,
+        }
+    }
+
+    #[test]
+    fn test_segment_offsets() {
+        let (tracker, events) = recording_tracker(2);
+        tracker.scope(1, 30_000).report_segment(&segment(" 第二块 ", 500, 2300));
+        tracker.scope(0, 0).report_segment(&segment("  ", 0, 100));
+
+        assert_eq!(
+            *events.lock(),
+            vec![DecodeProgress::Segment(segment("第二块", 30_500, 32_300))]
+        );
+    }
+}
@@ -0,0 +1,140 @@
+/// 基于能量的语音活动检测（VAD）
+/// 用于判断转录段落对应的音频区间内是否真的有人声
+
+/// 采样率（Whisper 输入固定为 16kHz）
+const SAMPLE_RATE: usize = 16000;
+
+/// 帧长 30ms
+const FRAME_MS: usize = 30;
+
+/// 绝对能量下限（RMS），低于此值一律视为静音
+const MIN_SPEECH_RMS: f32 = 0.004;
+
+/// 相隔小于该时长的语音区间会被合并
+const MERGE_GAP_MS: u64 = 300;
+
+/// 短于该时长的语音区间视为噪声
+const MIN_REGION_MS: u64 = 90;
+
+/// 语音区间（毫秒）
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct SpeechRegion {
+    pub start_ms: u64,
+    pub end_ms: u64,
+}
+
+impl SpeechRegion {
+    pub fn duration_ms(&self) -> u64 {
+        self.end_ms.saturating_sub(self.start_ms)
+    }
+}
+
+/// 检测音频中的语音区间
+///
+/// # 参数
+/// * `samples` - f32 格式的音频数据（16kHz, 单声道）
+///
+/// 阈值根据噪声底（帧能量的 10% 分位数）自适应调整，并限制在峰值的 30% 以内，
+/// 以便在持续说话（几乎没有静音帧）的录音中也能正常工作。
+pub fn detect_speech(samples: &[f32]) -> Vec<SpeechRegion> {
+    let frame_len = SAMPLE_RATE * FRAME_MS / 1000;
+    if samples.len() < frame_len {
+        return Vec::new();
+    }
+
+    let energies: Vec<f32> = samples
+        .chunks(frame_len)
+        .map(|frame| (frame.iter().map(|&s| s * s).sum::<f32>() / frame.len() as f32).sqrt())
+        .collect();
+
+    let peak = energies.iter().cloned().fold(0.0f32, f32::max);
+    if peak < MIN_SPEECH_RMS {
+        return Vec::new();
+    }
+
+    let mut sorted = energies.clone();
+    sorted.sort_by(|a, b| a.partial_cmp(b).unwrap_or(std::cmp::Ordering::Equal));
+    let noise_floor = sorted[sorted.len() / 10];
+
+    let threshold = (noise_floor * 3.0).min(peak * 0.3).max(MIN_SPEECH_RMS);
+
+    // 逐帧标记并合并为区间
+    let mut regions: Vec<SpeechRegion> = Vec::new();
+    for (i, &energy) in energies.iter().enumerate() {
+        if energy < threshold {
+            continue;
+        }
+
+        let start_ms = (i * FRAME_MS) as u64;
+        let end_ms = ((i + 1) * FRAME_MS) as u64;
+
+        match regions.last_mut() {
+            Some(last) if start_ms.saturating_sub(last.end_ms) < MERGE_GAP_MS => {
+                last.end_ms = end_ms;
+            }
+            _ => regions.push(SpeechRegion { start_ms, end_ms }),
+        }
+    }
+
+    regions.retain(|r| r.duration_ms() >= MIN_REGION_MS);
+    regions
+}
+
+/// 计算 [start_ms, end_ms) 区间内被语音覆盖的比例（0.0 - 1.0）
+pub fn speech_coverage(regions: &[SpeechRegion], start_ms: u64, end_ms: u64) -> f32 {
+    if end_ms <= start_ms {
+        return 0.0;
+    }
+
+    let covered: u64 = regions
+        .iter()
+        .map(|r| {
+            let overlap_start = r.start_ms.max(start_ms);
+            let overlap_end = r.end_ms.min(end_ms);
+            overlap_end.saturating_sub(overlap_start)
+        })
+        .sum();
+
+    (covered as f32 / (end_ms - start_ms) as f32).min(1.0)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn tone(duration_ms: usize, amplitude: f32) -> Vec<f32> {
+        let n = SAMPLE_RATE * duration_ms / 1000;
+        (0..n)
+            .map(|i| amplitude * (2.0 * std::f32::consts::PI * 220.0 * i as f32 / SAMPLE_RATE as f32).sin())
+            .collect()
+    }
+
+    #[test]
+    fn test_silence_has_no_speech() {
+        let silence = vec![0.0f32; SAMPLE_RATE * 2];
+        assert!(detect_speech(&silence).is_empty());
+    }
+
+    #[test]
+    fn test_detects_tone_between_silence() {
+        let mut audio = vec![0.0f32; SAMPLE_RATE]; // 1s 静音
+        audio.extend(tone(1000, 0.5)); // 1s 语音
+        audio.extend(vec![0.0f32; SAMPLE_RATE]); // 1s 静音
+
+        let regions = detect_speech(&audio);
+        assert_eq!(regions.len(), 1);
+        assert!((regions[0].start_ms as i64 - 1000).abs() <= 30);
+        assert!((regions[0].end_ms as i64 - 2000).abs() <= 30);
+
+        assert!(speech_coverage(&regions, 1000, 2000) > 0.9);
+        assert!(speech_coverage(&regions, 2100, 3000) < 0.01);
+    }
+
+    #[test]
+    fn test_continuous_speech() {
+        let audio = tone(2000, 0.3);
+        let regions = detect_speech(&audio);
+        assert_eq!(regions.len(), 1);
+        assert!(speech_coverage(&regions, 0, 2000) > 0.9);
+    }
+}
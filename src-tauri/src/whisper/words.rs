@@ -0,0 +1,127 @@
+/// 词级时间戳
+/// 把 whisper.cpp 的 token 时间戳合并为词：英文等以空格分词的语言按前导空格切分，
+/// 中日韩文字逐 token 成词；标点并入前一个词
+
+/// 词及其时间范围
+#[derive(Debug, Clone, PartialEq, serde::Serialize, serde::Deserialize)]
+pub struct WordTiming {
+    pub text: String,
+    pub start_ms: u64,
+    pub end_ms: u64,
+}
+
+/// 解码出的文本 token（`t0`/`t1` 为 whisper 的 10ms 单位）
+pub struct TimedToken<'a> {
+    pub bytes: &'a [u8],
+    pub t0: i64,
+    pub t1: i64,
+}
+
+/// 合并 token 为词
+///
+/// 多字节字符可能被拆到相邻 token 中，按字节累积到能解码为完整 UTF-8 再判断
+pub fn group_words(tokens: &[TimedToken]) -> Vec<WordTiming> {
+    let mut words: Vec<WordTiming> = Vec::new();
+    let mut pending: Vec<u8> = Vec::new();
+    let mut pending_start = 0u64;
+    let mut pending_end = 0u64;
+
+    for token in tokens {
+        if token.bytes.is_empty() {
+            continue;
+        }
+
+        let start_ms = token.t0.max(0) as u64 * 10;
+        let end_ms = token.t1.max(0) as u64 * 10;
+
+        // 前导空格表示新词开始
+        if token.bytes[0] == b' ' && !pending.is_empty() {
+            flush(&mut words, &mut pending, pending_start, pending_end);
+        }
+
+        if pending.is_empty() {
+            pending_start = start_ms;
+        }
+        pending.extend_from_slice(token.bytes);
+        pending_end = pending_end.max(end_ms);
+
+        // 完整的非 ASCII 文字（中日韩）各自成词
+        if let Ok(text) = std::str::from_utf8(&pending) {
+            if !text.is_ascii() {
+                flush(&mut words, &mut pending, pending_start, pending_end);
+            }
+        }
+    }
+
+    flush(&mut words, &mut pending, pending_start, pending_end);
+    words
+}
+
+fn flush(words: &mut Vec<WordTiming>, pending: &mut Vec<u8>, start_ms: u64, end_ms: u64) {
+    if pending.is_empty() {
+        return;
+    }
+
+    let text = String::from_utf8_lossy(pending).trim().to_string();
+    pending.clear();
+    if text.is_empty() {
+        return;
+    }
+
+    let end_ms = end_ms.max(start_ms);
+
+    // 纯标点并入前一个词
+    if text.chars().all(|c| c.is_ascii_punctuation() || is_cjk_punctuation(c)) {
+        if let Some(last) = words.last_mut() {
+            last.text.push_str(&text);
+            last.end_ms = last.end_ms.max(end_ms);
+            return;
+        }
+    }
+
+    words.push(WordTiming { text, start_ms, end_ms });
+}
+
+fn is_cjk_punctuation(c: char) -> bool {
+    matches!(c, '，' | '。' | '、' | '？' | '！' | '：' | '；' | '“' | '”' | '‘' | '’' | '（' | '）' | '《' | '》' | '…')
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn token(text: &str, t0: i64, t1: i64) -> TimedToken<'_> {
+        TimedToken { bytes: text.as_bytes(), t0, t1 }
+    }
+
+    fn word(text: &str, start_ms: u64, end_ms: u64) -> WordTiming {
+        WordTiming { text: text.to_string(), start_ms, end_ms }
+    }
+
+    #[test]
+    fn test_groups_subword_tokens() {
+        let tokens = [
+            token(" Hello", 0, 30),
+            token(" wor", 30, 50),
+            token("ld", 50, 70),
+            token(".", 70, 72),
+        ];
+        assert_eq!(
+            group_words(&tokens),
+            vec![word("Hello", 0, 300), word("world.", 300, 720)]
+        );
+    }
+
+    #[test]
+    fn test_cjk_and_split_utf8() {
+        // "好" 的 UTF-8 字节被拆成两个 token
+        let hao = "好".as_bytes();
+        let tokens = [
+            token("你", 0, 20),
+            TimedToken { bytes: &hao[..1], t0: 20, t1: 30 },
+            TimedToken { bytes: &hao[1..], t0: 30, t1: 40 },
+            token("。", 40, 41),
+        ];
+        assert_eq!(group_words(&tokens), vec![word("你", 0, 200), word("好。", 200, 410)]);
+    }
+}
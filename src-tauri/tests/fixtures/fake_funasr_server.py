@@ -0,0 +1,127 @@
+#!/usr/bin/env python3
+"""
+FunASR 服务器协议测试用的假服务器
+与 scripts/funasr_server.py 使用相同的 JSON-RPC 协议，但不加载模型；
+每个请求在独立线程中处理，响应顺序取决于各请求的耗时
+
+用法: fake_funasr_server.py [--protocol-version N]
+"""
+
+import json
+import os
+import sys
+import threading
+import time
+
+PROTOCOL_VERSION = 1
+METHOD_NOT_FOUND = -32601
+REQUEST_CANCELLED = -32800
+
+_output_lock = threading.Lock()
+_cancel_lock = threading.Lock()
+_cancelled_ids = []
+
+
+def send(message):
+    with _output_lock:
+        sys.stdout.write(json.dumps({"jsonrpc": "2.0", **message}, ensure_ascii=False) + "\n")
+        sys.stdout.flush()
+
+
+def stray(text):
+    """模拟第三方库直接打印到 stdout 的输出"""
+    with _output_lock:
+        sys.stdout.write(text + "\n")
+        sys.stdout.flush()
+
+
+def is_cancelled(request_id):
+    with _cancel_lock:
+        return request_id in _cancelled_ids
+
+
+def handle(request_id, method, params, protocol_version):
+    if method == "initialize":
+        return {"protocol_version": protocol_version, "methods": ["echo", "queued", "slow", "fail", "cancelled_ids", "exit"]}
+
+    if method == "queued":
+        # 模拟在工作线程队列中等待 wait_ms 后开始执行
+        send({"method": "queued", "params": {"id": request_id}})
+        time.sleep(params.get("wait_ms", 0) / 1000)
+        send({"method": "started", "params": {"id": request_id}})
+        method = "echo"
+
+    if method == "echo":
+        time.sleep(params.get("delay_ms", 0) / 1000)
+        return {"value": params.get("value")}
+
+    if method == "slow":
+        texts = []
+        total = params.get("chunks", 1)
+        if params.get("noisy"):
+            stray("Downloading Model to directory: /tmp/model")
+            send({"method": "log", "params": {"level": "info", "message": "model loaded"}})
+        for index in range(total):
+            time.sleep(params.get("interval_ms", 0) / 1000)
+            if is_cancelled(request_id):
+                raise RpcError(REQUEST_CANCELLED, "Request cancelled")
+            texts.append(f"chunk{index + 1}")
+            send({"method": "progress", "params": {"id": request_id, "done": index + 1, "total": total, "text": texts[-1]}})
+            if params.get("noisy"):
+                stray(f"rtf_avg: 0.0{index}")
+        return {"text": " ".join(texts)}
+
+    if method == "fail":
+        raise RpcError(params["code"], params["message"])
+
+    if method == "cancelled_ids":
+        with _cancel_lock:
+            return list(_cancelled_ids)
+
+    if method == "exit":
+        os._exit(3)
+
+    raise RpcError(METHOD_NOT_FOUND, f"Method not found: {method}")
+
+
+class RpcError(Exception):
+    def __init__(self, code, message):
+        super().__init__(message)
+        self.code = code
+        self.message = message
+
+
+def run(request_id, method, params, protocol_version):
+    try:
+        send({"id": request_id, "result": handle(request_id, method, params, protocol_version)})
+    except RpcError as e:
+        send({"id": request_id, "error": {"code": e.code, "message": e.message}})
+
+
+def main():
+    protocol_version = PROTOCOL_VERSION
+    if len(sys.argv) == 3 and sys.argv[1] == "--protocol-version":
+        protocol_version = int(sys.argv[2])
+
+    for line in sys.stdin:
+        message = json.loads(line)
+        method = message.get("method")
+        params = message.get("params") or {}
+
+        if "id" not in message:
+            if method == "cancel":
+                with _cancel_lock:
+                    _cancelled_ids.append(params["id"])
+            elif method == "shutdown":
+                os._exit(0)
+            continue
+
+        threading.Thread(
+            target=run,
+            args=(message["id"], method, params, protocol_version),
+            daemon=True,
+        ).start()
+
+
+if __name__ == "__main__":
+    main()
@@ -0,0 +1,104 @@
+#!/usr/bin/env python3
+"""生成 onnx_asr/features.rs 中 test_fbank_matches_reference 的参考值
+
+按 Kaldi compute-fbank-feats 的默认流程（kaldi/src/feat/feature-fbank.cc、
+feature-window.cc、mel-computations.cc）以双精度逐步实现，频谱用直接 DFT 计算，
+不依赖 Rust 实现中的 FFT 和滤波器代码，也不需要 numpy：
+
+    dither=0, remove_dc_offset=true, preemphasis_coefficient=0.97,
+    window_type=hamming, frame_length=25ms, frame_shift=10ms, snip_edges=true,
+    num_mel_bins=80, low_freq=20, high_freq=0 (奈奎斯特), use_power=true, use_log_fbank=true
+
+等价的 torchaudio 调用（FunASR WavFrontend 使用）：
+
+    torchaudio.compliance.kaldi.fbank(wave * 32768, num_mel_bins=80, frame_length=25,
+                                      frame_shift=10, dither=0.0, energy_floor=0.0,
+                                      window_type="hamming", sample_frequency=16000)
+
+用法：python3 kaldi_fbank_reference.py  输出测试信号第一帧中被测试引用的各 mel 频带
+"""
+
+import math
+
+SAMPLE_RATE = 16000
+FRAME_LENGTH = 400
+PADDED_LENGTH = 512
+NUM_MEL_BINS = 80
+LOW_FREQ = 20.0
+PREEMPHASIS = 0.97
+
+# test_fbank_matches_reference 检查的频带
+REFERENCE_BINS = [0, 13, 14, 15, 20, 40, 79]
+
+
+def mel_scale(freq):
+    return 1127.0 * math.log(1.0 + freq / 700.0)
+
+
+def mel_banks():
+    """Kaldi MelBanks：mel 域等间隔的三角滤波器，只覆盖 0..N/2（不含奈奎斯特频点）"""
+    num_fft_bins = PADDED_LENGTH // 2
+    fft_bin_width = SAMPLE_RATE / PADDED_LENGTH
+    mel_low = mel_scale(LOW_FREQ)
+    mel_high = mel_scale(SAMPLE_RATE / 2)
+    mel_delta = (mel_high - mel_low) / (NUM_MEL_BINS + 1)
+
+    banks = []
+    for b in range(NUM_MEL_BINS):
+        left = mel_low + b * mel_delta
+        center = left + mel_delta
+        right = center + mel_delta
+        weights = [0.0] * num_fft_bins
+        for i in range(num_fft_bins):
+            mel = mel_scale(fft_bin_width * i)
+            if left < mel < right:
+                if mel <= center:
+                    weights[i] = (mel - left) / (center - left)
+                else:
+                    weights[i] = (right - mel) / (right - center)
+        banks.append(weights)
+    return banks
+
+
+def fbank_frame(frame):
+    """单帧 fbank，`frame` 为 16 位整数幅度的 400 个采样点"""
+    frame = list(frame)
+
+    # remove_dc_offset
+    mean = sum(frame) / len(frame)
+    frame = [s - mean for s in frame]
+
+    # Preemphasize：从后往前，首个采样点与自身相减
+    for i in range(len(frame) - 1, 0, -1):
+        frame[i] -= PREEMPHASIS * frame[i - 1]
+    frame[0] -= PREEMPHASIS * frame[0]
+
+    # Hamming 窗（分母为 N - 1）
+    n = len(frame)
+    frame = [s * (0.54 - 0.46 * math.cos(2 * math.pi * i / (n - 1))) for i, s in enumerate(frame)]
+    frame += [0.0] * (PADDED_LENGTH - n)
+
+    # 功率谱（直接 DFT）
+    power = []
+    for k in range(PADDED_LENGTH // 2 + 1):
+        re = im = 0.0
+        for t, s in enumerate(frame):
+            angle = -2 * math.pi * k * t / PADDED_LENGTH
+            re += s * math.cos(angle)
+            im += s * math.sin(angle)
+        power.append(re * re + im * im)
+
+    epsilon = 1.1920928955078125e-07  # FLT_EPSILON，Kaldi 的对数下限
+    return [math.log(max(sum(w * p for w, p in zip(bank, power)), epsilon)) for bank in mel_banks()]
+
+
+def main():
+    # 测试信号：440Hz 正弦、幅度 0.5，取第一帧
+    samples = [0.5 * math.sin(2 * math.pi * 440 * i / SAMPLE_RATE) * 32768 for i in range(FRAME_LENGTH)]
+    energies = fbank_frame(samples)
+    for b in REFERENCE_BINS:
+        print(f"({b}, {energies[b]:.4f}),")
+
+
+if __name__ == "__main__":
+    main()
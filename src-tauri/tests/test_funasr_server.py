@@ -0,0 +1,148 @@
+#!/usr/bin/env python3
+"""
+scripts/funasr_server.py 中不依赖 FunASR 的逻辑的测试
+模型和 VAD 用假对象代替，不需要安装 funasr / numpy
+
+用法: python3 tests/test_funasr_server.py
+"""
+
+import io
+import json
+import os
+import queue
+import sys
+import tempfile
+import unittest
+from pathlib import Path
+
+sys.path.insert(0, str(Path(__file__).resolve().parent.parent / "scripts"))
+
+import funasr_server  # noqa: E402
+
+SAMPLE_RATE = funasr_server.SAMPLE_RATE
+
+
+def sensevoice_result(text: str) -> list:
+    """SenseVoice（output_timestamp=True）的输出：带标签的文本和 [token, start, end] 字级时间戳"""
+    timestamps = []
+    for index, ch in enumerate(text):
+        token = "▁" + ch if index == 0 else ch
+        timestamps.append([token, index * 120, index * 120 + 100])
+    return [{"key": "audio", "text": "<|zh|><|NEUTRAL|><|Speech|><|withitn|>" + text, "timestamp": timestamps}]
+
+
+class FakeModel:
+    """记录 generate 参数的假 AutoModel"""
+
+    def __init__(self, punc_model=None, texts=None):
+        self.punc_model = punc_model
+        self.texts = list(texts or [])
+        self.calls = []
+
+    def generate(self, **kwargs):
+        self.calls.append(kwargs)
+        if self.punc_model is not None:
+            return [{"key": "audio", "text": "你好。", "sentence_info": [
+                {"text": "你好。", "start": 100, "end": 900, "timestamp": [[100, 400], [400, 900]]},
+            ]}]
+        return sensevoice_result(self.texts.pop(0))
+
+
+class SenseVoiceTimestampTest(unittest.TestCase):
+    def setUp(self):
+        self._vad_segments = funasr_server.vad_segments
+        funasr_server.vad_segments = lambda audio: [(1000, 2500), (40000, 41200)]
+
+    def tearDown(self):
+        funasr_server.vad_segments = self._vad_segments
+
+    def test_result_segments_with_token_timestamps(self):
+        item = sensevoice_result("你好，世界")[0]
+        segments = funasr_server.result_segments(item, 5000, 10000)
+
+        self.assertEqual(len(segments), 1)
+        self.assertEqual(segments[0]["start_ms"], 5000)
+        self.assertEqual(segments[0]["end_ms"], 5000 + 4 * 120 + 100)
+        # 标点没有单独的词
+        words = segments[0]["words"]
+        self.assertEqual([word["text"] for word in words], ["你", "好", "世", "界"])
+        self.assertEqual(words[2]["start_ms"], 5000 + 3 * 120)
+
+    def test_splits_on_vad_segments(self):
+        model = FakeModel(texts=["今天天气不错", "我们出去走走"])
+        chunk = [0.0] * (60 * SAMPLE_RATE)
+
+        text, segments = funasr_server.transcribe_by_vad(
+            model, chunk, {"input": None, "output_timestamp": True}, 60000
+        )
+
+        # 60 秒的一块按语音段输出两段，而不是一整段
+        self.assertEqual(len(segments), 2)
+        self.assertEqual(segments[0]["start_ms"], 61000)
+        self.assertEqual(segments[1]["start_ms"], 100000)
+        self.assertTrue(segments[1]["text"].endswith("我们出去走走"))
+        self.assertIn("今天天气不错", text)
+        self.assertIn("我们出去走走", text)
+
+        # 每段只把该语音段的音频交给模型
+        self.assertEqual([len(call["input"]) for call in model.calls], [1500 * 16, 1200 * 16])
+
+    def test_timestamp_kwargs_follow_punctuation_model(self):
+        with tempfile.NamedTemporaryFile(suffix=".wav", delete=False) as f:
+            f.write(b"\0" * 4096)
+            path = f.name
+
+        load_model, read_wav = funasr_server.load_model, funasr_server.read_wav_16k
+        funasr_server.read_wav_16k = lambda audio_path: [0.0] * (45 * SAMPLE_RATE)
+        try:
+            # Paraformer（带标点模型）：由 FunASR 按句给出时间戳
+            paraformer = FakeModel(punc_model=object())
+            funasr_server.load_model = lambda name: paraformer
+            response = funasr_server.transcribe_audio(path, "paraformer-zh", timestamps=True)
+            self.assertTrue(paraformer.calls[0].get("sentence_timestamp"))
+            self.assertNotIn("output_timestamp", paraformer.calls[0])
+            self.assertEqual(response["segments"][0]["start_ms"], 100)
+
+            # SenseVoice（没有标点模型）：不传 sentence_timestamp，按 VAD 语音段切分
+            sensevoice = FakeModel(texts=["第一句", "第二句"])
+            funasr_server.load_model = lambda name: sensevoice
+            response = funasr_server.transcribe_audio(path, "sensevoice-small", timestamps=True)
+            self.assertTrue(all("sentence_timestamp" not in call for call in sensevoice.calls))
+            self.assertTrue(all(call.get("output_timestamp") for call in sensevoice.calls))
+            self.assertEqual([segment["start_ms"] for segment in response["segments"]], [1000, 40000])
+        finally:
+            funasr_server.load_model, funasr_server.read_wav_16k = load_model, read_wav
+            os.unlink(path)
+
+
+class ReadRequestsTest(unittest.TestCase):
+    def setUp(self):
+        self._stdin, self._send = sys.stdin, funasr_server.send
+        self.sent = []
+        funasr_server.send = self.sent.append
+
+    def tearDown(self):
+        sys.stdin, funasr_server.send = self._stdin, self._send
+        funasr_server._active_ids.clear()
+        funasr_server._cancelled_ids.clear()
+
+    def test_cancel_only_records_active_requests(self):
+        lines = [
+            {"jsonrpc": "2.0", "id": 1, "method": "transcribe", "params": {}},
+            {"jsonrpc": "2.0", "method": "cancel", "params": {"id": 1}},
+            # 已处理完或从未收到的请求
+            {"jsonrpc": "2.0", "method": "cancel", "params": {"id": 9}},
+        ]
+        sys.stdin = io.StringIO("".join(json.dumps(line) + "\n" for line in lines))
+        requests = queue.Queue()
+
+        funasr_server.read_requests(requests)
+
+        self.assertEqual(requests.get_nowait()[:2], (1, "transcribe"))
+        self.assertEqual(funasr_server._cancelled_ids, {1})
+        # 入队的请求先发送 queued 通知
+        self.assertEqual(self.sent[0], {"method": "queued", "params": {"id": 1}})
+
+
+if __name__ == "__main__":
+    unittest.main()
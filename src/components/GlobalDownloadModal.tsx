@@ -1,4 +1,5 @@
 import React, { useEffect } from 'react'
+import { invoke } from '@tauri-apps/api/core'
 import { useDownloadStore } from '../stores'
 
 export const GlobalDownloadModal: React.FC = () => {
@@ -16,6 +17,14 @@ export const GlobalDownloadModal: React.FC = () => {
     }
   }, [status, resetDownload])
 
+  const handleCancel = () => {
+    if (!modelName) return
+    // 取消后 download_model 会以“下载已取消”失败，已下载部分保留用于续传
+    invoke('cancel_model_download', { modelName }).catch((err) => {
+      console.error('Failed to cancel download:', err)
+    })
+  }
+
   if (!isVisible) return null
 
   return (
@@ -87,6 +96,16 @@ export const GlobalDownloadModal: React.FC = () => {
               <p className="text-xs text-center text-gray-500 mt-4">
                 请勿关闭窗口，下载过程可能需要几分钟...
               </p>
+
+              <div className="flex justify-center">
+                <button
+                  type="button"
+                  className="text-sm text-gray-500 hover:text-gray-700 underline"
+                  onClick={handleCancel}
+                >
+                  取消下载
+                </button>
+              </div>
             </>
           )}
 
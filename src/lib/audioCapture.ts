@@ -6,7 +6,7 @@
  * - 使用 Web Audio API + getUserMedia (确保系统麦克风指示器显示)
  * - 支持实时音频流采集
  * - 支持音频格式转换 (Float32 → PCM16)
- * - 为未来的实时转录功能预留扩展点
+ * - 录音过程中按固定长度输出 PCM16 音频块（供流式识别使用）
  */
 
 export interface AudioCaptureConfig {
@@ -23,6 +23,9 @@ export interface AudioCaptureDevice {
   groupId: string
 }
 
+/** PCM 音频块回调：16kHz 单声道 PCM16 采样 */
+export type PcmChunkHandler = (samples: Int16Array) => void
+
 // 🔑 全局实例跟踪，用于防止泄漏
 const activeInstances = new Set<AudioCapture>()
 
@@ -45,6 +48,10 @@ export class AudioCapture {
   private config: Required<AudioCaptureConfig>
   private _isDestroyed = false // 标记实例是否已销毁
   private _isPrewarmed = false // 标记是否已预热(getUserMedia完成但MediaRecorder未start)
+  private pcmSource: MediaStreamAudioSourceNode | null = null
+  private pcmProcessor: ScriptProcessorNode | null = null
+  private pcmBuffer: Float32Array[] = []
+  private pcmBuffered = 0
 
   constructor(config: AudioCaptureConfig = {}) {
     this.config = {
@@ -341,6 +348,74 @@ export class AudioCapture {
     })
   }
 
+  /**
+   * 开始输出 PCM 音频块（需在 start() 之后调用）
+   * 音频经 AudioContext 重采样到配置的采样率，每攒够 chunkSamples 个采样回调一次
+   * @param onChunk 音频块回调
+   * @param chunkSamples 每块采样数，默认 9600（16kHz 下 600ms）
+   */
+  startPcmTap(onChunk: PcmChunkHandler, chunkSamples = 9600): void {
+    if (!this.stream || !this.audioContext || this.pcmProcessor) {
+      return
+    }
+
+    const audioContext = this.audioContext
+    this.pcmBuffer = []
+    this.pcmBuffered = 0
+    this.pcmSource = audioContext.createMediaStreamSource(this.stream)
+    this.pcmProcessor = audioContext.createScriptProcessor(4096, 1, 1)
+    this.pcmProcessor.onaudioprocess = (event) => {
+      if (this._isDestroyed) return
+
+      this.pcmBuffer.push(new Float32Array(event.inputBuffer.getChannelData(0)))
+      this.pcmBuffered += event.inputBuffer.length
+      if (this.pcmBuffered >= chunkSamples) {
+        onChunk(this.flushPcmBuffer())
+      }
+    }
+
+    // ScriptProcessor 必须连接到输出才会被调度（输出为静音）
+    this.pcmSource.connect(this.pcmProcessor)
+    this.pcmProcessor.connect(audioContext.destination)
+
+    // 快捷键触发时 AudioContext 可能处于挂起状态
+    if (audioContext.state === 'suspended') {
+      void audioContext.resume()
+    }
+    console.log('[AudioCapture] 🎙️ PCM tap started, chunk samples:', chunkSamples)
+  }
+
+  /**
+   * 停止输出 PCM 音频块，未满一块的剩余音频被丢弃
+   */
+  stopPcmTap(): void {
+    if (this.pcmProcessor) {
+      this.pcmProcessor.onaudioprocess = null
+      this.pcmProcessor.disconnect()
+      this.pcmProcessor = null
+    }
+    if (this.pcmSource) {
+      this.pcmSource.disconnect()
+      this.pcmSource = null
+    }
+    this.pcmBuffer = []
+    this.pcmBuffered = 0
+  }
+
+  private flushPcmBuffer(): Int16Array {
+    const merged = new Float32Array(this.pcmBuffered)
+    let offset = 0
+    for (const block of this.pcmBuffer) {
+      merged.set(block, offset)
+      offset += block.length
+    }
+    this.pcmBuffer = []
+    this.pcmBuffered = 0
+
+    const pcm16 = AudioConverter.float32ToPCM16(merged)
+    return new Int16Array(pcm16.buffer, pcm16.byteOffset, pcm16.length / 2)
+  }
+
   /**
    * 取消录制
    */
@@ -389,6 +464,8 @@ export class AudioCapture {
       console.log('[AudioCapture] 🧹 MediaRecorder event handlers cleared')
     }
 
+    this.stopPcmTap()
+
     // 停止所有音频轨道（这会关闭麦克风并隐藏指示器）
     if (this.stream) {
       this.stream.getTracks().forEach((track) => {
@@ -1,6 +1,22 @@
 import { create } from 'zustand'
 import { invoke } from '@tauri-apps/api/core'
 
+export interface WordTiming {
+  text: string
+  start_ms: number
+  end_ms: number
+}
+
+export interface TranscriptionSegment {
+  text: string
+  start_ms: number
+  end_ms: number
+  words?: WordTiming[]
+  speaker?: number | null
+}
+
+export type ExportFormat = 'srt' | 'vtt' | 'json' | 'txt' | 'markdown'
+
 export interface Transcription {
   id?: number
   text: string
@@ -9,20 +25,39 @@ export interface Transcription {
   language: string
   created_at: string
   app_context?: string
+  source_language?: string | null
+  translated?: boolean
+  segments?: TranscriptionSegment[] | null
+  // SenseVoice 识别的情感和音频事件（小写）
+  emotion?: string | null
+  events?: string[]
 }
 
+// 按情感 / 音频事件筛选，两者都为空时不筛选
+export interface TagFilter {
+  emotion: string | null
+  event: string | null
+}
+
+const EMPTY_TAG_FILTER: TagFilter = { emotion: null, event: null }
+
+const isTagFilterActive = (filter: TagFilter) => Boolean(filter.emotion || filter.event)
+
 interface HistoryStore {
   transcriptions: Transcription[]
   loading: boolean
   error: string | null
   searchQuery: string
+  tagFilter: TagFilter
 
   // Actions
   loadRecent: (limit?: number) => Promise<void>
   search: (query: string) => Promise<void>
+  filterByTags: (filter: TagFilter) => Promise<void>
   deleteItem: (id: number) => Promise<void>
   deleteAll: () => Promise<void>
   setSearchQuery: (query: string) => void
+  exportItem: (id: number, format: ExportFormat) => Promise<string>
   refresh: () => Promise<void>
 }
 
@@ -31,6 +66,7 @@ export const useHistoryStore = create<HistoryStore>((set, get) => ({
   loading: false,
   error: null,
   searchQuery: '',
+  tagFilter: EMPTY_TAG_FILTER,
 
   loadRecent: async (limit = 50) => {
     set({ loading: true, error: null })
@@ -45,7 +81,7 @@ export const useHistoryStore = create<HistoryStore>((set, get) => ({
   },
 
   search: async (query: string) => {
-    set({ loading: true, error: null, searchQuery: query })
+    set({ loading: true, error: null, searchQuery: query, tagFilter: EMPTY_TAG_FILTER })
     try {
       if (!query.trim()) {
         // If query is empty, load recent
@@ -62,6 +98,25 @@ export const useHistoryStore = create<HistoryStore>((set, get) => ({
     }
   },
 
+  filterByTags: async (filter: TagFilter) => {
+    set({ loading: true, error: null, searchQuery: '', tagFilter: filter })
+    try {
+      if (!isTagFilterActive(filter)) {
+        await get().loadRecent()
+        return
+      }
+
+      const transcriptions = await invoke<Transcription[]>('filter_transcriptions', {
+        emotion: filter.emotion,
+        event: filter.event,
+        limit: 100,
+      })
+      set({ transcriptions, loading: false })
+    } catch (error) {
+      set({ error: String(error), loading: false })
+    }
+  },
+
   deleteItem: async (id: number) => {
     set({ loading: true, error: null })
     try {
@@ -89,10 +144,17 @@ export const useHistoryStore = create<HistoryStore>((set, get) => ({
     set({ searchQuery: query })
   },
 
+  // 导出到下载目录，返回文件路径
+  exportItem: async (id: number, format: ExportFormat) => {
+    return invoke<string>('export_transcription', { id, format })
+  },
+
   refresh: async () => {
-    const { searchQuery } = get()
+    const { searchQuery, tagFilter } = get()
     if (searchQuery) {
       await get().search(searchQuery)
+    } else if (isTagFilterActive(tagFilter)) {
+      await get().filterByTags(tagFilter)
     } else {
       await get().loadRecent()
     }
@@ -6,6 +6,6 @@ export { useLoadingStore } from './loadingStore'
 export { useDownloadStore } from './downloadStore'
 
 export type { Settings } from './settingsStore'
-export type { RecordingState } from './recordingStore'
-export type { Transcription } from './historyStore'
+export type { RecordingState, LiveTranscript } from './recordingStore'
+export type { Transcription, TagFilter } from './historyStore'
 export type { DownloadStatus } from './downloadStore'
@@ -3,13 +3,42 @@ import { invoke } from '@tauri-apps/api/core'
 import { writeText } from '@tauri-apps/plugin-clipboard-manager'
 import { getCurrentWindow } from '@tauri-apps/api/window'
 import { useSettingsStore } from './settingsStore'
+import type { TranscriptionSegment } from './historyStore'
 import { AudioCapture, AudioConverter } from '../lib/audioCapture'
 import { audioFeedback } from '../lib/audioFeedback'
 import type { InlineToastType } from '../components/InlineToast'
 
+/** 被取消的转录命令返回的错误信息（与后端 jobs::CANCELLED_MESSAGE 一致） */
+const TRANSCRIPTION_CANCELLED = 'Transcription cancelled'
+
+/** 拼接先行送出的段落：两侧都是拉丁字母/数字时补空格 */
+const joinTranscript = (previous: string, next: string): string =>
+  previous && /[A-Za-z0-9]$/.test(previous) && /^[A-Za-z0-9]/.test(next)
+    ? `${previous} ${next}`
+    : previous + next
+
+/** transcribe / finish_streaming_transcription 的返回结果 */
+interface TranscribeResult {
+  text: string
+  source_language: string | null
+  translated: boolean
+  model_type: 'whisper' | 'funasr' | 'remote' | 'onnx' | null
+  model_name: string | null
+  segments?: TranscriptionSegment[]
+  emotion?: string | null
+  events?: string[]
+}
+
 export type RecordingState = 'idle' | 'recording' | 'processing' | 'error'
 export type OperationMode = 'direct' | 'preview'
 
+/** 录音中的流式识别结果（与后端 funasr::StreamingText 一致） */
+export interface LiveTranscript {
+  confirmed: string // 已结束并经整句修正的文本
+  partial: string // 当前句的实时文本，后续可能被修正
+}
+
+const EMPTY_LIVE_TRANSCRIPT: LiveTranscript = { confirmed: '', partial: '' }
 // 气泡提示状态
 export interface ToastState {
   type: InlineToastType
@@ -39,6 +68,12 @@ interface RecordingStore {
   operationMode: OperationMode // 当前操作模式
   audioCapture: AudioCapture | null // 前端音频采集实例
 
+  // 转录进度（由后端 transcription-* 事件驱动）
+  transcriptionJobId: string | null // 当前转录任务 ID
+  transcriptionProgress: number | null // 0-100，null 表示尚无进度
+  partialTranscript: string // 解码过程中先行送出的文本
+  liveTranscript: LiveTranscript // 录音过程中的流式识别结果（FunASR）
+
   // 气泡提示状态
   toast: ToastState | null
   isFirstRecording: boolean // 是否首次录音（用于首次初始化提示）
@@ -53,6 +88,12 @@ interface RecordingStore {
   resetState: () => void
   setOperationMode: (mode: OperationMode) => void
 
+  // Transcription progress actions
+  startTranscriptionJob: (jobId: string) => void
+  setTranscriptionProgress: (jobId: string, percent: number) => void
+  appendPartialTranscript: (jobId: string, text: string) => void
+  setLiveTranscript: (transcript: LiveTranscript) => void
+
   // New text actions
   clearText: () => void
   copyText: () => Promise<void>
@@ -78,6 +119,10 @@ export const useRecordingStore = create<RecordingStore>((set, get) => ({
   audioLevel: 0,
   operationMode: 'preview', // 默认预览模式
   audioCapture: null, // 音频采集实例
+  transcriptionJobId: null,
+  transcriptionProgress: null,
+  partialTranscript: '',
+  liveTranscript: EMPTY_LIVE_TRANSCRIPT,
   toast: null,
   isFirstRecording: true,
   hasShownLongAudioTip: false,
@@ -241,8 +286,27 @@ export const useRecordingStore = create<RecordingStore>((set, get) => ({
         audioCapture: audioCapture,
         isFirstRecording: false, // 标记已完成首次录音
         hasShownLongAudioTip: false, // 重置长音频提示标记
+        liveTranscript: EMPTY_LIVE_TRANSCRIPT,
       })
 
+      // FunASR 流式识别：录音过程中推送音频，实时结果由 streaming-transcript 事件返回
+      // 松开快捷键后优先采用流式识别的修正结果，流式识别失败时改用整段转录，不影响录音
+      const capture = audioCapture
+      invoke<boolean>('start_streaming_transcription')
+        .then((started) => {
+          if (!started) return
+          if (get().state !== 'recording' || get().audioCapture !== capture) {
+            void invoke('stop_streaming_transcription')
+            return
+          }
+          capture.startPcmTap((samples) => {
+            void invoke('push_streaming_audio', { audioData: Array.from(samples) })
+          })
+        })
+        .catch((error) => {
+          console.warn('[RecordingStore] Failed to start streaming transcription:', error)
+        })
+
       // 6. 启动计时器
       const timer = setInterval(() => {
         set((state) => ({
@@ -268,6 +332,8 @@ export const useRecordingStore = create<RecordingStore>((set, get) => ({
   },
 
   stopRecording: async () => {
+    // 转录命令是否已发起：之前出错时需通知后端撤销快捷键预留的转录任务
+    let transcribeInvoked = false
     try {
       console.log('[RecordingStore] ⭐⭐⭐ stopRecording called, current state:', get().state)
 
@@ -277,9 +343,23 @@ export const useRecordingStore = create<RecordingStore>((set, get) => ({
         delete window.__recordingTimer
       }
 
+      // 结束流式识别：后端识别剩余音频并返回句末修正后的全文（没有会话或失败时为 null）
+      get().audioCapture?.stopPcmTap()
+      const streamingResult = invoke<TranscribeResult | null>('finish_streaming_transcription').catch(
+        (error) => {
+          console.warn('[RecordingStore] Failed to finish streaming transcription:', error)
+          return null
+        },
+      )
+
       const recordingDuration = get().duration
       console.log('[RecordingStore] 🔵 Setting state to PROCESSING (before transcription)')
-      set({ state: 'processing' })
+      set({
+        state: 'processing',
+        transcriptionJobId: null,
+        transcriptionProgress: null,
+        partialTranscript: '',
+      })
       console.log('[RecordingStore] 🔵 State set to PROCESSING, new state:', get().state)
 
       // 检查录音时长，如果超过20秒且未显示过提示，则显示长音频提示
@@ -357,67 +437,41 @@ export const useRecordingStore = create<RecordingStore>((set, get) => ({
         settings.language,
       )
 
-      // 判断模型类型
-      const funasrModels = ['paraformer-zh', 'paraformer-large', 'sensevoice-small']
-      const isFunASR = funasrModels.includes(modelVersion)
-
-      let transcriptionText: string
-
-      // 4. 调用转录
-      if (isFunASR) {
-        // 🔥 缓存检查：仅在模型变化时才重新初始化
-        const cachedModel = engineInitCache['funasr']
-        if (cachedModel !== modelVersion) {
-          console.log(
-            '[RecordingStore] Step 5: Initializing FunASR engine with model:',
-            modelVersion,
-          )
-          console.log('[RecordingStore] Previous cached model:', cachedModel || 'none')
-          try {
-            await invoke('initialize_funasr', { modelName: modelVersion })
-            engineInitCache['funasr'] = modelVersion // 更新缓存
-            console.log('[RecordingStore] ✅ FunASR engine initialized and cached')
-          } catch (error) {
-            console.error('[RecordingStore] Failed to initialize FunASR engine:', error)
-          }
-        } else {
-          console.log('[RecordingStore] ⚡ Using cached FunASR engine (model:', modelVersion, ')')
+      // 4. 调用转录（后端按模型类型选择 Whisper / FunASR）
+      // 🔥 缓存检查：仅在模型变化时才重新初始化
+      const cachedModel = engineInitCache['engine']
+      if (cachedModel !== modelVersion) {
+        console.log('[RecordingStore] Step 5: Initializing engine with model:', modelVersion)
+        console.log('[RecordingStore] Previous cached model:', cachedModel || 'none')
+        try {
+          await invoke('initialize_engine', { modelName: modelVersion })
+          engineInitCache['engine'] = modelVersion // 更新缓存
+          console.log('[RecordingStore] ✅ Engine initialized and cached')
+        } catch (error) {
+          console.error('[RecordingStore] Failed to initialize engine:', error)
         }
-
-        // 调用新的转录命令（接收前端音频数据）
-        console.log(
-          '[RecordingStore] Step 6: Calling transcribe_audio_funasr with frontend audio data...',
-        )
-        transcriptionText = await invoke<string>('transcribe_audio_funasr', {
-          audioData: Array.from(pcm16Samples),
-          language: language,
-        })
       } else {
-        // 🔥 缓存检查：仅在模型变化时才重新初始化
-        const cachedModel = engineInitCache['whisper']
-        if (cachedModel !== modelVersion) {
-          console.log(
-            '[RecordingStore] Step 5: Initializing Whisper engine with model:',
-            modelVersion,
-          )
-          console.log('[RecordingStore] Previous cached model:', cachedModel || 'none')
-          try {
-            await invoke('initialize_whisper', { modelName: modelVersion })
-            engineInitCache['whisper'] = modelVersion // 更新缓存
-            console.log('[RecordingStore] ✅ Whisper engine initialized and cached')
-          } catch (error) {
-            console.error('[RecordingStore] Failed to initialize Whisper engine:', error)
-          }
-        } else {
-          console.log('[RecordingStore] ⚡ Using cached Whisper engine (model:', modelVersion, ')')
-        }
+        console.log('[RecordingStore] ⚡ Using cached engine (model:', modelVersion, ')')
+      }
 
-        // 调用新的转录命令（接收前端音频数据）
-        console.log('[RecordingStore] Step 6: Calling transcribe_audio with frontend audio data...')
-        transcriptionText = await invoke<string>('transcribe_audio', {
+      // 调用统一转录命令（接收前端音频数据）
+      console.log('[RecordingStore] Step 6: Calling transcribe with frontend audio data...')
+      // 流式结果同样以转录任务完成，两条路径都会接管快捷键预留的任务
+      transcribeInvoked = true
+      // FunASR 流式识别的修正结果可用时直接采用，否则整段转录
+      const result =
+        (await streamingResult) ??
+        (await invoke<TranscribeResult>('transcribe', {
           audioData: Array.from(pcm16Samples),
           language: language,
-        })
+        }))
+      const transcriptionText = result.text
+      const sourceLanguage = result.source_language
+      const translated = result.translated
+      // 当前模型失败时后端会改用备用模型，记录实际使用的模型
+      const usedModel = result.model_name ?? modelVersion
+      if (usedModel !== modelVersion) {
+        console.warn('[RecordingStore] ↪️ Transcribed with fallback model:', usedModel)
       }
 
       console.log('[RecordingStore] ✅ Transcription result:', transcriptionText)
@@ -430,10 +484,17 @@ export const useRecordingStore = create<RecordingStore>((set, get) => ({
         transcription: {
           text: transcriptionText,
           audio_duration: recordingDuration,
-          model_version: modelVersion,
+          model_version: usedModel,
           language: language || 'auto',
           created_at: new Date().toISOString(),
           app_context: null,
+          source_language: sourceLanguage,
+          translated: translated,
+          // 引擎顺带给出的时间戳段落，导出字幕时使用
+          segments: result.segments?.length ? result.segments : null,
+          // SenseVoice 识别的情感和音频事件，用于历史筛选
+          emotion: result.emotion ?? null,
+          events: result.events ?? [],
         },
       })
 
@@ -493,6 +554,21 @@ export const useRecordingStore = create<RecordingStore>((set, get) => ({
         })
       }
     } catch (error) {
+      if (!transcribeInvoked) {
+        invoke('abandon_recording').catch((err) => {
+          console.warn('[RecordingStore] Failed to abandon recording:', err)
+        })
+      }
+
+      // 用户主动取消：不提示错误，直接回到空闲并隐藏窗口
+      if (String(error) === TRANSCRIPTION_CANCELLED) {
+        console.log('[RecordingStore] Transcription cancelled by user')
+        get().clearToast()
+        set({ state: 'idle', error: null, transcribedText: '', duration: 0, audioLevel: 0 })
+        await getCurrentWindow().hide()
+        return
+      }
+
       console.error('[RecordingStore] Transcription error:', error)
 
       // 根据操作模式处理错误（直接从 settingsStore 读取，确保同步）
@@ -531,6 +607,7 @@ export const useRecordingStore = create<RecordingStore>((set, get) => ({
     if (audioCapture) {
       audioCapture.cancel()
     }
+    void invoke('stop_streaming_transcription')
 
     set({
       state: 'idle',
@@ -540,6 +617,7 @@ export const useRecordingStore = create<RecordingStore>((set, get) => ({
       error: null,
       audioLevel: 0,
       audioCapture: null,
+      liveTranscript: EMPTY_LIVE_TRANSCRIPT,
     })
   },
 
@@ -555,6 +633,7 @@ export const useRecordingStore = create<RecordingStore>((set, get) => ({
       transcribedText: '',
       error: null,
       audioLevel: 0,
+      liveTranscript: EMPTY_LIVE_TRANSCRIPT,
     })
   },
 
@@ -562,6 +641,30 @@ export const useRecordingStore = create<RecordingStore>((set, get) => ({
     set({ operationMode: mode })
   },
 
+  // 转录进度：只接受处理中状态下最新启动的任务，忽略已被取消任务的迟到事件
+  startTranscriptionJob: (jobId: string) => {
+    if (get().state !== 'processing') return
+    set({ transcriptionJobId: jobId, transcriptionProgress: null, partialTranscript: '' })
+  },
+
+  setTranscriptionProgress: (jobId: string, percent: number) => {
+    if (get().state !== 'processing' || get().transcriptionJobId !== jobId) return
+    set({ transcriptionProgress: Math.max(0, Math.min(100, percent)) })
+  },
+
+  appendPartialTranscript: (jobId: string, text: string) => {
+    if (get().state !== 'processing' || get().transcriptionJobId !== jobId) return
+    set((state) => ({
+      partialTranscript: joinTranscript(state.partialTranscript, text),
+    }))
+  },
+
+  // 流式识别结果只在录音中接受，忽略停止后迟到的事件
+  setLiveTranscript: (transcript: LiveTranscript) => {
+    if (get().state !== 'recording') return
+    set({ liveTranscript: { confirmed: transcript.confirmed, partial: transcript.partial } })
+  },
+
   // New text actions
   setTranscribedText: (text: string) => {
     set({ transcribedText: text })
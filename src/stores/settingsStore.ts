@@ -6,14 +6,8 @@ import { emit } from '@tauri-apps/api/event'
 export interface Settings {
   [key: string]: any // 添加索引签名
   language: string
-  model:
-    | 'base'
-    | 'small'
-    | 'medium'
-    | 'large'
-    | 'paraformer-zh'
-    | 'paraformer-large'
-    | 'sensevoice-small'
+  /** Whisper 模型名（见后端模型目录，如 'base'、'large-v3-turbo-q5_0'）或 FunASR 模型名 */
+  model: string
   shortcut: string
   microphone: string
   theme: 'light' | 'dark' | 'auto'
@@ -22,6 +16,16 @@ export interface Settings {
   notifications: boolean
   autoDetectLanguage: boolean
   operationMode: 'direct' | 'preview'
+  /** 模型空闲多少分钟后释放内存，0 表示常驻 */
+  idleUnloadMinutes: number
+  /** 按下快捷键时预先加载已释放的模型 */
+  prewarmOnShortcut: boolean
+  /** 当前模型失败时依次尝试的备用模型 */
+  fallbackModels: string[]
+  /** 翻译为英文的快捷键，留空不启用 */
+  translateShortcut: string
+  /** 自定义 FunASR 脚本目录，留空使用应用内置脚本 */
+  funasrScriptsDir: string
 }
 
 interface SettingsStore {
@@ -46,6 +50,11 @@ const defaultSettings: Settings = {
   notifications: true,
   autoDetectLanguage: false, // 默认关闭自动检测，强制使用中文
   operationMode: 'preview',
+  idleUnloadMinutes: 10,
+  prewarmOnShortcut: true,
+  fallbackModels: ['small'],
+  translateShortcut: '',
+  funasrScriptsDir: '',
 }
 
 export const useSettingsStore = create<SettingsStore>()(
@@ -1,12 +1,68 @@
 import React, { useMemo } from 'react'
 import { useHistoryStore, useSettingsStore } from '../../stores'
+import type { ExportFormat, TagFilter } from '../../stores/historyStore'
+import { useToast } from '../../components'
 import { format, isToday, parseISO } from 'date-fns'
 import { zhCN } from 'date-fns/locale'
 import { getShortcutDisplayParts } from '../../utils/shortcutFormatter'
+import { ImportAudioPanel } from './ImportAudioPanel'
+
+// SenseVoice 情感和音频事件标签的显示名称
+const EMOTION_LABELS: Record<string, string> = {
+  happy: '😊 开心',
+  sad: '😢 悲伤',
+  angry: '😠 生气',
+  neutral: '😐 平静',
+  fearful: '😨 害怕',
+  disgusted: '🤢 厌恶',
+  surprised: '😮 惊讶',
+}
+
+const EVENT_LABELS: Record<string, string> = {
+  bgm: '🎵 背景音乐',
+  applause: '👏 掌声',
+  laughter: '😄 笑声',
+  cry: '😭 哭声',
+  sneeze: '🤧 喷嚏',
+  breath: '💨 呼吸',
+  cough: '😷 咳嗽',
+}
 
 export const HomePage: React.FC = () => {
-  const { transcriptions } = useHistoryStore()
+  const { transcriptions, exportItem, tagFilter, filterByTags } = useHistoryStore()
   const { settings } = useSettingsStore()
+  const toast = useToast()
+
+  const exportFormats: { value: ExportFormat; label: string }[] = [
+    { value: 'srt', label: 'SRT 字幕' },
+    { value: 'vtt', label: 'WebVTT 字幕' },
+    { value: 'txt', label: '文本（带时间）' },
+    { value: 'markdown', label: 'Markdown' },
+    { value: 'json', label: 'JSON' },
+  ]
+
+  const handleExport = async (id: number, format: ExportFormat) => {
+    try {
+      const path = await exportItem(id, format)
+      toast.success(`已导出到 ${path}`)
+    } catch (error) {
+      toast.error(`导出失败: ${String(error)}`)
+      console.error('Failed to export transcription:', error)
+    }
+  }
+
+  // 点击标签筛选，再次点击同一标签取消
+  const toggleTagFilter = (filter: Partial<TagFilter>) => {
+    const next: TagFilter = { emotion: null, event: null, ...filter }
+    const same = next.emotion === tagFilter.emotion && next.event === tagFilter.event
+    void filterByTags(same ? { emotion: null, event: null } : next)
+  }
+
+  const activeTagLabel = tagFilter.emotion
+    ? EMOTION_LABELS[tagFilter.emotion] ?? tagFilter.emotion
+    : tagFilter.event
+      ? EVENT_LABELS[tagFilter.event] ?? tagFilter.event
+      : null
 
   // 筛选今天的转录记录
   const todayTranscriptions = useMemo(() => {
@@ -56,10 +112,23 @@ export const HomePage: React.FC = () => {
         </p>
       </div>
 
+      {/* 导入音频文件转录 */}
+      <ImportAudioPanel />
+
       {/* 今日转录历史 */}
       <div className="flex-1 flex flex-col min-h-0">
         {/* 固定的标题 */}
-        <h3 className="text-lg font-semibold text-gray-700 mb-4 flex-shrink-0">今天</h3>
+        <div className="flex items-center gap-3 mb-4 flex-shrink-0">
+          <h3 className="text-lg font-semibold text-gray-700">今天</h3>
+          {activeTagLabel && (
+            <button
+              className="text-xs px-2 py-1 rounded-full bg-blue-50 text-blue-700 hover:bg-blue-100"
+              onClick={() => void filterByTags({ emotion: null, event: null })}
+            >
+              {activeTagLabel} ✕
+            </button>
+          )}
+        </div>
 
         {/* 可滚动的列表区域 */}
         <div className="flex-1 overflow-y-auto min-h-0">
@@ -76,8 +145,49 @@ export const HomePage: React.FC = () => {
                   key={item.id || index}
                   className="p-4 bg-white border border-gray-200 rounded-lg hover:border-gray-300 hover:shadow-sm transition-all"
                 >
-                  <div className="text-xs text-gray-500 mb-2">{formatTime(item.created_at)}</div>
+                  <div className="flex items-center justify-between mb-2">
+                    <div className="text-xs text-gray-500">{formatTime(item.created_at)}</div>
+                    {item.id !== undefined && (
+                      <select
+                        className="text-xs text-gray-500 bg-transparent border-none cursor-pointer"
+                        value=""
+                        onChange={(e) =>
+                          void handleExport(item.id as number, e.target.value as ExportFormat)
+                        }
+                      >
+                        <option value="" disabled>
+                          导出
+                        </option>
+                        {exportFormats.map((opt) => (
+                          <option key={opt.value} value={opt.value}>
+                            {opt.label}
+                          </option>
+                        ))}
+                      </select>
+                    )}
+                  </div>
                   <p className="text-gray-900 leading-relaxed whitespace-pre-wrap">{item.text}</p>
+                  {((item.emotion && item.emotion !== 'neutral') || !!item.events?.length) && (
+                    <div className="mt-2 flex flex-wrap gap-1">
+                      {item.emotion && item.emotion !== 'neutral' && (
+                        <button
+                          className="text-xs px-2 py-0.5 rounded-full bg-amber-50 text-amber-700 hover:bg-amber-100"
+                          onClick={() => toggleTagFilter({ emotion: item.emotion ?? null })}
+                        >
+                          {EMOTION_LABELS[item.emotion] ?? item.emotion}
+                        </button>
+                      )}
+                      {item.events?.map((event) => (
+                        <button
+                          key={event}
+                          className="text-xs px-2 py-0.5 rounded-full bg-purple-50 text-purple-700 hover:bg-purple-100"
+                          onClick={() => toggleTagFilter({ event })}
+                        >
+                          {EVENT_LABELS[event] ?? event}
+                        </button>
+                      ))}
+                    </div>
+                  )}
                   {item.app_context && (
                     <div className="mt-2 text-xs text-gray-400">来自: {item.app_context}</div>
                   )}
@@ -0,0 +1,164 @@
+import React, { useEffect, useRef, useState } from 'react'
+import { invoke } from '@tauri-apps/api/core'
+import { getCurrentWebview } from '@tauri-apps/api/webview'
+import { useHistoryStore, useSettingsStore } from '../../stores'
+import type { TranscriptionSegment } from '../../stores/historyStore'
+import { Button, Toggle, useToast } from '../../components'
+
+// 与后端 read_audio_file 支持的格式保持一致
+const SUPPORTED_EXTENSIONS = ['.wav']
+
+const isSupportedAudio = (path: string) =>
+  SUPPORTED_EXTENSIONS.some((ext) => path.toLowerCase().endsWith(ext))
+
+// 开启说话人分离时在每段前标注说话人
+const segmentsToText = (segments: TranscriptionSegment[]) => {
+  const labelled = segments.some((segment) => segment.speaker != null)
+  return segments
+    .map((segment) =>
+      labelled && segment.speaker != null
+        ? `说话人 ${segment.speaker + 1}: ${segment.text}`
+        : segment.text,
+    )
+    .join('\n')
+}
+
+/**
+ * 导入音频文件转录
+ *
+ * 拖入或填写 WAV 文件路径，按当前模型转录长音频，可选标注说话人；
+ * 结果带时间戳保存到历史记录，可直接导出字幕
+ */
+export const ImportAudioPanel: React.FC = () => {
+  const { settings } = useSettingsStore()
+  const refreshHistory = useHistoryStore((state) => state.refresh)
+  const toast = useToast()
+  // useToast 每次渲染返回新对象，拖放监听里通过 ref 使用
+  const toastRef = useRef(toast)
+  toastRef.current = toast
+
+  const [filePath, setFilePath] = useState('')
+  const [diarize, setDiarize] = useState(false)
+  // 空字符串表示自动判断说话人数
+  const [maxSpeakers, setMaxSpeakers] = useState('')
+  const [transcribing, setTranscribing] = useState(false)
+
+  // 拖入文件时直接取得本地路径
+  useEffect(() => {
+    const unlisten = getCurrentWebview().onDragDropEvent((event) => {
+      if (event.payload.type !== 'drop') {
+        return
+      }
+      const path = event.payload.paths.find(isSupportedAudio)
+      if (path) {
+        setFilePath(path)
+      } else if (event.payload.paths.length > 0) {
+        toastRef.current.error('仅支持 WAV 音频文件')
+      }
+    })
+
+    return () => {
+      void unlisten.then((fn) => fn())
+    }
+  }, [])
+
+  const handleTranscribe = async () => {
+    const path = filePath.trim()
+    if (!path) {
+      return
+    }
+
+    const speakerLimit = Number.parseInt(maxSpeakers, 10)
+    const language = settings.autoDetectLanguage ? null : settings.language || 'zh'
+
+    setTranscribing(true)
+    try {
+      const segments = await invoke<TranscriptionSegment[]>('transcribe_audio_file', {
+        filePath: path,
+        language,
+        diarize,
+        maxSpeakers: diarize && speakerLimit > 0 ? speakerLimit : null,
+      })
+
+      await invoke('create_transcription', {
+        transcription: {
+          text: segmentsToText(segments),
+          audio_duration: segments.length ? segments[segments.length - 1].end_ms / 1000 : 0,
+          model_version: settings.model || 'base',
+          language: language || 'auto',
+          created_at: new Date().toISOString(),
+          app_context: path,
+          source_language: null,
+          translated: false,
+          segments: segments.length ? segments : null,
+          emotion: null,
+          events: [],
+        },
+      })
+      await refreshHistory()
+
+      toast.success(`转录完成，共 ${segments.length} 段`)
+      setFilePath('')
+    } catch (error) {
+      toast.error(`转录失败: ${String(error)}`)
+      console.error('[ImportAudioPanel] Failed to transcribe file:', error)
+    } finally {
+      setTranscribing(false)
+    }
+  }
+
+  const handleCancel = () => {
+    invoke('cancel_transcription', { jobId: null }).catch((err) => {
+      console.warn('[ImportAudioPanel] Failed to cancel transcription:', err)
+    })
+  }
+
+  return (
+    <div className="flex-shrink-0 mb-8 p-4 bg-white border border-dashed border-gray-300 rounded-xl">
+      <h3 className="text-sm font-semibold text-gray-700 mb-3">导入音频</h3>
+      <div className="flex items-center gap-2">
+        <input
+          className="flex-1 px-3 py-2 text-sm border border-gray-300 rounded-lg focus:outline-none focus:ring-2 focus:ring-blue-500"
+          placeholder="拖入 WAV 文件，或填写文件路径"
+          value={filePath}
+          disabled={transcribing}
+          onChange={(e) => setFilePath(e.target.value)}
+        />
+        {transcribing ? (
+          <Button variant="secondary" size="sm" onClick={handleCancel}>
+            取消
+          </Button>
+        ) : (
+          <Button
+            size="sm"
+            disabled={!filePath.trim()}
+            onClick={() => void handleTranscribe()}
+          >
+            转录
+          </Button>
+        )}
+      </div>
+      <div className="mt-3 flex items-center gap-3 text-sm text-gray-700">
+        <Toggle checked={diarize} onChange={setDiarize} disabled={transcribing} />
+        <span>区分说话人</span>
+        {diarize && (
+          <label className="flex items-center gap-2 text-gray-500">
+            说话人数
+            <input
+              type="number"
+              min={1}
+              className="w-20 px-2 py-1 border border-gray-300 rounded-lg"
+              placeholder="自动"
+              value={maxSpeakers}
+              disabled={transcribing}
+              onChange={(e) => setMaxSpeakers(e.target.value)}
+            />
+          </label>
+        )}
+      </div>
+      {diarize && (
+        <p className="mt-2 text-xs text-gray-400">说话人分离需要 FunASR 环境；已知人数时填写可减少误分</p>
+      )}
+    </div>
+  )
+}
@@ -164,7 +164,7 @@ export const MainWindow: React.FC = () => {
 
           try {
             // 初始化 FunASR 引擎
-            await invoke('initialize_funasr', { modelName: currentModel })
+            await invoke('initialize_engine', { modelName: currentModel })
             console.log('[MainWindow] ✅ FunASR 引擎初始化成功')
 
             // 预热模型
@@ -178,7 +178,7 @@ export const MainWindow: React.FC = () => {
           }
         } else {
           // Whisper 模型：直接初始化
-          await invoke('initialize_whisper', { modelName: currentModel })
+          await invoke('initialize_engine', { modelName: currentModel })
           console.log('[MainWindow] ✅ Whisper 模型初始化完成')
         }
 
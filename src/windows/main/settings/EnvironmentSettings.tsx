@@ -2,7 +2,7 @@ import React, { useEffect, useState, useCallback } from 'react'
 import { invoke } from '@tauri-apps/api/core'
 import { listen } from '@tauri-apps/api/event'
 import { useSettingsStore } from '../../../stores'
-import { Button } from '../../../components'
+import { Button, Input } from '../../../components'
 import { useToast } from '../../../components'
 
 interface PermissionStatus {
@@ -10,6 +10,21 @@ interface PermissionStatus {
   accessibility: boolean | 'checking'
 }
 
+interface FunASRScriptStatus {
+  name: string
+  path: string | null
+  source: 'override' | 'resource' | 'source' | null
+  // 内容与应用内置版本一致
+  verified: boolean
+  error: string | null
+}
+
+const SCRIPT_SOURCE_LABELS: Record<string, string> = {
+  override: '自定义目录',
+  resource: '应用内置',
+  source: '源码目录',
+}
+
 interface PythonEnvStatus {
   status: 'checking' | 'ready' | 'missing' | 'error' | 'prewarmed'
   message: string
@@ -17,7 +32,7 @@ interface PythonEnvStatus {
 }
 
 export const EnvironmentSettings: React.FC = () => {
-  const { settings } = useSettingsStore()
+  const { settings, updateSetting } = useSettingsStore()
   const toast = useToast()
   const [permissionStatus, setPermissionStatus] = useState<PermissionStatus>({
     microphone: 'checking',
@@ -27,9 +42,38 @@ export const EnvironmentSettings: React.FC = () => {
     status: 'checking',
     message: '正在检查Python环境...',
   })
+  const [scriptStatus, setScriptStatus] = useState<FunASRScriptStatus[]>([])
+  const [scriptsDir, setScriptsDir] = useState<string>(settings.funasrScriptsDir ?? '')
   const [isCheckingMic, setIsCheckingMic] = useState(false)
   const [isCheckingAccessibility, setIsCheckingAccessibility] = useState(false)
 
+  // 检查 FunASR 脚本能否找到、是否完整
+  const checkScripts = useCallback(async () => {
+    try {
+      setScriptStatus(await invoke<FunASRScriptStatus[]>('check_funasr_scripts'))
+    } catch (error) {
+      console.error('Failed to check FunASR scripts:', error)
+    }
+  }, [])
+
+  useEffect(() => {
+    void checkScripts()
+  }, [checkScripts])
+
+  useEffect(() => {
+    setScriptsDir(settings.funasrScriptsDir ?? '')
+  }, [settings.funasrScriptsDir])
+
+  const handleSaveScriptsDir = async () => {
+    try {
+      await updateSetting('funasrScriptsDir', scriptsDir.trim())
+      await checkScripts()
+      toast.success(scriptsDir.trim() ? '脚本目录已保存，重启应用后生效' : '已恢复使用应用内置脚本')
+    } catch (error) {
+      toast.error(`保存失败: ${String(error)}`)
+    }
+  }
+
   // 检查麦克风权限
   const checkMicrophonePermission = useCallback(async () => {
     try {
@@ -123,7 +167,7 @@ export const EnvironmentSettings: React.FC = () => {
 
         try {
           // 触发检查（通过重新初始化）
-          await invoke('initialize_funasr', { modelName: settings.model })
+          await invoke('initialize_engine', { modelName: settings.model })
           clearTimeout(timeoutId)
           // 状态会通过事件监听器更新
           // 如果没有收到事件，超时会自动设置为 ready 状态
@@ -411,7 +455,7 @@ export const EnvironmentSettings: React.FC = () => {
                             settings.model,
                           )
                         ) {
-                          await invoke('initialize_funasr', { modelName: settings.model })
+                          await invoke('initialize_engine', { modelName: settings.model })
                           toast.success('Python 环境初始化成功')
                           await checkPythonEnvironment()
                         }
@@ -427,6 +471,45 @@ export const EnvironmentSettings: React.FC = () => {
             </div>
           </div>
         </div>
+
+        {/* FunASR 脚本 */}
+        <div className="p-4 bg-gray-50 rounded-lg border border-gray-200 space-y-3">
+          <div className="flex items-center gap-2">
+            <span className="text-2xl">
+              {scriptStatus.length > 0 && scriptStatus.every((script) => !script.error) ? '✅' : '⚠️'}
+            </span>
+            <h4 className="font-semibold text-gray-900">FunASR 脚本</h4>
+          </div>
+          <div className="space-y-1">
+            {scriptStatus.map((script) => (
+              <p key={script.name} className="text-xs text-gray-600">
+                <span className="font-medium">{script.name}</span>
+                {script.error ? (
+                  <span className="text-red-600 ml-2">{script.error}</span>
+                ) : (
+                  <span className="ml-2">
+                    {SCRIPT_SOURCE_LABELS[script.source ?? ''] ?? script.source}
+                    {!script.verified && <span className="text-amber-600">（已修改）</span>}
+                    <span className="text-gray-400 ml-2 break-all">{script.path}</span>
+                  </span>
+                )}
+              </p>
+            ))}
+          </div>
+          <div className="flex items-end gap-2">
+            <div className="flex-1">
+              <Input
+                label="自定义脚本目录"
+                placeholder="留空使用应用内置脚本"
+                value={scriptsDir}
+                onChange={(e) => setScriptsDir(e.target.value)}
+              />
+            </div>
+            <Button variant="secondary" size="sm" onClick={() => void handleSaveScriptsDir()}>
+              保存
+            </Button>
+          </div>
+        </div>
       </div>
 
       {/* 整体状态总结 */}
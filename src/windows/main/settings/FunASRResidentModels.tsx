@@ -0,0 +1,129 @@
+import React, { useEffect, useState } from 'react'
+import { invoke } from '@tauri-apps/api/core'
+import { Button } from '../../../components'
+import { useToast } from '../../../components'
+
+// 与后端 funasr::ResidentModel 一致
+interface ResidentModel {
+  name: string
+  // 模型参数占用的内存，服务器无法计算时为加载前后常驻内存之差（近似值）
+  memory_mb: number | null
+  load_ms: number
+}
+
+interface FunASRResidentModelsProps {
+  // 已下载的 FunASR 模型，可预加载
+  downloadedModels: string[]
+}
+
+/**
+ * FunASR 常驻模型
+ *
+ * 显示服务器中已加载的模型及其内存占用，可预加载模型避免首次转录等待，
+ * 也可手动释放不再使用的模型
+ */
+export const FunASRResidentModels: React.FC<FunASRResidentModelsProps> = ({ downloadedModels }) => {
+  const toast = useToast()
+  const [resident, setResident] = useState<ResidentModel[]>([])
+  // 正在预加载或释放的模型
+  const [busyModel, setBusyModel] = useState<string | null>(null)
+
+  const loadResident = async () => {
+    try {
+      setResident(await invoke<ResidentModel[]>('get_funasr_resident_models'))
+    } catch (error) {
+      console.error('[FunASRResidentModels] Failed to load resident models:', error)
+    }
+  }
+
+  useEffect(() => {
+    void loadResident()
+    // eslint-disable-next-line react-hooks/exhaustive-deps
+  }, [])
+
+  const handlePreload = async (modelName: string) => {
+    setBusyModel(modelName)
+    try {
+      const model = await invoke<ResidentModel>('preload_funasr_model', { modelName })
+      toast.success(`${modelName.toUpperCase()} 已加载（${(model.load_ms / 1000).toFixed(1)} 秒）`)
+    } catch (error) {
+      toast.error(`加载失败: ${String(error)}`)
+    } finally {
+      setBusyModel(null)
+      await loadResident()
+    }
+  }
+
+  const handleUnload = async (modelName: string) => {
+    setBusyModel(modelName)
+    try {
+      await invoke<boolean>('unload_funasr_model', { modelName })
+      toast.success(`${modelName.toUpperCase()} 已释放`)
+    } catch (error) {
+      toast.error(`释放失败: ${String(error)}`)
+    } finally {
+      setBusyModel(null)
+      await loadResident()
+    }
+  }
+
+  const residentNames = resident.map((model) => model.name)
+  const preloadable = downloadedModels.filter((name) => !residentNames.includes(name))
+
+  return (
+    <div className="p-4 bg-gray-50 rounded-lg space-y-3">
+      <div>
+        <div className="font-medium text-gray-900">FunASR 常驻模型</div>
+        <div className="text-sm text-gray-500 mt-1">
+          已加载的模型常驻内存，转录时无需再次加载；识别模型超出上限时自动释放最久未用的
+        </div>
+      </div>
+
+      {resident.length === 0 ? (
+        <div className="text-sm text-gray-500">暂无已加载的模型</div>
+      ) : (
+        <div className="space-y-2">
+          {resident.map((model) => (
+            <div
+              key={model.name}
+              className="p-3 bg-white rounded-lg flex items-center justify-between"
+            >
+              <div>
+                <span className="font-medium text-gray-900">{model.name.toUpperCase()}</span>
+                <span className="text-sm text-gray-500 ml-2">
+                  {model.memory_mb != null ? `约 ${Math.round(model.memory_mb)}MB，` : ''}
+                  加载 {(model.load_ms / 1000).toFixed(1)} 秒
+                </span>
+              </div>
+              <Button
+                variant="secondary"
+                size="sm"
+                disabled={busyModel !== null}
+                onClick={() => void handleUnload(model.name)}
+              >
+                释放
+              </Button>
+            </div>
+          ))}
+        </div>
+      )}
+
+      {preloadable.length > 0 && (
+        <div className="flex flex-wrap items-center gap-2">
+          <span className="text-sm text-gray-700">预加载：</span>
+          {preloadable.map((name) => (
+            <Button
+              key={name}
+              variant="secondary"
+              size="sm"
+              disabled={busyModel !== null}
+              onClick={() => void handlePreload(name)}
+            >
+              {busyModel === name ? '加载中...' : name.toUpperCase()}
+            </Button>
+          ))}
+        </div>
+      )}
+    </div>
+  )
+}
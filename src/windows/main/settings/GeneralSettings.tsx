@@ -6,6 +6,7 @@ import { useToast } from '../../../components'
 import { getShortcutDisplayParts } from '../../../utils/shortcutFormatter'
 import { useShortcutRecorder } from '../../../hooks'
 import { validateShortcut, getValidationMessage } from '../../../utils/shortcutValidator'
+import { OutputModeSettings } from './OutputModeSettings'
 
 interface AudioDevice {
   id: string
@@ -253,6 +254,9 @@ export const GeneralSettings: React.FC = () => {
         <p className="text-xs text-gray-500 mt-2">💡 提示：更改语言后需要重启应用才能完全生效</p>
       </div>
 
+      {/* 输出模式 */}
+      <OutputModeSettings />
+
       {/* 快捷键录制对话框 */}
       {showShortcutDialog && (
         <div className="fixed inset-0 z-50 flex items-center justify-center p-4">
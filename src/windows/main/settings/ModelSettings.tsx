@@ -4,11 +4,14 @@ import { listen } from '@tauri-apps/api/event'
 import { useSettingsStore, useDownloadStore } from '../../../stores'
 import { RadioGroup, RadioOption, Button } from '../../../components'
 import { useToast } from '../../../components'
+import { RemoteEngineSettings } from './RemoteEngineSettings'
+import { FunASRResidentModels } from './FunASRResidentModels'
 
-type ModelType = 'small' | 'base' | 'medium' | 'large'
+type ModelType = string
 
 interface ModelInfo {
   name: ModelType
+  engine: 'whisper' | 'funasr' | 'remote' | 'onnx'
   size: string
   size_bytes: number
   speed: string
@@ -16,8 +19,18 @@ interface ModelInfo {
   is_recommended: boolean
   is_downloaded: boolean
   download_url: string
+  description?: string
+  ram_mb?: number
+  sha256?: string
+  english_only?: boolean
+  // 已下载文件实际占用的磁盘空间
+  disk_bytes?: number
 }
 
+// 磁盘占用（MB / GB）
+const formatDiskSize = (bytes: number) =>
+  bytes >= 1024 ** 3 ? `${(bytes / 1024 ** 3).toFixed(1)}GB` : `${Math.round(bytes / 1024 ** 2)}MB`
+
 interface DownloadProgress {
   model_name?: string
   progress: number
@@ -80,7 +93,11 @@ export const ModelSettings: React.FC = () => {
     if (!model) return
 
     if (!model.is_downloaded) {
-      toast.warning('请先下载该模型')
+      if (model.engine === 'remote') {
+        toast.warning('请先配置远程引擎')
+      } else {
+        toast.warning('请先下载该模型')
+      }
       return
     }
 
@@ -92,6 +109,15 @@ export const ModelSettings: React.FC = () => {
     }
   }
 
+  const handleFallbackChange = async (value: string) => {
+    try {
+      await updateSetting('fallbackModels', value ? [value] : [])
+      toast.success(value ? `备用模型已设为 ${value.toUpperCase()}` : '已关闭备用模型')
+    } catch (error) {
+      toast.error(`设置备用模型失败: ${String(error)}`)
+    }
+  }
+
   const handleDownload = async (modelName: string) => {
     console.log('[ModelSettings] 🚀 Starting download for model:', modelName)
 
@@ -135,11 +161,20 @@ export const ModelSettings: React.FC = () => {
 
   const radioOptions: RadioOption[] = models.map((model) => ({
     value: model.name,
-    label: `${model.name.toUpperCase()} (${model.size}, ${model.speed}, ${model.accuracy})${model.is_recommended ? ' 推荐' : ''}`,
-    description: model.is_downloaded ? '✓ 已下载' : '',
+    label: `${model.name.toUpperCase()} (${model.size}, ${model.speed}, ${model.accuracy}${model.ram_mb ? `, 内存约 ${model.ram_mb}MB` : ''})${model.is_recommended ? ' 推荐' : ''}`,
+    description: model.is_downloaded
+      ? `✓ 已下载${model.disk_bytes ? `（占用 ${formatDiskSize(model.disk_bytes)}）` : ''}`
+      : '',
   }))
 
+  // 远程引擎没有本地文件，不参与下载 / 删除
+  const localModels = models.filter((m) => m.engine !== 'remote')
   const downloadedModels = models.filter((m) => m.is_downloaded)
+  const downloadedLocalModels = localModels.filter((m) => m.is_downloaded)
+  const fallbackCandidates = downloadedModels.filter((m) => m.name !== settings.model)
+  const downloadedFunASRModels = downloadedModels
+    .filter((m) => m.engine === 'funasr')
+    .map((m) => m.name)
 
   return (
     <div className="space-y-6">
@@ -161,7 +196,7 @@ export const ModelSettings: React.FC = () => {
 
             {/* 下载/删除按钮 */}
             <div className="space-y-2 mt-4">
-              {models.map((model) => (
+              {localModels.map((model) => (
                 <div
                   key={model.name}
                   className="flex items-center justify-between p-3 bg-gray-50 rounded-lg"
@@ -208,12 +243,42 @@ export const ModelSettings: React.FC = () => {
         )}
       </div>
 
+      {/* 远程引擎 */}
+      <RemoteEngineSettings onSaved={() => void loadModels()} />
+
+      {/* FunASR 常驻模型 */}
+      {downloadedFunASRModels.length > 0 && (
+        <FunASRResidentModels downloadedModels={downloadedFunASRModels} />
+      )}
+
+      {/* 备用模型 */}
+      <div className="p-4 bg-gray-50 rounded-lg flex items-center justify-between">
+        <div className="flex-1">
+          <div className="font-medium text-gray-900">备用模型</div>
+          <div className="text-sm text-gray-500 mt-1">
+            当前模型无法使用时（如 Python 环境仍在安装）自动改用该模型转录
+          </div>
+        </div>
+        <select
+          className="px-3 py-1.5 border border-gray-300 rounded-lg text-sm bg-white"
+          value={settings.fallbackModels[0] ?? ''}
+          onChange={(e) => void handleFallbackChange(e.target.value)}
+        >
+          <option value="">不使用</option>
+          {fallbackCandidates.map((model) => (
+            <option key={model.name} value={model.name}>
+              {model.name.toUpperCase()}
+            </option>
+          ))}
+        </select>
+      </div>
+
       {/* 已下载的模型 */}
-      {downloadedModels.length > 0 && (
+      {downloadedLocalModels.length > 0 && (
         <div>
           <h4 className="text-sm font-medium text-gray-700 mb-3">已下载的模型</h4>
           <div className="space-y-2">
-            {downloadedModels.map((model) => (
+            {downloadedLocalModels.map((model) => (
               <div
                 key={model.name}
                 className="p-3 bg-gray-50 rounded-lg flex items-center justify-between"
@@ -0,0 +1,166 @@
+import React, { useEffect, useState } from 'react'
+import { invoke } from '@tauri-apps/api/core'
+import { useSettingsStore } from '../../../stores'
+import { Button, Input, RadioGroup, type RadioOption } from '../../../components'
+import { useToast } from '../../../components'
+
+type OutputMode = 'transcribe' | 'translate'
+
+interface OutputModeSettingsDTO {
+  default_mode: OutputMode
+  // 应用 bundle ID -> 输出模式
+  app_modes: Record<string, OutputMode>
+}
+
+interface EngineCapabilities {
+  translation: boolean
+}
+
+const outputModeOptions: RadioOption[] = [
+  { value: 'transcribe', label: '原文转写', description: '按说话的语言输出文字' },
+  { value: 'translate', label: '翻译为英文', description: '输出英文译文（需要 Whisper 或远程引擎）' },
+]
+
+export const OutputModeSettings: React.FC = () => {
+  const { settings, updateSetting } = useSettingsStore()
+  const toast = useToast()
+  const [modes, setModes] = useState<OutputModeSettingsDTO>({ default_mode: 'transcribe', app_modes: {} })
+  const [translationSupported, setTranslationSupported] = useState(true)
+  const [translateShortcut, setTranslateShortcut] = useState<string>(settings.translateShortcut ?? '')
+  const [newBundleId, setNewBundleId] = useState('')
+  const [newMode, setNewMode] = useState<OutputMode>('translate')
+
+  const loadModes = async () => {
+    try {
+      setModes(await invoke<OutputModeSettingsDTO>('get_output_mode_settings'))
+    } catch (error) {
+      console.error('[OutputModeSettings] Failed to load output modes:', error)
+    }
+  }
+
+  useEffect(() => {
+    void loadModes()
+    invoke<EngineCapabilities>('get_engine_capabilities')
+      .then((caps) => setTranslationSupported(caps.translation))
+      .catch((error) => console.error('[OutputModeSettings] Failed to load capabilities:', error))
+  }, [settings.model])
+
+  useEffect(() => {
+    setTranslateShortcut(settings.translateShortcut ?? '')
+  }, [settings.translateShortcut])
+
+  const handleDefaultModeChange = async (mode: string) => {
+    try {
+      await invoke('set_default_output_mode', { mode })
+      await loadModes()
+      toast.success(mode === 'translate' ? '默认输出英文译文' : '默认输出原文')
+    } catch (error) {
+      toast.error(`设置失败: ${String(error)}`)
+    }
+  }
+
+  const handleSetAppMode = async (bundleId: string, mode: OutputMode | null) => {
+    try {
+      await invoke('set_app_output_mode', { bundleId, mode })
+      await loadModes()
+      if (mode) {
+        setNewBundleId('')
+      }
+    } catch (error) {
+      toast.error(`设置失败: ${String(error)}`)
+    }
+  }
+
+  // 翻译快捷键随主快捷键一起重新注册
+  const handleSaveTranslateShortcut = async () => {
+    try {
+      await invoke('unregister_shortcuts')
+      await updateSetting('translateShortcut', translateShortcut.trim())
+      await invoke('register_shortcuts_cmd')
+      toast.success(translateShortcut.trim() ? '翻译快捷键已保存' : '已关闭翻译快捷键')
+    } catch (error) {
+      toast.error(`保存快捷键失败: ${String(error)}`)
+    }
+  }
+
+  return (
+    <div className="space-y-4">
+      <h4 className="font-medium text-gray-900">输出模式</h4>
+      {!translationSupported && (
+        <div className="p-3 bg-yellow-50 rounded-lg border border-yellow-200 text-sm text-yellow-800">
+          ⚠️ 当前引擎不支持翻译，翻译请求会改用支持翻译的备用模型，没有时将提示错误
+        </div>
+      )}
+      <RadioGroup
+        name="outputMode"
+        value={modes.default_mode}
+        onChange={(mode) => void handleDefaultModeChange(mode)}
+        options={outputModeOptions}
+      />
+
+      <div className="p-4 bg-gray-50 rounded-lg flex items-end gap-2">
+        <div className="flex-1">
+          <Input
+            label="翻译快捷键"
+            placeholder="如 Cmd+Shift+E，留空不启用"
+            value={translateShortcut}
+            onChange={(e) => setTranslateShortcut(e.target.value)}
+          />
+        </div>
+        <Button variant="secondary" size="sm" onClick={() => void handleSaveTranslateShortcut()}>
+          保存
+        </Button>
+      </div>
+
+      <div className="p-4 bg-gray-50 rounded-lg space-y-3">
+        <div>
+          <div className="font-medium text-gray-900">按应用设置</div>
+          <div className="text-sm text-gray-500 mt-1">在指定应用中录音时使用的输出模式，优先于默认设置</div>
+        </div>
+        {Object.entries(modes.app_modes).map(([bundleId, mode]) => (
+          <div key={bundleId} className="flex items-center justify-between text-sm">
+            <code className="text-gray-700">{bundleId}</code>
+            <div className="flex items-center gap-2">
+              <select
+                className="px-2 py-1 border border-gray-300 rounded text-sm bg-white"
+                value={mode}
+                onChange={(e) => void handleSetAppMode(bundleId, e.target.value as OutputMode)}
+              >
+                <option value="transcribe">原文</option>
+                <option value="translate">英文译文</option>
+              </select>
+              <Button variant="secondary" size="sm" onClick={() => void handleSetAppMode(bundleId, null)}>
+                删除
+              </Button>
+            </div>
+          </div>
+        ))}
+        <div className="flex items-end gap-2">
+          <div className="flex-1">
+            <Input
+              label="应用标识"
+              placeholder="com.apple.Terminal"
+              value={newBundleId}
+              onChange={(e) => setNewBundleId(e.target.value)}
+            />
+          </div>
+          <select
+            className="px-2 py-2 border border-gray-300 rounded text-sm bg-white"
+            value={newMode}
+            onChange={(e) => setNewMode(e.target.value as OutputMode)}
+          >
+            <option value="transcribe">原文</option>
+            <option value="translate">英文译文</option>
+          </select>
+          <Button
+            size="sm"
+            disabled={!newBundleId.trim()}
+            onClick={() => void handleSetAppMode(newBundleId.trim(), newMode)}
+          >
+            添加
+          </Button>
+        </div>
+      </div>
+    </div>
+  )
+}
@@ -0,0 +1,166 @@
+import React, { useEffect, useState } from 'react'
+import { invoke } from '@tauri-apps/api/core'
+import { Button, Input } from '../../../components'
+import { useToast } from '../../../components'
+
+interface RemoteEngineConfig {
+  endpoint: string
+  // 后端不回传已保存的 API Key；为空时保存和测试沿用已保存的值
+  api_key: string | null
+  auth_header: string | null
+  model: string
+  timeout_secs: number
+}
+
+interface RemoteEngineConfigView extends RemoteEngineConfig {
+  api_key_set: boolean
+}
+
+const DEFAULT_CONFIG: RemoteEngineConfig = {
+  endpoint: '',
+  api_key: null,
+  auth_header: null,
+  model: 'whisper-1',
+  timeout_secs: 120,
+}
+
+interface RemoteEngineSettingsProps {
+  // 保存后通知父组件刷新模型列表（远程引擎是否可用取决于是否配置了地址）
+  onSaved?: () => void
+}
+
+export const RemoteEngineSettings: React.FC<RemoteEngineSettingsProps> = ({ onSaved }) => {
+  const toast = useToast()
+  const [config, setConfig] = useState<RemoteEngineConfig>(DEFAULT_CONFIG)
+  const [saving, setSaving] = useState(false)
+  const [testing, setTesting] = useState(false)
+  const [apiKeySet, setApiKeySet] = useState(false)
+  const [clearApiKey, setClearApiKey] = useState(false)
+  // 已保存的服务器地址，后端只对该地址沿用已保存的 API Key
+  const [savedEndpoint, setSavedEndpoint] = useState('')
+
+  useEffect(() => {
+    invoke<RemoteEngineConfigView>('get_remote_engine_config')
+      .then(({ api_key_set, ...loaded }) => {
+        setConfig(loaded)
+        setApiKeySet(api_key_set)
+        setSavedEndpoint(loaded.endpoint)
+      })
+      .catch((error) => console.error('[RemoteEngineSettings] Failed to load config:', error))
+  }, [])
+
+  const update = <K extends keyof RemoteEngineConfig>(key: K, value: RemoteEngineConfig[K]) => {
+    setConfig((prev) => ({ ...prev, [key]: value }))
+  }
+
+  const endpointChanged = config.endpoint.trim() !== savedEndpoint.trim()
+  const storedKeyUsable = apiKeySet && !clearApiKey && !endpointChanged
+
+  const handleSave = async () => {
+    setSaving(true)
+    try {
+      await invoke('set_remote_engine_config', { config, clearApiKey })
+      setApiKeySet(storedKeyUsable || !!config.api_key)
+      setSavedEndpoint(config.endpoint)
+      setClearApiKey(false)
+      update('api_key', null)
+      toast.success('远程引擎设置已保存')
+      onSaved?.()
+    } catch (error) {
+      toast.error(`保存失败: ${String(error)}`)
+    } finally {
+      setSaving(false)
+    }
+  }
+
+  const handleTest = async () => {
+    setTesting(true)
+    try {
+      await invoke<string>('test_remote_engine', { config })
+      toast.success('连接成功')
+    } catch (error) {
+      toast.error(`连接失败: ${String(error)}`)
+    } finally {
+      setTesting(false)
+    }
+  }
+
+  return (
+    <div className="p-4 bg-gray-50 rounded-lg space-y-3">
+      <div>
+        <div className="font-medium text-gray-900">远程引擎</div>
+        <div className="text-sm text-gray-500 mt-1">
+          使用 OpenAI 兼容的转录服务（whisper.cpp server、faster-whisper-server、OpenAI 等），配置后可在上方选择
+          REMOTE 模型
+        </div>
+      </div>
+
+      <Input
+        label="服务器地址"
+        placeholder="http://192.168.1.10:8080"
+        value={config.endpoint}
+        onChange={(e) => update('endpoint', e.target.value)}
+      />
+      <div className="flex items-end gap-2">
+        <Input
+          label="API Key"
+          type="password"
+          placeholder={
+            storedKeyUsable ? '已设置（留空保持不变）' : apiKeySet && endpointChanged ? '更换地址后需重新填写' : '可选'
+          }
+          value={config.api_key ?? ''}
+          onChange={(e) => {
+            update('api_key', e.target.value || null)
+            setClearApiKey(false)
+          }}
+        />
+        {storedKeyUsable && (
+          <Button
+            variant="ghost"
+            size="sm"
+            onClick={() => {
+              update('api_key', null)
+              setClearApiKey(true)
+            }}
+          >
+            清除
+          </Button>
+        )}
+      </div>
+      <div className="grid grid-cols-3 gap-3">
+        <Input
+          label="认证头"
+          placeholder="Authorization"
+          value={config.auth_header ?? ''}
+          onChange={(e) => update('auth_header', e.target.value || null)}
+        />
+        <Input
+          label="模型名"
+          value={config.model}
+          onChange={(e) => update('model', e.target.value)}
+        />
+        <Input
+          label="超时（秒）"
+          type="number"
+          min={1}
+          value={config.timeout_secs}
+          onChange={(e) => update('timeout_secs', Number(e.target.value) || DEFAULT_CONFIG.timeout_secs)}
+        />
+      </div>
+
+      <div className="flex justify-end gap-2">
+        <Button
+          variant="secondary"
+          size="sm"
+          disabled={testing || !config.endpoint.trim()}
+          onClick={() => void handleTest()}
+        >
+          {testing ? '测试中...' : '测试连接'}
+        </Button>
+        <Button size="sm" disabled={saving} onClick={() => void handleSave()}>
+          保存
+        </Button>
+      </div>
+    </div>
+  )
+}
@@ -75,6 +75,33 @@ export const SystemSettings: React.FC = () => {
     }
   }
 
+  const handleIdleUnloadChange = async (minutes: number) => {
+    try {
+      await updateSetting('idleUnloadMinutes', minutes)
+      toast.success(minutes === 0 ? '模型将常驻内存' : `模型空闲 ${minutes} 分钟后释放内存`)
+    } catch (error) {
+      toast.error(`设置失败: ${String(error)}`)
+      console.error('Failed to set idle unload minutes:', error)
+    }
+  }
+
+  const handlePrewarmOnShortcutChange = async (enabled: boolean) => {
+    try {
+      await updateSetting('prewarmOnShortcut', enabled)
+    } catch (error) {
+      toast.error(`设置失败: ${String(error)}`)
+      console.error('Failed to set prewarm on shortcut:', error)
+    }
+  }
+
+  const idleUnloadOptions = [
+    { value: 0, label: '从不' },
+    { value: 5, label: '5 分钟' },
+    { value: 10, label: '10 分钟' },
+    { value: 30, label: '30 分钟' },
+    { value: 60, label: '1 小时' },
+  ]
+
   const operationModeOptions: RadioOption[] = [
     {
       value: 'direct',
@@ -132,6 +159,39 @@ export const SystemSettings: React.FC = () => {
               disabled={loading}
             />
           </div>
+
+          {/* 空闲释放模型 */}
+          <div className="p-4 bg-gray-50 rounded-lg flex items-center justify-between">
+            <div className="flex-1">
+              <div className="font-medium text-gray-900">空闲时释放模型</div>
+              <div className="text-sm text-gray-500 mt-1">
+                Unload models after inactivity, reloaded on next dictation
+              </div>
+            </div>
+            <select
+              className="px-3 py-1.5 border border-gray-300 rounded-lg text-sm bg-white"
+              value={settings.idleUnloadMinutes}
+              onChange={(e) => void handleIdleUnloadChange(Number(e.target.value))}
+            >
+              {idleUnloadOptions.map((opt) => (
+                <option key={opt.value} value={opt.value}>
+                  {opt.label}
+                </option>
+              ))}
+            </select>
+          </div>
+
+          {/* 快捷键预热 */}
+          <div className="p-4 bg-gray-50 rounded-lg flex items-center justify-between">
+            <div className="flex-1">
+              <div className="font-medium text-gray-900">按下快捷键时预加载模型</div>
+              <div className="text-sm text-gray-500 mt-1">Prewarm model when shortcut is pressed</div>
+            </div>
+            <Toggle
+              checked={settings.prewarmOnShortcut}
+              onChange={(enabled) => void handlePrewarmOnShortcutChange(enabled)}
+            />
+          </div>
         </div>
       </div>
     </div>
@@ -4,11 +4,20 @@ import { getCurrentWindow } from '@tauri-apps/api/window'
 import { listen } from '@tauri-apps/api/event'
 import { invoke } from '@tauri-apps/api/core'
 import { useRecordingStore } from '../../stores'
+import type { LiveTranscript } from '../../stores'
 import { useSettingsStore } from '../../stores'
 import { AudioCapture } from '../../lib/audioCapture'
 import { AudioCacheManager } from '../../lib/audioCacheManager'
 import { InlineToast } from '../../components/InlineToast'
 
+/** 直接插入模式的胶囊宽度有限，只显示实时文本的末尾 */
+const LIVE_TAIL_LENGTH = 12
+
+const liveTail = ({ confirmed, partial }: LiveTranscript): string => {
+  const text = confirmed + partial
+  return text.length > LIVE_TAIL_LENGTH ? `…${text.slice(-LIVE_TAIL_LENGTH)}` : text
+}
+
 export const RecordingFloat = () => {
   console.log('[RecordingFloat] 🎬🎬🎬 Component function called (RE-RENDER)')
 
@@ -17,6 +26,10 @@ export const RecordingFloat = () => {
   const audioLevel = useRecordingStore((state) => state.audioLevel)
   const toast = useRecordingStore((state) => state.toast)
   const clearToast = useRecordingStore((state) => state.clearToast)
+  const transcriptionProgress = useRecordingStore((state) => state.transcriptionProgress)
+  const partialTranscript = useRecordingStore((state) => state.partialTranscript)
+  const liveTranscript = useRecordingStore((state) => state.liveTranscript)
+  const hasLiveTranscript = !!(liveTranscript.confirmed || liveTranscript.partial)
 
   // 直接从设置中读取操作模式，而不是从 recordingStore
   const settings = useSettingsStore((state) => state.settings)
@@ -293,6 +306,10 @@ export const RecordingFloat = () => {
         } else {
           // idle 或 error 状态 - 可能是异常情况,隐藏窗口
           console.log('[RecordingFloat] 🟠 Direct mode: unexpected state, hiding window')
+          // 没有录音可转录，撤销后端为本次录音预留的转录任务
+          invoke('abandon_recording').catch((err) => {
+            console.warn('[RecordingFloat] Failed to abandon recording:', err)
+          })
           const window = getCurrentWindow()
           await window.hide()
           clearText()
@@ -337,10 +354,65 @@ export const RecordingFloat = () => {
     }
   }, [audioCacheManager])
 
+  // 转录进度事件（按任务 ID 过滤）
+  useEffect(() => {
+    const unlisteners: Array<() => void> = []
+    let disposed = false
+
+    const setupProgressListeners = async () => {
+      const {
+        startTranscriptionJob,
+        setTranscriptionProgress,
+        appendPartialTranscript,
+        setLiveTranscript,
+      } = useRecordingStore.getState()
+
+      const handlers = await Promise.all([
+        listen<{ job_id: string }>('transcription-started', (event) => {
+          startTranscriptionJob(event.payload.job_id)
+        }),
+        listen<{ job_id: string; percent: number }>('transcription-progress', (event) => {
+          setTranscriptionProgress(event.payload.job_id, event.payload.percent)
+        }),
+        listen<{ job_id: string; text: string }>('transcription-segment', (event) => {
+          appendPartialTranscript(event.payload.job_id, event.payload.text)
+        }),
+        listen<LiveTranscript>('streaming-transcript', (event) => {
+          setLiveTranscript(event.payload)
+        }),
+      ])
+
+      if (disposed) {
+        handlers.forEach((unlisten) => unlisten())
+      } else {
+        unlisteners.push(...handlers)
+      }
+    }
+
+    setupProgressListeners().catch((error) => {
+      console.error('[RecordingFloat] Failed to setup transcription progress listeners:', error)
+    })
+
+    return () => {
+      disposed = true
+      unlisteners.forEach((unlisten) => unlisten())
+    }
+  }, [])
+
+  const processingLabel =
+    transcriptionProgress === null ? '正在转录...' : `正在转录... ${transcriptionProgress}%`
+
   // Handle Esc key to close window
   useEffect(() => {
     const handleKeyDown = (e: KeyboardEvent) => {
       if (e.key === 'Escape') {
+        // 转录中按 Esc：取消进行中的转录任务
+        if (useRecordingStore.getState().state === 'processing') {
+          invoke('cancel_transcription', { jobId: null }).catch((err) => {
+            console.error('[RecordingFloat] Failed to cancel transcription:', err)
+          })
+          return
+        }
         void handleClose()
       }
     }
@@ -434,8 +506,20 @@ export const RecordingFloat = () => {
             {/* Center: Text content area - shows real-time transcription or processing status */}
             <div className="flex-1 min-h-[24px] max-h-[60px] overflow-y-auto">
               {status === 'processing' ? (
-                <p className="text-blue-400 text-sm italic leading-relaxed animate-pulse">
-                  正在转录...
+                partialTranscript || hasLiveTranscript ? (
+                  <p className="text-white/70 text-sm leading-relaxed whitespace-pre-wrap">
+                    {partialTranscript || liveTranscript.confirmed + liveTranscript.partial}
+                    <span className="text-blue-400 italic animate-pulse"> {processingLabel}</span>
+                  </p>
+                ) : (
+                  <p className="text-blue-400 text-sm italic leading-relaxed animate-pulse">
+                    {processingLabel}
+                  </p>
+                )
+              ) : status === 'recording' && hasLiveTranscript ? (
+                <p className="text-white text-sm leading-relaxed whitespace-pre-wrap">
+                  {liveTranscript.confirmed}
+                  <span className="text-white/50">{liveTranscript.partial}</span>
                 </p>
               ) : transcribedText ? (
                 <p className="text-white text-sm leading-relaxed whitespace-pre-wrap">
@@ -546,7 +630,7 @@ export const RecordingFloat = () => {
     console.log('[RecordingFloat] 🎨 transcribedText:', transcribedText)
     console.log(
       '[RecordingFloat] 🎨 Will show:',
-      status === 'processing' ? '正在转录...' : '正在录制...',
+      status === 'processing' ? processingLabel : '正在录制...',
     )
     return (
       <div className="fixed inset-0 flex flex-col items-center justify-center gap-3">
@@ -609,7 +693,11 @@ export const RecordingFloat = () => {
           {/* Center: Status text */}
           <div className="flex-1 min-w-0">
             <p className="text-white/80 text-xs truncate">
-              {status === 'processing' ? '正在转录...' : '正在录制...'}
+              {status === 'processing'
+                ? processingLabel
+                : hasLiveTranscript
+                  ? liveTail(liveTranscript)
+                  : '正在录制...'}
             </p>
           </div>
 